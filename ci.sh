@@ -37,7 +37,7 @@ echo "------------------------------------------------"
 
 if [ $FAILURES -gt 0 ]; then
     echo "🛑 CI/CD Pipeline Failed! Found compliance violations in $FAILURES file(s)."
-    echo "📄 Check the generated compliance_report.html or compliance_report.json for details."
+    echo "📄 Re-run with --format html --output compliance_report.html for a detailed report."
     exit 1
 else
     echo "🎉 Success! All analyzed files meet the required compliance threshold."
@@ -1,4 +1,5 @@
 // src/api/errors.rs
+use crate::error::HubstryError;
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -7,24 +8,46 @@ use axum::{
 use serde_json::json;
 
 pub enum ApiError {
-    InternalServerError(anyhow::Error),
+    Engine(HubstryError),
     BadRequest(String),
     Forbidden(String),
 }
 
+/// Status HTTP correspondente a cada área de `HubstryError`; o corpo da resposta
+/// leva o código estável (`HUB-XXX-NNN`) do erro.
+fn engine_status(err: &HubstryError) -> StatusCode {
+    match err {
+        HubstryError::Parse(_) | HubstryError::Rule(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        HubstryError::Fetch(_) => StatusCode::BAD_GATEWAY,
+        HubstryError::License(_) => StatusCode::FORBIDDEN,
+        HubstryError::Config(_)
+        | HubstryError::Report(_)
+        | HubstryError::History(_)
+        | HubstryError::Lsp(_)
+        | HubstryError::Io { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            ApiError::InternalServerError(err) => {
-                tracing::error!("Internal server error: {}", err);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
+        let (status, code, error_message) = match self {
+            ApiError::Engine(err) => {
+                let status = engine_status(&err);
+                let message = if status == StatusCode::INTERNAL_SERVER_ERROR {
+                    tracing::error!("Internal server error [{}]: {}", err.code(), err);
+                    "Internal server error".to_string()
+                } else {
+                    err.to_string()
+                };
+                (status, err.code(), message)
             }
-            ApiError::BadRequest(err) => (StatusCode::BAD_REQUEST, err),
-            ApiError::Forbidden(err) => (StatusCode::FORBIDDEN, err),
+            ApiError::BadRequest(err) => (StatusCode::BAD_REQUEST, "HUB-API-400", err),
+            ApiError::Forbidden(err) => (StatusCode::FORBIDDEN, "HUB-API-403", err),
         };
 
         let body = Json(json!({
-            "error": error_message
+            "error": error_message,
+            "code": code
         }));
 
         (status, body).into_response()
@@ -33,9 +56,9 @@ impl IntoResponse for ApiError {
 
 impl<E> From<E> for ApiError
 where
-    E: Into<anyhow::Error>,
+    E: Into<HubstryError>,
 {
     fn from(err: E) -> Self {
-        Self::InternalServerError(err.into())
+        Self::Engine(err.into())
     }
 }
@@ -3,8 +3,12 @@
 //! to decouple the core validation logic from any specific parser like `syn`.
 
 /// Represents a source code file.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct FileAst {
+    /// Path of the file, or an empty string when the AST was not read from disk.
+    pub path: String,
+    /// The original source text, or an empty string when it is not available.
+    pub source: String,
     pub functions: Vec<FunctionAst>,
 }
 
@@ -14,6 +18,59 @@ pub struct FunctionAst {
     pub name: String,
     pub doc_comments: Vec<String>,
     pub called_functions: Vec<String>,
+    /// Call sites in source order, with their literal arguments.
+    pub calls: Vec<CallSite>,
+    /// String literals in the function body, in source order.
+    pub string_literals: Vec<StringLiteral>,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    /// Position of the opening brace of the body.
+    pub body_line: usize,
+    pub body_column: usize,
+}
+
+impl FunctionAst {
+    /// Compliance prefixes this function is annotated with (`/// PREFIX: description`).
+    /// Suppression comments (`/// hubstry:allow(...)`) and processing declarations
+    /// (`/// hubstry:processing ...`) are not annotations.
+    pub fn annotations(&self) -> impl Iterator<Item = &str> {
+        self.doc_comments
+            .iter()
+            .filter(|comment| {
+                let comment = comment.trim();
+                !comment.starts_with(crate::suppression::MARKER)
+                    && !comment.starts_with(crate::inventory::MARKER)
+            })
+            .filter_map(|comment| comment.trim().split_once(':'))
+            .map(|(prefix, _)| prefix.trim())
+    }
+
+    /// Text of the annotation with the given prefix, after `PREFIX:`.
+    pub fn annotation(&self, prefix: &str) -> Option<&str> {
+        self.doc_comments
+            .iter()
+            .filter_map(|comment| comment.trim().split_once(':'))
+            .find(|(name, _)| name.trim() == prefix)
+            .map(|(_, text)| text.trim())
+    }
+}
+
+/// Represents a single function or method call inside a function body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallSite {
+    pub name: String,
+    /// One entry per argument: the literal value as written (without quotes),
+    /// or `None` when the argument is not a literal.
+    pub args: Vec<Option<String>>,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Represents a string literal inside a function body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringLiteral {
+    pub value: String,
     pub line: usize,
     pub column: usize,
 }
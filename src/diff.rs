@@ -0,0 +1,342 @@
+//! Comparação entre duas análises (`hubstry diff`).
+//!
+//! As violações são associadas pela impressão digital de
+//! [`ComplianceViolation::fingerprint`], que não depende da linha: uma violação que
+//! só mudou de lugar continua "inalterada". Violações repetidas (mesma impressão
+//! digital) são associadas em ordem. Uma violação que sumiu das ativas mas aparece
+//! entre as suprimidas da nova análise é classificada como suprimida, não corrigida.
+
+use crate::error::{HubstryError, ReportError};
+use crate::i18n::Locale;
+use crate::models::{AnalysisResult, ComplianceViolation};
+use crate::report::group_name;
+use crate::scoring::SubScore;
+use crate::tr;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Variação de um score entre as duas análises. `None` quando o grupo não existe
+/// numa delas.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreDelta {
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+    pub delta: Option<f64>,
+}
+
+impl ScoreDelta {
+    fn new(before: Option<f64>, after: Option<f64>) -> Self {
+        let delta = match (before, after) {
+            (Some(before), Some(after)) => Some(((after - before) * 100.0).round() / 100.0),
+            _ => None,
+        };
+        ScoreDelta {
+            before,
+            after,
+            delta,
+        }
+    }
+}
+
+/// O que mudou na conformidade entre duas análises.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportDiff {
+    /// Violações que só existem na nova análise.
+    pub new: Vec<ComplianceViolation>,
+    /// Violações da análise anterior que deixaram de existir.
+    pub fixed: Vec<ComplianceViolation>,
+    /// Violações da análise anterior que passaram a ser suprimidas.
+    pub suppressed: Vec<ComplianceViolation>,
+    /// Violações presentes nas duas, com a posição da nova análise.
+    pub unchanged: Vec<ComplianceViolation>,
+    pub score: ScoreDelta,
+    pub by_jurisdiction: BTreeMap<String, ScoreDelta>,
+    pub by_category: BTreeMap<String, ScoreDelta>,
+}
+
+/// Lê um relatório `json`, `yaml` ou `yml` gerado por `hubstry analyze`.
+pub fn load_report(path: &Path) -> Result<AnalysisResult, HubstryError> {
+    let content = std::fs::read_to_string(path).map_err(|e| HubstryError::io(path, e))?;
+    let invalid = |message: String| ReportError::Read {
+        path: path.to_path_buf(),
+        message,
+    };
+    let result = match path.extension().and_then(|e| e.to_str()) {
+        Some("yaml" | "yml") => {
+            serde_yaml::from_str(&content).map_err(|e| invalid(e.to_string()))?
+        }
+        _ => serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?,
+    };
+    Ok(result)
+}
+
+/// Compara a análise anterior (`old`) com a nova (`new`).
+pub fn diff(old: &AnalysisResult, new: &AnalysisResult) -> ReportDiff {
+    // Índices das violações anteriores ainda não associadas, por impressão digital.
+    let mut remaining: HashMap<String, VecDeque<usize>> = HashMap::new();
+    for (index, violation) in old.violations.iter().enumerate() {
+        remaining
+            .entry(violation.fingerprint())
+            .or_default()
+            .push_back(index);
+    }
+
+    let mut added = Vec::new();
+    let mut unchanged = Vec::new();
+    for violation in &new.violations {
+        let matched = remaining
+            .get_mut(&violation.fingerprint())
+            .and_then(VecDeque::pop_front);
+        match matched {
+            Some(_) => unchanged.push(violation.clone()),
+            None => added.push(violation.clone()),
+        }
+    }
+
+    let mut waived: HashMap<String, usize> = HashMap::new();
+    for violation in &new.suppressed {
+        *waived.entry(violation.fingerprint()).or_default() += 1;
+    }
+    let mut gone: Vec<usize> = remaining.into_values().flatten().collect();
+    gone.sort_unstable();
+    let mut fixed = Vec::new();
+    let mut suppressed = Vec::new();
+    for index in gone {
+        let violation = &old.violations[index];
+        match waived
+            .get_mut(&violation.fingerprint())
+            .filter(|count| **count > 0)
+        {
+            Some(count) => {
+                *count -= 1;
+                suppressed.push(violation.clone());
+            }
+            None => fixed.push(violation.clone()),
+        }
+    }
+
+    ReportDiff {
+        new: added,
+        fixed,
+        suppressed,
+        unchanged,
+        score: ScoreDelta::new(Some(old.compliance_score), Some(new.compliance_score)),
+        by_jurisdiction: deltas(
+            &old.score_breakdown.by_jurisdiction,
+            &new.score_breakdown.by_jurisdiction,
+        ),
+        by_category: deltas(
+            &old.score_breakdown.by_category,
+            &new.score_breakdown.by_category,
+        ),
+    }
+}
+
+fn deltas(
+    old: &BTreeMap<String, SubScore>,
+    new: &BTreeMap<String, SubScore>,
+) -> BTreeMap<String, ScoreDelta> {
+    old.keys()
+        .chain(new.keys())
+        .map(|key| {
+            (
+                key.clone(),
+                ScoreDelta::new(old.get(key).map(|s| s.score), new.get(key).map(|s| s.score)),
+            )
+        })
+        .collect()
+}
+
+impl ReportDiff {
+    /// Se a nova análise trouxe violações que a anterior não tinha.
+    pub fn has_regressions(&self) -> bool {
+        !self.new.is_empty()
+    }
+
+    /// Texto para o terminal, no idioma `locale`.
+    pub fn to_text(&self, locale: Locale) -> String {
+        let mut out = String::new();
+        let title = tr!(locale, "diff.title");
+        let _ = writeln!(out, "{}\n{}\n", title, "=".repeat(title.chars().count()));
+        let _ = writeln!(
+            out,
+            "{}: {}",
+            tr!(locale, "report.score"),
+            format_delta(&self.score)
+        );
+        let _ = writeln!(
+            out,
+            "{}",
+            tr!(
+                locale,
+                "diff.summary",
+                new = self.new.len(),
+                fixed = self.fixed.len(),
+                suppressed = self.suppressed.len(),
+                unchanged = self.unchanged.len()
+            )
+        );
+
+        for (title, scores) in [
+            ("diff.by_jurisdiction", &self.by_jurisdiction),
+            ("diff.by_category", &self.by_category),
+        ] {
+            if !scores.is_empty() {
+                let _ = writeln!(out, "\n{}:", locale.text(title, &[]));
+                for (name, delta) in scores {
+                    let _ = writeln!(out, "  {:<16} {}", name, format_delta(delta));
+                }
+            }
+        }
+
+        for (title, violations) in self.sections() {
+            if !violations.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "\n{}:",
+                    locale.text(title, &[("count", violations.len().to_string())])
+                );
+                for violation in violations {
+                    let _ = writeln!(
+                        out,
+                        "  [{}] {} {}",
+                        violation.severity,
+                        violation.rule_id,
+                        tr!(locale, "report.at", location = place(violation))
+                    );
+                }
+            }
+        }
+        out
+    }
+
+    /// Markdown para um comentário de merge request, no idioma `locale`.
+    pub fn to_markdown(&self, locale: Locale) -> String {
+        let mut out = String::new();
+        let icon = if self.has_regressions() { "❌" } else { "✅" };
+        let _ = writeln!(out, "## {} {}\n", icon, tr!(locale, "diff.heading"));
+        let _ = writeln!(
+            out,
+            "**{}:** {}\n",
+            tr!(locale, "report.score"),
+            format_delta(&self.score)
+        );
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} |\n|---|---|---|---|\n| {} | {} | {} | {} |\n",
+            tr!(locale, "diff.column.new"),
+            tr!(locale, "diff.column.fixed"),
+            tr!(locale, "diff.column.suppressed"),
+            tr!(locale, "diff.column.unchanged"),
+            self.new.len(),
+            self.fixed.len(),
+            self.suppressed.len(),
+            self.unchanged.len()
+        );
+
+        if !self.by_jurisdiction.is_empty() || !self.by_category.is_empty() {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |\n|---|---|---|---|---|",
+                tr!(locale, "report.column.group"),
+                tr!(locale, "report.column.name"),
+                tr!(locale, "diff.column.before"),
+                tr!(locale, "diff.column.after"),
+                tr!(locale, "diff.column.delta")
+            );
+            for (group, scores) in [
+                ("jurisdiction", &self.by_jurisdiction),
+                ("category", &self.by_category),
+            ] {
+                for (name, delta) in scores {
+                    let _ = writeln!(
+                        out,
+                        "| {} | {} | {} | {} | {} |",
+                        group_name(group, locale),
+                        name,
+                        format_score(delta.before),
+                        format_score(delta.after),
+                        delta
+                            .delta
+                            .map_or("-".to_string(), |d| format!("{:+.1}", d))
+                    );
+                }
+            }
+            out.push('\n');
+        }
+
+        for (title, violations) in self.sections() {
+            if violations.is_empty() {
+                continue;
+            }
+            let _ = writeln!(
+                out,
+                "### {}\n",
+                locale.text(title, &[("count", violations.len().to_string())])
+            );
+            for violation in violations {
+                let _ = writeln!(
+                    out,
+                    "- **{}** `{}` {}: {}",
+                    violation.severity,
+                    violation.rule_id,
+                    tr!(
+                        locale,
+                        "report.at",
+                        location = format!("`{}`", place(violation))
+                    ),
+                    locale.message(violation)
+                );
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Chave do título e violações de cada seção: novas, corrigidas e suprimidas.
+    fn sections(&self) -> [(&'static str, &Vec<ComplianceViolation>); 3] {
+        [
+            ("diff.new", &self.new),
+            ("diff.fixed", &self.fixed),
+            ("diff.suppressed", &self.suppressed),
+        ]
+    }
+}
+
+fn format_score(score: Option<f64>) -> String {
+    score.map_or("-".to_string(), |s| format!("{:.1}%", s))
+}
+
+/// `90.0% -> 95.0% (+5.0)`.
+fn format_delta(delta: &ScoreDelta) -> String {
+    match delta.delta {
+        Some(d) => format!(
+            "{} -> {} ({:+.1})",
+            format_score(delta.before),
+            format_score(delta.after),
+            d
+        ),
+        None => format!(
+            "{} -> {}",
+            format_score(delta.before),
+            format_score(delta.after)
+        ),
+    }
+}
+
+/// `arquivo:linha (função)`.
+fn place(violation: &ComplianceViolation) -> String {
+    let mut place = violation
+        .file
+        .clone()
+        .unwrap_or_else(|| "(project)".to_string());
+    if let Some(line) = violation.line {
+        let _ = write!(place, ":{}", line);
+    }
+    if let Some(function) = &violation.function {
+        let _ = write!(place, " ({})", function);
+    }
+    place
+}
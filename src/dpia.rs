@@ -0,0 +1,451 @@
+//! Rascunho do Relatório de Impacto à Proteção de Dados Pessoais (RIPD/DPIA, LGPD
+//! art. 38) preenchido a partir de uma análise (`hubstry dpia`).
+//!
+//! O rascunho segue as seções de `docs/templates/dpia_template.md`. O que está no
+//! código vem da análise: as operações de tratamento são as funções anotadas, as
+//! categorias de dados são as `data_collection_keywords` alcançadas, os riscos são
+//! as violações abertas e as medidas de mitigação são as `expected_calls`
+//! encontradas. O que não está no código (finalidade, consulta aos titulares,
+//! aprovação) fica marcado para o encarregado completar.
+
+use crate::i18n::Locale;
+use crate::models::{AnalysisResult, CheckedFunction, ComplianceViolation, RuleSeverity};
+use crate::report::{citations, escape_xml};
+use crate::tr;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Identificação do projeto na capa do relatório.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectInfo {
+    pub name: String,
+    /// Versão ou commit analisado, quando conhecido.
+    pub version: Option<String>,
+    /// Data de geração, `AAAA-MM-DD`.
+    pub date: String,
+}
+
+/// Conteúdo do RIPD extraído de uma análise.
+#[derive(Debug, Clone)]
+pub struct Dpia {
+    pub project: ProjectInfo,
+    pub score: f64,
+    /// Funções anotadas: as operações de tratamento.
+    pub operations: Vec<CheckedFunction>,
+    /// Categoria de dados (palavra-chave) → funções que a tratam.
+    pub data_categories: BTreeMap<String, Vec<String>>,
+    /// Salvaguarda encontrada (chamada esperada) → funções que a chamam.
+    pub safeguards: BTreeMap<String, Vec<String>>,
+    /// Violações abertas: os riscos identificados.
+    pub risks: Vec<ComplianceViolation>,
+    /// Violações suprimidas: riscos aceitos, com justificativa e aprovador.
+    pub accepted_risks: Vec<ComplianceViolation>,
+}
+
+impl Dpia {
+    pub fn from_analysis(result: &AnalysisResult, project: ProjectInfo) -> Self {
+        let mut data_categories: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut safeguards: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for func in &result.checked_functions {
+            for (index, keywords) in [
+                (&mut data_categories, &func.data_collection),
+                (&mut safeguards, &func.safeguards),
+            ] {
+                for keyword in keywords {
+                    let functions = index.entry(keyword.clone()).or_default();
+                    if !functions.contains(&func.name) {
+                        functions.push(func.name.clone());
+                    }
+                }
+            }
+        }
+        Dpia {
+            project,
+            score: result.compliance_score,
+            operations: result.checked_functions.clone(),
+            data_categories,
+            safeguards,
+            risks: result.violations.clone(),
+            accepted_risks: result.suppressed.clone(),
+        }
+    }
+
+    /// Funções que tratam dados pessoais sem nenhuma salvaguarda encontrada.
+    pub fn unprotected_operations(&self) -> Vec<&CheckedFunction> {
+        self.operations
+            .iter()
+            .filter(|func| !func.data_collection.is_empty() && func.safeguards.is_empty())
+            .collect()
+    }
+
+    pub fn to_markdown(&self, locale: Locale) -> String {
+        let mut out = String::new();
+        for block in self.blocks(locale) {
+            match block {
+                Block::Heading(level, text) => {
+                    let _ = writeln!(out, "{} {}\n", "#".repeat(level), text);
+                }
+                Block::Note(text) => {
+                    let _ = writeln!(out, "> {}\n", text);
+                }
+                Block::Fields(fields) => {
+                    for (label, value) in fields {
+                        let _ = writeln!(out, "- **{}:** {}", label, value);
+                    }
+                    out.push('\n');
+                }
+                Block::Table(header, rows) => {
+                    let _ = writeln!(out, "| {} |", header.join(" | "));
+                    let _ = writeln!(out, "|{}", "---|".repeat(header.len()));
+                    for row in rows {
+                        let cells: Vec<String> =
+                            row.iter().map(|c| c.replace('|', "\\|")).collect();
+                        let _ = writeln!(out, "| {} |", cells.join(" | "));
+                    }
+                    out.push('\n');
+                }
+                Block::Rule => out.push_str("---\n\n"),
+            }
+        }
+        out
+    }
+
+    pub fn to_html(&self, locale: Locale) -> String {
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"UTF-8\">\n<title>{}</title>\n",
+            locale.code(),
+            escape_xml(&tr!(locale, "dpia.title"))
+        );
+        out.push_str("<style>\n");
+        out.push_str("body { font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif; margin: 0; padding: 20px; background-color: #f9f9fb; color: #333; }\n");
+        out.push_str(".container { max-width: 900px; margin: auto; background: #fff; padding: 30px; border-radius: 8px; box-shadow: 0 4px 6px rgba(0,0,0,0.1); }\n");
+        out.push_str(
+            "h1 { color: #2c3e50; border-bottom: 2px solid #ecf0f1; padding-bottom: 10px; }\n",
+        );
+        out.push_str(".note { background-color: #fff3cd; color: #856404; padding: 10px; border-radius: 4px; }\n");
+        out.push_str("table { border-collapse: collapse; width: 100%; margin: 10px 0; }\n");
+        out.push_str("th, td { border-bottom: 1px solid #e2e8f0; padding: 6px 10px; text-align: left; vertical-align: top; }\n");
+        out.push_str("</style>\n</head>\n<body>\n<div class=\"container\">\n");
+        for block in self.blocks(locale) {
+            match block {
+                Block::Heading(level, text) => {
+                    let _ = writeln!(out, "<h{0}>{1}</h{0}>", level, escape_xml(&text));
+                }
+                Block::Note(text) => {
+                    let _ = writeln!(out, "<p class=\"note\">{}</p>", escape_xml(&text));
+                }
+                Block::Fields(fields) => {
+                    out.push_str("<ul>\n");
+                    for (label, value) in fields {
+                        let _ = writeln!(
+                            out,
+                            "<li><strong>{}:</strong> {}</li>",
+                            escape_xml(&label),
+                            escape_xml(&value)
+                        );
+                    }
+                    out.push_str("</ul>\n");
+                }
+                Block::Table(header, rows) => {
+                    out.push_str("<table>\n<tr>");
+                    for cell in header {
+                        let _ = write!(out, "<th>{}</th>", escape_xml(&cell));
+                    }
+                    out.push_str("</tr>\n");
+                    for row in rows {
+                        out.push_str("<tr>");
+                        for cell in row {
+                            let _ = write!(out, "<td>{}</td>", escape_xml(&cell));
+                        }
+                        out.push_str("</tr>\n");
+                    }
+                    out.push_str("</table>\n");
+                }
+                Block::Rule => out.push_str("<hr>\n"),
+            }
+        }
+        out.push_str("</div>\n</body>\n</html>\n");
+        out
+    }
+
+    /// O relatório como uma sequência de blocos, renderizada em Markdown ou HTML.
+    fn blocks(&self, locale: Locale) -> Vec<Block> {
+        let todo = || tr!(locale, "dpia.todo");
+        let list = |items: &[String]| {
+            if items.is_empty() {
+                "-".to_string()
+            } else {
+                items.join(", ")
+            }
+        };
+        let mut blocks = vec![
+            Block::Heading(1, tr!(locale, "dpia.title")),
+            Block::Note(tr!(locale, "dpia.draft_notice")),
+            Block::Fields(vec![
+                (tr!(locale, "dpia.project"), self.project.name.clone()),
+                (tr!(locale, "dpia.date"), self.project.date.clone()),
+                (
+                    tr!(locale, "dpia.version"),
+                    self.project.version.clone().unwrap_or_else(todo),
+                ),
+                (tr!(locale, "report.score"), format!("{:.1}%", self.score)),
+            ]),
+            Block::Rule,
+        ];
+
+        // 1. Descrição da operação de tratamento
+        blocks.push(Block::Heading(2, tr!(locale, "dpia.section.operations")));
+        let categories: Vec<String> = self.data_categories.keys().cloned().collect();
+        blocks.push(Block::Fields(vec![
+            (tr!(locale, "dpia.nature"), todo()),
+            (
+                tr!(locale, "dpia.scope"),
+                if categories.is_empty() {
+                    todo()
+                } else {
+                    tr!(
+                        locale,
+                        "dpia.scope_value",
+                        operations = self.operations.len(),
+                        categories = categories.join(", ")
+                    )
+                },
+            ),
+            (tr!(locale, "dpia.context"), todo()),
+            (tr!(locale, "dpia.purpose"), {
+                let mut purposes: Vec<&str> = Vec::new();
+                for purpose in self.operations.iter().filter_map(|f| f.purpose.as_deref()) {
+                    if !purposes.contains(&purpose) {
+                        purposes.push(purpose);
+                    }
+                }
+                if purposes.is_empty() {
+                    todo()
+                } else {
+                    purposes.join("; ")
+                }
+            }),
+        ]));
+        if self.operations.is_empty() {
+            blocks.push(Block::Note(tr!(locale, "dpia.no_operations")));
+        } else {
+            blocks.push(Block::Table(
+                vec![
+                    tr!(locale, "dpia.column.function"),
+                    tr!(locale, "report.location"),
+                    tr!(locale, "dpia.column.prefixes"),
+                    tr!(locale, "dpia.column.description"),
+                    tr!(locale, "dpia.column.data"),
+                    tr!(locale, "dpia.column.safeguards"),
+                ],
+                self.operations
+                    .iter()
+                    .map(|func| {
+                        vec![
+                            func.name.clone(),
+                            location(func),
+                            func.prefixes.join(", "),
+                            func.description.clone().unwrap_or_else(|| "-".to_string()),
+                            list(&func.data_collection),
+                            list(&func.safeguards),
+                        ]
+                    })
+                    .collect(),
+            ));
+        }
+        blocks.push(Block::Heading(3, tr!(locale, "dpia.data_categories")));
+        if self.data_categories.is_empty() {
+            blocks.push(Block::Note(tr!(locale, "dpia.no_data_categories")));
+        } else {
+            blocks.push(Block::Table(
+                vec![
+                    tr!(locale, "dpia.column.category"),
+                    tr!(locale, "dpia.column.functions"),
+                ],
+                self.data_categories
+                    .iter()
+                    .map(|(category, functions)| vec![category.clone(), functions.join(", ")])
+                    .collect(),
+            ));
+        }
+        blocks.push(Block::Rule);
+
+        // 2. Necessidade e proporcionalidade
+        blocks.push(Block::Heading(2, tr!(locale, "dpia.section.necessity")));
+        let unprotected: Vec<String> = self
+            .unprotected_operations()
+            .iter()
+            .map(|func| func.name.clone())
+            .collect();
+        blocks.push(Block::Fields(vec![
+            (tr!(locale, "dpia.justification"), todo()),
+            (
+                tr!(locale, "dpia.minimization"),
+                if unprotected.is_empty() {
+                    todo()
+                } else {
+                    tr!(
+                        locale,
+                        "dpia.unprotected",
+                        todo = todo(),
+                        functions = unprotected.join(", ")
+                    )
+                },
+            ),
+        ]));
+        blocks.push(Block::Rule);
+
+        // 3. Consulta às partes interessadas
+        blocks.push(Block::Heading(2, tr!(locale, "dpia.section.consultation")));
+        blocks.push(Block::Fields(vec![
+            (tr!(locale, "dpia.consultation_method"), todo()),
+            (tr!(locale, "dpia.feedback"), todo()),
+        ]));
+        blocks.push(Block::Rule);
+
+        // 4. Avaliação de riscos
+        blocks.push(Block::Heading(2, tr!(locale, "dpia.section.risks")));
+        if self.risks.is_empty() {
+            blocks.push(Block::Note(tr!(locale, "dpia.no_risks")));
+        } else {
+            blocks.push(Block::Table(
+                vec![
+                    tr!(locale, "dpia.column.risk"),
+                    tr!(locale, "dpia.column.likelihood"),
+                    tr!(locale, "dpia.column.impact"),
+                    tr!(locale, "dpia.column.level"),
+                ],
+                self.risks
+                    .iter()
+                    .map(|violation| {
+                        let mut risk = format!(
+                            "[{}] {} ({})",
+                            violation.rule_id,
+                            locale.message(violation),
+                            violation_location(violation)
+                        );
+                        if !violation.citations.is_empty() {
+                            risk.push_str(&format!(
+                                ". {}: {}",
+                                tr!(locale, "report.legal_basis"),
+                                citations(&violation.citations, locale)
+                            ));
+                        }
+                        vec![
+                            risk,
+                            todo(),
+                            impact(&violation.severity, locale),
+                            violation.severity.to_string(),
+                        ]
+                    })
+                    .collect(),
+            ));
+        }
+        if !self.accepted_risks.is_empty() {
+            blocks.push(Block::Heading(3, tr!(locale, "dpia.accepted_risks")));
+            blocks.push(Block::Table(
+                vec![
+                    tr!(locale, "dpia.column.risk"),
+                    tr!(locale, "dpia.column.reason"),
+                    tr!(locale, "dpia.column.approver"),
+                ],
+                self.accepted_risks
+                    .iter()
+                    .map(|violation| {
+                        let suppression = violation.suppression.as_ref();
+                        vec![
+                            format!("[{}] {}", violation.rule_id, violation_location(violation)),
+                            suppression
+                                .and_then(|s| s.reason.clone())
+                                .unwrap_or_else(|| tr!(locale, "report.no_reason")),
+                            suppression
+                                .and_then(|s| s.approver.clone())
+                                .unwrap_or_else(|| tr!(locale, "report.nobody")),
+                        ]
+                    })
+                    .collect(),
+            ));
+        }
+        blocks.push(Block::Rule);
+
+        // 5. Medidas para mitigação
+        blocks.push(Block::Heading(2, tr!(locale, "dpia.section.mitigation")));
+        if self.safeguards.is_empty() {
+            blocks.push(Block::Note(tr!(locale, "dpia.no_safeguards")));
+        } else {
+            blocks.push(Block::Table(
+                vec![
+                    tr!(locale, "dpia.column.safeguard"),
+                    tr!(locale, "dpia.column.functions"),
+                ],
+                self.safeguards
+                    .iter()
+                    .map(|(safeguard, functions)| vec![safeguard.clone(), functions.join(", ")])
+                    .collect(),
+            ));
+        }
+        let mut pending: Vec<String> = Vec::new();
+        for suggestion in self.risks.iter().filter_map(|v| locale.suggestion(v)) {
+            if !pending.contains(&suggestion) {
+                pending.push(suggestion);
+            }
+        }
+        blocks.push(Block::Fields(vec![
+            (
+                tr!(locale, "dpia.pending_measures"),
+                if pending.is_empty() {
+                    "-".to_string()
+                } else {
+                    pending.join(" ")
+                },
+            ),
+            (tr!(locale, "dpia.organizational_measures"), todo()),
+            (tr!(locale, "dpia.incident_response"), todo()),
+        ]));
+        blocks.push(Block::Rule);
+
+        // 6. Aprovação e conclusão
+        blocks.push(Block::Heading(2, tr!(locale, "dpia.section.approval")));
+        blocks.push(Block::Fields(vec![
+            (tr!(locale, "dpia.responsible"), todo()),
+            (tr!(locale, "dpia.approval_date"), todo()),
+            (tr!(locale, "dpia.conclusion"), todo()),
+        ]));
+        blocks
+    }
+}
+
+/// Elementos do relatório, comuns ao Markdown e ao HTML.
+enum Block {
+    Heading(usize, String),
+    Note(String),
+    /// Lista de `rótulo: valor`.
+    Fields(Vec<(String, String)>),
+    Table(Vec<String>, Vec<Vec<String>>),
+    Rule,
+}
+
+fn location(func: &CheckedFunction) -> String {
+    match &func.file {
+        Some(file) => format!("{}:{}", file, func.line),
+        None => func.line.to_string(),
+    }
+}
+
+fn violation_location(violation: &ComplianceViolation) -> String {
+    let function = violation.function.as_deref().unwrap_or("-");
+    match (&violation.file, violation.line) {
+        (Some(file), Some(line)) => format!("{}, {}:{}", function, file, line),
+        (None, Some(line)) => format!("{}, {}", function, line),
+        _ => function.to_string(),
+    }
+}
+
+/// Impacto sobre os titulares, pela severidade da regra.
+fn impact(severity: &RuleSeverity, locale: Locale) -> String {
+    match severity {
+        RuleSeverity::Critical | RuleSeverity::High => tr!(locale, "dpia.impact.high"),
+        RuleSeverity::Medium => tr!(locale, "dpia.impact.medium"),
+        RuleSeverity::Low | RuleSeverity::Info => tr!(locale, "dpia.impact.low"),
+    }
+}
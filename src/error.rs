@@ -0,0 +1,404 @@
+//! Tipo de erro unificado da biblioteca Hubstry-ISO_Code.
+//!
+//! Todas as operações públicas que podem falhar retornam `HubstryError`, cujas
+//! variantes agrupam as falhas por área (configuração, parsing, avaliação de
+//! regras, busca web, licenciamento, relatórios, histórico e servidor de
+//! linguagem). Cada erro possui um código estável (`HUB-<ÁREA>-<NNN>`) que pode
+//! ser usado por integrações, além do código de saída correspondente na CLI.
+//!
+//! Os textos ficam nos catálogos (`error.*` em `locales/*.yml`): `message` traduz o
+//! erro para um idioma, e o `Display` usa o idioma padrão.
+
+use crate::i18n::{Locale, Message};
+use crate::tr;
+use std::fmt;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Atalho para resultados da biblioteca.
+pub type Result<T> = std::result::Result<T, HubstryError>;
+
+/// Erro raiz da biblioteca.
+#[derive(Debug, Error)]
+pub enum HubstryError {
+    Config(#[from] ConfigError),
+    Parse(#[from] ParseError),
+    Rule(#[from] RuleError),
+    Fetch(#[from] FetchError),
+    License(#[from] LicenseError),
+    Report(#[from] ReportError),
+    History(#[from] HistoryError),
+    Lsp(#[from] LspError),
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Falhas ao carregar arquivos de configuração (ex.: `prefixes.yml`).
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    Yaml {
+        path: PathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
+    Invalid {
+        path: PathBuf,
+        message: Message,
+    },
+}
+
+/// Falhas ao transformar código-fonte em AST.
+#[derive(Debug, Error)]
+pub enum ParseError {
+    Syntax {
+        path: String,
+        #[source]
+        source: syn::Error,
+    },
+}
+
+/// Falhas durante a avaliação das regras de conformidade.
+#[derive(Debug, Error)]
+pub enum RuleError {
+    Invalid {
+        rule_id: String,
+        message: Message,
+    },
+    Checksum {
+        pack: String,
+        declared: String,
+        actual: String,
+    },
+    UnknownPack {
+        name: String,
+    },
+    UnknownRule {
+        rule_id: String,
+    },
+}
+
+/// Falhas ao buscar páginas para o Web Scanner.
+#[derive(Debug, Error)]
+pub enum FetchError {
+    Http {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+}
+
+/// Falhas de licenciamento.
+#[derive(Debug, Error)]
+pub enum LicenseError {
+    FeatureUnavailable { feature: String, tier: String },
+}
+
+/// Falhas ao gerar relatórios.
+#[derive(Debug, Error)]
+pub enum ReportError {
+    /// Falha do serializador (`message` vem da biblioteca de serialização).
+    Serialize {
+        format: &'static str,
+        message: String,
+    },
+    UnsupportedFormat {
+        format: String,
+        command: &'static str,
+    },
+    Unverifiable {
+        message: Message,
+    },
+    Read {
+        path: PathBuf,
+        message: String,
+    },
+    SigningKey {
+        path: PathBuf,
+        message: Message,
+    },
+    /// Falha ao embutir a assinatura no PDF.
+    Signing {
+        message: Message,
+    },
+}
+
+/// Falhas no histórico local de análises (`.hubstry/history`).
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    Database {
+        path: PathBuf,
+        #[source]
+        source: rusqlite::Error,
+    },
+}
+
+/// Falhas de comunicação do servidor de linguagem (`hubstry lsp`) com o editor.
+#[derive(Debug, Error)]
+pub enum LspError {
+    Protocol { message: String },
+}
+
+impl ConfigError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            ConfigError::Io { path, source } => tr!(
+                locale,
+                "error.config.io",
+                path = path.display(),
+                source = source
+            ),
+            ConfigError::Yaml { path, source } => tr!(
+                locale,
+                "error.config.yaml",
+                path = path.display(),
+                source = source
+            ),
+            ConfigError::Invalid { path, message } => tr!(
+                locale,
+                "error.config.invalid",
+                path = path.display(),
+                message = message.text(locale)
+            ),
+        }
+    }
+}
+
+impl ParseError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            ParseError::Syntax { path, source } => {
+                tr!(locale, "error.parse.syntax", path = path, source = source)
+            }
+        }
+    }
+}
+
+impl RuleError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            RuleError::Invalid { rule_id, message } => tr!(
+                locale,
+                "error.rule.invalid",
+                rule_id = rule_id,
+                message = message.text(locale)
+            ),
+            RuleError::Checksum {
+                pack,
+                declared,
+                actual,
+            } => tr!(
+                locale,
+                "error.rule.checksum",
+                pack = pack,
+                declared = declared,
+                actual = actual
+            ),
+            RuleError::UnknownPack { name } => tr!(locale, "error.rule.unknown_pack", name = name),
+            RuleError::UnknownRule { rule_id } => {
+                tr!(locale, "error.rule.unknown_rule", rule_id = rule_id)
+            }
+        }
+    }
+}
+
+impl FetchError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            FetchError::Http { url, source } => {
+                tr!(locale, "error.fetch.http", url = url, source = source)
+            }
+        }
+    }
+}
+
+impl LicenseError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            LicenseError::FeatureUnavailable { feature, tier } => tr!(
+                locale,
+                "error.license.feature_unavailable",
+                feature = feature,
+                tier = tier
+            ),
+        }
+    }
+}
+
+impl ReportError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            ReportError::Serialize { format, message } => tr!(
+                locale,
+                "error.report.serialize",
+                format = format,
+                message = message
+            ),
+            ReportError::UnsupportedFormat { format, command } => tr!(
+                locale,
+                "error.report.unsupported_format",
+                format = format,
+                command = command
+            ),
+            ReportError::Unverifiable { message } => tr!(
+                locale,
+                "error.report.unverifiable",
+                message = message.text(locale)
+            ),
+            ReportError::Read { path, message } => tr!(
+                locale,
+                "error.report.read",
+                path = path.display(),
+                message = message
+            ),
+            ReportError::SigningKey { path, message } => tr!(
+                locale,
+                "error.report.signing_key",
+                path = path.display(),
+                message = message.text(locale)
+            ),
+            ReportError::Signing { message } => tr!(
+                locale,
+                "error.report.signing",
+                message = message.text(locale)
+            ),
+        }
+    }
+}
+
+impl HistoryError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            HistoryError::Database { path, source } => tr!(
+                locale,
+                "error.history.database",
+                path = path.display(),
+                source = source
+            ),
+        }
+    }
+}
+
+impl LspError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            LspError::Protocol { message } => {
+                tr!(locale, "error.lsp.protocol", message = message)
+            }
+        }
+    }
+}
+
+/// `Display` no idioma padrão, a partir de `message`.
+macro_rules! display_in_default_locale {
+    ($($error:ty),+) => {
+        $(impl fmt::Display for $error {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.message(Locale::default()))
+            }
+        })+
+    };
+}
+
+display_in_default_locale!(
+    HubstryError,
+    ConfigError,
+    ParseError,
+    RuleError,
+    FetchError,
+    LicenseError,
+    ReportError,
+    HistoryError,
+    LspError
+);
+
+impl From<serde_json::Error> for ReportError {
+    fn from(err: serde_json::Error) -> Self {
+        ReportError::Serialize {
+            format: "JSON",
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for HubstryError {
+    fn from(err: serde_json::Error) -> Self {
+        HubstryError::Report(err.into())
+    }
+}
+
+impl HubstryError {
+    /// Código estável do erro, independente da mensagem (que pode mudar).
+    pub fn code(&self) -> &'static str {
+        match self {
+            HubstryError::Config(ConfigError::Io { .. }) => "HUB-CFG-001",
+            HubstryError::Config(ConfigError::Yaml { .. }) => "HUB-CFG-002",
+            HubstryError::Config(ConfigError::Invalid { .. }) => "HUB-CFG-003",
+            HubstryError::Parse(ParseError::Syntax { .. }) => "HUB-PARSE-001",
+            HubstryError::Rule(RuleError::Invalid { .. }) => "HUB-RULE-001",
+            HubstryError::Rule(RuleError::Checksum { .. }) => "HUB-RULE-002",
+            HubstryError::Rule(RuleError::UnknownPack { .. }) => "HUB-RULE-003",
+            HubstryError::Rule(RuleError::UnknownRule { .. }) => "HUB-RULE-004",
+            HubstryError::Fetch(FetchError::Http { .. }) => "HUB-FETCH-001",
+            HubstryError::License(LicenseError::FeatureUnavailable { .. }) => "HUB-LIC-001",
+            HubstryError::Report(ReportError::Serialize { .. }) => "HUB-REPORT-001",
+            HubstryError::Report(ReportError::UnsupportedFormat { .. }) => "HUB-REPORT-002",
+            HubstryError::Report(ReportError::Unverifiable { .. }) => "HUB-REPORT-003",
+            HubstryError::Report(ReportError::Read { .. }) => "HUB-REPORT-004",
+            HubstryError::Report(ReportError::SigningKey { .. }) => "HUB-REPORT-005",
+            HubstryError::Report(ReportError::Signing { .. }) => "HUB-REPORT-006",
+            HubstryError::History(HistoryError::Database { .. }) => "HUB-HIST-001",
+            HubstryError::Lsp(LspError::Protocol { .. }) => "HUB-LSP-001",
+            HubstryError::Io { .. } => "HUB-IO-001",
+        }
+    }
+
+    /// Mensagem do erro no idioma `locale`.
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            HubstryError::Config(err) => err.message(locale),
+            HubstryError::Parse(err) => err.message(locale),
+            HubstryError::Rule(err) => err.message(locale),
+            HubstryError::Fetch(err) => err.message(locale),
+            HubstryError::License(err) => err.message(locale),
+            HubstryError::Report(err) => err.message(locale),
+            HubstryError::History(err) => err.message(locale),
+            HubstryError::Lsp(err) => err.message(locale),
+            HubstryError::Io { path, source } => {
+                tr!(locale, "error.io", path = path.display(), source = source)
+            }
+        }
+    }
+
+    /// Código de saída da CLI para este erro.
+    ///
+    /// O código `1` é reservado para "análise concluída abaixo do limite mínimo".
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            HubstryError::Config(_) => 2,
+            HubstryError::Parse(_) => 3,
+            HubstryError::Rule(_) => 4,
+            HubstryError::Fetch(_) => 5,
+            HubstryError::License(_) => 6,
+            HubstryError::Io { .. } => 7,
+            HubstryError::Report(_) => 8,
+            HubstryError::History(_) => 9,
+            HubstryError::Lsp(_) => 10,
+        }
+    }
+
+    /// Cria um erro de I/O associado a um caminho.
+    pub fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        HubstryError::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
@@ -0,0 +1,454 @@
+//! Correções automáticas (`hubstry fix`).
+//!
+//! Uma correção é uma lista de [`TextEdit`]s sobre o arquivo da violação. As
+//! regras de código declaram a sua em `fix:` no pacote (ver
+//! [`crate::rules::CodeFix`]): uma instrução inserida no início do corpo da função
+//! (`guard`) e as chamadas que fazem uma função sem anotação receber a anotação
+//! do prefixo (`annotate_callers_of`). A regra web de rastreadores envolve cada
+//! script num `<template data-consent="marketing">`.
+//!
+//! As edições só inserem texto, com a indentação e o fim de linha do arquivo, de
+//! modo que o restante da formatação é preservado.
+
+use crate::ast::{FileAst, FunctionAst};
+use crate::i18n::Locale;
+use crate::models::{ComplianceViolation, Fix, Jurisdiction, TextEdit};
+use crate::prefix_manager::PrefixRegistry;
+use crate::rules::CodeRule;
+use crate::scanner::{ad_tracker_detector, WebViolation};
+use crate::tr;
+use regex::Regex;
+use std::fmt::Write;
+
+/// Atributos do `<template>` que desativa um script até o consentimento.
+pub const CONSENT_GATE: &str = r#"<template data-consent="marketing">"#;
+/// Linhas de contexto ao redor de cada alteração no diff.
+pub const DIFF_CONTEXT: usize = 3;
+
+/// Uma correção a aplicar num arquivo, com a descrição no idioma da CLI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedFix {
+    pub file: String,
+    pub rule_id: String,
+    pub function: Option<String>,
+    pub description: String,
+    pub edits: Vec<TextEdit>,
+}
+
+impl PlannedFix {
+    /// A correção de uma violação de código, se ela tem uma e se o arquivo é conhecido.
+    pub fn from_violation(violation: &ComplianceViolation, locale: Locale) -> Option<Self> {
+        let fix = violation.fix.as_ref()?;
+        Some(Self {
+            file: violation.file.clone()?,
+            rule_id: violation.rule_id.clone(),
+            function: violation.function.clone(),
+            description: locale
+                .rule_text(&violation.rule_id, "fix")
+                .map(str::to_string)
+                .unwrap_or_else(|| fix.description.clone()),
+            edits: fix.edits.clone(),
+        })
+    }
+
+    /// A correção de uma violação do Web Scanner, se ela tem uma. O arquivo é o
+    /// endereço da página.
+    pub fn from_web_violation(violation: &WebViolation) -> Option<Self> {
+        let fix = violation.fix.as_ref()?;
+        Some(Self {
+            file: violation.page_url.clone(),
+            rule_id: violation.rule_id.clone(),
+            function: None,
+            description: fix.description.clone(),
+            edits: fix.edits.clone(),
+        })
+    }
+}
+
+/// A correção de `rule` para uma violação em `func`: a instrução `guard` no início
+/// do corpo. `None` quando a regra não tem `guard`.
+pub fn code_fix(rule: &CodeRule, source: &str, func: &FunctionAst) -> Option<Fix> {
+    let guard = rule.fix.as_ref()?.guard.as_ref()?;
+    Some(Fix {
+        description: rule.fix.as_ref()?.description.clone(),
+        edits: vec![insert_guard(source, func, guard)],
+    })
+}
+
+/// Insere `statement` como primeira instrução do corpo de `func`: numa linha
+/// própria, com a indentação da primeira linha do corpo, ou logo após a chave
+/// quando o corpo está na mesma linha.
+pub fn insert_guard(source: &str, func: &FunctionAst, statement: &str) -> TextEdit {
+    let lines: Vec<&str> = source.lines().collect();
+    let line = |number: usize| lines.get(number.wrapping_sub(1)).copied().unwrap_or("");
+    let brace_line = line(func.body_line);
+    let rest: String = brace_line.chars().skip(func.body_column + 1).collect();
+    let rest = rest.trim();
+    if !rest.is_empty() && !rest.starts_with("//") {
+        return TextEdit::insert(
+            func.body_line,
+            func.body_column + 1,
+            format!(" {}", statement),
+        );
+    }
+
+    let first = ((func.body_line + 1)..=func.end_line)
+        .map(line)
+        .find(|text| !text.trim().is_empty());
+    let indent = match first {
+        Some(text) if !text.trim_start().starts_with('}') => indentation(text).to_string(),
+        _ => format!("{}    ", indentation(line(func.line))),
+    };
+    TextEdit::insert(
+        func.body_line,
+        brace_line.chars().count(),
+        format!("{}{}{}", line_ending(source), indent, statement),
+    )
+}
+
+/// Acrescenta a anotação `/// {prefix}: {description}` acima de `func`, com a
+/// mesma indentação.
+pub fn insert_annotation(
+    source: &str,
+    func: &FunctionAst,
+    prefix: &str,
+    description: &str,
+) -> TextEdit {
+    insert_doc_comment(source, func, &format!("{}: {}", prefix, description))
+}
+
+/// Acrescenta acima de `func` uma supressão da regra (ver [`crate::suppression`]),
+/// com a justificativa e o aprovador em branco para o desenvolvedor preencher.
+pub fn insert_suppression(source: &str, func: &FunctionAst, rule_id: &str) -> TextEdit {
+    insert_doc_comment(
+        source,
+        func,
+        &format!(
+            "{}({}) reason = \"\" approver = \"\"",
+            crate::suppression::MARKER,
+            rule_id
+        ),
+    )
+}
+
+/// Acrescenta o doc comment `/// {text}` acima de `func`, com a mesma indentação.
+fn insert_doc_comment(source: &str, func: &FunctionAst, text: &str) -> TextEdit {
+    let line = source
+        .lines()
+        .nth(func.line.saturating_sub(1))
+        .unwrap_or("");
+    TextEdit::insert(
+        func.line,
+        0,
+        format!("{}/// {}{}", indentation(line), text, line_ending(source)),
+    )
+}
+
+/// Anotações que faltam: as funções sem a anotação do prefixo de uma regra que
+/// chamam alguma das funções em `annotate_callers_of`. A anotação usa a descrição
+/// do prefixo no registro; regras de prefixos fora do registro ou de jurisdições
+/// desabilitadas são ignoradas.
+pub fn annotations<'a>(
+    files: &[FileAst],
+    rules: impl IntoIterator<Item = &'a CodeRule>,
+    registry: &PrefixRegistry,
+    enabled: &[Jurisdiction],
+    locale: Locale,
+) -> Vec<PlannedFix> {
+    let rules: Vec<&CodeRule> = rules
+        .into_iter()
+        .filter(|rule| {
+            rule.fix
+                .as_ref()
+                .is_some_and(|fix| !fix.annotate_callers_of.is_empty())
+                && enabled.contains(&rule.jurisdiction)
+        })
+        .collect();
+    let mut fixes: Vec<PlannedFix> = Vec::new();
+    for file in files.iter().filter(|file| !file.path.is_empty()) {
+        for func in &file.functions {
+            for rule in &rules {
+                let Some(info) = registry.get(&rule.prefix) else {
+                    continue;
+                };
+                let callers_of = &rule
+                    .fix
+                    .as_ref()
+                    .expect("filtrada acima")
+                    .annotate_callers_of;
+                let annotated = func.annotations().any(|prefix| prefix == rule.prefix);
+                let planned = fixes.iter().any(|fix| {
+                    fix.file == file.path
+                        && fix.function.as_deref() == Some(func.name.as_str())
+                        && fix.edits[0]
+                            .new_text
+                            .contains(&format!("/// {}:", rule.prefix))
+                });
+                if annotated
+                    || planned
+                    || !func
+                        .called_functions
+                        .iter()
+                        .any(|call| callers_of.contains(call))
+                {
+                    continue;
+                }
+                fixes.push(PlannedFix {
+                    file: file.path.clone(),
+                    rule_id: rule.id.clone(),
+                    function: Some(func.name.clone()),
+                    description: tr!(locale, "fix.annotate", prefix = rule.prefix),
+                    edits: vec![insert_annotation(
+                        &file.source,
+                        func,
+                        &rule.prefix,
+                        &info.description,
+                    )],
+                });
+            }
+        }
+    }
+    fixes
+}
+
+/// Envolve cada script de rastreamento de `html` (ver
+/// [`ad_tracker_detector::is_tracker`]) que ainda não está num `<template>` com
+/// [`CONSENT_GATE`].
+pub fn consent_gate(html: &str) -> Vec<TextEdit> {
+    let script =
+        Regex::new(r#"(?is)<script\b[^>]*?\bsrc\s*=\s*["']?([^"'\s>]+)[^>]*>.*?</script\s*>"#)
+            .expect("padrão válido");
+    let mut edits = Vec::new();
+    for found in script.captures_iter(html) {
+        let whole = found.get(0).expect("grupo 0");
+        if !ad_tracker_detector::is_tracker(&found[1]) || inside_template(&html[..whole.start()]) {
+            continue;
+        }
+        let (line, column) = position(html, whole.start());
+        edits.push(TextEdit::insert(line, column, CONSENT_GATE));
+        let (line, column) = position(html, whole.end());
+        edits.push(TextEdit::insert(line, column, "</template>"));
+    }
+    edits
+}
+
+/// Se a posição que segue `before` está dentro de um `<template>` aberto.
+fn inside_template(before: &str) -> bool {
+    let before = before.to_lowercase();
+    match (before.rfind("<template"), before.rfind("</template")) {
+        (Some(open), Some(close)) => open > close,
+        (open, _) => open.is_some(),
+    }
+}
+
+/// Aplica as edições a `source`. Edições que se sobrepõem a outra já aplicada, ou
+/// repetidas, são ignoradas. Devolve o texto e o número de edições aplicadas.
+pub fn apply(source: &str, edits: &[TextEdit]) -> (String, usize) {
+    let mut ranges: Vec<(usize, usize, usize, &str)> = edits
+        .iter()
+        .enumerate()
+        .filter_map(|(index, edit)| {
+            let start = offset(source, edit.line, edit.column)?;
+            let end = offset(source, edit.end_line, edit.end_column)?;
+            (start <= end).then_some((start, end, index, edit.new_text.as_str()))
+        })
+        .collect();
+    // Da última para a primeira, para que os deslocamentos continuem válidos.
+    // Inserções na mesma posição ficam na ordem recebida.
+    ranges.sort_by_key(|&(start, _, index, _)| std::cmp::Reverse((start, index)));
+    let mut applied: Vec<(usize, usize, &str)> = Vec::new();
+    let mut result = source.to_string();
+    for (start, end, _, text) in ranges {
+        let clashes = applied.iter().any(|&(s, e, t)| {
+            (s, e, t) == (start, end, text) || (start < e && s < end) || (s < start && start < e)
+        });
+        if clashes {
+            continue;
+        }
+        result.replace_range(start..end, text);
+        applied.push((start, end, text));
+    }
+    (result, applied.len())
+}
+
+/// Diff unificado entre duas versões de um arquivo, com [`DIFF_CONTEXT`] linhas de
+/// contexto. Vazio quando não há diferença.
+pub fn unified_diff(path: &str, before: &str, after: &str) -> String {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let ops = line_diff(&old, &new);
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, Op::Equal(..)))
+        .map(|(index, _)| index)
+        .collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    // Caminhos relativos levam os prefixos `a/` e `b/`, como no `git diff`.
+    let mut out = if path.starts_with('/') {
+        format!("--- {}\n+++ {}\n", path, path)
+    } else {
+        format!("--- a/{}\n+++ b/{}\n", path, path)
+    };
+    let mut group_start = 0;
+    while group_start < changes.len() {
+        let mut group_end = group_start;
+        while group_end + 1 < changes.len()
+            && changes[group_end + 1] - changes[group_end] <= 2 * DIFF_CONTEXT + 1
+        {
+            group_end += 1;
+        }
+        let first = changes[group_start].saturating_sub(DIFF_CONTEXT);
+        let last = (changes[group_end] + DIFF_CONTEXT).min(ops.len() - 1);
+        let hunk = &ops[first..=last];
+
+        // Posição de cada lado no início do trecho.
+        let (old_line, new_line) = ops[..first].iter().fold((0, 0), |(o, n), op| match op {
+            Op::Equal(..) => (o + 1, n + 1),
+            Op::Delete(_) => (o + 1, n),
+            Op::Insert(_) => (o, n + 1),
+        });
+        let old_len = hunk
+            .iter()
+            .filter(|op| !matches!(op, Op::Insert(_)))
+            .count();
+        let new_len = hunk
+            .iter()
+            .filter(|op| !matches!(op, Op::Delete(_)))
+            .count();
+        let start = |line: usize, len: usize| if len == 0 { line } else { line + 1 };
+        let _ = writeln!(
+            out,
+            "@@ -{},{} +{},{} @@",
+            start(old_line, old_len),
+            old_len,
+            start(new_line, new_len),
+            new_len
+        );
+        for op in hunk {
+            match *op {
+                Op::Equal(o, _) => {
+                    let _ = writeln!(out, " {}", old[o]);
+                }
+                Op::Delete(o) => {
+                    let _ = writeln!(out, "-{}", old[o]);
+                }
+                Op::Insert(n) => {
+                    let _ = writeln!(out, "+{}", new[n]);
+                }
+            }
+        }
+        group_start = group_end + 1;
+    }
+    out
+}
+
+/// Operação do diff de linhas, com os índices nas versões antiga e nova.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Menor sequência de operações que transforma `old` em `new` (algoritmo de Myers).
+fn line_diff(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = n + m;
+    let index = |k: isize| (k + max + 1) as usize;
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    let mut trace = Vec::new();
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                v[index(k + 1)]
+            } else {
+                v[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[index(prev_k)];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            ops.push(Op::Equal(x as usize, y as usize));
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push(Op::Insert(prev_y as usize));
+            } else {
+                ops.push(Op::Delete(prev_x as usize));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.reverse();
+    ops
+}
+
+/// Deslocamento em bytes da linha (a partir de 1) e coluna (em caracteres, a
+/// partir de 0). A coluna pode ser o fim da linha; `None` fora do texto.
+fn offset(source: &str, line: usize, column: usize) -> Option<usize> {
+    let mut start = 0;
+    for _ in 1..line {
+        start += source[start..].find('\n')? + 1;
+    }
+    let text = &source[start..];
+    let text = &text[..text.find('\n').unwrap_or(text.len())];
+    let text = text.strip_suffix('\r').unwrap_or(text);
+    match text.char_indices().nth(column) {
+        Some((byte, _)) => Some(start + byte),
+        None if text.chars().count() == column => Some(start + text.len()),
+        None => None,
+    }
+}
+
+/// Linha (a partir de 1) e coluna (em caracteres, a partir de 0) de um
+/// deslocamento em bytes.
+fn position(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count(),
+    )
+}
+
+fn indentation(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// `\r\n` se o arquivo usa esse fim de linha; senão `\n`.
+fn line_ending(source: &str) -> &'static str {
+    if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
@@ -0,0 +1,484 @@
+//! Exportação do grafo de chamadas do projeto em Graphviz DOT e Mermaid
+//! (`hubstry graph`).
+//!
+//! Cada nó é uma função do projeto ou uma chamada feita por ela, colorido pelo
+//! seu papel de conformidade. O papel de uma chamada vem das palavras-chave de
+//! `prefixes.yml` que ela satisfaz e da categoria das regras do prefixo: as
+//! `expected_calls` das regras `consent` são consentimento, as das regras `age` e
+//! `lootbox` são verificação de idade, as das regras `ads` são publicidade, e as
+//! `data_collection_keywords` são coleta de dados (ou publicidade, nas regras
+//! `ads`). Os caminhos das violações (ver `CallChain`) são destacados.
+
+use crate::ast::FileAst;
+use crate::i18n::Locale;
+use crate::models::ComplianceViolation;
+use crate::prefix_manager::PrefixRegistry;
+use crate::rules::{self, RuleSet};
+use crate::tr;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+/// Cor da borda dos nós e arestas que fazem parte de uma violação.
+const VIOLATION_COLOR: &str = "#e53e3e";
+
+/// Papel de conformidade de um nó. Um nó com vários papéis fica com o primeiro
+/// na ordem desta enumeração.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Role {
+    /// Função anotada com um prefixo conhecido.
+    Annotated,
+    Consent,
+    AgeCheck,
+    Ad,
+    DataCollection,
+    Other,
+}
+
+impl Role {
+    pub const ALL: [Role; 6] = [
+        Role::Annotated,
+        Role::Consent,
+        Role::AgeCheck,
+        Role::Ad,
+        Role::DataCollection,
+        Role::Other,
+    ];
+
+    /// Cor de preenchimento do nó.
+    pub fn color(self) -> &'static str {
+        match self {
+            Role::Annotated => "#bee3f8",
+            Role::Consent => "#c6f6d5",
+            Role::AgeCheck => "#fefcbf",
+            Role::Ad => "#fed7e2",
+            Role::DataCollection => "#feebc8",
+            Role::Other => "#edf2f7",
+        }
+    }
+
+    /// Nome usado nas classes do Mermaid e nas chaves do catálogo.
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::Annotated => "annotated",
+            Role::Consent => "consent",
+            Role::AgeCheck => "age_check",
+            Role::Ad => "ad",
+            Role::DataCollection => "data_collection",
+            Role::Other => "other",
+        }
+    }
+
+    fn label(self, locale: Locale) -> String {
+        locale.text(&format!("graph.role.{}", self.name()), &[])
+    }
+
+    /// Papel de uma palavra-chave de um prefixo cujas regras têm a `category` dada.
+    fn of_keyword(category: &str, expected_call: bool) -> Option<Role> {
+        match (category, expected_call) {
+            ("ads", _) => Some(Role::Ad),
+            ("consent", true) => Some(Role::Consent),
+            ("age" | "lootbox", true) => Some(Role::AgeCheck),
+            (_, false) => Some(Role::DataCollection),
+            _ => None,
+        }
+    }
+}
+
+/// Formato de saída do `hubstry graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+    #[default]
+    Dot,
+    Mermaid,
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(GraphFormat::Dot),
+            "mermaid" | "mmd" => Ok(GraphFormat::Mermaid),
+            _ => Err(format!("formato desconhecido '{}' (use: dot, mermaid)", s)),
+        }
+    }
+}
+
+impl fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Mermaid => "mermaid",
+        })
+    }
+}
+
+/// Uma função ou chamada do grafo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    pub name: String,
+    pub role: Role,
+    /// Prefixos conhecidos com que a função está anotada.
+    pub prefixes: Vec<String>,
+    /// Se a função está definida no projeto (e não é só chamada por ele).
+    pub defined: bool,
+    /// Se o nó está no caminho de alguma violação.
+    pub violating: bool,
+}
+
+/// Grafo de chamadas do projeto com os papéis de conformidade.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComplianceGraph {
+    pub nodes: BTreeMap<String, GraphNode>,
+    /// Arestas `(função, chamada)`.
+    pub edges: BTreeSet<(String, String)>,
+    /// Arestas que fazem parte do caminho de alguma violação.
+    pub violating_edges: BTreeSet<(String, String)>,
+}
+
+impl ComplianceGraph {
+    /// Junta os grafos de chamadas dos arquivos e classifica cada nó com as
+    /// palavras-chave dos prefixos do registro. No modo estrito, as listas de
+    /// fallback não são usadas e as chamadas precisam ter exatamente o nome da
+    /// palavra-chave.
+    pub fn build(
+        files: &[FileAst],
+        registry: &PrefixRegistry,
+        rules: &RuleSet,
+        strict: bool,
+    ) -> Self {
+        let mut graph = ComplianceGraph::default();
+        for func in files.iter().flat_map(|file| &file.functions) {
+            let node = graph.node(&func.name);
+            node.defined = true;
+            for prefix in func.annotations() {
+                if registry.get(prefix).is_some() && !node.prefixes.iter().any(|p| p == prefix) {
+                    node.prefixes.push(prefix.to_string());
+                }
+            }
+            for call in &func.called_functions {
+                graph.node(call);
+                graph.edges.insert((func.name.clone(), call.clone()));
+            }
+        }
+
+        // (palavra-chave, papel) de todos os prefixos com regras.
+        let mut keywords = Vec::new();
+        for prefix_info in registry.iter() {
+            let (expected_calls, data_collection) = rules.keyword_lists(prefix_info, strict);
+            for rule in rules.rules_for(&prefix_info.prefix) {
+                for (list, expected_call) in [(&expected_calls, true), (&data_collection, false)] {
+                    if let Some(role) = Role::of_keyword(&rule.category, expected_call) {
+                        keywords.extend(list.iter().map(|keyword| (keyword.clone(), role)));
+                    }
+                }
+            }
+        }
+        let matching = rules::matching(strict);
+        for node in graph.nodes.values_mut() {
+            node.role = if node.prefixes.is_empty() {
+                keywords
+                    .iter()
+                    .filter(|(keyword, _)| matching.matches(&node.name, keyword))
+                    .map(|&(_, role)| role)
+                    .min()
+                    .unwrap_or(Role::Other)
+            } else {
+                Role::Annotated
+            };
+        }
+        graph
+    }
+
+    fn node(&mut self, name: &str) -> &mut GraphNode {
+        self.nodes
+            .entry(name.to_string())
+            .or_insert_with(|| GraphNode {
+                name: name.to_string(),
+                role: Role::Other,
+                prefixes: Vec::new(),
+                defined: false,
+                violating: false,
+            })
+    }
+
+    /// Destaca os caminhos das violações. Violações sem caminho destacam a função.
+    pub fn highlight(&mut self, violations: &[ComplianceViolation]) {
+        for violation in violations {
+            let path: Vec<&String> = match &violation.call_chain {
+                Some(chain) => chain.path.iter().collect(),
+                None => violation.function.iter().collect(),
+            };
+            for name in &path {
+                if let Some(node) = self.nodes.get_mut(name.as_str()) {
+                    node.violating = true;
+                }
+            }
+            for pair in path.windows(2) {
+                let edge = (pair[0].clone(), pair[1].clone());
+                if self.edges.contains(&edge) {
+                    self.violating_edges.insert(edge);
+                }
+            }
+        }
+    }
+
+    /// Funções anotadas com `prefix` ou com um prefixo abaixo dele (`LGPD` inclui
+    /// `LGPD.CONSENT.REQUIRED`).
+    pub fn annotated_with(&self, prefix: &str) -> Vec<String> {
+        let nested = format!("{}.", prefix);
+        self.nodes
+            .values()
+            .filter(|node| {
+                node.prefixes
+                    .iter()
+                    .any(|p| p == prefix || p.starts_with(&nested))
+            })
+            .map(|node| node.name.clone())
+            .collect()
+    }
+
+    /// Subgrafo alcançável a partir das funções `roots`.
+    pub fn reachable_from(&self, roots: &[String]) -> Self {
+        let mut reached = BTreeSet::new();
+        let mut queue: VecDeque<&str> = roots
+            .iter()
+            .filter(|root| self.nodes.contains_key(root.as_str()))
+            .map(String::as_str)
+            .collect();
+        while let Some(name) = queue.pop_front() {
+            if !reached.insert(name) {
+                continue;
+            }
+            queue.extend(
+                self.edges
+                    .iter()
+                    .filter(|(from, _)| from == name)
+                    .map(|(_, to)| to.as_str()),
+            );
+        }
+        let keep = |(from, to): &&(String, String)| {
+            reached.contains(from.as_str()) && reached.contains(to.as_str())
+        };
+        ComplianceGraph {
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|(name, _)| reached.contains(name.as_str()))
+                .map(|(name, node)| (name.clone(), node.clone()))
+                .collect(),
+            edges: self.edges.iter().filter(keep).cloned().collect(),
+            violating_edges: self.violating_edges.iter().filter(keep).cloned().collect(),
+        }
+    }
+
+    /// Papéis presentes no grafo, na ordem da legenda.
+    fn roles(&self) -> Vec<Role> {
+        Role::ALL
+            .into_iter()
+            .filter(|role| self.nodes.values().any(|node| node.role == *role))
+            .collect()
+    }
+
+    fn has_violations(&self) -> bool {
+        self.nodes.values().any(|node| node.violating)
+    }
+
+    pub fn render(&self, format: GraphFormat, locale: Locale) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(locale),
+            GraphFormat::Mermaid => self.to_mermaid(locale),
+        }
+    }
+
+    /// Grafo em Graphviz DOT, com uma legenda dos papéis presentes.
+    pub fn to_dot(&self, locale: Locale) -> String {
+        let mut out = String::from("digraph compliance {\n");
+        out.push_str("  rankdir=LR;\n");
+        out.push_str("  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n");
+        for node in self.nodes.values() {
+            let mut label = node.name.clone();
+            for prefix in &node.prefixes {
+                label.push('\n');
+                label.push_str(prefix);
+            }
+            let mut attrs = vec![
+                format!("label={}", dot_quote(&label)),
+                format!("fillcolor=\"{}\"", node.role.color()),
+            ];
+            if !node.defined {
+                attrs.push("style=\"rounded,filled,dashed\"".to_string());
+            }
+            if node.violating {
+                attrs.push(format!("color=\"{}\", penwidth=2.5", VIOLATION_COLOR));
+            }
+            let _ = writeln!(out, "  {} [{}];", dot_quote(&node.name), attrs.join(", "));
+        }
+        for edge in &self.edges {
+            let style = if self.violating_edges.contains(edge) {
+                format!(" [color=\"{}\", penwidth=2.5]", VIOLATION_COLOR)
+            } else {
+                String::new()
+            };
+            let _ = writeln!(
+                out,
+                "  {} -> {}{};",
+                dot_quote(&edge.0),
+                dot_quote(&edge.1),
+                style
+            );
+        }
+
+        let roles = self.roles();
+        if roles.is_empty() {
+            out.push_str("}\n");
+            return out;
+        }
+        let _ = writeln!(out, "  subgraph cluster_legend {{");
+        let _ = writeln!(
+            out,
+            "    label={}; style=dashed; fontname=\"Helvetica\";",
+            dot_quote(&tr!(locale, "graph.legend"))
+        );
+        for role in roles {
+            let _ = writeln!(
+                out,
+                "    \"legend:{}\" [label={}, fillcolor=\"{}\"];",
+                role.name(),
+                dot_quote(&role.label(locale)),
+                role.color()
+            );
+        }
+        if self.has_violations() {
+            let _ = writeln!(
+                out,
+                "    \"legend:violation\" [label={}, fillcolor=\"white\", color=\"{}\", penwidth=2.5];",
+                dot_quote(&tr!(locale, "graph.violation")),
+                VIOLATION_COLOR
+            );
+        }
+        out.push_str("  }\n}\n");
+        out
+    }
+
+    /// Grafo em Mermaid (`flowchart`), com uma legenda dos papéis presentes.
+    pub fn to_mermaid(&self, locale: Locale) -> String {
+        let ids: BTreeMap<&str, String> = self
+            .nodes
+            .keys()
+            .enumerate()
+            .map(|(index, name)| (name.as_str(), format!("n{}", index)))
+            .collect();
+        let mut out = String::from("flowchart LR\n");
+        for node in self.nodes.values() {
+            let mut label = mermaid_escape(&node.name);
+            for prefix in &node.prefixes {
+                let _ = write!(label, "<br/>{}", mermaid_escape(prefix));
+            }
+            let _ = writeln!(out, "  {}[\"{}\"]", ids[node.name.as_str()], label);
+        }
+        let mut violating_links = Vec::new();
+        for (index, edge) in self.edges.iter().enumerate() {
+            let arrow = if self.nodes[&edge.1].defined {
+                "-->"
+            } else {
+                "-.->"
+            };
+            let _ = writeln!(
+                out,
+                "  {} {} {}",
+                ids[edge.0.as_str()],
+                arrow,
+                ids[edge.1.as_str()]
+            );
+            if self.violating_edges.contains(edge) {
+                violating_links.push(index.to_string());
+            }
+        }
+
+        let roles = self.roles();
+        let violations = self.has_violations();
+        if roles.is_empty() {
+            return out;
+        }
+        let _ = writeln!(
+            out,
+            "  subgraph legend[\"{}\"]",
+            mermaid_escape(&tr!(locale, "graph.legend"))
+        );
+        for role in &roles {
+            let _ = writeln!(
+                out,
+                "    legend_{}[\"{}\"]:::{}",
+                role.name(),
+                mermaid_escape(&role.label(locale)),
+                role.name()
+            );
+        }
+        if violations {
+            let _ = writeln!(
+                out,
+                "    legend_violation[\"{}\"]:::violation",
+                mermaid_escape(&tr!(locale, "graph.violation"))
+            );
+        }
+        out.push_str("  end\n");
+
+        for role in &roles {
+            let _ = writeln!(
+                out,
+                "  classDef {} fill:{},stroke:#4a5568",
+                role.name(),
+                role.color()
+            );
+            let members: Vec<&str> = self
+                .nodes
+                .values()
+                .filter(|node| node.role == *role)
+                .map(|node| ids[node.name.as_str()].as_str())
+                .collect();
+            let _ = writeln!(out, "  class {} {}", members.join(","), role.name());
+        }
+        if violations {
+            let _ = writeln!(
+                out,
+                "  classDef violation stroke:{},stroke-width:3px",
+                VIOLATION_COLOR
+            );
+            let members: Vec<&str> = self
+                .nodes
+                .values()
+                .filter(|node| node.violating)
+                .map(|node| ids[node.name.as_str()].as_str())
+                .collect();
+            let _ = writeln!(out, "  class {} violation", members.join(","));
+        }
+        if !violating_links.is_empty() {
+            let _ = writeln!(
+                out,
+                "  linkStyle {} stroke:{},stroke-width:3px",
+                violating_links.join(","),
+                VIOLATION_COLOR
+            );
+        }
+        out
+    }
+}
+
+/// Identificador ou rótulo DOT entre aspas.
+fn dot_quote(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        text.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+/// Texto seguro dentro de um rótulo Mermaid entre aspas.
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
@@ -0,0 +1,584 @@
+//! Histórico local das análises e varreduras web (`.hubstry/history`).
+//!
+//! Cada execução de `hubstry analyze` e `hubstry scan` é gravada num banco SQLite
+//! com o score geral, os sub-scores, os metadados e a impressão digital de cada
+//! violação. Execuções do mesmo tipo sobre o mesmo alvo (diretório, arquivo ou URL)
+//! formam uma série, a partir da qual o `hubstry history` calcula:
+//!
+//! - a evolução do score geral e por jurisdição;
+//! - o tempo médio de correção por regra: da primeira execução em que a violação
+//!   aparece até a primeira em que ela deixa de aparecer;
+//! - as violações abertas mais antigas, presentes na última execução da série.
+
+use crate::error::{HistoryError, HubstryError};
+use crate::i18n::Locale;
+use crate::models::AnalysisResult;
+use crate::report::sub_scores;
+use crate::scanner::WebScanResult;
+use crate::scoring::ScoreBreakdown;
+use crate::tr;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Diretório padrão do histórico, relativo ao diretório atual.
+pub const DEFAULT_DIR: &str = ".hubstry/history";
+
+const DATABASE_FILE: &str = "history.db";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    kind TEXT NOT NULL,
+    target TEXT NOT NULL,
+    recorded_at TEXT NOT NULL,
+    score REAL NOT NULL,
+    git_commit TEXT,
+    metadata TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sub_scores (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    grp TEXT NOT NULL,
+    name TEXT NOT NULL,
+    score REAL NOT NULL,
+    obligations INTEGER NOT NULL,
+    failed INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS findings (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    fingerprint TEXT NOT NULL,
+    rule_id TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    location TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS findings_run ON findings(run_id);
+CREATE INDEX IF NOT EXISTS sub_scores_run ON sub_scores(run_id);
+";
+
+/// Tipo de execução gravada.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunKind {
+    Analysis,
+    WebScan,
+}
+
+impl RunKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            RunKind::Analysis => "analysis",
+            RunKind::WebScan => "web_scan",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "web_scan" => RunKind::WebScan,
+            _ => RunKind::Analysis,
+        }
+    }
+}
+
+impl fmt::Display for RunKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Uma execução gravada.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Run {
+    pub id: i64,
+    pub kind: RunKind,
+    /// Diretório ou arquivo analisado, ou a URL da varredura.
+    pub target: String,
+    pub recorded_at: DateTime<Utc>,
+    pub score: f64,
+    pub git_commit: Option<String>,
+    /// Score de cada jurisdição nesta execução.
+    pub by_jurisdiction: BTreeMap<String, f64>,
+    pub violations: usize,
+}
+
+/// Tempo médio de correção das violações de uma regra.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleFixTime {
+    pub rule_id: String,
+    /// Quantas violações da regra foram corrigidas.
+    pub fixed: usize,
+    pub mean_hours: f64,
+}
+
+/// Violação presente na última execução da sua série.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpenViolation {
+    pub kind: RunKind,
+    pub target: String,
+    pub rule_id: String,
+    pub severity: String,
+    pub location: String,
+    /// Início da sequência de execuções em que a violação está presente.
+    pub first_seen: DateTime<Utc>,
+    /// Dias entre `first_seen` e a última execução da série.
+    pub open_days: i64,
+}
+
+/// Resultado do `hubstry history`.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryReport {
+    pub runs: Vec<Run>,
+    pub time_to_fix: Vec<RuleFixTime>,
+    pub oldest_open: Vec<OpenViolation>,
+}
+
+/// Violação como gravada no histórico.
+struct Finding {
+    fingerprint: String,
+    rule_id: String,
+    severity: String,
+    location: String,
+}
+
+/// Banco do histórico.
+pub struct HistoryStore {
+    connection: Connection,
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    /// Abre (ou cria) o banco em `dir/history.db`.
+    pub fn open(dir: &Path) -> Result<Self, HubstryError> {
+        std::fs::create_dir_all(dir).map_err(|e| HubstryError::io(dir, e))?;
+        let path = dir.join(DATABASE_FILE);
+        let connection = Connection::open(&path).map_err(|source| HistoryError::Database {
+            path: path.clone(),
+            source,
+        })?;
+        let store = HistoryStore { connection, path };
+        store
+            .connection
+            .execute_batch(SCHEMA)
+            .map_err(|e| store.error(e))?;
+        Ok(store)
+    }
+
+    /// Caminho do arquivo do banco.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn error(&self, source: rusqlite::Error) -> HubstryError {
+        HistoryError::Database {
+            path: self.path.clone(),
+            source,
+        }
+        .into()
+    }
+
+    /// Grava uma análise de código de `target`.
+    pub fn record_analysis(
+        &mut self,
+        target: &str,
+        result: &AnalysisResult,
+        recorded_at: DateTime<Utc>,
+    ) -> Result<i64, HubstryError> {
+        let findings = result
+            .violations
+            .iter()
+            .map(|violation| {
+                let mut location = violation.file.clone().unwrap_or_default();
+                if let Some(function) = &violation.function {
+                    let _ = write!(location, "::{}", function);
+                }
+                Finding {
+                    fingerprint: violation.fingerprint(),
+                    rule_id: violation.rule_id.clone(),
+                    severity: violation.severity.to_string(),
+                    location,
+                }
+            })
+            .collect::<Vec<_>>();
+        self.insert(
+            RunKind::Analysis,
+            target,
+            recorded_at,
+            &result.score_breakdown,
+            result.metadata.get("git.commit").map(String::as_str),
+            &serde_json::to_string(&result.metadata)?,
+            &findings,
+        )
+    }
+
+    /// Grava uma varredura web; o alvo é a URL.
+    pub fn record_web_scan(
+        &mut self,
+        result: &WebScanResult,
+        recorded_at: DateTime<Utc>,
+    ) -> Result<i64, HubstryError> {
+        let findings = result
+            .violations
+            .iter()
+            .map(|violation| Finding {
+                fingerprint: violation.fingerprint(),
+                rule_id: violation.rule_id.clone(),
+                severity: violation.severity.clone(),
+                location: match &violation.element_selector {
+                    Some(selector) => format!("{} {}", violation.page_url, selector),
+                    None => violation.page_url.clone(),
+                },
+            })
+            .collect::<Vec<_>>();
+        let metadata = serde_json::json!({
+            "scan_date": result.scan_date,
+            "pages_scanned": result.pages_scanned,
+            "elements_analyzed": result.elements_analyzed,
+            "rule_packs": result.rule_packs,
+        });
+        self.insert(
+            RunKind::WebScan,
+            &result.url,
+            recorded_at,
+            &result.score_breakdown,
+            None,
+            &metadata.to_string(),
+            &findings,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn insert(
+        &mut self,
+        kind: RunKind,
+        target: &str,
+        recorded_at: DateTime<Utc>,
+        breakdown: &ScoreBreakdown,
+        git_commit: Option<&str>,
+        metadata: &str,
+        findings: &[Finding],
+    ) -> Result<i64, HubstryError> {
+        let path = self.path.clone();
+        let error = |source| HistoryError::Database {
+            path: path.clone(),
+            source,
+        };
+        let tx = self.connection.transaction().map_err(error)?;
+        tx.execute(
+            "INSERT INTO runs (kind, target, recorded_at, score, git_commit, metadata)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                kind.as_str(),
+                target,
+                recorded_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+                breakdown.score,
+                git_commit,
+                metadata
+            ],
+        )
+        .map_err(error)?;
+        let run_id = tx.last_insert_rowid();
+        for (group, name, score) in sub_scores(breakdown) {
+            tx.execute(
+                "INSERT INTO sub_scores (run_id, grp, name, score, obligations, failed)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    run_id,
+                    group,
+                    name,
+                    score.score,
+                    score.obligations as i64,
+                    score.failed as i64
+                ],
+            )
+            .map_err(error)?;
+        }
+        for finding in findings {
+            tx.execute(
+                "INSERT INTO findings (run_id, fingerprint, rule_id, severity, location)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    run_id,
+                    finding.fingerprint,
+                    finding.rule_id,
+                    finding.severity,
+                    finding.location
+                ],
+            )
+            .map_err(error)?;
+        }
+        tx.commit().map_err(error)?;
+        Ok(run_id)
+    }
+
+    /// Todas as execuções, da mais antiga para a mais recente.
+    pub fn runs(&self) -> Result<Vec<Run>, HubstryError> {
+        let mut statement = self
+            .connection
+            .prepare(
+                "SELECT r.id, r.kind, r.target, r.recorded_at, r.score, r.git_commit,
+                        (SELECT COUNT(*) FROM findings f WHERE f.run_id = r.id)
+                 FROM runs r ORDER BY r.recorded_at, r.id",
+            )
+            .map_err(|e| self.error(e))?;
+        let rows = statement
+            .query_map([], |row| {
+                let recorded_at: String = row.get(3)?;
+                Ok(Run {
+                    id: row.get(0)?,
+                    kind: RunKind::parse(&row.get::<_, String>(1)?),
+                    target: row.get(2)?,
+                    recorded_at: DateTime::parse_from_rfc3339(&recorded_at)
+                        .map(|date| date.with_timezone(&Utc))
+                        .unwrap_or_default(),
+                    score: row.get(4)?,
+                    git_commit: row.get(5)?,
+                    by_jurisdiction: BTreeMap::new(),
+                    violations: row.get::<_, i64>(6)? as usize,
+                })
+            })
+            .map_err(|e| self.error(e))?;
+        let mut runs = rows
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| self.error(e))?;
+
+        let mut statement = self
+            .connection
+            .prepare("SELECT run_id, name, score FROM sub_scores WHERE grp = 'jurisdiction'")
+            .map_err(|e| self.error(e))?;
+        let mut scores: HashMap<i64, BTreeMap<String, f64>> = HashMap::new();
+        let rows = statement
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, f64>(2)?,
+                ))
+            })
+            .map_err(|e| self.error(e))?;
+        for row in rows {
+            let (run_id, name, score) = row.map_err(|e| self.error(e))?;
+            scores.entry(run_id).or_default().insert(name, score);
+        }
+        for run in &mut runs {
+            run.by_jurisdiction = scores.remove(&run.id).unwrap_or_default();
+        }
+        Ok(runs)
+    }
+
+    /// Execuções de uma série, da mais antiga para a mais recente.
+    pub fn trend(&self, kind: RunKind, target: &str) -> Result<Vec<Run>, HubstryError> {
+        Ok(self
+            .runs()?
+            .into_iter()
+            .filter(|run| run.kind == kind && run.target == target)
+            .collect())
+    }
+
+    fn findings(&self) -> Result<HashMap<i64, Vec<Finding>>, HubstryError> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT run_id, fingerprint, rule_id, severity, location FROM findings")
+            .map_err(|e| self.error(e))?;
+        let rows = statement
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    Finding {
+                        fingerprint: row.get(1)?,
+                        rule_id: row.get(2)?,
+                        severity: row.get(3)?,
+                        location: row.get(4)?,
+                    },
+                ))
+            })
+            .map_err(|e| self.error(e))?;
+        let mut findings: HashMap<i64, Vec<Finding>> = HashMap::new();
+        for row in rows {
+            let (run_id, finding) = row.map_err(|e| self.error(e))?;
+            findings.entry(run_id).or_default().push(finding);
+        }
+        Ok(findings)
+    }
+
+    /// Tendências, tempo médio de correção e as `limit` violações abertas mais antigas.
+    pub fn report(&self, limit: usize) -> Result<HistoryReport, HubstryError> {
+        let runs = self.runs()?;
+        let mut findings = self.findings()?;
+
+        let mut series: BTreeMap<(RunKind, &str), Vec<&Run>> = BTreeMap::new();
+        for run in &runs {
+            series
+                .entry((run.kind, run.target.as_str()))
+                .or_default()
+                .push(run);
+        }
+
+        let mut fix_hours: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        let mut oldest_open = Vec::new();
+        for ((kind, target), series_runs) in series {
+            // Violações abertas: impressão digital -> (primeira aparição, violação).
+            let mut open: HashMap<String, (DateTime<Utc>, Finding)> = HashMap::new();
+            for run in &series_runs {
+                let current = findings.remove(&run.id).unwrap_or_default();
+                let present: HashSet<&str> =
+                    current.iter().map(|f| f.fingerprint.as_str()).collect();
+                open.retain(|fingerprint, (first_seen, finding)| {
+                    if present.contains(fingerprint.as_str()) {
+                        return true;
+                    }
+                    let hours = (run.recorded_at - *first_seen).num_seconds() as f64 / 3600.0;
+                    fix_hours
+                        .entry(finding.rule_id.clone())
+                        .or_default()
+                        .push(hours);
+                    false
+                });
+                for finding in current {
+                    open.entry(finding.fingerprint.clone())
+                        .and_modify(|(_, latest)| latest.location = finding.location.clone())
+                        .or_insert((run.recorded_at, finding));
+                }
+            }
+
+            let last = series_runs.last().map(|run| run.recorded_at);
+            for (first_seen, finding) in open.into_values() {
+                oldest_open.push(OpenViolation {
+                    kind,
+                    target: target.to_string(),
+                    rule_id: finding.rule_id,
+                    severity: finding.severity,
+                    location: finding.location,
+                    first_seen,
+                    open_days: last.map_or(0, |last| (last - first_seen).num_days()),
+                });
+            }
+        }
+
+        oldest_open.sort_by(|a, b| {
+            a.first_seen
+                .cmp(&b.first_seen)
+                .then_with(|| a.rule_id.cmp(&b.rule_id))
+                .then_with(|| a.location.cmp(&b.location))
+        });
+        oldest_open.truncate(limit);
+
+        let time_to_fix = fix_hours
+            .into_iter()
+            .map(|(rule_id, hours)| RuleFixTime {
+                rule_id,
+                fixed: hours.len(),
+                mean_hours: (hours.iter().sum::<f64>() / hours.len() as f64 * 10.0).round() / 10.0,
+            })
+            .collect();
+
+        Ok(HistoryReport {
+            runs,
+            time_to_fix,
+            oldest_open,
+        })
+    }
+}
+
+impl HistoryReport {
+    /// Texto para o terminal, no idioma `locale`.
+    pub fn to_text(&self, locale: Locale) -> String {
+        let mut out = String::new();
+        let title = tr!(locale, "history.title");
+        let _ = writeln!(out, "{}\n{}", title, "=".repeat(title.chars().count()));
+
+        if self.runs.is_empty() {
+            let _ = writeln!(out, "\n{}", tr!(locale, "history.empty"));
+            return out;
+        }
+
+        let mut series: BTreeMap<(RunKind, &str), Vec<&Run>> = BTreeMap::new();
+        for run in &self.runs {
+            series
+                .entry((run.kind, run.target.as_str()))
+                .or_default()
+                .push(run);
+        }
+        for ((kind, target), runs) in series {
+            let _ = writeln!(
+                out,
+                "\n{}",
+                tr!(
+                    locale,
+                    "history.trend",
+                    kind = kind,
+                    target = target,
+                    count = runs.len()
+                )
+            );
+            for run in runs {
+                let jurisdictions: Vec<String> = run
+                    .by_jurisdiction
+                    .iter()
+                    .map(|(name, score)| format!("{} {:.1}%", name, score))
+                    .collect();
+                let _ = writeln!(
+                    out,
+                    "  {}  {:>6.1}%  {}  {}{}",
+                    run.recorded_at.format("%Y-%m-%d %H:%M"),
+                    run.score,
+                    tr!(
+                        locale,
+                        "history.violations",
+                        count = format!("{:>3}", run.violations)
+                    ),
+                    jurisdictions.join(", "),
+                    run.git_commit
+                        .as_deref()
+                        .map(|commit| format!("  [{}]", commit.chars().take(8).collect::<String>()))
+                        .unwrap_or_default()
+                );
+            }
+        }
+
+        let _ = writeln!(out, "\n{}:", tr!(locale, "history.time_to_fix"));
+        if self.time_to_fix.is_empty() {
+            let _ = writeln!(out, "  {}", tr!(locale, "history.none_fixed"));
+        }
+        for rule in &self.time_to_fix {
+            let _ = writeln!(
+                out,
+                "  {:<28} {} ({})",
+                rule.rule_id,
+                format_hours(rule.mean_hours),
+                tr!(locale, "history.fixed", count = rule.fixed)
+            );
+        }
+
+        let _ = writeln!(out, "\n{}:", tr!(locale, "history.oldest_open"));
+        if self.oldest_open.is_empty() {
+            let _ = writeln!(out, "  {}", tr!(locale, "history.none_open"));
+        }
+        for violation in &self.oldest_open {
+            let _ = writeln!(
+                out,
+                "  {}  {}  [{}] {} {}",
+                violation.first_seen.format("%Y-%m-%d"),
+                tr!(
+                    locale,
+                    "history.open_days",
+                    days = format!("{:>4}", violation.open_days)
+                ),
+                violation.severity,
+                violation.rule_id,
+                tr!(locale, "report.at", location = violation.location)
+            );
+        }
+        out
+    }
+}
+
+/// `5.0h` ou `3.2d`.
+fn format_hours(hours: f64) -> String {
+    if hours < 48.0 {
+        format!("{:.1}h", hours)
+    } else {
+        format!("{:.1}d", hours / 24.0)
+    }
+}
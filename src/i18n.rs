@@ -0,0 +1,255 @@
+//! Catálogos de mensagens (pt-BR, en, es).
+//!
+//! Os textos exibidos ao usuário ficam em `locales/<locale>.yml`, embutidos no
+//! binário. A seção `messages` guarda os textos dos relatórios e da CLI, com
+//! argumentos `{nome}`; a seção `rules`, os textos de cada regra, pela id.
+//!
+//! As violações guardam o texto do pacote de regras, que entra na impressão digital
+//! e nos relatórios JSON e YAML. Os relatórios para pessoas traduzem esse texto ao
+//! renderizar, de modo que uma mesma análise gera relatórios em vários idiomas.
+//! Uma mensagem ausente de um catálogo cai para o inglês; o texto de uma regra
+//! ausente cai para o texto do pacote (regras personalizadas e pacotes de terceiros).
+
+use crate::models::ComplianceViolation;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Traduz uma mensagem do catálogo: `tr!(locale, "report.title")` ou
+/// `tr!(locale, "cli.report_written", format = format, path = path.display())`.
+#[macro_export]
+macro_rules! tr {
+    ($locale:expr, $key:expr) => {
+        $locale.text($key, &[])
+    };
+    ($locale:expr, $key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $locale.text($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+/// Monta uma [`Message`]: `msg!("error.rule.unknown_pack", name = name)`.
+#[macro_export]
+macro_rules! msg {
+    ($key:expr) => {
+        $crate::i18n::Message::new($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::Message::new($key)$(.arg(stringify!($name), &$value))+
+    };
+}
+
+/// Idioma dos relatórios e da CLI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    #[default]
+    PtBr,
+    En,
+    Es,
+}
+
+impl Locale {
+    /// Todos os idiomas com catálogo.
+    pub const ALL: [Locale; 3] = [Locale::PtBr, Locale::En, Locale::Es];
+
+    /// Código BCP 47, ex.: `pt-BR`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::PtBr => "pt-BR",
+            Locale::En => "en",
+            Locale::Es => "es",
+        }
+    }
+
+    fn catalog(self) -> &'static Catalog {
+        static CATALOGS: OnceLock<[Catalog; 3]> = OnceLock::new();
+        let catalogs = CATALOGS.get_or_init(|| {
+            [
+                Catalog::parse(include_str!("../locales/pt-BR.yml"), Locale::PtBr),
+                Catalog::parse(include_str!("../locales/en.yml"), Locale::En),
+                Catalog::parse(include_str!("../locales/es.yml"), Locale::Es),
+            ]
+        });
+        match self {
+            Locale::PtBr => &catalogs[0],
+            Locale::En => &catalogs[1],
+            Locale::Es => &catalogs[2],
+        }
+    }
+
+    /// Mensagem `key` com os argumentos `{nome}` substituídos. Sem tradução, usa o
+    /// inglês; sem nenhuma, devolve a própria chave.
+    pub fn text(self, key: &str, args: &[(&str, String)]) -> String {
+        let template = self
+            .catalog()
+            .messages
+            .get(key)
+            .or_else(|| Locale::En.catalog().messages.get(key));
+        match template {
+            Some(template) => fill(template, args),
+            None => key.to_string(),
+        }
+    }
+
+    /// Campo `field` (ex.: `message`, `business_description`) da regra `rule_id`.
+    pub fn rule_text(self, rule_id: &str, field: &str) -> Option<&'static str> {
+        self.catalog()
+            .rules
+            .get(rule_id)
+            .and_then(|texts| texts.get(field))
+            .map(String::as_str)
+    }
+
+    /// Mensagem da violação neste idioma, com o trecho encontrado por uma regra
+    /// personalizada (`args.matched`) quando o catálogo não traduz a regra.
+    pub fn message(self, violation: &ComplianceViolation) -> String {
+        if let Some(text) = self.violation_text(violation, "message") {
+            return text;
+        }
+        match violation.args.get("matched") {
+            Some(matched) => tr!(
+                self,
+                "violation.matched",
+                message = violation.message,
+                matched = matched
+            ),
+            None => violation.message.clone(),
+        }
+    }
+
+    /// Sugestão de correção da violação neste idioma.
+    pub fn suggestion(self, violation: &ComplianceViolation) -> Option<String> {
+        self.violation_text(violation, "suggestion")
+            .or_else(|| violation.suggestion.clone())
+    }
+
+    fn violation_text(self, violation: &ComplianceViolation, field: &str) -> Option<String> {
+        let template = self.rule_text(&violation.rule_id, field)?;
+        let mut args: Vec<(&str, String)> = violation
+            .args
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .collect();
+        args.push(("function", violation.function.clone().unwrap_or_default()));
+        Some(fill(template, &args))
+    }
+
+    /// Chaves presentes no catálogo inglês (a referência) e ausentes neste, como
+    /// `messages.<chave>` ou `rules.<id>.<campo>`.
+    pub fn missing_keys(self) -> Vec<String> {
+        let reference = Locale::En.catalog();
+        let catalog = self.catalog();
+        let mut missing: Vec<String> = reference
+            .messages
+            .keys()
+            .filter(|key| !catalog.messages.contains_key(*key))
+            .map(|key| format!("messages.{}", key))
+            .collect();
+        for (rule_id, texts) in &reference.rules {
+            for field in texts.keys() {
+                if self.rule_text(rule_id, field).is_none() {
+                    missing.push(format!("rules.{}.{}", rule_id, field));
+                }
+            }
+        }
+        missing
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = Message;
+
+    /// Aceita o código com ou sem região, em qualquer caixa (`pt-BR`, `pt_br`, `pt`,
+    /// `en-US`, `es`).
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let language = value
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "pt" => Ok(Locale::PtBr),
+            "en" => Ok(Locale::En),
+            "es" => Ok(Locale::Es),
+            _ => Err(msg!("error.locale.unsupported", value = value)),
+        }
+    }
+}
+
+/// Mensagem do catálogo com os seus argumentos, traduzida só ao ser exibida. Erros e
+/// avisos criados sem saber o idioma de quem vai lê-los guardam uma `Message` no
+/// lugar do texto pronto; o `Display` usa o idioma padrão.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    key: &'static str,
+    args: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    /// Acrescenta o argumento `{name}`.
+    pub fn arg(mut self, name: &'static str, value: impl fmt::Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    /// Chave da mensagem no catálogo.
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    /// Texto da mensagem no idioma `locale`.
+    pub fn text(&self, locale: Locale) -> String {
+        locale.text(self.key, &self.args)
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text(Locale::default()))
+    }
+}
+
+impl std::error::Error for Message {}
+
+/// Conteúdo de `locales/<locale>.yml`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Catalog {
+    locale: String,
+    #[serde(default)]
+    messages: BTreeMap<String, String>,
+    #[serde(default)]
+    rules: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Catalog {
+    fn parse(content: &str, locale: Locale) -> Self {
+        let catalog: Catalog = serde_yaml::from_str(content)
+            .unwrap_or_else(|e| panic!("os catálogos embutidos devem ser válidos: {}", e));
+        assert_eq!(catalog.locale, locale.code(), "catálogo no arquivo errado");
+        catalog
+    }
+}
+
+/// Substitui cada `{nome}` do modelo pelo argumento correspondente.
+fn fill(template: &str, args: &[(&str, String)]) -> String {
+    let mut text = template.to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
@@ -0,0 +1,219 @@
+//! Registro das operações de tratamento (LGPD art. 37) gerado a partir das
+//! anotações do código (`hubstry inventory`).
+//!
+//! Cada função anotada com um prefixo `LGPD.*` ou `ECA.*` é uma operação. A base
+//! legal e a finalidade, que não podem ser deduzidas do código, são declaradas na
+//! própria função:
+//!
+//! ```text
+//! /// LGPD.DATA.COLLECTION: Cadastro de alunos.
+//! /// hubstry:processing legal_basis = "consentimento (art. 7º, I)" purpose = "matrícula"
+//! ```
+
+use crate::ast::FunctionAst;
+use crate::i18n::Locale;
+use crate::models::AnalysisResult;
+use crate::report::csv_row;
+use crate::tr;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Início de uma declaração de tratamento.
+pub const MARKER: &str = "hubstry:processing";
+
+/// Prefixos cujas funções entram no inventário.
+pub const PREFIXES: [&str; 2] = ["LGPD.", "ECA."];
+
+/// Base legal e finalidade declaradas numa função.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessingDeclaration {
+    pub legal_basis: Option<String>,
+    pub purpose: Option<String>,
+}
+
+/// Interpreta uma declaração de tratamento; `None` se o comentário não for uma.
+pub fn parse(comment: &str) -> Option<ProcessingDeclaration> {
+    let options = comment.trim().strip_prefix(MARKER)?;
+    let option = Regex::new(r#"(\w+)\s*=\s*"([^"]*)""#).expect("padrão válido");
+    let mut declaration = ProcessingDeclaration::default();
+    for caps in option.captures_iter(options) {
+        let value = Some(caps[2].trim().to_string()).filter(|v| !v.is_empty());
+        match &caps[1] {
+            "legal_basis" => declaration.legal_basis = value,
+            "purpose" => declaration.purpose = value,
+            _ => {}
+        }
+    }
+    Some(declaration)
+}
+
+/// Declarações de tratamento de uma função, combinadas; a primeira de cada campo vale.
+pub fn of_function(func: &FunctionAst) -> ProcessingDeclaration {
+    let mut declaration = ProcessingDeclaration::default();
+    for found in func.doc_comments.iter().filter_map(|c| parse(c)) {
+        declaration.legal_basis = declaration.legal_basis.or(found.legal_basis);
+        declaration.purpose = declaration.purpose.or(found.purpose);
+    }
+    declaration
+}
+
+/// Uma operação de tratamento.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InventoryEntry {
+    pub function: String,
+    pub file: Option<String>,
+    pub line: usize,
+    pub prefixes: Vec<String>,
+    pub description: Option<String>,
+    /// Categorias de dados: as `data_collection_keywords` alcançadas pela função.
+    pub data_categories: Vec<String>,
+    pub legal_basis: Option<String>,
+    pub purpose: Option<String>,
+}
+
+impl InventoryEntry {
+    /// `arquivo:linha`.
+    pub fn location(&self) -> String {
+        match &self.file {
+            Some(file) => format!("{}:{}", file, self.line),
+            None => self.line.to_string(),
+        }
+    }
+}
+
+/// O registro das operações de tratamento de um projeto.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Inventory {
+    pub entries: Vec<InventoryEntry>,
+}
+
+impl Inventory {
+    /// Operações das funções verificadas com algum prefixo `LGPD.*` ou `ECA.*`, em
+    /// ordem de arquivo e linha.
+    pub fn from_analysis(result: &AnalysisResult) -> Self {
+        let mut entries: Vec<InventoryEntry> = result
+            .checked_functions
+            .iter()
+            .filter_map(|func| {
+                let prefixes: Vec<String> = func
+                    .prefixes
+                    .iter()
+                    .filter(|prefix| PREFIXES.iter().any(|p| prefix.starts_with(p)))
+                    .cloned()
+                    .collect();
+                (!prefixes.is_empty()).then(|| InventoryEntry {
+                    function: func.name.clone(),
+                    file: func.file.clone(),
+                    line: func.line,
+                    prefixes,
+                    description: func.description.clone(),
+                    data_categories: func.data_collection.clone(),
+                    legal_basis: func.legal_basis.clone(),
+                    purpose: func.purpose.clone(),
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+        Inventory { entries }
+    }
+
+    /// Operações sem base legal declarada.
+    pub fn missing_legal_basis(&self) -> Vec<&InventoryEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.legal_basis.is_none())
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        Ok(format!("{}\n", serde_json::to_string_pretty(self)?))
+    }
+
+    /// CSV com cabeçalho; listas separadas por `; `.
+    pub fn to_csv(&self) -> String {
+        let mut csv = csv_row(&[
+            "file",
+            "line",
+            "function",
+            "prefixes",
+            "description",
+            "data_categories",
+            "legal_basis",
+            "purpose",
+        ]);
+        for entry in &self.entries {
+            csv.push_str(&csv_row(&[
+                entry.file.as_deref().unwrap_or(""),
+                &entry.line.to_string(),
+                &entry.function,
+                &entry.prefixes.join("; "),
+                entry.description.as_deref().unwrap_or(""),
+                &entry.data_categories.join("; "),
+                entry.legal_basis.as_deref().unwrap_or(""),
+                entry.purpose.as_deref().unwrap_or(""),
+            ]));
+        }
+        csv
+    }
+
+    pub fn to_markdown(&self, locale: Locale) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}\n", tr!(locale, "inventory.title"));
+        let _ = writeln!(
+            out,
+            "{}\n",
+            tr!(locale, "inventory.summary", count = self.entries.len())
+        );
+        if self.entries.is_empty() {
+            return out;
+        }
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} | {} |\n|---|---|---|---|---|---|---|",
+            tr!(locale, "inventory.column.function"),
+            tr!(locale, "inventory.column.description"),
+            tr!(locale, "inventory.column.data"),
+            tr!(locale, "inventory.column.legal_basis"),
+            tr!(locale, "inventory.column.purpose"),
+            tr!(locale, "inventory.column.prefixes"),
+            tr!(locale, "report.location")
+        );
+        let missing = tr!(locale, "inventory.not_declared");
+        let cell = |value: Option<&str>| value.unwrap_or(&missing).replace('|', "\\|");
+        for entry in &self.entries {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} | {} | {} | {} |",
+                entry.function,
+                cell(entry.description.as_deref()),
+                if entry.data_categories.is_empty() {
+                    "-".to_string()
+                } else {
+                    entry.data_categories.join(", ")
+                },
+                cell(entry.legal_basis.as_deref()),
+                cell(entry.purpose.as_deref()),
+                entry.prefixes.join(", "),
+                entry.location()
+            );
+        }
+        let missing_basis = self.missing_legal_basis();
+        if !missing_basis.is_empty() {
+            let functions: Vec<String> = missing_basis
+                .iter()
+                .map(|entry| format!("`{}`", entry.function))
+                .collect();
+            let _ = writeln!(
+                out,
+                "\n> {}",
+                tr!(
+                    locale,
+                    "inventory.missing_legal_basis",
+                    functions = functions.join(", ")
+                )
+            );
+        }
+        out
+    }
+}
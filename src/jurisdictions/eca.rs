@@ -1,20 +1,25 @@
-//! Lógica de validação de conformidade para a jurisdição do ECA Digital.
+//! Grafo de chamadas e coleta de chamadas usados pela validação de conformidade.
+//!
+//! As regras do ECA Digital e da LGPD em si são declarativas e ficam nos pacotes
+//! de regras (ver o módulo `rules`).
 
-use crate::models::{ComplianceViolation, RuleSeverity};
-use crate::prefix_manager::PrefixInfo;
-use syn::{visit::Visit, Expr, ExprCall};
+use crate::ast::{CallSite, StringLiteral};
+use syn::{spanned::Spanned, visit::Visit, Expr, ExprCall, Lit};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Um Grafo de Chamadas básico (Call Graph) para funções no mesmo arquivo.
 pub struct CallGraph {
     pub function_calls: HashMap<String, Vec<String>>,
+    /// Chamadas de cada função, em ordem no código, com seus argumentos literais.
+    pub call_sites: HashMap<String, Vec<CallSite>>,
 }
 
 impl CallGraph {
     pub fn new() -> Self {
         CallGraph {
             function_calls: HashMap::new(),
+            call_sites: HashMap::new(),
         }
     }
 }
@@ -34,7 +39,10 @@ impl CallGraph {
                 let func_name = func.sig.ident.to_string();
                 let mut call_finder = CallFinder::new();
                 call_finder.visit_item_fn(func);
-                graph.function_calls.insert(func_name, call_finder.calls);
+                graph
+                    .function_calls
+                    .insert(func_name.clone(), call_finder.calls);
+                graph.call_sites.insert(func_name, call_finder.sites);
             }
         }
 
@@ -48,22 +56,34 @@ impl CallGraph {
             let func_name = func.name.clone();
             graph
                 .function_calls
-                .insert(func_name, func.called_functions.clone());
+                .insert(func_name.clone(), func.called_functions.clone());
+            graph.call_sites.insert(func_name, func.calls.clone());
         }
 
         graph
     }
 
     pub fn calls(&self, func_name: &str, target_keyword: &str) -> bool {
-        let mut visited = std::collections::HashSet::new();
-        self.calls_recursive(func_name, target_keyword, &mut visited)
+        self.calls_matching(func_name, target_keyword, KeywordMatch::Substring)
+    }
+
+    /// Como [`CallGraph::calls`], escolhendo como a palavra-chave é comparada.
+    pub fn calls_matching(
+        &self,
+        func_name: &str,
+        target_keyword: &str,
+        matching: KeywordMatch,
+    ) -> bool {
+        let mut visited = HashSet::new();
+        self.calls_recursive(func_name, target_keyword, matching, &mut visited)
     }
 
     fn calls_recursive(
         &self,
         func_name: &str,
         target_keyword: &str,
-        visited: &mut std::collections::HashSet<String>,
+        matching: KeywordMatch,
+        visited: &mut HashSet<String>,
     ) -> bool {
         if !visited.insert(func_name.to_string()) {
             return false;
@@ -71,10 +91,10 @@ impl CallGraph {
 
         if let Some(calls) = self.function_calls.get(func_name) {
             for call in calls {
-                if call.to_lowercase().contains(&target_keyword.to_lowercase()) {
+                if matching.matches(call, target_keyword) {
                     return true;
                 }
-                if self.calls_recursive(call, target_keyword, visited) {
+                if self.calls_recursive(call, target_keyword, matching, visited) {
                     return true;
                 }
             }
@@ -82,260 +102,123 @@ impl CallGraph {
 
         false
     }
-}
 
-/// Valida uma função da AST de acordo com uma regra específica do ECA Digital.
-pub fn validate(
-    func: &crate::ast::FunctionAst,
-    prefix_info: &PrefixInfo,
-    call_graph: &CallGraph,
-) -> Vec<ComplianceViolation> {
-    let mut violations = Vec::new();
-
-    match prefix_info.prefix.as_str() {
-        "ECA.AGE.VERIFY" => {
-            violations.extend(validate_age_verification(func, prefix_info, call_graph));
-        }
-        "ECA.PARENT.CONSENT" => {
-            violations.extend(validate_parental_consent(func, prefix_info, call_graph));
-        }
-        "ECA.LOOTBOX.BLOCK" => {
-            violations.extend(validate_lootbox_block(func, prefix_info, call_graph));
-        }
-        "ECA.AD.NO_RETENTION" => {
-            violations.extend(validate_ad_retention(func, prefix_info, call_graph));
-        }
-        "ECA.AD.NO_TARGETING" => {
-            violations.extend(validate_ad_targeting(func, prefix_info, call_graph));
-        }
-        // Outros prefixos do ECA podem ser adicionados aqui.
-        _ => {}
+    /// Sequência de chamadas executadas por uma função, em ordem: cada chamada é
+    /// seguida pelas chamadas da função chamada (quando ela está no grafo).
+    pub fn call_sequence(&self, func_name: &str) -> Vec<&CallSite> {
+        let mut sequence = Vec::new();
+        let mut visited = HashSet::new();
+        self.sequence_recursive(func_name, &mut visited, &mut sequence);
+        sequence
     }
 
-    violations
-}
-
-// A função `get_compliance_prefix` foi removida. A responsabilidade de encontrar
-// e validar o prefixo agora é inteiramente do `semantic_engine`, que passará
-// o prefixo validado para a função `validate`.
-
-/// Valida se uma função anotada com `ECA.AGE.VERIFY` realmente executa uma verificação de idade.
-fn validate_age_verification(
-    func: &crate::ast::FunctionAst,
-    prefix_info: &PrefixInfo,
-    call_graph: &CallGraph,
-) -> Vec<ComplianceViolation> {
-    let mut violations = Vec::new();
-    let keywords = if !prefix_info.expected_calls.is_empty() {
-        prefix_info.expected_calls.clone()
-    } else {
-        vec![
-            "age".to_string(),
-            "birthdate".to_string(),
-            "dob".to_string(),
-            "verify_age".to_string(),
-            "check_age".to_string(),
-        ]
-    };
-
-    let func_name = func.name.clone();
-    let has_verification_call = keywords.iter().any(|kw| call_graph.calls(&func_name, kw));
-
-    if !has_verification_call {
-        violations.push(ComplianceViolation {
-            rule_id: "ECA.AGE.VERIFY.1".to_string(),
-            severity: RuleSeverity::High,
-            message: "Function is annotated for age verification, but does not appear to call a relevant verification function.".to_string(),
-            line: Some(func.line),
-            column: Some(func.column),
-            suggestion: Some("Ensure the function calls a service or helper for age verification (e.g., 'verify_age_with_id()' or 'serpro_datavalid.verify_age()').".to_string()),
-        });
+    /// Caminho até a primeira chamada, na ordem de [`CallGraph::call_sequence`], que
+    /// satisfaz `found`: a primeira chamada do caminho está em `func_name` e cada
+    /// uma das seguintes, na função chamada pela anterior.
+    pub fn path_to(
+        &self,
+        func_name: &str,
+        found: impl Fn(&CallSite) -> bool,
+    ) -> Option<Vec<&CallSite>> {
+        let mut path = Vec::new();
+        let mut visited = HashSet::new();
+        self.path_recursive(func_name, &found, &mut visited, &mut path)
+            .then_some(path)
     }
 
-    violations
-}
-
-/// Valida se uma função que coleta dados também obtém o consentimento parental.
-fn validate_parental_consent(
-    func: &crate::ast::FunctionAst,
-    prefix_info: &PrefixInfo,
-    call_graph: &CallGraph,
-) -> Vec<ComplianceViolation> {
-    let mut violations = Vec::new();
-    let data_collection_keywords = if !prefix_info.data_collection_keywords.is_empty() {
-        prefix_info.data_collection_keywords.clone()
-    } else {
-        vec![
-            "collect".to_string(),
-            "save".to_string(),
-            "store".to_string(),
-            "get_data".to_string(),
-            "user_profile".to_string(),
-        ]
-    };
-
-    let consent_keywords = if !prefix_info.expected_calls.is_empty() {
-        prefix_info.expected_calls.clone()
-    } else {
-        vec![
-            "consent".to_string(),
-            "permission".to_string(),
-            "authorization".to_string(),
-            "parent_ok".to_string(),
-            "get_parental_consent".to_string(),
-        ]
-    };
-
-    let func_name = func.name.clone();
-    let mentions_data_collection = data_collection_keywords
-        .iter()
-        .any(|kw| call_graph.calls(&func_name, kw));
-    let mentions_consent = consent_keywords
-        .iter()
-        .any(|kw| call_graph.calls(&func_name, kw));
-
-    if mentions_data_collection && !mentions_consent {
-        violations.push(ComplianceViolation {
-            rule_id: "ECA.PARENT.CONSENT.1".to_string(),
-            severity: RuleSeverity::High,
-            message: "Function appears to collect user data but lacks a call to a parental consent function.".to_string(),
-            line: Some(func.line),
-            column: Some(func.column),
-            suggestion: Some("Ensure that any data collection from minors is preceded by a call to a verifiable parental consent mechanism (e.g., 'get_parental_consent()').".to_string()),
-        });
+    fn path_recursive<'a>(
+        &'a self,
+        func_name: &str,
+        found: &dyn Fn(&CallSite) -> bool,
+        visited: &mut HashSet<String>,
+        path: &mut Vec<&'a CallSite>,
+    ) -> bool {
+        if !visited.insert(func_name.to_string()) {
+            return false;
+        }
+        for site in self.call_sites.get(func_name).into_iter().flatten() {
+            path.push(site);
+            if found(site) || self.path_recursive(&site.name, found, visited, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
     }
 
-    violations
-}
-
-/// Valida se uma função que implementa uma loot box está protegida por verificação de idade.
-fn validate_lootbox_block(
-    func: &crate::ast::FunctionAst,
-    prefix_info: &PrefixInfo,
-    call_graph: &CallGraph,
-) -> Vec<ComplianceViolation> {
-    let mut violations = Vec::new();
-    let lootbox_keywords = if !prefix_info.data_collection_keywords.is_empty() {
-        prefix_info.data_collection_keywords.clone()
-    } else {
-        vec![
-            "lootbox".to_string(),
-            "crate".to_string(),
-            "pack".to_string(),
-            "random_reward".to_string(),
-            "gacha".to_string(),
-            "open_box".to_string(),
-        ]
-    };
-    let age_check_keywords = if !prefix_info.expected_calls.is_empty() {
-        prefix_info.expected_calls.clone()
-    } else {
-        vec![
-            "age".to_string(),
-            "birthdate".to_string(),
-            "dob".to_string(),
-            "verify_age".to_string(),
-            "check_age".to_string(),
-        ]
-    };
-
-    let func_name = func.name.clone();
-    let mentions_lootbox = lootbox_keywords
-        .iter()
-        .any(|kw| call_graph.calls(&func_name, kw));
-    let mentions_age_check = age_check_keywords
-        .iter()
-        .any(|kw| call_graph.calls(&func_name, kw));
-
-    if mentions_lootbox && !mentions_age_check {
-        violations.push(ComplianceViolation {
-            rule_id: "ECA.LOOTBOX.BLOCK.1".to_string(),
-            severity: RuleSeverity::High,
-            message: "Function appears to implement a loot box mechanic without an age verification check.".to_string(),
-            line: Some(func.line),
-            column: Some(func.column),
-            suggestion: Some("Ensure that access to loot box mechanics is protected by a call to an age verification function.".to_string()),
-        });
+    fn sequence_recursive<'a>(
+        &'a self,
+        func_name: &str,
+        visited: &mut HashSet<String>,
+        sequence: &mut Vec<&'a CallSite>,
+    ) {
+        if !visited.insert(func_name.to_string()) {
+            return;
+        }
+        if let Some(sites) = self.call_sites.get(func_name) {
+            for site in sites {
+                sequence.push(site);
+                self.sequence_recursive(&site.name, visited, sequence);
+            }
+        }
     }
-
-    violations
 }
 
-/// Valida se uma função não retém dados para publicidade.
-fn validate_ad_retention(
-    func: &crate::ast::FunctionAst,
-    prefix_info: &PrefixInfo,
-    call_graph: &CallGraph,
-) -> Vec<ComplianceViolation> {
-    let mut violations = Vec::new();
-    let keywords = if !prefix_info.expected_calls.is_empty() {
-        prefix_info.expected_calls.clone()
-    } else {
-        vec![
-            "disable_ad_tracking".to_string(),
-            "prevent_data_retention".to_string(),
-        ]
-    };
-
-    let func_name = func.name.clone();
-    let has_verification_call = keywords.iter().any(|kw| call_graph.calls(&func_name, kw));
-
-    if !has_verification_call {
-        violations.push(ComplianceViolation {
-            rule_id: "ECA.AD.NO_RETENTION.1".to_string(),
-            severity: RuleSeverity::High,
-            message: "Function handles advertising data without a mechanism to prevent data retention.".to_string(),
-            line: Some(func.line),
-            column: Some(func.column),
-            suggestion: Some("Ensure you are calling a function to explicitly disable tracking or prevent ad data retention.".to_string()),
-        });
-    }
+/// Indica se o nome de uma chamada corresponde a uma palavra-chave de `prefixes.yml`.
+/// A comparação ignora maiúsculas e aceita a palavra-chave como substring.
+pub fn keyword_matches(call: &str, keyword: &str) -> bool {
+    call.to_lowercase().contains(&keyword.to_lowercase())
+}
 
-    violations
+/// Como o nome de uma chamada é comparado a uma palavra-chave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeywordMatch {
+    /// Ignora maiúsculas e aceita a palavra-chave como substring (ver [`keyword_matches`]).
+    #[default]
+    Substring,
+    /// O nome precisa ser exatamente a palavra-chave (modo estrito).
+    Exact,
 }
 
-/// Valida se uma função não realiza publicidade direcionada.
-fn validate_ad_targeting(
-    func: &crate::ast::FunctionAst,
-    prefix_info: &PrefixInfo,
-    call_graph: &CallGraph,
-) -> Vec<ComplianceViolation> {
-    let mut violations = Vec::new();
-    let keywords = if !prefix_info.expected_calls.is_empty() {
-        prefix_info.expected_calls.clone()
-    } else {
-        vec![
-            "disable_targeted_ads".to_string(),
-            "serve_generic_ads".to_string(),
-        ]
-    };
-
-    let func_name = func.name.clone();
-    let has_verification_call = keywords.iter().any(|kw| call_graph.calls(&func_name, kw));
-
-    if !has_verification_call {
-        violations.push(ComplianceViolation {
-            rule_id: "ECA.AD.NO_TARGETING.1".to_string(),
-            severity: RuleSeverity::High,
-            message: "Function appears to serve ads without explicitly serving generic, non-targeted ads.".to_string(),
-            line: Some(func.line),
-            column: Some(func.column),
-            suggestion: Some("Ensure the ad delivery explicitly limits to generic ads without user targeting.".to_string()),
-        });
+impl KeywordMatch {
+    pub fn matches(self, call: &str, keyword: &str) -> bool {
+        match self {
+            KeywordMatch::Substring => keyword_matches(call, keyword),
+            KeywordMatch::Exact => call == keyword,
+        }
     }
-
-    violations
 }
 
 // --- AST Visitor para encontrar chamadas de função ---
 
 pub struct CallFinder {
     pub calls: Vec<String>,
+    pub sites: Vec<CallSite>,
+    pub literals: Vec<StringLiteral>,
 }
 
 impl CallFinder {
     pub fn new() -> Self {
-        CallFinder { calls: Vec::new() }
+        CallFinder {
+            calls: Vec::new(),
+            sites: Vec::new(),
+            literals: Vec::new(),
+        }
+    }
+
+    fn record<'a>(
+        &mut self,
+        name: String,
+        span: proc_macro2::Span,
+        args: impl Iterator<Item = &'a Expr>,
+    ) {
+        self.calls.push(name.clone());
+        self.sites.push(CallSite {
+            name,
+            args: args.map(literal_value).collect(),
+            line: span.start().line,
+            column: span.start().column,
+        });
     }
 }
 
@@ -345,11 +228,32 @@ impl Default for CallFinder {
     }
 }
 
+/// Valor literal de um argumento, como escrito no código (strings sem aspas).
+fn literal_value(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(expr_lit) => match &expr_lit.lit {
+            Lit::Str(s) => Some(s.value()),
+            Lit::Int(i) => Some(i.base10_digits().to_string()),
+            Lit::Float(f) => Some(f.base10_digits().to_string()),
+            Lit::Bool(b) => Some(b.value.to_string()),
+            _ => None,
+        },
+        Expr::Unary(unary) if matches!(unary.op, syn::UnOp::Neg(_)) => {
+            literal_value(&unary.expr).map(|value| format!("-{}", value))
+        }
+        _ => None,
+    }
+}
+
 impl<'ast> Visit<'ast> for CallFinder {
     fn visit_expr_call(&mut self, node: &'ast ExprCall) {
         if let Expr::Path(expr_path) = &*node.func {
             if let Some(path_segment) = expr_path.path.segments.last() {
-                self.calls.push(path_segment.ident.to_string());
+                self.record(
+                    path_segment.ident.to_string(),
+                    node.span(),
+                    node.args.iter(),
+                );
             }
         }
         // Continue visiting children of the expression, like arguments
@@ -358,9 +262,25 @@ impl<'ast> Visit<'ast> for CallFinder {
 
     fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
         // Add the method name to our list of calls
-        self.calls.push(node.method.to_string());
+        self.record(
+            node.method.to_string(),
+            node.method.span(),
+            node.args.iter(),
+        );
 
         // Continue visiting children, like arguments
         syn::visit::visit_expr_method_call(self, node);
     }
+
+    fn visit_lit_str(&mut self, node: &'ast syn::LitStr) {
+        let start = node.span().start();
+        self.literals.push(StringLiteral {
+            value: node.value(),
+            line: start.line,
+            column: start.column,
+        });
+    }
+
+    // Doc comments are attributes; their text is not part of the function body.
+    fn visit_attribute(&mut self, _node: &'ast syn::Attribute) {}
 }
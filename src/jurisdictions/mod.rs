@@ -1,4 +1,6 @@
-//! Módulo que agrupa as lógicas de validação por jurisdição.
+//! Módulo que agrupa o suporte às jurisdições.
+//!
+//! As regras de cada jurisdição são declarativas (ver o módulo `rules`); aqui fica
+//! a análise de chamadas compartilhada por todas elas.
 
 pub mod eca;
-pub mod lgpd;
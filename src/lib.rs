@@ -6,10 +6,27 @@
 // - Defining interfaces for target compilers (Python, JS, Java).
 
 pub mod ast;
+pub mod diff;
+pub mod dpia;
+pub mod error;
+pub mod fix;
+pub mod graph;
+pub mod history;
+pub mod i18n;
+pub mod inventory;
+pub mod lsp;
 pub mod models;
 pub mod parser;
+pub mod prefix_lint;
 pub mod prefix_manager;
+pub mod project_config;
+pub mod provenance;
+pub mod report;
+pub mod rules;
+pub mod scoring;
 pub mod semantic_engine;
+pub mod snippet;
+pub mod suppression;
 
 // Módulos de Jurisdição
 pub mod jurisdictions;
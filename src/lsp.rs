@@ -0,0 +1,576 @@
+//! Servidor de linguagem (`hubstry lsp`).
+//!
+//! Analisa com o [`SemanticEngine`] os arquivos Rust abertos no editor e publica
+//! as violações como diagnósticos, com o id da regra como código. Também oferece:
+//!
+//! - hover nos prefixos de conformidade e nos ids de regra dos comentários, com
+//!   a descrição do prefixo e as entradas do [`RuleCatalog`];
+//! - completar os nomes dos prefixos de `prefixes.yml` num doc comment e os ids
+//!   de regra dentro de `hubstry:allow(...)`;
+//! - ações de código com a correção automática da violação (ver [`crate::fix`]),
+//!   a supressão da regra na função e as anotações que faltam.
+//!
+//! O texto é sincronizado por inteiro a cada alteração. Enquanto o arquivo não
+//! compila, não há análise: os diagnósticos são limpos e nenhuma ação de código é
+//! oferecida, já que as posições da última análise não valem para o texto novo. Quando o `prefixes.yml` muda no
+//! disco, o registro é recarregado e os documentos abertos são analisados de novo.
+
+use crate::ast::{FileAst, FunctionAst};
+use crate::error::{HubstryError, LspError};
+use crate::fix::{self, PlannedFix};
+use crate::i18n::Locale;
+use crate::models::{ComplianceViolation, RuleSeverity};
+use crate::rules::catalog::{RuleCatalog, RuleKind};
+use crate::semantic_engine::SemanticEngine;
+use crate::tr;
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    PublishDiagnostics, ShowMessage,
+};
+use lsp_types::request::{CodeActionRequest, Completion, HoverRequest, Request as _};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, CompletionItem, CompletionItemKind, CompletionOptions,
+    CompletionParams, CompletionResponse, Diagnostic, DiagnosticSeverity, Documentation, Hover,
+    HoverContents, HoverParams, HoverProviderCapability, MarkupContent, MarkupKind, MessageType,
+    NumberOrString, Position, PublishDiagnosticsParams, Range, ServerCapabilities,
+    ShowMessageParams, TextDocumentSyncCapability, TextDocumentSyncKind, Url, WorkspaceEdit,
+};
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Origem dos diagnósticos exibida pelo editor.
+pub const SOURCE: &str = "hubstry";
+
+/// Análise do texto atual de um documento.
+struct Analysis {
+    file: FileAst,
+    violations: Vec<ComplianceViolation>,
+    annotations: Vec<PlannedFix>,
+}
+
+/// Um documento aberto no editor.
+struct Document {
+    text: String,
+    /// `None` se o texto não compila ou não é Rust.
+    analysis: Option<Analysis>,
+}
+
+/// Estado do servidor: o motor, o catálogo das regras e os documentos abertos.
+pub struct LanguageServer {
+    engine: SemanticEngine,
+    catalog: RuleCatalog,
+    locale: Locale,
+    documents: HashMap<Url, Document>,
+    /// Último erro ao recarregar os prefixos, para avisar o editor uma só vez.
+    reload_error: Option<String>,
+}
+
+/// Atende o editor pela entrada e saída padrão até o pedido de encerramento.
+pub fn run(engine: SemanticEngine, locale: Locale) -> Result<(), HubstryError> {
+    let (connection, io_threads) = Connection::stdio();
+    LanguageServer::new(engine, locale).serve(&connection)?;
+    // A thread de escrita só termina quando a conexão é descartada.
+    drop(connection);
+    io_threads.join().map_err(protocol)?;
+    Ok(())
+}
+
+impl LanguageServer {
+    pub fn new(engine: SemanticEngine, locale: Locale) -> Self {
+        let catalog = RuleCatalog::new(engine.rules().packs(), locale);
+        Self {
+            engine,
+            catalog,
+            locale,
+            documents: HashMap::new(),
+            reload_error: None,
+        }
+    }
+
+    /// Funcionalidades anunciadas ao editor.
+    pub fn capabilities() -> ServerCapabilities {
+        ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            completion_provider: Some(CompletionOptions {
+                trigger_characters: Some(vec![" ".to_string(), ".".to_string(), "(".to_string()]),
+                ..CompletionOptions::default()
+            }),
+            code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+            ..ServerCapabilities::default()
+        }
+    }
+
+    /// Faz o handshake de inicialização e atende as mensagens até o encerramento.
+    pub fn serve(mut self, connection: &Connection) -> Result<(), HubstryError> {
+        let capabilities = serde_json::to_value(Self::capabilities())?;
+        connection.initialize(capabilities).map_err(protocol)?;
+        for message in &connection.receiver {
+            for published in self.reload_prefixes() {
+                connection.sender.send(published.into()).map_err(protocol)?;
+            }
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request).map_err(protocol)? {
+                        return Ok(());
+                    }
+                    let response = self.handle_request(request);
+                    connection.sender.send(response.into()).map_err(protocol)?;
+                }
+                Message::Notification(notification) => {
+                    if let Some(published) = self.handle_notification(notification) {
+                        connection.sender.send(published.into()).map_err(protocol)?;
+                    }
+                }
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => params::<HoverParams>(request)
+                .map(|params| serde_json::to_value(self.hover(params))),
+            Completion::METHOD => params::<CompletionParams>(request)
+                .map(|params| serde_json::to_value(self.completion(params))),
+            CodeActionRequest::METHOD => params::<CodeActionParams>(request)
+                .map(|params| serde_json::to_value(self.code_actions(params))),
+            _ => {
+                return Response::new_err(
+                    id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    request.method,
+                )
+            }
+        };
+        match result {
+            Ok(Ok(value)) => Response {
+                id,
+                result: Some(value),
+                error: None,
+            },
+            Ok(Err(err)) => Response::new_err(
+                id,
+                lsp_server::ErrorCode::InternalError as i32,
+                err.to_string(),
+            ),
+            Err((id, message)) => {
+                Response::new_err(id, lsp_server::ErrorCode::InvalidParams as i32, message)
+            }
+        }
+    }
+
+    /// Atualiza os documentos abertos; devolve os diagnósticos a publicar.
+    fn handle_notification(&mut self, notification: Notification) -> Option<Notification> {
+        let uri = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params).ok()?;
+                let document = params.text_document;
+                self.update(document.uri.clone(), document.text);
+                document.uri
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params).ok()?;
+                let text = params.content_changes.into_iter().last()?.text;
+                self.update(params.text_document.uri.clone(), text);
+                params.text_document.uri
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params).ok()?;
+                self.documents.remove(&params.text_document.uri);
+                return Some(publish(params.text_document.uri, Vec::new()));
+            }
+            _ => return None,
+        };
+        self.publish_diagnostics(uri)
+    }
+
+    /// Recarrega o registro de prefixos se o arquivo mudou no disco e refaz a
+    /// análise dos documentos abertos. Se o arquivo novo é inválido, o registro
+    /// anterior continua em uso e o editor recebe um aviso.
+    fn reload_prefixes(&mut self) -> Vec<Notification> {
+        match self.engine.reload_prefixes_if_changed() {
+            Ok(false) => Vec::new(),
+            Ok(true) => {
+                self.reload_error = None;
+                let uris: Vec<Url> = self.documents.keys().cloned().collect();
+                uris.into_iter()
+                    .filter_map(|uri| {
+                        let text = self.documents.get(&uri)?.text.clone();
+                        self.update(uri.clone(), text);
+                        self.publish_diagnostics(uri)
+                    })
+                    .collect()
+            }
+            Err(err) => {
+                let message = format!("[{}] {}", err.code(), err.message(self.locale));
+                if self.reload_error.as_ref() == Some(&message) {
+                    return Vec::new();
+                }
+                self.reload_error = Some(message.clone());
+                vec![Notification::new(
+                    ShowMessage::METHOD.to_string(),
+                    ShowMessageParams {
+                        typ: MessageType::WARNING,
+                        message,
+                    },
+                )]
+            }
+        }
+    }
+
+    /// Diagnósticos de um documento Rust; vazios se o texto atual não compila.
+    fn publish_diagnostics(&self, uri: Url) -> Option<Notification> {
+        if !is_rust(&document_path(&uri)) {
+            return None;
+        }
+        let document = self.documents.get(&uri)?;
+        let diagnostics = document
+            .analysis
+            .iter()
+            .flat_map(|analysis| &analysis.violations)
+            .filter_map(|violation| self.diagnostic(&document.text, violation))
+            .collect();
+        Some(publish(uri, diagnostics))
+    }
+
+    /// Guarda o novo texto e refaz a análise.
+    fn update(&mut self, uri: Url, text: String) {
+        let path = document_path(&uri);
+        let analysis = if is_rust(&path) {
+            self.analyze(&path, &text)
+        } else {
+            None
+        };
+        self.documents.insert(uri, Document { text, analysis });
+    }
+
+    fn analyze(&self, path: &str, text: &str) -> Option<Analysis> {
+        let file = SemanticEngine::load_file(path, text).ok()?;
+        let result = self
+            .engine
+            .analyze_project(std::slice::from_ref(&file))
+            .ok()?;
+        let annotations = self
+            .engine
+            .annotation_fixes(std::slice::from_ref(&file), self.locale);
+        Some(Analysis {
+            file,
+            violations: result.violations,
+            annotations,
+        })
+    }
+
+    fn diagnostic(&self, text: &str, violation: &ComplianceViolation) -> Option<Diagnostic> {
+        let line = violation.line?;
+        Some(Diagnostic {
+            range: violation_range(text, violation, line),
+            severity: Some(severity(&violation.severity)),
+            code: Some(NumberOrString::String(violation.rule_id.clone())),
+            source: Some(SOURCE.to_string()),
+            message: self.locale.message(violation),
+            ..Diagnostic::default()
+        })
+    }
+
+    /// Descrição do prefixo ou da regra sob o cursor, num comentário.
+    pub fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params;
+        let text = &self.documents.get(&position.text_document.uri)?.text;
+        let line = text.lines().nth(position.position.line as usize)?;
+        if !line.trim_start().starts_with("//") {
+            return None;
+        }
+        let column = char_column(line, position.position.character);
+        let identifier =
+            Regex::new(r"[A-Za-z][A-Za-z0-9_]*(\.[A-Za-z0-9_]+)+").expect("padrão válido");
+        let found = identifier.find_iter(line).find(|found| {
+            let start = line[..found.start()].chars().count();
+            let end = start + found.as_str().chars().count();
+            (start..=end).contains(&column)
+        })?;
+        let word = found.as_str();
+
+        let value = if let Some(info) = self.engine.registry().get(word) {
+            let mut value = format!(
+                "**{}** · {}\n\n{}",
+                info.prefix, info.standard, info.description
+            );
+            for entry in self.catalog.entries.iter().filter(|entry| {
+                entry.kind == RuleKind::Code && entry.applies_to.as_deref() == Some(word)
+            }) {
+                value.push_str("\n\n---\n\n");
+                value.push_str(&entry.to_markdown(self.locale));
+            }
+            value
+        } else {
+            self.catalog.get(word)?.to_markdown(self.locale)
+        };
+        let start = line[..found.start()].chars().count();
+        let line_number = position.position.line;
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(Range::new(
+                Position::new(line_number, utf16_column(line, start)),
+                Position::new(
+                    line_number,
+                    utf16_column(line, start + word.chars().count()),
+                ),
+            )),
+        })
+    }
+
+    /// Nomes dos prefixos num doc comment (`/// ECA.`) e ids de regra dentro de
+    /// `hubstry:allow(`.
+    pub fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let position = params.text_document_position;
+        let text = &self.documents.get(&position.text_document.uri)?.text;
+        let line = text
+            .lines()
+            .nth(position.position.line as usize)
+            .unwrap_or("");
+        let before: String = line
+            .chars()
+            .take(char_column(line, position.position.character))
+            .collect();
+
+        let prefix_position = Regex::new(r"^\s*///\s*[A-Za-z0-9_.]*$").expect("padrão válido");
+        let rule_position = Regex::new(&format!(
+            r"^\s*///\s*{}\(([A-Za-z0-9_.]*,\s*)*[A-Za-z0-9_.]*$",
+            regex::escape(crate::suppression::MARKER)
+        ))
+        .expect("padrão válido");
+        let items: Vec<CompletionItem> = if prefix_position.is_match(&before) {
+            let registry = self.engine.registry();
+            let mut prefixes: Vec<_> = registry.iter().collect();
+            prefixes.sort_by(|a, b| a.prefix.cmp(&b.prefix));
+            prefixes
+                .into_iter()
+                .map(|info| CompletionItem {
+                    label: info.prefix.clone(),
+                    kind: Some(CompletionItemKind::CONSTANT),
+                    detail: Some(info.standard.clone()),
+                    documentation: Some(Documentation::String(info.description.clone())),
+                    insert_text: Some(format!("{}: ", info.prefix)),
+                    ..CompletionItem::default()
+                })
+                .collect()
+        } else if rule_position.is_match(&before) {
+            self.catalog
+                .entries
+                .iter()
+                .filter(|entry| entry.kind != RuleKind::Web)
+                .map(|entry| CompletionItem {
+                    label: entry.id.clone(),
+                    kind: Some(CompletionItemKind::CONSTANT),
+                    detail: Some(entry.severity.to_string()),
+                    documentation: Some(Documentation::String(entry.summary.clone())),
+                    ..CompletionItem::default()
+                })
+                .collect()
+        } else {
+            return None;
+        };
+        Some(CompletionResponse::Array(items))
+    }
+
+    /// Correções automáticas e supressões das violações no intervalo pedido, e
+    /// as anotações que faltam nas funções do intervalo.
+    pub fn code_actions(&self, params: CodeActionParams) -> Vec<CodeActionOrCommand> {
+        let uri = params.text_document.uri;
+        let Some(document) = self.documents.get(&uri) else {
+            return Vec::new();
+        };
+        let Some(analysis) = &document.analysis else {
+            return Vec::new();
+        };
+        let text = &document.text;
+        // Linhas a partir de 1, como nas violações.
+        let first = params.range.start.line as usize + 1;
+        let last = params.range.end.line as usize + 1;
+        let overlaps = |start: usize, end: usize| start <= last && first <= end;
+
+        let mut actions = Vec::new();
+        for violation in &analysis.violations {
+            let Some(line) = violation.line else {
+                continue;
+            };
+            if !overlaps(line, violation.end_line.unwrap_or(line).max(line)) {
+                continue;
+            }
+            let diagnostics = self
+                .diagnostic(text, violation)
+                .into_iter()
+                .collect::<Vec<_>>();
+            if let Some(planned) = PlannedFix::from_violation(violation, self.locale) {
+                actions.push(action(
+                    format!("Hubstry: {}", planned.description),
+                    &uri,
+                    text,
+                    &planned.edits,
+                    diagnostics.clone(),
+                    true,
+                ));
+            }
+            if let Some(func) = function(&analysis.file, violation.function.as_deref()) {
+                actions.push(action(
+                    format!(
+                        "Hubstry: {}",
+                        tr!(self.locale, "lsp.suppress", rule = violation.rule_id)
+                    ),
+                    &uri,
+                    text,
+                    &[fix::insert_suppression(text, func, &violation.rule_id)],
+                    diagnostics,
+                    false,
+                ));
+            }
+        }
+        for planned in &analysis.annotations {
+            let Some(func) = function(&analysis.file, planned.function.as_deref()) else {
+                continue;
+            };
+            if overlaps(func.line, func.end_line) {
+                actions.push(action(
+                    format!("Hubstry: {}", planned.description),
+                    &uri,
+                    text,
+                    &planned.edits,
+                    Vec::new(),
+                    false,
+                ));
+            }
+        }
+        actions
+    }
+}
+
+/// Parâmetros de um pedido, ou o erro a responder.
+fn params<P: serde::de::DeserializeOwned>(request: Request) -> Result<P, (RequestId, String)> {
+    serde_json::from_value(request.params).map_err(|err| (request.id, err.to_string()))
+}
+
+fn publish(uri: Url, diagnostics: Vec<Diagnostic>) -> Notification {
+    Notification::new(
+        PublishDiagnostics::METHOD.to_string(),
+        PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        },
+    )
+}
+
+fn action(
+    title: String,
+    uri: &Url,
+    text: &str,
+    edits: &[crate::models::TextEdit],
+    diagnostics: Vec<Diagnostic>,
+    preferred: bool,
+) -> CodeActionOrCommand {
+    let edits = edits.iter().map(|edit| text_edit(text, edit)).collect();
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: (!diagnostics.is_empty()).then_some(diagnostics),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), edits)])),
+            ..WorkspaceEdit::default()
+        }),
+        is_preferred: preferred.then_some(true),
+        ..CodeAction::default()
+    })
+}
+
+fn function<'a>(file: &'a FileAst, name: Option<&str>) -> Option<&'a FunctionAst> {
+    let name = name?;
+    file.functions.iter().find(|func| func.name == name)
+}
+
+fn is_rust(path: &str) -> bool {
+    path.ends_with(".rs")
+}
+
+/// Caminho do documento, usado como o arquivo das violações.
+fn document_path(uri: &Url) -> String {
+    uri.to_file_path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|_| uri.path().to_string())
+}
+
+/// Intervalo do diagnóstico: do início da violação até o fim da coluna final, se
+/// ela estiver na mesma linha, ou até o fim da linha.
+fn violation_range(text: &str, violation: &ComplianceViolation, line: usize) -> Range {
+    let content = text.lines().nth(line - 1).unwrap_or("");
+    let start = violation.column.unwrap_or(0);
+    let end = match (violation.end_line, violation.end_column) {
+        (Some(end_line), Some(end_column)) if end_line == line => end_column,
+        (None, Some(end_column)) => end_column,
+        _ => content.chars().count(),
+    };
+    let number = (line - 1) as u32;
+    Range::new(
+        Position::new(number, utf16_column(content, start)),
+        Position::new(number, utf16_column(content, end.max(start))),
+    )
+}
+
+fn text_edit(text: &str, edit: &crate::models::TextEdit) -> lsp_types::TextEdit {
+    let position = |line: usize, column: usize| {
+        let content = text.lines().nth(line.saturating_sub(1)).unwrap_or("");
+        Position::new(line.saturating_sub(1) as u32, utf16_column(content, column))
+    };
+    lsp_types::TextEdit {
+        range: Range::new(
+            position(edit.line, edit.column),
+            position(edit.end_line, edit.end_column),
+        ),
+        new_text: edit.new_text.clone(),
+    }
+}
+
+fn severity(severity: &RuleSeverity) -> DiagnosticSeverity {
+    match severity {
+        RuleSeverity::Critical | RuleSeverity::High => DiagnosticSeverity::ERROR,
+        RuleSeverity::Medium => DiagnosticSeverity::WARNING,
+        RuleSeverity::Low => DiagnosticSeverity::INFORMATION,
+        RuleSeverity::Info => DiagnosticSeverity::HINT,
+    }
+}
+
+/// O LSP conta as colunas em unidades UTF-16; as violações, em caracteres.
+fn utf16_column(line: &str, column: usize) -> u32 {
+    line.chars()
+        .take(column)
+        .map(char::len_utf16)
+        .sum::<usize>() as u32
+}
+
+fn char_column(line: &str, utf16: u32) -> usize {
+    let mut units = 0;
+    for (index, c) in line.chars().enumerate() {
+        if units >= utf16 as usize {
+            return index;
+        }
+        units += c.len_utf16();
+    }
+    line.chars().count()
+}
+
+fn protocol(err: impl std::fmt::Display) -> HubstryError {
+    LspError::Protocol {
+        message: err.to_string(),
+    }
+    .into()
+}
@@ -1,14 +1,45 @@
 // src/main.rs
 
-use clap::{Parser, Subcommand};
-use hubstry_iso_code::{models::EngineConfig, scanner, semantic_engine::SemanticEngine};
+use chrono::Utc;
+use clap::{Args, Parser, Subcommand};
+use hubstry_iso_code::ast::FileAst;
+use hubstry_iso_code::error::{HubstryError, LicenseError, ReportError, RuleError};
+use hubstry_iso_code::fix::{self, PlannedFix};
+use hubstry_iso_code::graph::GraphFormat;
+use hubstry_iso_code::history::{self, HistoryStore, RunKind};
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::licensing::tier_checker::{validate_license_key, Feature, Tier};
+use hubstry_iso_code::models::{AnalysisResult, EngineConfig, OutputFormat, TextEdit};
+use hubstry_iso_code::prefix_lint;
+use hubstry_iso_code::prefix_manager::PrefixRegistry;
+use hubstry_iso_code::project_config::ProjectConfig;
+use hubstry_iso_code::provenance::{self, GitInfo, RecordedReport, Worktree};
+use hubstry_iso_code::report::SigningKey;
+use hubstry_iso_code::rules::catalog::{CatalogEntry, RuleCatalog};
+use hubstry_iso_code::rules::{pack, PackVerification, RuleSet};
+use hubstry_iso_code::{
+    diff, dpia, inventory, lsp, msg, report, scanner, semantic_engine::SemanticEngine, tr,
+};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 #[derive(Parser)]
 #[command(name = "hubstry")]
 #[command(about = "Hubstry CaaS — Compliance as a Service")]
 struct Cli {
+    /// Arquivo de prefixos de conformidade (padrão: `prefixes.yml` mais próximo ou o embutido).
+    /// Pode ser repetido; cada arquivo é aplicado sobre os anteriores.
+    #[arg(long, global = true)]
+    prefixes: Vec<PathBuf>,
+    /// Configuração do projeto (padrão: `hubstry.yml` mais próximo, se existir).
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Idioma dos relatórios e mensagens: pt-BR (padrão), en ou es. Pode ser repetido
+    /// no `analyze` para gerar cada relatório em vários idiomas; as mensagens da CLI
+    /// usam o primeiro.
+    #[arg(long, global = true)]
+    locale: Vec<Locale>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -17,35 +48,56 @@ struct Cli {
 enum Commands {
     /// Analisar código-fonte localmente
     Analyze {
+        #[command(flatten)]
+        target: AnalysisTarget,
+        /// Formato do relatório: text (ou terminal), markdown, html, json, yaml, xml, sarif,
+        /// junit, checkstyle, csv ou pdf.
+        /// Pode ser repetido para gerar vários relatórios na mesma execução.
+        #[arg(long, default_value = "text")]
+        format: Vec<OutputFormat>,
+        /// Arquivo do relatório (padrão: saída padrão). Com mais de um `--format`,
+        /// é o diretório onde cada relatório é gravado como `compliance_report.<ext>`.
+        /// Com mais de um `--locale`, o idioma entra no nome: `compliance_report.en.<ext>`.
         #[arg(short, long)]
-        file: Option<String>,
-        #[arg(short, long)]
-        dir: Option<String>,
-        #[arg(short, long, default_value = "auto")]
-        lang: String,
-        #[arg(short, long, default_value = "rules/eca_digital.yml")]
-        rules: String,
-        #[arg(long, default_value = "terminal")]
-        format: String,
-        #[arg(short, long)]
-        output: Option<String>,
+        output: Option<PathBuf>,
         #[arg(short, long, default_value_t = 90.0)]
         threshold: f64,
+        /// Não gravar esta execução no histórico local (`.hubstry/history`)
         #[arg(long)]
-        license_key: Option<String>,
+        no_history: bool,
+        /// Chave privada RSA (PEM) para assinar os relatórios em PDF; a assinatura fica
+        /// embutida em cada `.pdf`
+        #[arg(long, requires = "sign_cert")]
+        sign_key: Option<PathBuf>,
+        /// Certificado X.509 (PEM) da chave de `--sign-key`
+        #[arg(long, requires = "sign_key")]
+        sign_cert: Option<PathBuf>,
     },
     /// Escanear URL de website
     Scan {
         #[arg(short, long)]
         url: String,
-        #[arg(short, long, default_value = "rules/eca_digital.yml")]
-        rules: String,
-        #[arg(long, default_value = "html")]
-        format: String,
+        /// Pacote de regras (caminho ou nome, ex.: `lgpd` ou `eca_digital@1.0.0`).
+        /// Pode ser repetido; por padrão, todos os pacotes disponíveis.
         #[arg(short, long)]
-        output: Option<String>,
+        rules: Vec<String>,
+        /// Formato do relatório: json ou pdf
+        #[arg(long, default_value = "json")]
+        format: OutputFormat,
+        /// Arquivo do relatório (padrão: saída padrão; para pdf, `compliance_report.pdf`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
         #[arg(long)]
         license_key: Option<String>,
+        /// Não gravar esta varredura no histórico local (`.hubstry/history`)
+        #[arg(long)]
+        no_history: bool,
+        /// Chave privada RSA (PEM) para assinar o relatório em PDF, como no `analyze`
+        #[arg(long, requires = "sign_cert")]
+        sign_key: Option<PathBuf>,
+        /// Certificado X.509 (PEM) da chave de `--sign-key`
+        #[arg(long, requires = "sign_key")]
+        sign_cert: Option<PathBuf>,
     },
     /// Escanear rapidamente a URL
     QuickScan {
@@ -54,157 +106,1489 @@ enum Commands {
         #[arg(long)]
         license_key: Option<String>,
     },
+    /// Gerenciar as regras de conformidade
+    Rules {
+        #[command(subcommand)]
+        command: RulesCommands,
+    },
+    /// Inspecionar a configuração de prefixos
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Refazer a análise de um relatório JSON ou YAML no commit em que ele foi gerado
+    /// e confirmar que o resultado é o mesmo
+    VerifyReport {
+        /// Relatório gerado por `analyze --format json` ou `--format yaml`
+        report: PathBuf,
+        /// Chave de licença, para relatórios gerados com regras personalizadas
+        #[arg(long)]
+        license_key: Option<String>,
+    },
+    /// Conferir a assinatura embutida num relatório em PDF
+    VerifyPdf {
+        /// Relatório gerado por `analyze --format pdf --sign-key` ou `scan`
+        pdf: PathBuf,
+        /// Certificado X.509 (PEM) de quem deveria ter assinado. O certificado que
+        /// vem dentro do PDF não é usado, já que qualquer um pode gerar o seu
+        #[arg(long)]
+        cert: PathBuf,
+    },
+    /// Mostrar a evolução do score, o tempo médio de correção por regra e as
+    /// violações abertas mais antigas, a partir do histórico local
+    History {
+        /// Diretório do histórico
+        #[arg(long, default_value = history::DEFAULT_DIR)]
+        dir: PathBuf,
+        /// Quantas violações abertas listar
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Formato da saída: text ou json
+        #[arg(long, default_value = "text")]
+        format: OutputFormat,
+    },
+    /// Comparar dois relatórios JSON ou YAML: violações novas, corrigidas e
+    /// inalteradas, e a variação dos scores
+    Diff {
+        /// Relatório anterior (ex.: da branch principal)
+        old: PathBuf,
+        /// Relatório novo (ex.: da branch em revisão)
+        new: PathBuf,
+        /// Formato da saída: text, markdown ou json
+        #[arg(long, default_value = "text")]
+        format: OutputFormat,
+        /// Arquivo de saída (padrão: saída padrão)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Gerar o rascunho do Relatório de Impacto à Proteção de Dados Pessoais (RIPD,
+    /// LGPD art. 38) a partir da análise do código
+    Dpia {
+        #[command(flatten)]
+        target: AnalysisTarget,
+        /// Relatório JSON ou YAML de `analyze` a usar em vez de analisar o código
+        #[arg(long, conflicts_with_all = ["file", "dir"])]
+        report: Option<PathBuf>,
+        /// Nome do projeto (padrão: nome do diretório atual)
+        #[arg(long)]
+        project: Option<String>,
+        /// Formato do relatório: markdown ou html
+        #[arg(long, default_value = "markdown")]
+        format: OutputFormat,
+        /// Arquivo do relatório (padrão: saída padrão)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Gerar o registro das operações de tratamento (LGPD art. 37) a partir das
+    /// funções anotadas com prefixos `LGPD.*` e `ECA.*`
+    Inventory {
+        #[command(flatten)]
+        target: AnalysisTarget,
+        /// Relatório JSON ou YAML de `analyze` a usar em vez de analisar o código
+        #[arg(long, conflicts_with_all = ["file", "dir"])]
+        report: Option<PathBuf>,
+        /// Formato do inventário: markdown, csv ou json
+        #[arg(long, default_value = "markdown")]
+        format: OutputFormat,
+        /// Arquivo do inventário (padrão: saída padrão)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Explicar uma regra: descrição, base legal, exemplos e como corrigir
+    Explain {
+        /// Id da regra (ex.: `ECA.LOOTBOX.BLOCK.1`)
+        rule_id: String,
+        /// Formato da saída: text, markdown ou json
+        #[arg(long, default_value = "text")]
+        format: OutputFormat,
+    },
+    /// Exportar o grafo de chamadas em Graphviz DOT ou Mermaid, com os nós coloridos
+    /// pelo papel de conformidade e os caminhos das violações destacados
+    Graph {
+        #[command(flatten)]
+        target: AnalysisTarget,
+        /// Formato do grafo: dot ou mermaid
+        #[arg(long, default_value = "dot")]
+        format: GraphFormat,
+        /// Manter só o subgrafo alcançável a partir desta função. Pode ser repetido
+        #[arg(long)]
+        from: Vec<String>,
+        /// Manter só o subgrafo alcançável a partir das funções anotadas com este
+        /// prefixo (ex.: `LGPD` ou `ECA.PARENT.CONSENT`). Pode ser repetido
+        #[arg(long)]
+        prefix: Vec<String>,
+        /// Arquivo do grafo (padrão: saída padrão)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Corrigir automaticamente as violações que têm correção: exibe o diff
+    /// (`--dry-run`, padrão) ou reescreve os arquivos (`--apply`)
+    Fix {
+        #[command(flatten)]
+        target: AnalysisTarget,
+        /// Exibir o diff das correções sem alterar os arquivos (padrão)
+        #[arg(long, conflicts_with = "apply")]
+        dry_run: bool,
+        /// Reescrever os arquivos com as correções
+        #[arg(long, conflicts_with = "dry_run")]
+        apply: bool,
+        /// Corrigir só as violações desta regra. Pode ser repetido
+        #[arg(long = "rule")]
+        rule_ids: Vec<String>,
+        /// Página HTML local cujos scripts de rastreamento serão envolvidos num
+        /// bloqueio de consentimento. Pode ser repetido
+        #[arg(long)]
+        html: Vec<PathBuf>,
+    },
+    /// Servidor de linguagem (LSP) pela entrada e saída padrão: diagnósticos,
+    /// hover, completar prefixos e ações de correção no editor
+    Lsp {
+        /// Pacote de regras (caminho ou nome). Pode ser repetido; por padrão,
+        /// todos os pacotes disponíveis
+        #[arg(short, long)]
+        rules: Vec<String>,
+        #[arg(long)]
+        license_key: Option<String>,
+        /// Modo estrito, como no `analyze`
+        #[arg(long)]
+        strict: bool,
+    },
+}
+
+/// O código e as regras analisados, e o plano. Comum ao `analyze` e aos comandos
+/// que partem de uma análise.
+#[derive(Args)]
+struct AnalysisTarget {
+    #[arg(short, long)]
+    file: Option<String>,
+    #[arg(short, long)]
+    dir: Option<String>,
+    #[arg(short, long, default_value = "auto")]
+    lang: String,
+    /// Pacote de regras (caminho ou nome, ex.: `lgpd` ou `eca_digital@1.0.0`).
+    /// Pode ser repetido; por padrão, todos os pacotes disponíveis.
+    #[arg(short, long)]
+    rules: Vec<String>,
+    #[arg(long)]
+    license_key: Option<String>,
+    /// Modo estrito: prefixos desconhecidos são violações, sem listas de fallback
+    /// nem comparação por substring, e supressões exigem `approver`
+    #[arg(long)]
+    strict: bool,
+}
+
+#[derive(Subcommand)]
+enum RulesCommands {
+    /// Verificar a configuração de prefixos em busca de erros e inconsistências
+    Lint,
+    /// Listar os pacotes de regras disponíveis e as regras de cada um
+    List,
+    /// Exibir os metadados e as regras de um pacote
+    Show {
+        /// Nome ou caminho do pacote
+        pack: String,
+    },
+    /// Verificar o checksum dos pacotes (por padrão, de todos os disponíveis)
+    Verify { packs: Vec<String> },
+    /// Instalar um pacote no projeto (em `.hubstry/rules`)
+    Install { path: PathBuf },
+    /// Gerar o catálogo das regras como um site estático em HTML
+    Site {
+        /// Diretório do site
+        #[arg(short, long, default_value = "rule-catalog")]
+        output: PathBuf,
+    },
+    /// Calcular o checksum de um pacote
+    Checksum {
+        path: PathBuf,
+        /// Gravar o checksum calculado no próprio arquivo
+        #[arg(long)]
+        write: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Exibir a configuração efetiva após combinar todas as camadas
+    Show,
 }
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> ExitCode {
     let cli = Cli::parse();
-    match cli.command {
-        Commands::Analyze {
-            file,
-            dir: _,
-            lang: _,
-            rules: _,
-            format,
-            output: _,
-            threshold,
-            license_key,
-        } => {
-            use hubstry_iso_code::licensing::tier_checker::{validate_license_key, Feature};
-            let tier = validate_license_key(license_key.as_deref());
+    let locale = cli.locale.first().copied().unwrap_or_default();
+    match run(cli, locale).await {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!(
+                "❌ {} [{}]: {}",
+                tr!(locale, "cli.error"),
+                err.code(),
+                err.message(locale)
+            );
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
 
-            let mut effective_format = format;
-            if effective_format == "html" && !tier.has_access(Feature::ReportHtml) {
-                println!("👋 Ops! Relatórios HTML avançados e multicamadas estão disponíveis a partir do plano Starter (R$197/mês). Assine para ativar!");
-                effective_format = "terminal".to_string();
-            }
+fn current_dir() -> Result<PathBuf, HubstryError> {
+    std::env::current_dir().map_err(|e| HubstryError::io(".", e))
+}
 
-            let file_path = file.unwrap_or_else(|| "src/main.rs".to_string());
-            let path = PathBuf::from(file_path);
+/// Carrega o registro de prefixos indicado por `--prefixes` ou descoberto a partir
+/// de `base`.
+fn load_registry(prefixes: &[PathBuf], base: &Path) -> Result<PrefixRegistry, HubstryError> {
+    if prefixes.is_empty() {
+        PrefixRegistry::discover(base)
+    } else {
+        PrefixRegistry::from_layers(prefixes)
+    }
+}
 
-            println!("🔎 Analisando o arquivo: {}", path.display());
+/// Carrega a configuração do projeto indicada por `--config` ou descoberta a partir
+/// de `base`.
+fn load_project_config(path: Option<&Path>, base: &Path) -> Result<ProjectConfig, HubstryError> {
+    match path {
+        Some(path) => ProjectConfig::from_path(path),
+        None => ProjectConfig::discover(base),
+    }
+}
 
-            let content = fs::read_to_string(&path)?;
-            let ast = syn::parse_file(&content)?;
+/// Arquivos `.rs` de um diretório e de seus subdiretórios, em ordem, ignorando
+/// `target/` e diretórios ocultos.
+fn collect_sources(dir: &Path) -> Result<Vec<PathBuf>, HubstryError> {
+    let mut sources = Vec::new();
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| HubstryError::io(dir, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    entries.sort();
+    for path in entries {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if path.is_dir() {
+            if name != "target" && !name.starts_with('.') {
+                sources.extend(collect_sources(&path)?);
+            }
+        } else if path.extension().and_then(|e| e.to_str()) == Some("rs") {
+            sources.push(path);
+        }
+    }
+    Ok(sources)
+}
 
-            let engine = SemanticEngine::new(EngineConfig::default());
-            let results = engine.analyze(&ast)?;
+/// Carrega os pacotes indicados por `--rules` ou, se nenhum for indicado, todos os
+/// pacotes disponíveis (embutidos e instalados no projeto em `base`).
+fn load_rules(specs: &[String], base: &Path) -> Result<RuleSet, HubstryError> {
+    let packs = if specs.is_empty() {
+        let packs = pack::available_packs(base)?;
+        for pack in &packs {
+            pack.verify()?;
+        }
+        packs
+    } else {
+        specs
+            .iter()
+            .map(|spec| pack::resolve(spec, base))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    Ok(RuleSet::from_packs(packs))
+}
 
-            if effective_format == "terminal" {
-                let report = engine.generate_report(&results);
-                println!("\n{}", report);
+/// Gera um relatório por formato e idioma. Sem `output`, os relatórios vão para a
+/// saída padrão (o PDF, para `compliance_report.pdf`); com um único formato, `output`
+/// é o arquivo; com vários, é o diretório. Com mais de um idioma, o código do idioma
+/// entra no nome do arquivo. O HTML inclui a evolução do score em `trend`, e os PDFs
+/// são assinados com `sign_key`.
+fn write_reports(
+    formats: &[OutputFormat],
+    locales: &[Locale],
+    result: &AnalysisResult,
+    output: Option<&Path>,
+    trend: &[history::Run],
+    sign_key: Option<&SigningKey>,
+) -> Result<(), HubstryError> {
+    for &format in formats {
+        let report_locales = if report::is_localized(format) {
+            locales
+        } else {
+            &locales[..1]
+        };
+        for &locale in report_locales {
+            let reporter: Box<dyn report::Reporter> = match format {
+                OutputFormat::Html => {
+                    Box::new(report::HtmlReporter::new(locale).with_trend(trend.to_vec()))
+                }
+                _ => report::reporter(format, locale),
+            };
+            let mut content = reporter.render(result)?;
+            if let (OutputFormat::Pdf, Some(key)) = (format, sign_key) {
+                content = key.sign(&content, Utc::now())?;
             }
-
-            if tier.has_access(Feature::ReportJson) {
-                let json_report = engine.generate_json_report(&results);
-                let _ = fs::write("compliance_report.json", json_report);
+            let suffix = if report_locales.len() > 1 {
+                format!(".{}", locale)
+            } else {
+                String::new()
+            };
+            let file_name = format!(
+                "{}{}.{}",
+                report::DEFAULT_REPORT_NAME,
+                suffix,
+                reporter.extension()
+            );
+            let path = match output {
+                None if format == OutputFormat::Pdf => PathBuf::from(file_name),
+                None => {
+                    println!("\n{}", content);
+                    continue;
+                }
+                Some(path) if formats.len() == 1 => with_suffix(path, &suffix),
+                Some(dir) => {
+                    fs::create_dir_all(dir).map_err(|e| HubstryError::io(dir, e))?;
+                    dir.join(file_name)
+                }
+            };
+            fs::write(&path, &content).map_err(|e| HubstryError::io(&path, e))?;
+            println!(
+                "📄 {}",
+                tr!(
+                    locales[0],
+                    "cli.report_written",
+                    format = format,
+                    path = path.display()
+                )
+            );
+            if let (OutputFormat::Pdf, Some(key)) = (format, sign_key) {
+                print_signed(key, locales[0]);
             }
+        }
+    }
+    Ok(())
+}
+
+/// Convite para o plano que libera os relatórios no formato `format`.
+fn upgrade_message(format: OutputFormat, locale: Locale) -> String {
+    match format {
+        OutputFormat::Html => tr!(locale, "cli.upgrade.html"),
+        OutputFormat::Pdf => tr!(locale, "cli.upgrade.pdf"),
+        _ => tr!(locale, "cli.upgrade.format", format = format),
+    }
+}
+
+/// Carrega a chave de `--sign-key` e o certificado de `--sign-cert`, que só valem
+/// quando há um PDF a assinar. O clap garante que vêm juntos.
+fn load_sign_key(
+    key: Option<&Path>,
+    cert: Option<&Path>,
+    formats: &[OutputFormat],
+    command: &'static str,
+) -> Result<Option<SigningKey>, HubstryError> {
+    let (Some(key), Some(cert)) = (key, cert) else {
+        return Ok(None);
+    };
+    if !formats.contains(&OutputFormat::Pdf) {
+        return Err(ReportError::UnsupportedFormat {
+            format: formats[0].to_string(),
+            command,
+        }
+        .into());
+    }
+    SigningKey::from_pem_files(key, cert).map(Some)
+}
+
+/// Informa quem assinou o PDF recém-gravado.
+fn print_signed(key: &SigningKey, locale: Locale) {
+    let certificate = key.certificate();
+    println!(
+        "🔏 {}",
+        tr!(
+            locale,
+            "cli.pdf_signed",
+            subject = certificate.subject(),
+            fingerprint = certificate.fingerprint()
+        )
+    );
+}
+
+/// Grava `content` em `output` e exibe a mensagem `written`, ou imprime `content` na
+/// saída padrão.
+fn write_output(
+    output: Option<&Path>,
+    content: &str,
+    written: impl FnOnce(&Path) -> String,
+) -> Result<(), HubstryError> {
+    match output {
+        Some(path) => {
+            fs::write(path, content).map_err(|e| HubstryError::io(path, e))?;
+            println!("📄 {}", written(path));
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+/// Acrescenta `suffix` ao nome do arquivo, antes da extensão: `relatorio.en.html`.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    if suffix.is_empty() {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}{}.{}", stem, suffix, extension.to_string_lossy()),
+        None => format!("{}{}", stem, suffix),
+    };
+    path.with_file_name(name)
+}
+
+/// Acrescenta aos metadados da engine o que só a CLI conhece: o estado do git, os
+/// arquivos de `--prefixes` e `--config` (relativos a `dir`), o hash do
+/// `hubstry.yml` e o plano.
+fn record_metadata(
+    result: &mut AnalysisResult,
+    project: &ProjectConfig,
+    inputs: &EngineInputs,
+    tier: Tier,
+    dir: &Path,
+) -> Result<(), HubstryError> {
+    if let Some(git) = GitInfo::discover(dir) {
+        git.record(&mut result.metadata);
+    }
+    let relative = |path: &Path| path.strip_prefix(dir).unwrap_or(path).display().to_string();
+    if !inputs.prefixes.is_empty() {
+        let prefixes: Vec<String> = inputs.prefixes.iter().map(|p| relative(p)).collect();
+        result
+            .metadata
+            .insert(PREFIXES_KEY.to_string(), prefixes.join(LIST_SEPARATOR));
+    }
+    if let Some(config) = &inputs.config {
+        result
+            .metadata
+            .insert(CONFIG_KEY.to_string(), relative(config));
+    }
+    let config_hash = match &project.path {
+        Some(path) => provenance::sha256(&fs::read(path).map_err(|e| HubstryError::io(path, e))?),
+        None => "none".to_string(),
+    };
+    result
+        .metadata
+        .insert("config.file.sha256".to_string(), config_hash);
+    result
+        .metadata
+        .insert("license.tier".to_string(), format!("{:?}", tier));
+    Ok(())
+}
+
+/// Grava a execução no histórico local e devolve a evolução do score do alvo. Uma
+/// falha no histórico não interrompe a análise: vira um aviso e uma evolução vazia.
+fn record_history(
+    locale: Locale,
+    record: impl FnOnce(&mut HistoryStore) -> Result<Vec<history::Run>, HubstryError>,
+) -> Vec<history::Run> {
+    let result = current_dir()
+        .and_then(|dir| HistoryStore::open(&dir.join(history::DEFAULT_DIR)))
+        .and_then(|mut store| record(&mut store));
+    result.unwrap_or_else(|e| {
+        eprintln!(
+            "⚠️  {}",
+            tr!(
+                locale,
+                "cli.history_not_recorded",
+                error = e.message(locale)
+            )
+        );
+        Vec::new()
+    })
+}
+
+fn verification_label(verification: &PackVerification, locale: Locale) -> String {
+    match verification {
+        PackVerification::Valid => format!("✅ {}", tr!(locale, "cli.checksum.valid")),
+        PackVerification::Unsigned => format!("⚠️  {}", tr!(locale, "cli.checksum.unsigned")),
+        PackVerification::Mismatch { .. } => {
+            format!("❌ {}", tr!(locale, "cli.checksum.mismatch"))
+        }
+    }
+}
+
+/// O motor configurado para o alvo e os arquivos-fonte `(caminho, conteúdo)` a
+/// analisar, com o nome do alvo.
+struct PreparedAnalysis {
+    name: String,
+    engine: SemanticEngine,
+    sources: Vec<(String, String)>,
+    project: ProjectConfig,
+    inputs: EngineInputs,
+}
+
+fn prepare_analysis(
+    target: AnalysisTarget,
+    prefixes: &[PathBuf],
+    config_path: Option<&Path>,
+    tier: Tier,
+    locale: Locale,
+) -> Result<PreparedAnalysis, HubstryError> {
+    let inputs = EngineInputs {
+        rule_packs: target.rules.clone(),
+        strict: target.strict,
+        prefixes: prefixes.to_vec(),
+        config: config_path.map(Path::to_path_buf),
+    };
+    let (engine, project) = build_engine(&inputs, tier, locale, &current_dir()?)?;
 
-            if tier.has_access(Feature::ReportHtml) {
-                let html_report = engine.generate_html_report(&results);
-                let _ = fs::write("compliance_report.html", html_report);
+    let (name, paths) = match target.dir {
+        Some(dir) => {
+            eprintln!("🔎 {}", tr!(locale, "cli.analyzing_dir", dir = dir));
+            let paths = collect_sources(Path::new(&dir))?;
+            (dir, paths)
+        }
+        None => {
+            let file = target.file.unwrap_or_else(|| "src/main.rs".to_string());
+            eprintln!("🔎 {}", tr!(locale, "cli.analyzing_file", file = file));
+            let path = PathBuf::from(&file);
+            (file, vec![path])
+        }
+    };
+
+    let mut sources = Vec::new();
+    for path in &paths {
+        let content = fs::read_to_string(path).map_err(|e| HubstryError::io(path.clone(), e))?;
+        sources.push((path.display().to_string(), content));
+    }
+
+    Ok(PreparedAnalysis {
+        name,
+        engine,
+        sources,
+        project,
+        inputs,
+    })
+}
+
+/// O que a CLI usa para montar o motor. Os caminhos relativos valem a partir do
+/// diretório passado a [`build_engine`].
+struct EngineInputs {
+    rule_packs: Vec<String>,
+    strict: bool,
+    prefixes: Vec<PathBuf>,
+    config: Option<PathBuf>,
+}
+
+/// Metadados com os arquivos de `--prefixes` e `--config`, para o `verify-report`.
+const PREFIXES_KEY: &str = "config.prefixes";
+const CONFIG_KEY: &str = "config.file";
+const LIST_SEPARATOR: &str = ", ";
+
+/// Monta o motor com os pacotes de regras, a configuração do projeto e o
+/// registro de prefixos, a partir de `base`. Comum à análise, ao `verify-report` e
+/// ao servidor de linguagem.
+fn build_engine(
+    inputs: &EngineInputs,
+    tier: Tier,
+    locale: Locale,
+    base: &Path,
+) -> Result<(SemanticEngine, ProjectConfig), HubstryError> {
+    let rules = load_rules(&inputs.rule_packs, base)?;
+
+    let project = load_project_config(inputs.config.as_deref(), base)?;
+    if !project.custom_rules.is_empty() && !tier.has_access(Feature::CustomRules) {
+        eprintln!("👋 {}", tr!(locale, "cli.upgrade.custom_rules"));
+        return Err(LicenseError::FeatureUnavailable {
+            feature: format!("{:?}", Feature::CustomRules),
+            tier: format!("{:?}", tier),
+        }
+        .into());
+    }
+    let mut config = EngineConfig {
+        strict_mode: inputs.strict,
+        locale,
+        ..EngineConfig::default()
+    };
+    project.apply_to(&mut config);
+
+    let mut registry = load_registry(&inputs.prefixes, base)?;
+    registry.apply_packs(rules.packs())?;
+    let engine = SemanticEngine::with_registry(config, registry).with_rules(rules);
+    Ok((engine, project))
+}
+
+impl PreparedAnalysis {
+    /// Interpreta os arquivos uma vez e os analisa, para os comandos que também
+    /// precisam das ASTs. Os avisos da análise são exibidos.
+    fn analyze_files(&self) -> Result<(Vec<FileAst>, AnalysisResult), HubstryError> {
+        let (files, skipped) = self.engine.load_sources(&self.sources)?;
+        let mut result = self.engine.analyze_project(&files)?;
+        result.warnings.extend(skipped);
+        for warning in &result.warnings {
+            eprintln!("⚠️  {}", warning);
+        }
+        Ok((files, result))
+    }
+}
+
+/// Analisa o arquivo ou diretório de `target` com as regras, os prefixos e a
+/// configuração do projeto, como o `analyze`. Devolve o nome do alvo e o resultado.
+fn analyze_target(
+    target: AnalysisTarget,
+    prefixes: &[PathBuf],
+    config_path: Option<&Path>,
+    tier: Tier,
+    locale: Locale,
+) -> Result<(String, AnalysisResult), HubstryError> {
+    let prepared = prepare_analysis(target, prefixes, config_path, tier, locale)?;
+    let mut result = prepared.engine.analyze_sources(&prepared.sources)?;
+    record_metadata(
+        &mut result,
+        &prepared.project,
+        &prepared.inputs,
+        tier,
+        &current_dir()?,
+    )?;
+    for warning in &result.warnings {
+        eprintln!("⚠️  {}", warning);
+    }
+    Ok((prepared.name, result))
+}
+
+async fn run(cli: Cli, locale: Locale) -> Result<ExitCode, HubstryError> {
+    match cli.command {
+        Commands::Analyze {
+            target,
+            format,
+            output,
+            threshold,
+            no_history,
+            sign_key,
+            sign_cert,
+        } => {
+            let tier = validate_license_key(target.license_key.as_deref());
+
+            let mut formats = Vec::new();
+            for format in format {
+                if formats.contains(&format) {
+                    continue;
+                }
+                if !tier.has_access(report::required_feature(format)) {
+                    println!("👋 {}", upgrade_message(format, locale));
+                    continue;
+                }
+                formats.push(format);
+            }
+            if formats.is_empty() {
+                formats.push(OutputFormat::PlainText);
             }
+            let mut locales = Vec::new();
+            for locale in &cli.locale {
+                if !locales.contains(locale) {
+                    locales.push(*locale);
+                }
+            }
+            if locales.is_empty() {
+                locales.push(locale);
+            }
+            let sign_key = load_sign_key(
+                sign_key.as_deref(),
+                sign_cert.as_deref(),
+                &formats,
+                "analyze --sign-key",
+            )?;
+
+            let (target, results) =
+                analyze_target(target, &cli.prefixes, cli.config.as_deref(), tier, locale)?;
+
+            let trend = if no_history {
+                Vec::new()
+            } else {
+                record_history(locale, |store| {
+                    store.record_analysis(&target, &results, Utc::now())?;
+                    store.trend(RunKind::Analysis, &target)
+                })
+            };
+            write_reports(
+                &formats,
+                &locales,
+                &results,
+                output.as_deref(),
+                &trend,
+                sign_key.as_ref(),
+            )?;
 
             if results.compliance_score < threshold {
                 eprintln!(
-                    "❌ Falha de Compliance: O score de {:.1}% está abaixo do limite mínimo de {:.1}%!",
-                    results.compliance_score, threshold
+                    "❌ {}",
+                    tr!(
+                        locale,
+                        "cli.threshold_failed",
+                        score = format!("{:.1}", results.compliance_score),
+                        threshold = format!("{:.1}", threshold)
+                    )
                 );
-                std::process::exit(1);
+                return Ok(ExitCode::from(1));
             }
         }
         Commands::Scan {
             url,
-            rules: _,
-            format: _,
-            output: _,
+            rules,
+            format,
+            output,
             license_key,
+            no_history,
+            sign_key,
+            sign_cert,
         } => {
-            use hubstry_iso_code::licensing::tier_checker::{validate_license_key, Feature};
             let tier = validate_license_key(license_key.as_deref());
             if !tier.has_access(Feature::WebScanning) {
-                eprintln!("👋 Ops! O Web Scanning é uma funcionalidade disponível a partir do plano Pro (R$497/mês).");
-                std::process::exit(1);
+                eprintln!("👋 {}", tr!(locale, "cli.upgrade.web_scanning"));
+                return Err(LicenseError::FeatureUnavailable {
+                    feature: format!("{:?}", Feature::WebScanning),
+                    tier: format!("{:?}", tier),
+                }
+                .into());
+            }
+
+            if format == OutputFormat::Pdf && !tier.has_access(Feature::ReportPdf) {
+                eprintln!("👋 {}", tr!(locale, "cli.upgrade.pdf"));
+                return Err(LicenseError::FeatureUnavailable {
+                    feature: format!("{:?}", Feature::ReportPdf),
+                    tier: format!("{:?}", tier),
+                }
+                .into());
+            }
+            if !matches!(format, OutputFormat::Json | OutputFormat::Pdf) {
+                return Err(ReportError::UnsupportedFormat {
+                    format: format.to_string(),
+                    command: "scan",
+                }
+                .into());
             }
+            let sign_key = load_sign_key(
+                sign_key.as_deref(),
+                sign_cert.as_deref(),
+                &[format],
+                "scan --sign-key",
+            )?;
 
-            println!("Iniciando Web Scan para URL: {}", url);
+            let rules = load_rules(&rules, &current_dir()?)?;
+
+            println!("{}", tr!(locale, "cli.scan_started", url = url));
             let html = if url.starts_with("http") {
-                let client = reqwest::Client::builder()
-                    .timeout(std::time::Duration::from_secs(30))
-                    .build()?;
-                let res = client.get(&url).send().await?;
-                res.text().await?
+                scanner::fetch_html(&url).await?
             } else {
-                fs::read_to_string(&url)?
+                fs::read_to_string(&url).map_err(|e| HubstryError::io(&url, e))?
             };
 
-            use scanner::WebScanner;
             let dom_scanner = scanner::StaticDomScanner::new();
             let config = scanner::ScanConfig {
                 max_pages: 1,
                 follow_links: false,
                 check_subpages: vec![],
-                rules_path: "".to_string(),
+                rules,
+                scoring: load_project_config(cli.config.as_deref(), &current_dir()?)?.scoring,
+                locale,
             };
 
-            println!("Html length: {} bytes", html.len());
+            println!("{}", tr!(locale, "cli.html_length", bytes = html.len()));
 
-            let res = dom_scanner.scan(&url, &config).await?;
-            println!("{}", serde_json::to_string_pretty(&res)?);
+            let res = dom_scanner.scan_html(&url, &html, &config);
+            if !no_history {
+                record_history(locale, |store| {
+                    store.record_web_scan(&res, Utc::now())?;
+                    Ok(Vec::new())
+                });
+            }
+            let (content, default_path) = match format {
+                OutputFormat::Pdf => (
+                    match &sign_key {
+                        Some(key) => key.sign(&report::web_scan_pdf(&res, locale), Utc::now())?,
+                        None => report::web_scan_pdf(&res, locale),
+                    },
+                    Some(PathBuf::from(format!(
+                        "{}.pdf",
+                        report::DEFAULT_REPORT_NAME
+                    ))),
+                ),
+                _ => (serde_json::to_string_pretty(&res)?, None),
+            };
+            match output.or(default_path) {
+                Some(path) => {
+                    fs::write(&path, &content).map_err(|e| HubstryError::io(&path, e))?;
+                    println!(
+                        "📄 {}",
+                        tr!(
+                            locale,
+                            "cli.report_written",
+                            format = format,
+                            path = path.display()
+                        )
+                    );
+                    if let Some(key) = &sign_key {
+                        print_signed(key, locale);
+                    }
+                }
+                None => println!("{}", content),
+            }
 
-            println!("Detailed scanner analysis complete.");
+            println!("{}", tr!(locale, "cli.scan_complete"));
         }
         Commands::QuickScan {
             url,
             license_key: _,
         } => {
-            println!("Iniciando Quick Scan para URL: {}", url);
+            println!("{}", tr!(locale, "cli.quick_scan_started", url = url));
 
             let res = if url.starts_with("http") {
-                scanner::quick_scan(&url).await?
+                scanner::quick_scan(&url, locale).await?
             } else {
-                let html = fs::read_to_string(&url)?;
-                let age_gate_result = scanner::age_gate_detector::detect_age_gate(&html);
+                let html = fs::read_to_string(&url).map_err(|e| HubstryError::io(&url, e))?;
+                scanner::quick_scan_html(&url, &html, locale)
+            };
+            println!("{}", tr!(locale, "cli.quick_scan_summary"));
+            println!("{}", serde_json::to_string_pretty(&res)?);
+        }
+        Commands::Rules {
+            command: RulesCommands::Lint,
+        } => {
+            let rules = load_rules(&[], &current_dir()?)?;
+            let mut registry = load_registry(&cli.prefixes, &current_dir()?)?;
+            registry.apply_packs(rules.packs())?;
+            let diagnostics = prefix_lint::lint(&registry, &rules);
+            for diagnostic in &diagnostics {
+                println!("{}", diagnostic.text(locale));
+            }
+            if diagnostics.is_empty() {
+                println!("✅ {}", tr!(locale, "cli.lint_clean"));
+            }
+            if prefix_lint::has_errors(&diagnostics) {
+                return Ok(ExitCode::from(1));
+            }
+        }
+        Commands::Rules {
+            command: RulesCommands::List,
+        } => {
+            let cwd = current_dir()?;
+            let packs = pack::available_packs(&cwd)?;
+            let catalog = RuleCatalog::new(&packs, locale);
+            let print_rules = |entries: Vec<&CatalogEntry>| {
+                for entry in entries {
+                    println!(
+                        "    {:<30} {:<8} {}",
+                        entry.id,
+                        entry.severity.to_string(),
+                        entry.summary
+                    );
+                }
+            };
+            for pack in &packs {
+                println!(
+                    "{:<16} {:<10} {} {}  {:<12} {}  ({})",
+                    pack.name(),
+                    pack.version(),
+                    tr!(locale, "cli.pack.effective"),
+                    pack.metadata.effective_date,
+                    pack.metadata.authority,
+                    verification_label(&pack.verification(), locale),
+                    pack.origin.display()
+                );
+                print_rules(catalog.of_pack(Some(&format!("{}@{}", pack.name(), pack.version()))));
+            }
+            let engine_rules = catalog.of_pack(None);
+            println!(
+                "{}",
+                tr!(
+                    locale,
+                    "cli.catalog.engine_rules",
+                    count = engine_rules.len()
+                )
+            );
+            print_rules(engine_rules);
+        }
+        Commands::Rules {
+            command: RulesCommands::Site { output },
+        } => {
+            let packs = pack::available_packs(&current_dir()?)?;
+            let pages = RuleCatalog::new(&packs, locale).site(locale);
+            fs::create_dir_all(&output).map_err(|e| HubstryError::io(output.clone(), e))?;
+            for (name, html) in &pages {
+                let path = output.join(name);
+                fs::write(&path, html).map_err(|e| HubstryError::io(path.clone(), e))?;
+            }
+            println!(
+                "✅ {}",
+                tr!(
+                    locale,
+                    "cli.catalog_written",
+                    count = pages.len(),
+                    path = output.join("index.html").display()
+                )
+            );
+        }
+        Commands::Explain { rule_id, format } => {
+            if !matches!(
+                format,
+                OutputFormat::PlainText | OutputFormat::Markdown | OutputFormat::Json
+            ) {
+                return Err(ReportError::UnsupportedFormat {
+                    format: format.to_string(),
+                    command: "explain",
+                }
+                .into());
+            }
+            let packs = pack::available_packs(&current_dir()?)?;
+            let catalog = RuleCatalog::new(&packs, locale);
+            let Some(entry) = catalog.get(&rule_id) else {
+                let similar = catalog.similar(&rule_id);
+                if !similar.is_empty() {
+                    eprintln!(
+                        "💡 {}",
+                        tr!(locale, "cli.explain_similar", rules = similar.join(", "))
+                    );
+                }
+                return Err(RuleError::UnknownRule { rule_id }.into());
+            };
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(entry)?),
+                OutputFormat::Markdown => print!("{}", entry.to_markdown(locale)),
+                _ => print!("{}", entry.to_text(locale)),
+            }
+        }
+        Commands::Rules {
+            command: RulesCommands::Show { pack: spec },
+        } => {
+            let cwd = current_dir()?;
+            let pack = pack::find(&spec, &cwd)?;
+            let fields = [
+                ("cli.pack.name", pack.name().to_string()),
+                ("cli.pack.version", pack.version().to_string()),
+                (
+                    "cli.pack.effective_date",
+                    pack.metadata.effective_date.to_string(),
+                ),
+                ("cli.pack.authority", pack.metadata.authority.to_string()),
+                ("cli.pack.jurisdiction", pack.jurisdiction.to_string()),
+                ("cli.pack.origin", pack.origin.display().to_string()),
+                (
+                    "cli.pack.checksum",
+                    format!(
+                        "{} ({})",
+                        pack.checksum(),
+                        verification_label(&pack.verification(), locale)
+                    ),
+                ),
+            ];
+            for (key, value) in fields {
+                println!("{:<13}{}", format!("{}:", locale.text(key, &[])), value);
+            }
+            if !pack.rules.is_empty() {
+                println!(
+                    "\n{}:",
+                    tr!(locale, "cli.pack.code_rules", count = pack.rules.len())
+                );
+                for rule in &pack.rules {
+                    println!(
+                        "  {:<28} {:<8} {:<24} {}",
+                        rule.id,
+                        rule.severity.to_string(),
+                        rule.prefix,
+                        locale
+                            .rule_text(&rule.id, "message")
+                            .unwrap_or(&rule.message)
+                    );
+                }
+            }
+            if !pack.web_rules.is_empty() {
+                println!(
+                    "\n{}:",
+                    tr!(locale, "cli.pack.web_rules", count = pack.web_rules.len())
+                );
+                for rule in &pack.web_rules {
+                    println!(
+                        "  {:<28} {:<8} {:<8} {}",
+                        rule.id,
+                        rule.severity.to_string(),
+                        rule.category,
+                        locale
+                            .rule_text(&rule.id, "business_description")
+                            .unwrap_or(&rule.business_description)
+                    );
+                }
+            }
+        }
+        Commands::Rules {
+            command: RulesCommands::Verify { packs },
+        } => {
+            let cwd = current_dir()?;
+            let packs = if packs.is_empty() {
+                pack::available_packs(&cwd)?
+            } else {
+                packs
+                    .iter()
+                    .map(|spec| pack::find(spec, &cwd))
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+            let mut failed = false;
+            for pack in &packs {
+                let verification = pack.verification();
+                println!(
+                    "{}@{}: {}",
+                    pack.name(),
+                    pack.version(),
+                    verification_label(&verification, locale)
+                );
+                if let PackVerification::Mismatch { declared, actual } = verification {
+                    println!(
+                        "    {}: {}\n    {}: {}",
+                        tr!(locale, "cli.checksum.declared"),
+                        declared,
+                        tr!(locale, "cli.checksum.computed"),
+                        actual
+                    );
+                    failed = true;
+                }
+            }
+            if failed {
+                return Ok(ExitCode::from(1));
+            }
+        }
+        Commands::Rules {
+            command: RulesCommands::Install { path },
+        } => {
+            let cwd = current_dir()?;
+            let target = pack::install(&path, &cwd)?;
+            println!(
+                "✅ {}",
+                tr!(locale, "cli.pack_installed", path = target.display())
+            );
+        }
+        Commands::Rules {
+            command: RulesCommands::Checksum { path, write },
+        } => {
+            let content =
+                fs::read_to_string(&path).map_err(|e| HubstryError::io(path.clone(), e))?;
+            println!("{}", pack::compute_checksum(&content));
+            if write {
+                fs::write(&path, pack::with_checksum(&content))
+                    .map_err(|e| HubstryError::io(path.clone(), e))?;
+            }
+        }
+        Commands::VerifyReport {
+            report,
+            license_key,
+        } => {
+            let recorded = RecordedReport::from_path(&report)?;
+            let Some(commit) = recorded.get("git.commit") else {
+                return Err(ReportError::Unverifiable {
+                    message: msg!("error.provenance.no_commit"),
+                }
+                .into());
+            };
+            if recorded.get("git.dirty") == Some("true") {
+                eprintln!("⚠️  {}", tr!(locale, "cli.verify.dirty", commit = commit));
+            }
+
+            println!(
+                "🔁 {}",
+                tr!(locale, "cli.verify.replaying", commit = commit)
+            );
+            let worktree = Worktree::checkout(&current_dir()?, commit)?;
+            let base = worktree
+                .path()
+                .join(recorded.get("git.prefix").unwrap_or_default());
+
+            // Os mesmos pacotes, prefixos e configuração da análise original, lidos
+            // do relatório e não da linha de comando atual.
+            let inputs = EngineInputs {
+                rule_packs: recorded.rule_packs(),
+                strict: recorded.get("config.strict_mode") == Some("true"),
+                prefixes: recorded
+                    .get(PREFIXES_KEY)
+                    .map(|list| list.split(LIST_SEPARATOR).map(|p| base.join(p)).collect())
+                    .unwrap_or_default(),
+                config: recorded.get(CONFIG_KEY).map(|path| base.join(path)),
+            };
+            let tier = validate_license_key(license_key.as_deref());
+            let (engine, project) = build_engine(&inputs, tier, locale, &base)?;
+
+            let mut sources = Vec::new();
+            for path in recorded.files() {
+                if Path::new(path).is_absolute() {
+                    return Err(ReportError::Unverifiable {
+                        message: msg!("error.provenance.absolute_path", path = path),
+                    }
+                    .into());
+                }
+                let full = base.join(path);
+                let content = fs::read_to_string(&full).map_err(|e| HubstryError::io(full, e))?;
+                sources.push((path.to_string(), content));
+            }
 
-                let has_age_verification = age_gate_result.method
-                    != scanner::age_gate_detector::AgeVerificationMethod::None;
-                let verification_method = format!("{:?}", age_gate_result.method);
+            let mut result = engine.analyze_sources(&sources)?;
+            record_metadata(&mut result, &project, &inputs, tier, &base)?;
 
-                let risk_level = match age_gate_result.method {
-                    scanner::age_gate_detector::AgeVerificationMethod::SelfDeclarationOnly => {
-                        "CRITICAL"
+            let differences = provenance::compare(&recorded, &result, locale)?;
+            if !differences.is_empty() {
+                eprintln!(
+                    "❌ {}:",
+                    tr!(locale, "cli.verify.not_reproduced", commit = commit)
+                );
+                for difference in &differences {
+                    eprintln!("  - {}", difference);
+                }
+                return Ok(ExitCode::from(1));
+            }
+            println!(
+                "✅ {}",
+                tr!(
+                    locale,
+                    "cli.verify.reproduced",
+                    score = format!("{:.1}", result.compliance_score),
+                    violations = result.violations.len(),
+                    files = sources.len(),
+                    commit = commit
+                )
+            );
+        }
+        Commands::VerifyPdf { pdf, cert } => {
+            let certificate = report::Certificate::from_pem_file(&cert)?;
+            let content = fs::read(&pdf).map_err(|e| HubstryError::io(&pdf, e))?;
+            report::verify_signature(&content, &certificate)?;
+            println!(
+                "✅ {}",
+                tr!(
+                    locale,
+                    "cli.verify_pdf.valid",
+                    path = pdf.display(),
+                    subject = certificate.subject(),
+                    fingerprint = certificate.fingerprint()
+                )
+            );
+        }
+        Commands::History { dir, limit, format } => {
+            if !dir.join("history.db").exists() {
+                println!("{}", tr!(locale, "cli.history_empty", dir = dir.display()));
+                return Ok(ExitCode::SUCCESS);
+            }
+            let report = HistoryStore::open(&dir)?.report(limit)?;
+            match format {
+                OutputFormat::PlainText => print!("{}", report.to_text(locale)),
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+                _ => {
+                    return Err(ReportError::UnsupportedFormat {
+                        format: format.to_string(),
+                        command: "history",
                     }
-                    scanner::age_gate_detector::AgeVerificationMethod::None => "CRITICAL",
-                    _ => "OK",
+                    .into())
+                }
+            }
+        }
+        Commands::Diff {
+            old,
+            new,
+            format,
+            output,
+        } => {
+            let diff = diff::diff(&diff::load_report(&old)?, &diff::load_report(&new)?);
+            let content = match format {
+                OutputFormat::PlainText => diff.to_text(locale),
+                OutputFormat::Markdown => diff.to_markdown(locale),
+                OutputFormat::Json => format!("{}\n", serde_json::to_string_pretty(&diff)?),
+                _ => {
+                    return Err(ReportError::UnsupportedFormat {
+                        format: format.to_string(),
+                        command: "diff",
+                    }
+                    .into())
+                }
+            };
+            write_output(output.as_deref(), &content, |path| {
+                tr!(
+                    locale,
+                    "cli.diff_written",
+                    format = format,
+                    path = path.display()
+                )
+            })?;
+        }
+        Commands::Dpia {
+            target,
+            report: report_path,
+            project,
+            format,
+            output,
+        } => {
+            if !matches!(format, OutputFormat::Markdown | OutputFormat::Html) {
+                return Err(ReportError::UnsupportedFormat {
+                    format: format.to_string(),
+                    command: "dpia",
+                }
+                .into());
+            }
+            let tier = validate_license_key(target.license_key.as_deref());
+            if !tier.has_access(report::required_feature(format)) {
+                eprintln!("👋 {}", upgrade_message(format, locale));
+                return Err(LicenseError::FeatureUnavailable {
+                    feature: format!("{:?}", report::required_feature(format)),
+                    tier: format!("{:?}", tier),
+                }
+                .into());
+            }
+
+            let result = match report_path {
+                Some(path) => diff::load_report(&path)?,
+                None => {
+                    analyze_target(target, &cli.prefixes, cli.config.as_deref(), tier, locale)?.1
+                }
+            };
+            let name = match project {
+                Some(name) => name,
+                None => current_dir()?
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            };
+            let info = dpia::ProjectInfo {
+                name,
+                version: result
+                    .metadata
+                    .get("git.commit")
+                    .map(|commit| commit.chars().take(12).collect()),
+                date: Utc::now().format("%Y-%m-%d").to_string(),
+            };
+            let dpia = dpia::Dpia::from_analysis(&result, info);
+            let content = match format {
+                OutputFormat::Html => dpia.to_html(locale),
+                _ => dpia.to_markdown(locale),
+            };
+            write_output(output.as_deref(), &content, |path| {
+                tr!(
+                    locale,
+                    "cli.dpia_written",
+                    format = format,
+                    path = path.display()
+                )
+            })?;
+        }
+        Commands::Inventory {
+            target,
+            report: report_path,
+            format,
+            output,
+        } => {
+            if !matches!(
+                format,
+                OutputFormat::Markdown | OutputFormat::Csv | OutputFormat::Json
+            ) {
+                return Err(ReportError::UnsupportedFormat {
+                    format: format.to_string(),
+                    command: "inventory",
+                }
+                .into());
+            }
+            let tier = validate_license_key(target.license_key.as_deref());
+            if !tier.has_access(report::required_feature(format)) {
+                eprintln!("👋 {}", tr!(locale, "cli.upgrade.format", format = format));
+                return Err(LicenseError::FeatureUnavailable {
+                    feature: format!("{:?}", report::required_feature(format)),
+                    tier: format!("{:?}", tier),
+                }
+                .into());
+            }
+
+            let result = match report_path {
+                Some(path) => diff::load_report(&path)?,
+                None => {
+                    analyze_target(target, &cli.prefixes, cli.config.as_deref(), tier, locale)?.1
+                }
+            };
+            let inventory = inventory::Inventory::from_analysis(&result);
+            let content = match format {
+                OutputFormat::Csv => inventory.to_csv(),
+                OutputFormat::Json => inventory.to_json()?,
+                _ => inventory.to_markdown(locale),
+            };
+            write_output(output.as_deref(), &content, |path| {
+                tr!(
+                    locale,
+                    "cli.inventory_written",
+                    format = format,
+                    path = path.display()
+                )
+            })?;
+        }
+        Commands::Graph {
+            target,
+            format,
+            from,
+            prefix,
+            output,
+        } => {
+            let tier = validate_license_key(target.license_key.as_deref());
+            let prepared =
+                prepare_analysis(target, &cli.prefixes, cli.config.as_deref(), tier, locale)?;
+            let (files, result) = prepared.analyze_files()?;
+            let mut graph = prepared.engine.compliance_graph(&files, &result.violations);
+            if !from.is_empty() || !prefix.is_empty() {
+                let mut roots = from.clone();
+                for prefix in &prefix {
+                    roots.extend(graph.annotated_with(prefix));
+                }
+                graph = graph.reachable_from(&roots);
+                if graph.nodes.is_empty() {
+                    let requested: Vec<&String> = from.iter().chain(&prefix).collect();
+                    eprintln!(
+                        "⚠️  {}",
+                        tr!(
+                            locale,
+                            "cli.graph_no_root",
+                            roots = requested
+                                .iter()
+                                .map(|root| format!("'{}'", root))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    );
+                }
+            }
+            write_output(output.as_deref(), &graph.render(format, locale), |path| {
+                tr!(
+                    locale,
+                    "cli.graph_written",
+                    format = format,
+                    path = path.display()
+                )
+            })?;
+        }
+        Commands::Fix {
+            target,
+            dry_run: _,
+            apply,
+            rule_ids,
+            html,
+        } => {
+            let tier = validate_license_key(target.license_key.as_deref());
+            if !html.is_empty() && !tier.has_access(Feature::WebScanning) {
+                eprintln!("👋 {}", tr!(locale, "cli.upgrade.web_scanning"));
+                return Err(LicenseError::FeatureUnavailable {
+                    feature: format!("{:?}", Feature::WebScanning),
+                    tier: format!("{:?}", tier),
+                }
+                .into());
+            }
+            let prepared =
+                prepare_analysis(target, &cli.prefixes, cli.config.as_deref(), tier, locale)?;
+            // `--dry-run` é o padrão; o clap recusa as duas opções juntas.
+            let (files, result) = prepared.analyze_files()?;
+            // Violações suprimidas não são corrigidas.
+            let mut planned: Vec<PlannedFix> = result
+                .violations
+                .iter()
+                .filter_map(|violation| PlannedFix::from_violation(violation, locale))
+                .collect();
+            let annotations = prepared.engine.annotation_fixes(&files, locale);
+            let annotated = !annotations.is_empty();
+            planned.extend(annotations);
+
+            let mut contents = prepared.sources.clone();
+            if !html.is_empty() {
+                let config = scanner::ScanConfig {
+                    max_pages: 1,
+                    follow_links: false,
+                    check_subpages: vec![],
+                    rules: prepared.engine.rules().clone(),
+                    scoring: prepared.project.scoring.clone(),
+                    locale,
                 };
+                for path in &html {
+                    let page = fs::read_to_string(path).map_err(|e| HubstryError::io(path, e))?;
+                    let path = path.display().to_string();
+                    let scan = scanner::StaticDomScanner::new().scan_html(&path, &page, &config);
+                    planned.extend(
+                        scan.violations
+                            .iter()
+                            .filter_map(PlannedFix::from_web_violation),
+                    );
+                    contents.push((path, page));
+                }
+            }
+            if !rule_ids.is_empty() {
+                planned.retain(|fix| rule_ids.contains(&fix.rule_id));
+            }
+            if planned.is_empty() {
+                println!("✅ {}", tr!(locale, "cli.fix_none"));
+                return Ok(ExitCode::SUCCESS);
+            }
 
-                let (summary, recommendation) = match age_gate_result.method {
-                     scanner::age_gate_detector::AgeVerificationMethod::SelfDeclarationOnly => (
-                         "Detectada autodeclaração de idade na página.".to_string(),
-                         "A autodeclaração é proibida pelo ECA Digital. Substitua por verificação oficial via API (Serpro/Gov.br).".to_string()
-                     ),
-                     scanner::age_gate_detector::AgeVerificationMethod::None => (
-                         "Nenhum bloqueio ou verificação de idade foi encontrado.".to_string(),
-                         "Implemente um Age-Gate na entrada da aplicação ou em fluxos de acesso sensíveis.".to_string()
-                     ),
-                     _ => (
-                         "Métodos de verificação oficiais detectados (API/ZKP).".to_string(),
-                         "A verificação está em conformidade. Continue garantindo a proteção aos menores.".to_string()
-                     ),
-                 };
-                scanner::QuickScanResult {
-                    url,
-                    has_age_verification,
-                    verification_method,
-                    risk_level: risk_level.to_string(),
-                    summary,
-                    recommendation,
-                }
-            };
-            println!("Resumo da Avaliação Rápida:");
-            println!("{}", serde_json::to_string_pretty(&res)?);
+            let mut changed_files = 0;
+            for (path, source) in &contents {
+                let fixes: Vec<&PlannedFix> =
+                    planned.iter().filter(|fix| fix.file == *path).collect();
+                if fixes.is_empty() {
+                    continue;
+                }
+                changed_files += 1;
+                for fix in &fixes {
+                    let target = match &fix.function {
+                        Some(function) => format!("{} ({})", path, function),
+                        None => path.clone(),
+                    };
+                    eprintln!("🔧 {} [{}]: {}", target, fix.rule_id, fix.description);
+                }
+                let edits: Vec<TextEdit> = fixes.iter().flat_map(|fix| fix.edits.clone()).collect();
+                let (fixed, applied) = fix::apply(source, &edits);
+                if applied < edits.len() {
+                    eprintln!(
+                        "⚠️  {}",
+                        tr!(
+                            locale,
+                            "cli.fix_skipped",
+                            file = path,
+                            count = edits.len() - applied
+                        )
+                    );
+                }
+                if apply {
+                    fs::write(path, &fixed).map_err(|e| HubstryError::io(path, e))?;
+                    println!(
+                        "📝 {}",
+                        tr!(locale, "cli.fix_applied", file = path, count = applied)
+                    );
+                } else {
+                    print!("{}", fix::unified_diff(path, source, &fixed));
+                }
+            }
+            if !apply {
+                eprintln!(
+                    "💡 {}",
+                    tr!(
+                        locale,
+                        "cli.fix_planned",
+                        count = planned.len(),
+                        files = changed_files
+                    )
+                );
+            } else if annotated {
+                eprintln!("💡 {}", tr!(locale, "cli.fix_rerun"));
+            }
+        }
+        Commands::Lsp {
+            rules,
+            license_key,
+            strict,
+        } => {
+            let tier = validate_license_key(license_key.as_deref());
+            let inputs = EngineInputs {
+                rule_packs: rules,
+                strict,
+                prefixes: cli.prefixes.clone(),
+                config: cli.config.clone(),
+            };
+            let (engine, _) = build_engine(&inputs, tier, locale, &current_dir()?)?;
+            lsp::run(engine, locale)?;
+        }
+        Commands::Config {
+            command: ConfigCommands::Show,
+        } => {
+            let registry = load_registry(&cli.prefixes, &current_dir()?)?;
+            print!("{}", registry.to_yaml());
         }
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
@@ -1,12 +1,16 @@
 //! Data models representing ISO codes, rules, and analysis results.
 //! This module defines the core data structures used throughout the Hubstry-ISO_Code engine.
 
+use crate::i18n::Locale;
+use crate::scoring::{ScoreBreakdown, ScoringConfig};
 use serde::Deserialize;
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::str::FromStr;
 
 /// Represents different legal jurisdictions for compliance.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Jurisdiction {
     Eca,
     Lgpd,
@@ -14,14 +18,74 @@ pub enum Jurisdiction {
 }
 
 /// Represents a compliance rule with its metadata
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ComplianceRule {
     pub id: String,
+    #[serde(default = "generic_jurisdiction")]
     pub jurisdiction: Jurisdiction,
     pub severity: RuleSeverity,
     pub description: String,
+    /// Regular expression matched against `target`.
     pub validation_pattern: Option<String>,
     pub remediation_hint: Option<String>,
+    /// What `validation_pattern` is matched against.
+    #[serde(default)]
+    pub target: PatternTarget,
+    /// Where the rule is evaluated.
+    #[serde(default)]
+    pub scope: RuleScope,
+    /// Whether a match is a violation (`forbid`) or its absence is (`require`).
+    #[serde(default)]
+    pub mode: MatchMode,
+    /// Restricts function-scoped rules to functions annotated with this prefix.
+    pub prefix: Option<String>,
+    /// Category used for the per-category sub-scores (e.g. `consent`).
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Provisions of law the rule enforces, copied to its violations.
+    #[serde(default)]
+    pub citations: Vec<LegalCitation>,
+}
+
+fn generic_jurisdiction() -> Jurisdiction {
+    Jurisdiction::Generic
+}
+
+/// The text a custom rule's pattern is matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternTarget {
+    FunctionName,
+    DocText,
+    #[default]
+    CallNames,
+    StringLiterals,
+    SourceText,
+}
+
+/// Where a custom rule is evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleScope {
+    /// Each function (optionally only those annotated with `prefix`).
+    #[default]
+    Function,
+    /// Each file as a whole.
+    File,
+    /// All analyzed files together.
+    Project,
+}
+
+/// How a custom rule's pattern is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    /// Every match is a violation.
+    #[default]
+    Forbid,
+    /// Having no match in the scope is a violation.
+    Require,
 }
 
 /// Severity levels for compliance rules
@@ -46,33 +110,238 @@ pub struct ComplianceContext {
 use serde::Serialize;
 
 /// Represents a compliance violation found during analysis
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComplianceViolation {
     pub rule_id: String,
     pub severity: RuleSeverity,
     pub message: String,
+    /// File the violation was found in, when known.
+    pub file: Option<String>,
+    /// Function the violation was found in, when it applies to a single function.
+    pub function: Option<String>,
     pub line: Option<usize>,
     pub column: Option<usize>,
+    /// Last line of the offending code, when known (e.g. the end of the function).
+    pub end_line: Option<usize>,
+    /// Column just past the offending code, when known (e.g. the end of a pattern match).
+    pub end_column: Option<usize>,
     pub suggestion: Option<String>,
+    /// Set when the violation was waived by a `hubstry:allow` comment.
+    pub suppression: Option<Suppression>,
+    /// Values of the `{name}` placeholders of the rule's translated message,
+    /// e.g. the unknown `prefix` (see [`crate::i18n`]).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub args: BTreeMap<String, String>,
+    /// Calls that led to the violation, when it was found in the call graph.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_chain: Option<CallChain>,
+    /// Excerpt of the offending code, with the relevant lines highlighted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<Snippet>,
+    /// Provisions of law the violated rule enforces.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<LegalCitation>,
+    /// Machine-applicable change that resolves the violation, when the rule has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<Fix>,
+}
+
+impl ComplianceViolation {
+    /// Identifies the violation regardless of its line, so that it is still recognized
+    /// when the surrounding code changes: SHA-256 of the rule, file and function, plus
+    /// what tells apart violations of the same rule in one function (the placeholder
+    /// values, such as the matched code, and the target and missing calls of the call
+    /// chain). The message is left out, so rewording a rule or changing the locale
+    /// keeps the fingerprint.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        let mut update = |part: &str| {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        };
+        update(&self.rule_id);
+        update(self.file.as_deref().unwrap_or(""));
+        update(self.function.as_deref().unwrap_or(""));
+        for (name, value) in &self.args {
+            update(name);
+            update(value);
+        }
+        if let Some(chain) = &self.call_chain {
+            update(
+                chain
+                    .path
+                    .get(1..)
+                    .and_then(<[String]>::last)
+                    .map_or("", String::as_str),
+            );
+            for missing in &chain.missing {
+                update(missing);
+            }
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// The path through the call graph behind a violation, shown in reports as
+/// `register → save_profile → store (no call to consent on the path)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallChain {
+    /// The annotated function followed by each call on the path to the offending
+    /// call. Just the annotated function when an expected call is missing.
+    pub path: Vec<String>,
+    /// Expected calls that were not found on the path.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing: Vec<String>,
+    /// Line of the first call on the path, in the annotated function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for CallChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path.join(" → "))
+    }
+}
+
+/// Source lines around a violation. Lines are in order; a gap in the numbers means
+/// lines were left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub lines: Vec<SnippetLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnippetLine {
+    /// 1-based line number in the file.
+    pub number: usize,
+    pub text: String,
+    /// Whether the line is the annotation, the offending call or the matched code.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub highlight: bool,
+}
+
+/// A waiver for a rule, written as a doc comment on the function:
+/// `/// hubstry:allow(RULE.ID) reason = "..." approver = "..."`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suppression {
+    pub rule_ids: Vec<String>,
+    pub reason: Option<String>,
+    /// Who approved the waiver. Required in strict mode.
+    pub approver: Option<String>,
 }
 
 /// Configuration for the semantic engine
 #[derive(Debug, Clone)]
 pub struct EngineConfig {
     pub enabled_jurisdictions: Vec<Jurisdiction>,
+    /// Strict mode: unknown prefixes are violations, fallback keyword lists are
+    /// disabled, calls must match keywords exactly, parse failures fail the run and
+    /// suppressions need an approver.
     pub strict_mode: bool,
     pub output_format: OutputFormat,
     pub custom_rules: Vec<ComplianceRule>,
+    /// Severity weights of the compliance score.
+    pub scoring: ScoringConfig,
+    /// Language of the warnings the engine reports.
+    pub locale: Locale,
 }
 
 /// Output formats supported by the engine
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputFormat {
     Json,
     Yaml,
     Xml,
     PlainText,
     Markdown,
+    Html,
+    /// SARIF 2.1.0, for code scanning integrations.
+    Sarif,
+    /// JUnit XML: one test case per annotated function.
+    JUnit,
+    /// Checkstyle XML: violations grouped per file.
+    Checkstyle,
+    /// PDF for the audit file. Never printed to the terminal.
+    Pdf,
+    /// CSV: one row per violation, for spreadsheets.
+    Csv,
+}
+
+impl OutputFormat {
+    pub const ALL: [OutputFormat; 11] = [
+        OutputFormat::Json,
+        OutputFormat::Yaml,
+        OutputFormat::Xml,
+        OutputFormat::PlainText,
+        OutputFormat::Markdown,
+        OutputFormat::Html,
+        OutputFormat::Sarif,
+        OutputFormat::JUnit,
+        OutputFormat::Checkstyle,
+        OutputFormat::Pdf,
+        OutputFormat::Csv,
+    ];
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    /// Accepts the names used by `--format` (`terminal` is an alias for `text`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "yaml" | "yml" => Ok(OutputFormat::Yaml),
+            "xml" => Ok(OutputFormat::Xml),
+            "text" | "txt" | "terminal" => Ok(OutputFormat::PlainText),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            "html" => Ok(OutputFormat::Html),
+            "sarif" => Ok(OutputFormat::Sarif),
+            "junit" => Ok(OutputFormat::JUnit),
+            "checkstyle" => Ok(OutputFormat::Checkstyle),
+            "pdf" => Ok(OutputFormat::Pdf),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => {
+                let known: Vec<String> = Self::ALL.iter().map(ToString::to_string).collect();
+                Err(format!(
+                    "formato desconhecido '{}' (use: {})",
+                    s,
+                    known.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Xml => "xml",
+            OutputFormat::PlainText => "text",
+            OutputFormat::Markdown => "markdown",
+            OutputFormat::Html => "html",
+            OutputFormat::Sarif => "sarif",
+            OutputFormat::JUnit => "junit",
+            OutputFormat::Checkstyle => "checkstyle",
+            OutputFormat::Pdf => "pdf",
+            OutputFormat::Csv => "csv",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Jurisdiction {
+    /// Converts the `standard:` value used in `prefixes.yml` into a jurisdiction.
+    /// Returns `None` for unknown values.
+    pub fn from_standard(standard: &str) -> Option<Self> {
+        match standard {
+            "Eca" => Some(Jurisdiction::Eca),
+            "Lgpd" => Some(Jurisdiction::Lgpd),
+            "Generic" => Some(Jurisdiction::Generic),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Jurisdiction {
@@ -86,13 +355,129 @@ impl fmt::Display for Jurisdiction {
 }
 
 /// Represents the result of a semantic analysis.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisResult {
     pub compliance_score: f64,
+    /// How the score was computed: sub-scores per jurisdiction and category, and the formula.
+    #[serde(default)]
+    pub score_breakdown: ScoreBreakdown,
     pub violations: Vec<ComplianceViolation>,
+    /// Distinct suggestions of the active violations, in order of first occurrence.
+    #[serde(default)]
     pub suggestions: Vec<String>,
+    #[serde(default)]
     pub warnings: Vec<String>,
-    pub metadata: HashMap<String, String>,
+    /// Reproducibility data (engine and rule pack versions, file hashes, git commit...);
+    /// see [`crate::provenance`].
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    /// Pacotes de regras aplicados, para que a auditoria possa ser reproduzida.
+    #[serde(default)]
+    pub rule_packs: Vec<RulePackRef>,
+    /// Violations waived by an accepted suppression; they do not affect the score.
+    #[serde(default)]
+    pub suppressed: Vec<ComplianceViolation>,
+    /// Whether the analysis ran in strict mode.
+    #[serde(default)]
+    pub strict_mode: bool,
+    /// Functions checked against at least one known prefix, whether or not they passed.
+    #[serde(default)]
+    pub checked_functions: Vec<CheckedFunction>,
+}
+
+/// A function annotated with known compliance prefixes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckedFunction {
+    pub file: Option<String>,
+    pub name: String,
+    pub line: usize,
+    pub prefixes: Vec<String>,
+    /// Text of the first checked annotation, after `PREFIX:`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Data-collection keywords of the prefixes that the function reaches in the
+    /// call graph, i.e. the personal data it handles.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub data_collection: Vec<String>,
+    /// Expected calls of the prefixes (consent, age checks, anonymization...) that
+    /// the function reaches in the call graph.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub safeguards: Vec<String>,
+    /// Legal basis declared with `/// hubstry:processing legal_basis = "..."`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legal_basis: Option<String>,
+    /// Purpose declared with `/// hubstry:processing purpose = "..."`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+}
+
+/// Identifies the exact version of a rule pack applied to an analysis.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RulePackRef {
+    pub name: String,
+    pub version: String,
+    pub effective_date: String,
+    /// `sha256:<hex>` of the pack contents.
+    pub checksum: String,
+}
+
+impl fmt::Display for RulePackRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.name, self.version)
+    }
+}
+
+/// A provision of law that a rule enforces, e.g. LGPD Art. 14, § 1º.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LegalCitation {
+    /// Name the law is known by, e.g. `LGPD`.
+    pub law: String,
+    /// Official number, e.g. `Lei nº 13.709/2018`.
+    pub number: String,
+    /// Provision within the law, e.g. `Art. 14, § 1º`.
+    pub article: String,
+    /// Date (YYYY-MM-DD) from which the provision is in force.
+    pub effective_date: String,
+}
+
+/// A machine-applicable change that resolves a violation, applied by `hubstry fix`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fix {
+    /// What the fix does, e.g. "Insert a verify_age guard".
+    pub description: String,
+    /// Edits to the violation's file, in no particular order and never overlapping.
+    pub edits: Vec<TextEdit>,
+}
+
+/// Replaces the text between two positions of a file. An insertion has the same
+/// start and end. Lines are 1-based; columns are 0-based and count characters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub new_text: String,
+}
+
+impl TextEdit {
+    /// Inserts `text` at a position.
+    pub fn insert(line: usize, column: usize, text: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            end_line: line,
+            end_column: column,
+            new_text: text.into(),
+        }
+    }
+}
+
+impl fmt::Display for LegalCitation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}), {}", self.law, self.number, self.article)
+    }
 }
 
 impl fmt::Display for RuleSeverity {
@@ -114,6 +499,8 @@ impl Default for EngineConfig {
             strict_mode: false,
             output_format: OutputFormat::Json,
             custom_rules: Vec::new(),
+            scoring: ScoringConfig::default(),
+            locale: Locale::default(),
         }
     }
 }
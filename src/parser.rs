@@ -1,6 +1,7 @@
 //! Parser module for the Hubstry-ISO_Code framework.
 //! This module now uses the `syn` crate to parse Rust source code into a robust AST.
 
+use crate::error::ParseError;
 use syn::{File, Result};
 
 /// Parses a string of source code into a `syn::File` AST.
@@ -16,6 +17,15 @@ pub fn parse(code: &str) -> Result<File> {
     syn::parse_file(code)
 }
 
+/// Parses the source code of a named file, reporting failures as a [`ParseError`]
+/// that carries the file path.
+pub fn parse_source(path: &str, code: &str) -> std::result::Result<File, ParseError> {
+    syn::parse_file(code).map_err(|source| ParseError::Syntax {
+        path: path.to_string(),
+        source,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -38,4 +48,16 @@ mod tests {
         let result = parse(code);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_source_reports_path_and_code() {
+        let Err(err) = parse_source("src/broken.rs", "fn my_function() {") else {
+            panic!("expected a parse error");
+        };
+        assert!(err.to_string().contains("src/broken.rs"));
+
+        let err = crate::error::HubstryError::from(err);
+        assert_eq!(err.code(), "HUB-PARSE-001");
+        assert_eq!(err.exit_code(), 3);
+    }
 }
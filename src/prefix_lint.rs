@@ -0,0 +1,447 @@
+//! Validação (lint) da configuração de prefixos.
+//!
+//! Detecta problemas que o carregamento aceitaria em silêncio: prefixos
+//! duplicados, valores de `standard:` desconhecidos, prefixos sem regras,
+//! listas de palavras-chave vazias e palavras-chave que satisfazem uma regra ao
+//! mesmo tempo em que disparam outra. Cada diagnóstico aponta a linha do YAML.
+
+use crate::i18n::{Locale, Message};
+use crate::models::Jurisdiction;
+use crate::prefix_manager::{PrefixInfo, PrefixLayer, PrefixRegistry, PrefixSource};
+use crate::rules::{KeywordUsage, RuleSet};
+use crate::{msg, tr};
+use std::collections::HashSet;
+use std::fmt;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+
+/// Gravidade de um diagnóstico de lint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Error,
+    Warning,
+}
+
+impl LintLevel {
+    /// Nome da gravidade no idioma `locale`.
+    pub fn label(self, locale: Locale) -> String {
+        match self {
+            LintLevel::Error => tr!(locale, "lint.level.error"),
+            LintLevel::Warning => tr!(locale, "lint.level.warning"),
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label(Locale::default()))
+    }
+}
+
+/// Um problema encontrado na configuração de prefixos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintDiagnostic {
+    pub level: LintLevel,
+    /// Identificador estável da verificação, ex.: `duplicate-prefix`.
+    pub code: &'static str,
+    pub message: Message,
+    /// Camada (arquivo) onde o problema foi encontrado.
+    pub source: PrefixSource,
+    /// Linha (1-based) no arquivo, quando foi possível localizá-la.
+    pub line: Option<usize>,
+}
+
+impl LintDiagnostic {
+    /// O diagnóstico no idioma `locale`: `arquivo:linha: nível [código] mensagem`.
+    pub fn text(&self, locale: Locale) -> String {
+        let location = match self.line {
+            Some(line) => format!("{}:{}", self.source.label(locale), line),
+            None => self.source.label(locale),
+        };
+        format!(
+            "{}: {} [{}] {}",
+            location,
+            self.level.label(locale),
+            self.code,
+            self.message.text(locale)
+        )
+    }
+}
+
+impl fmt::Display for LintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text(Locale::default()))
+    }
+}
+
+/// Indica se algum diagnóstico é um erro.
+pub fn has_errors(diagnostics: &[LintDiagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.level == LintLevel::Error)
+}
+
+/// Executa todas as verificações sobre as camadas e a configuração efetiva do
+/// registro, considerando as regras que serão aplicadas a cada prefixo.
+pub fn lint(registry: &PrefixRegistry, rules: &RuleSet) -> Vec<LintDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let layers: Vec<(&PrefixSource, Vec<EntrySpan>)> = registry
+        .layer_contents()
+        .map(|(source, content)| (source, lint_layer(source, content, &mut diagnostics)))
+        .collect();
+
+    let effective: Vec<&PrefixInfo> = registry
+        .effective()
+        .jurisdictions
+        .values()
+        .flatten()
+        .collect();
+    let locate = |prefix: &str, keyword: Option<&str>| locate(&layers, prefix, keyword);
+
+    for info in &effective {
+        let Some(jurisdiction) = Jurisdiction::from_standard(&info.standard) else {
+            continue; // Reportado em `lint_layer`.
+        };
+        if jurisdiction == Jurisdiction::Generic {
+            continue; // Reportado em `lint_layer`.
+        }
+        let (source, line) = locate(&info.prefix, None);
+        match rules.keyword_usage(&info.prefix) {
+            None => diagnostics.push(LintDiagnostic {
+                level: LintLevel::Warning,
+                code: "no-validator",
+                message: msg!(
+                    "lint.no_validator",
+                    prefix = info.prefix,
+                    jurisdiction = jurisdiction
+                ),
+                source,
+                line,
+            }),
+            Some(usage) => {
+                for (used, list, name) in [
+                    (usage.expected_calls, &info.expected_calls, "expected_calls"),
+                    (
+                        usage.data_collection_keywords,
+                        &info.data_collection_keywords,
+                        "data_collection_keywords",
+                    ),
+                ] {
+                    if used && list.is_empty() {
+                        diagnostics.push(LintDiagnostic {
+                            level: LintLevel::Warning,
+                            code: "empty-keywords",
+                            message: msg!("lint.empty_keywords", prefix = info.prefix, list = name),
+                            source: source.clone(),
+                            line,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // Uma chamada que satisfaz uma regra (`expected_calls`) não deve também disparar
+    // outra (`data_collection_keywords`), já que a comparação é por substring.
+    for satisfier in &effective {
+        let Some(satisfier_usage) = usage_of(satisfier, rules) else {
+            continue;
+        };
+        if !satisfier_usage.expected_calls {
+            continue;
+        }
+        for trigger in &effective {
+            let Some(trigger_usage) = usage_of(trigger, rules) else {
+                continue;
+            };
+            if !trigger_usage.data_collection_keywords {
+                continue;
+            }
+            for expected in &satisfier.expected_calls {
+                for keyword in &trigger.data_collection_keywords {
+                    if !keywords_overlap(expected, keyword) {
+                        continue;
+                    }
+                    let (source, line) = locate(&satisfier.prefix, Some(expected));
+                    diagnostics.push(LintDiagnostic {
+                        level: LintLevel::Warning,
+                        code: "keyword-overlap",
+                        message: msg!(
+                            "lint.keyword_overlap",
+                            expected = expected,
+                            satisfier = satisfier.prefix,
+                            keyword = keyword,
+                            trigger = trigger.prefix
+                        ),
+                        source,
+                        line,
+                    });
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Verificações que dependem do conteúdo bruto de uma camada. Devolve as entradas
+/// da camada, com as suas linhas, para localizar os demais diagnósticos.
+fn lint_layer(
+    source: &PrefixSource,
+    content: &str,
+    diagnostics: &mut Vec<LintDiagnostic>,
+) -> Vec<EntrySpan> {
+    if serde_yaml::from_str::<PrefixLayer>(content).is_err() {
+        return Vec::new(); // O registro não teria sido carregado; nada a verificar.
+    }
+    let entries = entry_spans(content);
+
+    let mut seen = HashSet::new();
+    for entry in &entries {
+        let line = Some(entry.line);
+        if !seen.insert(entry.prefix.as_str()) {
+            diagnostics.push(LintDiagnostic {
+                level: LintLevel::Error,
+                code: "duplicate-prefix",
+                message: msg!("lint.duplicate_prefix", prefix = entry.prefix),
+                source: source.clone(),
+                line,
+            });
+        }
+
+        if let Some(standard) = &entry.standard {
+            match Jurisdiction::from_standard(standard) {
+                None => diagnostics.push(LintDiagnostic {
+                    level: LintLevel::Error,
+                    code: "unknown-standard",
+                    message: msg!(
+                        "lint.unknown_standard",
+                        prefix = entry.prefix,
+                        standard = standard
+                    ),
+                    source: source.clone(),
+                    line,
+                }),
+                Some(Jurisdiction::Generic) => diagnostics.push(LintDiagnostic {
+                    level: LintLevel::Warning,
+                    code: "no-validator",
+                    message: msg!("lint.generic_standard", prefix = entry.prefix),
+                    source: source.clone(),
+                    line,
+                }),
+                Some(_) => {}
+            }
+        }
+    }
+    entries
+}
+
+fn usage_of(info: &PrefixInfo, rules: &RuleSet) -> Option<KeywordUsage> {
+    match Jurisdiction::from_standard(&info.standard)? {
+        Jurisdiction::Generic => None,
+        _ => rules.keyword_usage(&info.prefix),
+    }
+}
+
+fn keywords_overlap(a: &str, b: &str) -> bool {
+    let (a, b) = (a.to_lowercase(), b.to_lowercase());
+    a.contains(&b) || b.contains(&a)
+}
+
+/// Localiza a definição mais alta de um prefixo (ou de uma palavra-chave dentro dele).
+fn locate(
+    layers: &[(&PrefixSource, Vec<EntrySpan>)],
+    prefix: &str,
+    keyword: Option<&str>,
+) -> (PrefixSource, Option<usize>) {
+    // As definições do prefixo numa camada, da última para a primeira.
+    fn definitions<'a>(
+        entries: &'a [EntrySpan],
+        prefix: &'a str,
+    ) -> impl Iterator<Item = &'a EntrySpan> {
+        entries
+            .iter()
+            .rev()
+            .filter(move |entry| entry.prefix == prefix)
+    }
+
+    if let Some(keyword) = keyword {
+        for (source, entries) in layers.iter().rev() {
+            let line = definitions(entries, prefix).find_map(|entry| {
+                entry
+                    .items
+                    .iter()
+                    .find(|(item, _)| item == keyword)
+                    .map(|(_, line)| *line)
+            });
+            if line.is_some() {
+                return ((*source).clone(), line);
+            }
+        }
+    }
+
+    // A palavra-chave pode vir de uma camada inferior à que menciona o prefixo.
+    layers
+        .iter()
+        .rev()
+        .find_map(|(source, entries)| {
+            let entry = definitions(entries, prefix).next()?;
+            Some(((*source).clone(), Some(entry.line)))
+        })
+        .unwrap_or_else(|| {
+            let source = layers
+                .last()
+                .map_or(PrefixSource::Embedded, |(s, _)| (*s).clone());
+            (source, None)
+        })
+}
+
+/// Uma entrada `- prefix: ...` de uma camada, na ordem do arquivo, com as linhas
+/// (1-based) informadas pelo parser YAML.
+struct EntrySpan {
+    prefix: String,
+    line: usize,
+    standard: Option<String>,
+    /// Itens das listas da entrada (`expected_calls`, `data_collection_keywords`...).
+    items: Vec<(String, usize)>,
+}
+
+fn entry_spans(content: &str) -> Vec<EntrySpan> {
+    let mut tree = TreeBuilder::default();
+    if Parser::new_from_str(content)
+        .load(&mut tree, false)
+        .is_err()
+    {
+        return Vec::new();
+    }
+    let Some(root) = tree.root else {
+        return Vec::new();
+    };
+    let Some(jurisdictions) = root.get("jurisdictions") else {
+        return Vec::new();
+    };
+    jurisdictions
+        .values()
+        .flat_map(Marked::items)
+        .filter_map(|entry| {
+            let prefix = entry.get("prefix")?;
+            let items = entry
+                .values()
+                .flat_map(Marked::items)
+                .filter_map(|item| Some((item.as_str()?.to_string(), item.line)))
+                .collect();
+            Some(EntrySpan {
+                prefix: prefix.as_str()?.to_string(),
+                line: prefix.line,
+                standard: entry
+                    .get("standard")
+                    .and_then(Marked::as_str)
+                    .map(str::to_string),
+                items,
+            })
+        })
+        .collect()
+}
+
+/// Nó do YAML com a linha em que começa.
+struct Marked {
+    node: Node,
+    line: usize,
+}
+
+enum Node {
+    Scalar(String),
+    Seq(Vec<Marked>),
+    Map(Vec<(Marked, Marked)>),
+}
+
+impl Marked {
+    fn as_str(&self) -> Option<&str> {
+        match &self.node {
+            Node::Scalar(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Marked> {
+        match &self.node {
+            Node::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| k.as_str() == Some(key))
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Os valores de um mapeamento, na ordem do arquivo.
+    fn values(&self) -> impl Iterator<Item = &Marked> {
+        let entries: &[(Marked, Marked)] = match &self.node {
+            Node::Map(entries) => entries,
+            _ => &[],
+        };
+        entries.iter().map(|(_, value)| value)
+    }
+
+    fn items(&self) -> &[Marked] {
+        match &self.node {
+            Node::Seq(items) => items,
+            _ => &[],
+        }
+    }
+}
+
+/// Monta a árvore de [`Marked`] a partir dos eventos do parser.
+#[derive(Default)]
+struct TreeBuilder {
+    stack: Vec<Frame>,
+    root: Option<Marked>,
+}
+
+enum Frame {
+    Seq(Vec<Marked>, usize),
+    /// Pares já lidos e a chave que aguarda o seu valor.
+    Map(Vec<(Marked, Marked)>, Option<Marked>, usize),
+}
+
+impl MarkedEventReceiver for TreeBuilder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let line = mark.line();
+        let node = match event {
+            Event::Scalar(value, ..) => Marked {
+                node: Node::Scalar(value),
+                line,
+            },
+            // Âncoras não são resolvidas; o alias só ocupa o seu lugar.
+            Event::Alias(_) => Marked {
+                node: Node::Scalar(String::new()),
+                line,
+            },
+            Event::SequenceStart(..) => {
+                self.stack.push(Frame::Seq(Vec::new(), line));
+                return;
+            }
+            Event::MappingStart(..) => {
+                self.stack.push(Frame::Map(Vec::new(), None, line));
+                return;
+            }
+            Event::SequenceEnd | Event::MappingEnd => match self.stack.pop() {
+                Some(Frame::Seq(items, line)) => Marked {
+                    node: Node::Seq(items),
+                    line,
+                },
+                Some(Frame::Map(entries, _, line)) => Marked {
+                    node: Node::Map(entries),
+                    line,
+                },
+                None => return,
+            },
+            _ => return,
+        };
+        match self.stack.last_mut() {
+            Some(Frame::Seq(items, _)) => items.push(node),
+            Some(Frame::Map(entries, key, _)) => match key.take() {
+                Some(key) => entries.push((key, node)),
+                None => *key = Some(node),
+            },
+            None => self.root = Some(node),
+        }
+    }
+}
@@ -1,13 +1,40 @@
 //! Módulo para gerenciar o carregamento de prefixos de conformidade a partir de arquivos de configuração.
+//!
+//! A configuração pode ser composta por camadas: os padrões embutidos, a política
+//! da organização e os ajustes de cada projeto. Um arquivo declara seu pai com a
+//! chave `extends:` (um caminho relativo ao próprio arquivo, ou `builtin` para os
+//! padrões embutidos), e cada entrada escolhe como se combina com a camada anterior
+//! através de `merge:`:
+//!
+//! - `append` (padrão): acrescenta `expected_calls` e `data_collection_keywords`,
+//!   e remove as palavras listadas em `remove_expected_calls` e
+//!   `remove_data_collection_keywords`;
+//! - `replace`: substitui a entrada inteira;
+//! - `remove`: remove o prefixo da configuração efetiva.
 
-use serde::Deserialize;
-use std::collections::HashMap;
-use std::error::Error;
-use std::fmt;
+use crate::error::{ConfigError, HubstryError};
+use crate::i18n::Locale;
+use crate::rules::RulePack;
+use crate::{msg, tr};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::sync::OnceLock;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-#[derive(Debug, Deserialize, Clone)]
+/// Nome do arquivo de prefixos procurado por [`PrefixRegistry::discover`].
+pub const PREFIXES_FILE_NAME: &str = "prefixes.yml";
+
+/// Conteúdo padrão de `prefixes.yml`, embutido no binário.
+pub const EMBEDDED_PREFIXES: &str = include_str!("../prefixes.yml");
+
+/// Valor de `extends:` que aponta para os padrões embutidos.
+pub const BUILTIN_LAYER: &str = "builtin";
+
+/// Caminho usado nos erros de um registro criado a partir de uma string YAML.
+const INLINE_PATH: &str = "<yaml>";
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PrefixInfo {
     pub prefix: String,
     pub description: String,
@@ -18,67 +45,480 @@ pub struct PrefixInfo {
     pub data_collection_keywords: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Configuração efetiva de prefixos, agrupada por jurisdição.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct Jurisdictions {
-    pub jurisdictions: HashMap<String, Vec<PrefixInfo>>,
+    pub jurisdictions: BTreeMap<String, Vec<PrefixInfo>>,
 }
 
-/// Enum para erros personalizados no gerenciamento de prefixos.
-#[derive(Debug)]
-pub enum PrefixError {
-    Io(std::io::Error),
-    Parse(serde_yaml::Error),
+/// Como uma entrada de uma camada se combina com a configuração anterior.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMode {
+    #[default]
+    Append,
+    Replace,
+    Remove,
 }
 
-impl fmt::Display for PrefixError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+/// Uma entrada de prefixo como escrita em um arquivo de camada.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PrefixEntry {
+    pub prefix: String,
+    #[serde(default)]
+    pub merge: MergeMode,
+    pub description: Option<String>,
+    pub standard: Option<String>,
+    #[serde(default)]
+    pub expected_calls: Vec<String>,
+    #[serde(default)]
+    pub data_collection_keywords: Vec<String>,
+    #[serde(default)]
+    pub remove_expected_calls: Vec<String>,
+    #[serde(default)]
+    pub remove_data_collection_keywords: Vec<String>,
+}
+
+/// Um arquivo de prefixos, possivelmente estendendo outro.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PrefixLayer {
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub extends: Vec<String>,
+    #[serde(default)]
+    pub jurisdictions: BTreeMap<String, Vec<PrefixEntry>>,
+}
+
+/// Origem de uma camada de um [`PrefixRegistry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrefixSource {
+    /// O `prefixes.yml` padrão embutido no binário.
+    Embedded,
+    /// Um arquivo no disco.
+    File(PathBuf),
+    /// Uma string YAML fornecida em memória.
+    Inline,
+    /// A seção `prefixes:` de um pacote de regras.
+    Pack(String),
+}
+
+impl PrefixSource {
+    /// Nome da camada no idioma `locale`: o caminho do arquivo ou uma descrição
+    /// entre `<>`.
+    pub fn label(&self, locale: Locale) -> String {
         match self {
-            PrefixError::Io(e) => write!(f, "Falha de I/O ao ler 'prefixes.yml': {}", e),
-            PrefixError::Parse(e) => {
-                write!(f, "Falha ao analisar o conteúdo de 'prefixes.yml': {}", e)
-            }
+            PrefixSource::Embedded => tr!(locale, "prefixes.source.embedded"),
+            PrefixSource::File(path) => path.display().to_string(),
+            PrefixSource::Inline => tr!(locale, "prefixes.source.inline"),
+            PrefixSource::Pack(name) => tr!(locale, "prefixes.source.pack", name = name),
         }
     }
 }
 
-impl Error for PrefixError {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
-        match self {
-            PrefixError::Io(e) => Some(e),
-            PrefixError::Parse(e) => Some(e),
-        }
+impl std::fmt::Display for PrefixSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.label(Locale::default()))
     }
 }
 
-/// Carrega os prefixos do arquivo de configuração `prefixes.yml`.
-/// Usa um OnceLock para garantir que o arquivo seja lido e analisado apenas uma vez.
-/// Retorna um Result, pois a operação pode falhar.
-pub fn try_get_prefix_map() -> Result<&'static HashMap<String, PrefixInfo>, PrefixError> {
-    static PREFIX_MAP: OnceLock<HashMap<String, PrefixInfo>> = OnceLock::new();
+/// Conjunto de prefixos de conformidade usado por um `SemanticEngine`.
+///
+/// Cada engine possui o seu próprio registro, de modo que várias engines com
+/// regras diferentes podem coexistir no mesmo processo.
+#[derive(Debug, Clone)]
+pub struct PrefixRegistry {
+    effective: Jurisdictions,
+    prefixes: HashMap<String, PrefixInfo>,
+    /// Camadas aplicadas, da base para o topo.
+    layers: Vec<PrefixSource>,
+    /// Conteúdo YAML de cada camada, na mesma ordem de `layers`.
+    layer_contents: Vec<String>,
+    /// O que foi pedido ao carregar; usado por [`PrefixRegistry::reload`].
+    roots: Vec<PrefixSource>,
+    /// Pacotes aplicados com [`PrefixRegistry::apply_pack`]; reaplicados por `reload`.
+    packs: Vec<RulePack>,
+    modified: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl PrefixRegistry {
+    fn empty() -> Self {
+        PrefixRegistry {
+            effective: Jurisdictions::default(),
+            prefixes: HashMap::new(),
+            layers: Vec::new(),
+            layer_contents: Vec::new(),
+            roots: Vec::new(),
+            packs: Vec::new(),
+            modified: Vec::new(),
+        }
+    }
+
+    /// Carrega o `prefixes.yml` padrão embutido no binário.
+    pub fn embedded() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .apply_embedded()
+            .expect("o prefixes.yml embutido deve ser válido");
+        registry.roots.push(PrefixSource::Embedded);
+        registry.reindex();
+        registry
+    }
+
+    /// Carrega os prefixos de um arquivo YAML, incluindo as camadas que ele estende.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, HubstryError> {
+        Self::from_layers([path.as_ref()])
+    }
+
+    /// Carrega vários arquivos de prefixos em ordem; cada arquivo é aplicado sobre
+    /// o resultado dos anteriores.
+    pub fn from_layers<I, P>(paths: I) -> Result<Self, HubstryError>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut registry = Self::empty();
+        for path in paths {
+            let path = path.as_ref();
+            registry.apply_file(path, &mut Vec::new())?;
+            registry.roots.push(PrefixSource::File(path.to_path_buf()));
+        }
+        registry.reindex();
+        Ok(registry)
+    }
 
-    // Tenta obter o valor. Se já estiver inicializado, retorna.
-    if let Some(map) = PREFIX_MAP.get() {
-        return Ok(map);
+    /// Carrega os prefixos a partir de uma string YAML em memória. Caminhos em
+    /// `extends:` são relativos ao diretório atual.
+    pub fn from_yaml_str(yaml: &str) -> Result<Self, HubstryError> {
+        let mut registry = Self::empty();
+        let layer = parse_layer(yaml, Path::new(INLINE_PATH))?;
+        registry.apply_layer(
+            layer,
+            Path::new("."),
+            Path::new(INLINE_PATH),
+            &mut Vec::new(),
+        )?;
+        registry.layers.push(PrefixSource::Inline);
+        registry.layer_contents.push(yaml.to_string());
+        registry.roots.push(PrefixSource::Inline);
+        registry.reindex();
+        Ok(registry)
     }
 
-    // Se não estiver inicializado, executa a lógica de inicialização.
-    let file_content = fs::read_to_string("prefixes.yml").map_err(PrefixError::Io)?;
-    let config: Jurisdictions = serde_yaml::from_str(&file_content).map_err(PrefixError::Parse)?;
+    /// Procura um `prefixes.yml` no diretório informado e em seus ancestrais.
+    /// Se nenhum arquivo for encontrado, usa o padrão embutido.
+    pub fn discover(start_dir: impl AsRef<Path>) -> Result<Self, HubstryError> {
+        for dir in start_dir.as_ref().ancestors() {
+            let candidate = dir.join(PREFIXES_FILE_NAME);
+            if candidate.is_file() {
+                return Self::from_path(candidate);
+            }
+        }
+        Ok(Self::embedded())
+    }
 
-    let mut map = HashMap::new();
-    for (_, prefixes) in config.jurisdictions {
-        for prefix_info in prefixes {
-            map.insert(prefix_info.prefix.clone(), prefix_info);
+    /// Aplica os prefixos de um pacote de regras como uma nova camada no topo.
+    /// Pacotes sem `prefixes:` não alteram o registro.
+    pub fn apply_pack(&mut self, pack: &RulePack) -> Result<(), HubstryError> {
+        let Some(content) = pack.prefix_layer() else {
+            return Ok(());
+        };
+        let layer = parse_layer(&content, &pack.origin)?;
+        if !layer.extends.is_empty() {
+            return Err(ConfigError::Invalid {
+                path: pack.origin.clone(),
+                message: msg!("error.prefixes.pack_extends"),
+            }
+            .into());
         }
+        self.apply_layer(layer, Path::new("."), &pack.origin, &mut Vec::new())?;
+        self.layers
+            .push(PrefixSource::Pack(pack.name().to_string()));
+        self.layer_contents.push(content);
+        self.packs.push(pack.clone());
+        self.reindex();
+        Ok(())
     }
 
-    // Tenta inserir o mapa no OnceLock. Se falhar, significa que outra thread
-    // inicializou o valor enquanto estávamos trabalhando. Isso é seguro.
-    // Ignoramos o resultado de `set` porque, em qualquer caso, o valor estará presente.
-    let _ = PREFIX_MAP.set(map);
+    /// Aplica os prefixos de todos os pacotes, na ordem recebida.
+    pub fn apply_packs<'a>(
+        &mut self,
+        packs: impl IntoIterator<Item = &'a RulePack>,
+    ) -> Result<(), HubstryError> {
+        for pack in packs {
+            self.apply_pack(pack)?;
+        }
+        Ok(())
+    }
+
+    fn apply_embedded(&mut self) -> Result<(), HubstryError> {
+        let layer = parse_layer(EMBEDDED_PREFIXES, Path::new(BUILTIN_LAYER))?;
+        self.apply_layer(
+            layer,
+            Path::new("."),
+            Path::new(BUILTIN_LAYER),
+            &mut Vec::new(),
+        )?;
+        self.layers.push(PrefixSource::Embedded);
+        self.layer_contents.push(EMBEDDED_PREFIXES.to_string());
+        Ok(())
+    }
+
+    /// Aplica um arquivo e, antes dele, as camadas de `extends:`.
+    /// `chain` guarda os arquivos em processamento para detectar ciclos.
+    fn apply_file(&mut self, path: &Path, chain: &mut Vec<PathBuf>) -> Result<(), HubstryError> {
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if chain.contains(&canonical) {
+            return Err(ConfigError::Invalid {
+                path: path.to_path_buf(),
+                message: msg!("error.prefixes.extends_cycle"),
+            }
+            .into());
+        }
+
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let layer = parse_layer(&content, path)?;
+        let base_dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+
+        chain.push(canonical);
+        self.apply_layer(layer, &base_dir, path, chain)?;
+        chain.pop();
+
+        self.layers.push(PrefixSource::File(path.to_path_buf()));
+        self.layer_contents.push(content);
+        self.modified
+            .push((path.to_path_buf(), modified_time(path)));
+        Ok(())
+    }
+
+    fn apply_layer(
+        &mut self,
+        layer: PrefixLayer,
+        base_dir: &Path,
+        path: &Path,
+        chain: &mut Vec<PathBuf>,
+    ) -> Result<(), HubstryError> {
+        for parent in &layer.extends {
+            if parent == BUILTIN_LAYER {
+                self.apply_embedded()?;
+            } else {
+                self.apply_file(&base_dir.join(parent), chain)?;
+            }
+        }
+
+        for (jurisdiction, entries) in layer.jurisdictions {
+            for entry in entries {
+                self.apply_entry(&jurisdiction, entry, path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_entry(
+        &mut self,
+        jurisdiction: &str,
+        entry: PrefixEntry,
+        path: &Path,
+    ) -> Result<(), HubstryError> {
+        let existing = self
+            .effective
+            .jurisdictions
+            .iter_mut()
+            .find_map(|(_, infos)| infos.iter_mut().find(|info| info.prefix == entry.prefix));
+
+        match (entry.merge, existing) {
+            (MergeMode::Remove, _) => {
+                for infos in self.effective.jurisdictions.values_mut() {
+                    infos.retain(|info| info.prefix != entry.prefix);
+                }
+            }
+            (MergeMode::Append, Some(info)) => {
+                if let Some(description) = entry.description {
+                    info.description = description;
+                }
+                if let Some(standard) = entry.standard {
+                    info.standard = standard;
+                }
+                append_unique(&mut info.expected_calls, entry.expected_calls);
+                append_unique(
+                    &mut info.data_collection_keywords,
+                    entry.data_collection_keywords,
+                );
+                info.expected_calls
+                    .retain(|kw| !entry.remove_expected_calls.contains(kw));
+                info.data_collection_keywords
+                    .retain(|kw| !entry.remove_data_collection_keywords.contains(kw));
+            }
+            (MergeMode::Replace, Some(info)) => {
+                *info = PrefixInfo {
+                    prefix: entry.prefix,
+                    description: entry.description.unwrap_or(info.description.clone()),
+                    standard: entry.standard.unwrap_or(info.standard.clone()),
+                    expected_calls: entry.expected_calls,
+                    data_collection_keywords: entry.data_collection_keywords,
+                };
+            }
+            (_, None) => {
+                let (Some(description), Some(standard)) = (entry.description, entry.standard)
+                else {
+                    return Err(ConfigError::Invalid {
+                        path: path.to_path_buf(),
+                        message: msg!("error.prefixes.new_prefix_fields", prefix = entry.prefix),
+                    }
+                    .into());
+                };
+                self.effective
+                    .jurisdictions
+                    .entry(jurisdiction.to_string())
+                    .or_default()
+                    .push(PrefixInfo {
+                        prefix: entry.prefix,
+                        description,
+                        standard,
+                        expected_calls: entry.expected_calls,
+                        data_collection_keywords: entry.data_collection_keywords,
+                    });
+            }
+        }
+        Ok(())
+    }
+
+    fn reindex(&mut self) {
+        self.effective
+            .jurisdictions
+            .retain(|_, infos| !infos.is_empty());
+        self.prefixes = self
+            .effective
+            .jurisdictions
+            .values()
+            .flatten()
+            .map(|info| (info.prefix.clone(), info.clone()))
+            .collect();
+    }
+
+    /// Busca as informações de um prefixo.
+    pub fn get(&self, prefix: &str) -> Option<&PrefixInfo> {
+        self.prefixes.get(prefix)
+    }
+
+    /// Itera sobre todos os prefixos registrados.
+    pub fn iter(&self) -> impl Iterator<Item = &PrefixInfo> {
+        self.prefixes.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.prefixes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty()
+    }
+
+    /// Origem da camada mais alta (a que foi aplicada por último).
+    pub fn source(&self) -> &PrefixSource {
+        self.layers.last().unwrap_or(&PrefixSource::Embedded)
+    }
+
+    /// Todas as camadas aplicadas, da base para o topo.
+    pub fn layers(&self) -> &[PrefixSource] {
+        &self.layers
+    }
+
+    /// Cada camada aplicada junto com o seu conteúdo YAML, da base para o topo.
+    pub fn layer_contents(&self) -> impl Iterator<Item = (&PrefixSource, &str)> {
+        self.layers
+            .iter()
+            .zip(self.layer_contents.iter().map(String::as_str))
+    }
+
+    /// A configuração efetiva após a combinação de todas as camadas.
+    pub fn effective(&self) -> &Jurisdictions {
+        &self.effective
+    }
+
+    /// Serializa a configuração efetiva em YAML, precedida das camadas aplicadas.
+    pub fn to_yaml(&self) -> String {
+        let mut out = String::from("# Configuração efetiva de prefixos. Camadas aplicadas:\n");
+        for (i, layer) in self.layers.iter().enumerate() {
+            out.push_str(&format!("#   {}. {}\n", i + 1, layer));
+        }
+        out.push_str(&serde_yaml::to_string(&self.effective).unwrap_or_default());
+        out
+    }
+
+    /// Indica se algum arquivo de origem foi modificado desde o carregamento.
+    /// Registros embutidos ou em memória nunca ficam desatualizados.
+    pub fn is_stale(&self) -> bool {
+        self.modified
+            .iter()
+            .any(|(path, modified)| modified_time(path) != *modified)
+    }
+
+    /// Relê os arquivos de origem. Para registros embutidos ou em memória, não faz nada.
+    pub fn reload(&mut self) -> Result<(), HubstryError> {
+        let files: Vec<PathBuf> = self
+            .roots
+            .iter()
+            .filter_map(|root| match root {
+                PrefixSource::File(path) => Some(path.clone()),
+                _ => None,
+            })
+            .collect();
+        if !files.is_empty() {
+            let packs = self.packs.clone();
+            let mut reloaded = Self::from_layers(files)?;
+            reloaded.apply_packs(&packs)?;
+            *self = reloaded;
+        }
+        Ok(())
+    }
+}
+
+impl Default for PrefixRegistry {
+    fn default() -> Self {
+        Self::embedded()
+    }
+}
+
+fn parse_layer(content: &str, path: &Path) -> Result<PrefixLayer, HubstryError> {
+    serde_yaml::from_str(content).map_err(|source| {
+        ConfigError::Yaml {
+            path: path.to_path_buf(),
+            source,
+        }
+        .into()
+    })
+}
+
+fn append_unique(target: &mut Vec<String>, items: Vec<String>) {
+    for item in items {
+        if !target.contains(&item) {
+            target.push(item);
+        }
+    }
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(item) => vec![item],
+        OneOrMany::Many(items) => items,
+    })
+}
 
-    // Agora, o valor com certeza está inicializado, então podemos usar `get().unwrap()`.
-    Ok(PREFIX_MAP.get().unwrap())
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
 }
 
 // A função `is_compliance_prefix` foi removida, pois sua lógica foi centralizada
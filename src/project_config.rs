@@ -0,0 +1,98 @@
+//! Configuração do projeto (`hubstry.yml`).
+//!
+//! O arquivo é procurado no diretório atual e em seus ancestrais, como o
+//! `prefixes.yml`, e define o modo estrito e as regras personalizadas do projeto:
+//!
+//! ```yaml
+//! strict_mode: true
+//! custom_rules:
+//!   - id: "ACME.NO_RAW_CPF"
+//!     severity: High
+//!     description: "CPF não pode ser registrado em log."
+//!     validation_pattern: "log_cpf|print_cpf"
+//!     target: call_names
+//!     scope: function
+//!     mode: forbid
+//!     remediation_hint: "Use `mask_cpf()` antes de registrar."
+//!     category: data
+//! scoring:
+//!   weights:
+//!     critical: 20
+//!     info: 0
+//! ```
+
+use crate::error::{ConfigError, HubstryError};
+use crate::models::{ComplianceRule, EngineConfig};
+use crate::rules::custom;
+use crate::scoring::ScoringConfig;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Nome do arquivo procurado por [`ProjectConfig::discover`].
+pub const PROJECT_CONFIG_FILE: &str = "hubstry.yml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Regras personalizadas, avaliadas além das regras dos pacotes.
+    #[serde(default)]
+    pub custom_rules: Vec<ComplianceRule>,
+    /// Ativa o modo estrito (ver `EngineConfig.strict_mode`).
+    #[serde(default)]
+    pub strict_mode: bool,
+    /// Pesos das severidades no score (ver [`crate::scoring`]).
+    #[serde(default)]
+    pub scoring: ScoringConfig,
+    /// Arquivo de origem, ou `None` quando nenhum arquivo foi encontrado.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+impl ProjectConfig {
+    /// Carrega e valida um arquivo de configuração.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, HubstryError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let mut config: ProjectConfig =
+            serde_yaml::from_str(&content).map_err(|source| ConfigError::Yaml {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        custom::compile(&config.custom_rules)?;
+        config
+            .scoring
+            .weights
+            .validate()
+            .map_err(|message| ConfigError::Invalid {
+                path: path.to_path_buf(),
+                message,
+            })?;
+        config.path = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Procura um `hubstry.yml` no diretório informado e em seus ancestrais. Sem
+    /// arquivo, devolve a configuração vazia.
+    pub fn discover(start_dir: impl AsRef<Path>) -> Result<Self, HubstryError> {
+        for dir in start_dir.as_ref().ancestors() {
+            let candidate = dir.join(PROJECT_CONFIG_FILE);
+            if candidate.is_file() {
+                return Self::from_path(candidate);
+            }
+        }
+        Ok(Self::default())
+    }
+
+    /// Copia para a configuração da engine o que o projeto define.
+    pub fn apply_to(&self, config: &mut EngineConfig) {
+        config.strict_mode |= self.strict_mode;
+        config.scoring = self.scoring.clone();
+        config
+            .custom_rules
+            .extend(self.custom_rules.iter().cloned());
+    }
+}
@@ -0,0 +1,385 @@
+//! Dados de reprodutibilidade de uma análise e verificação de relatórios.
+//!
+//! A engine grava em `AnalysisResult.metadata` tudo o que determina o resultado,
+//! sem depender da máquina em que a análise rodou:
+//!
+//! | Chave | Conteúdo |
+//! | ----- | -------- |
+//! | `engine.version` | versão do Hubstry |
+//! | `analysis.timestamp` | início da análise (RFC 3339, UTC) |
+//! | `rule_pack.<nome>` | versão e checksum de cada pacote aplicado |
+//! | `prefixes.sha256` | hash das camadas de prefixos aplicadas |
+//! | `config.*` | modo estrito, jurisdições, regras personalizadas e pesos do score |
+//! | `files.count`, `file.<caminho>` | arquivos analisados e o SHA-256 de cada um |
+//!
+//! A CLI acrescenta `git.commit`, `git.branch`, `git.dirty`, `git.prefix` (diretório
+//! da análise relativo à raiz do repositório), `config.file.sha256` (o `hubstry.yml`)
+//! e `license.tier`. O `hubstry verify-report` refaz a análise no commit registrado
+//! e compara o resultado com [`compare`].
+
+use crate::error::{HubstryError, ReportError};
+use crate::i18n::{Locale, Message};
+use crate::models::{AnalysisResult, EngineConfig, RulePackRef};
+use crate::prefix_manager::PrefixRegistry;
+use crate::{msg, tr};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Prefixo das chaves com o hash de cada arquivo analisado.
+pub const FILE_KEY_PREFIX: &str = "file.";
+
+/// Chaves que mudam a cada execução e não entram na comparação.
+const VOLATILE_KEYS: &[&str] = &["analysis.timestamp", "license.tier"];
+const VOLATILE_PREFIXES: &[&str] = &["git."];
+
+/// `sha256:<hex>` de um conteúdo.
+pub fn sha256(content: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(content)))
+}
+
+/// Metadados que a engine conhece: versão, pacotes, prefixos, configuração e arquivos.
+pub(crate) fn engine_metadata<'a>(
+    config: &EngineConfig,
+    registry: &PrefixRegistry,
+    rule_packs: &[RulePackRef],
+    files: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> BTreeMap<String, String> {
+    let mut metadata = BTreeMap::new();
+    metadata.insert(
+        "engine.version".to_string(),
+        env!("CARGO_PKG_VERSION").to_string(),
+    );
+    metadata.insert(
+        "analysis.timestamp".to_string(),
+        chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    );
+    for pack in rule_packs {
+        metadata.insert(
+            format!("rule_pack.{}", pack.name),
+            format!("{} {}", pack.version, pack.checksum),
+        );
+    }
+
+    let mut prefixes = Sha256::new();
+    for (_, content) in registry.layer_contents() {
+        prefixes.update(content.as_bytes());
+        prefixes.update([0]);
+    }
+    metadata.insert(
+        "prefixes.sha256".to_string(),
+        format!("sha256:{}", hex::encode(prefixes.finalize())),
+    );
+
+    metadata.insert(
+        "config.strict_mode".to_string(),
+        config.strict_mode.to_string(),
+    );
+    let jurisdictions: Vec<String> = config
+        .enabled_jurisdictions
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    metadata.insert("config.jurisdictions".to_string(), jurisdictions.join(", "));
+    let custom_rules: Vec<&str> = config.custom_rules.iter().map(|r| r.id.as_str()).collect();
+    metadata.insert("config.custom_rules".to_string(), custom_rules.join(", "));
+    let weights = &config.scoring.weights;
+    metadata.insert(
+        "config.scoring.weights".to_string(),
+        format!(
+            "critical={}, high={}, medium={}, low={}, info={}",
+            weights.critical, weights.high, weights.medium, weights.low, weights.info
+        ),
+    );
+
+    let mut count = 0;
+    for (path, source) in files {
+        metadata.insert(
+            format!("{}{}", FILE_KEY_PREFIX, path),
+            sha256(source.as_bytes()),
+        );
+        count += 1;
+    }
+    metadata.insert("files.count".to_string(), count.to_string());
+    metadata
+}
+
+/// Estado do repositório git em que a análise rodou.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitInfo {
+    pub commit: String,
+    /// `None` com o HEAD destacado.
+    pub branch: Option<String>,
+    /// Se havia alterações não commitadas em arquivos versionados.
+    pub dirty: bool,
+    /// Diretório da análise relativo à raiz do repositório (vazio na raiz).
+    pub prefix: String,
+}
+
+impl GitInfo {
+    /// Lê o estado do repositório que contém `dir`; `None` fora de um repositório
+    /// ou sem o `git` instalado.
+    pub fn discover(dir: &Path) -> Option<Self> {
+        let commit = git(dir, &["rev-parse", "HEAD"]).ok()?;
+        let branch = git(dir, &["symbolic-ref", "--quiet", "--short", "HEAD"]).ok();
+        let dirty = git(dir, &["status", "--porcelain", "--untracked-files=no"])
+            .map(|status| !status.is_empty())
+            .unwrap_or(false);
+        let prefix = git(dir, &["rev-parse", "--show-prefix"]).unwrap_or_default();
+        Some(GitInfo {
+            commit,
+            branch,
+            dirty,
+            prefix,
+        })
+    }
+
+    /// Grava as chaves `git.*`.
+    pub fn record(&self, metadata: &mut BTreeMap<String, String>) {
+        metadata.insert("git.commit".to_string(), self.commit.clone());
+        if let Some(branch) = &self.branch {
+            metadata.insert("git.branch".to_string(), branch.clone());
+        }
+        metadata.insert("git.dirty".to_string(), self.dirty.to_string());
+        metadata.insert("git.prefix".to_string(), self.prefix.clone());
+    }
+}
+
+/// Executa um comando git em `dir` e devolve a saída sem espaços nas pontas.
+fn git(dir: &Path, args: &[&str]) -> Result<String, Message> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| msg!("error.provenance.git_unavailable", detail = e))?;
+    if !output.status.success() {
+        return Err(msg!(
+            "error.provenance.git_failed",
+            command = args.join(" "),
+            detail = String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Cópia de trabalho temporária de um commit, removida ao sair de escopo.
+#[derive(Debug)]
+pub struct Worktree {
+    repo: PathBuf,
+    path: PathBuf,
+}
+
+impl Worktree {
+    /// Cria a cópia do `commit` do repositório que contém `repo_dir`.
+    pub fn checkout(repo_dir: &Path, commit: &str) -> Result<Self, HubstryError> {
+        let path = std::env::temp_dir().join(format!(
+            "hubstry-verify-{}-{}",
+            std::process::id(),
+            commit.chars().take(12).collect::<String>()
+        ));
+        git(
+            repo_dir,
+            &[
+                "worktree",
+                "add",
+                "--detach",
+                "--quiet",
+                &path.to_string_lossy(),
+                commit,
+            ],
+        )
+        .map_err(unverifiable)?;
+        Ok(Worktree {
+            repo: repo_dir.to_path_buf(),
+            path,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let _ = git(
+            &self.repo,
+            &[
+                "worktree",
+                "remove",
+                "--force",
+                &self.path.to_string_lossy(),
+            ],
+        );
+    }
+}
+
+fn unverifiable(message: Message) -> HubstryError {
+    ReportError::Unverifiable { message }.into()
+}
+
+/// Um relatório JSON ou YAML gravado anteriormente.
+#[derive(Debug, Clone)]
+pub struct RecordedReport {
+    report: Value,
+    metadata: BTreeMap<String, String>,
+}
+
+impl RecordedReport {
+    /// Lê um relatório `json`, `yaml` ou `yml`.
+    pub fn from_path(path: &Path) -> Result<Self, HubstryError> {
+        let content = std::fs::read_to_string(path).map_err(|e| HubstryError::io(path, e))?;
+        let yaml = matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yaml" | "yml")
+        );
+        let report: Value = if yaml {
+            serde_yaml::from_str(&content)
+                .map_err(|e| unverifiable(msg!("error.provenance.unreadable", detail = e)))?
+        } else {
+            serde_json::from_str(&content)
+                .map_err(|e| unverifiable(msg!("error.provenance.unreadable", detail = e)))?
+        };
+        Self::from_value(report)
+    }
+
+    pub fn from_value(report: Value) -> Result<Self, HubstryError> {
+        let metadata: BTreeMap<String, String> = report
+            .get("metadata")
+            .cloned()
+            .and_then(|m| serde_json::from_value(m).ok())
+            .unwrap_or_default();
+        if !metadata.contains_key("engine.version") {
+            return Err(unverifiable(msg!("error.provenance.no_metadata")));
+        }
+        Ok(RecordedReport { report, metadata })
+    }
+
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
+    }
+
+    /// Caminhos dos arquivos analisados, como gravados no relatório.
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.metadata
+            .keys()
+            .filter_map(|key| key.strip_prefix(FILE_KEY_PREFIX))
+    }
+
+    /// Pacotes aplicados, como `nome@versão`.
+    pub fn rule_packs(&self) -> Vec<String> {
+        self.metadata
+            .iter()
+            .filter_map(|(key, value)| {
+                let name = key.strip_prefix("rule_pack.")?;
+                let version = value.split(' ').next()?;
+                Some(format!("{}@{}", name, version))
+            })
+            .collect()
+    }
+}
+
+/// Diferenças entre um relatório gravado e uma nova análise; vazio quando o
+/// resultado é o mesmo. Data, plano e estado do git não são comparados.
+pub fn compare(
+    recorded: &RecordedReport,
+    actual: &AnalysisResult,
+    locale: Locale,
+) -> Result<Vec<String>, HubstryError> {
+    let mut differences = Vec::new();
+    let absent = tr!(locale, "provenance.absent");
+
+    let volatile = |key: &str| {
+        VOLATILE_KEYS.contains(&key) || VOLATILE_PREFIXES.iter().any(|p| key.starts_with(p))
+    };
+    let mut keys: Vec<&String> = recorded
+        .metadata
+        .keys()
+        .chain(actual.metadata.keys())
+        .filter(|key| !volatile(key))
+        .collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        let before = recorded.metadata.get(key);
+        let after = actual.metadata.get(key);
+        if before != after {
+            differences.push(format!(
+                "{}: {} -> {}",
+                key,
+                before.map_or(absent.as_str(), String::as_str),
+                after.map_or(absent.as_str(), String::as_str)
+            ));
+        }
+    }
+
+    let actual_report = serde_json::to_value(actual)?;
+    let before = recorded
+        .report
+        .get("compliance_score")
+        .and_then(Value::as_f64);
+    if before != Some(actual.compliance_score) {
+        differences.push(format!(
+            "compliance_score: {} -> {}",
+            before.map_or(absent.clone(), |s| s.to_string()),
+            actual.compliance_score
+        ));
+    }
+    for list in ["violations", "suppressed"] {
+        let before = findings(&recorded.report, list);
+        let after = findings(&actual_report, list);
+        for missing in before.iter().filter(|f| !after.contains(f)) {
+            differences.push(tr!(
+                locale,
+                "provenance.missing",
+                list = list,
+                finding = missing
+            ));
+        }
+        for new in after.iter().filter(|f| !before.contains(f)) {
+            differences.push(tr!(locale, "provenance.new", list = list, finding = new));
+        }
+    }
+    Ok(differences)
+}
+
+/// Violações de uma lista do relatório, como `regra arquivo:função:linha`, ordenadas.
+fn findings(report: &Value, list: &str) -> Vec<String> {
+    let text = |violation: &Value, field: &str| {
+        violation
+            .get(field)
+            .map(|value| match value {
+                Value::String(s) => s.clone(),
+                Value::Null => "-".to_string(),
+                other => other.to_string(),
+            })
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let mut findings: Vec<String> = report
+        .get(list)
+        .and_then(Value::as_array)
+        .map(|violations| {
+            violations
+                .iter()
+                .map(|v| {
+                    format!(
+                        "{} {}:{}:{}",
+                        text(v, "rule_id"),
+                        text(v, "file"),
+                        text(v, "function"),
+                        text(v, "line")
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    findings.sort();
+    findings
+}
@@ -0,0 +1,94 @@
+use super::{citations, escape_xml, Reporter};
+use crate::error::HubstryError;
+use crate::i18n::Locale;
+use crate::models::{AnalysisResult, ComplianceViolation, OutputFormat, RuleSeverity};
+use crate::tr;
+
+/// Nome usado no lugar do arquivo para violações de escopo `project`.
+const PROJECT_FILE: &str = "(project)";
+
+/// Relatório Checkstyle XML, com as violações agrupadas por arquivo. Violações
+/// suprimidas não entram: o formato não tem como representá-las.
+pub struct CheckstyleReporter {
+    pub locale: Locale,
+}
+
+impl Reporter for CheckstyleReporter {
+    fn format(&self) -> OutputFormat {
+        OutputFormat::Checkstyle
+    }
+
+    fn extension(&self) -> &'static str {
+        "checkstyle.xml"
+    }
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError> {
+        let mut files: Vec<(&str, Vec<&ComplianceViolation>)> = Vec::new();
+        for violation in &result.violations {
+            let file = violation.file.as_deref().unwrap_or(PROJECT_FILE);
+            match files.iter_mut().find(|(name, _)| *name == file) {
+                Some((_, violations)) => violations.push(violation),
+                None => files.push((file, vec![violation])),
+            }
+        }
+
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<checkstyle version=\"4.3\">\n");
+        // O formato não tem campo para o score; ele vai num comentário.
+        let comment = format!(
+            "{}: {:.1}% ({}). {}: {}",
+            tr!(self.locale, "report.score"),
+            result.compliance_score,
+            tr!(
+                self.locale,
+                "report.failed_ratio",
+                failed = result.score_breakdown.failed,
+                obligations = result.score_breakdown.obligations
+            ),
+            tr!(self.locale, "report.formula"),
+            result.score_breakdown.formula
+        );
+        xml.push_str(&format!("  <!-- {} -->\n", comment.replace("--", "- -")));
+        for (file, violations) in files {
+            xml.push_str(&format!("  <file name=\"{}\">\n", escape_xml(file)));
+            for violation in violations {
+                let mut message = self.locale.message(violation);
+                if let Some(suggestion) = self.locale.suggestion(violation) {
+                    message.push_str(&format!(
+                        " {}: {}",
+                        tr!(self.locale, "report.suggestion"),
+                        suggestion
+                    ));
+                }
+                if !violation.citations.is_empty() {
+                    message.push_str(&format!(
+                        " {}: {}",
+                        tr!(self.locale, "report.legal_basis"),
+                        citations(&violation.citations, self.locale)
+                    ));
+                }
+                xml.push_str(&format!(
+                    "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"hubstry.{}\"/>\n",
+                    violation.line.unwrap_or(0),
+                    // O Checkstyle conta colunas a partir de 1.
+                    violation.column.map_or(0, |column| column + 1),
+                    severity(&violation.severity),
+                    escape_xml(&message),
+                    escape_xml(&violation.rule_id)
+                ));
+            }
+            xml.push_str("  </file>\n");
+        }
+        xml.push_str("</checkstyle>\n");
+        Ok(xml)
+    }
+}
+
+fn severity(severity: &RuleSeverity) -> &'static str {
+    match severity {
+        RuleSeverity::Critical | RuleSeverity::High => "error",
+        RuleSeverity::Medium => "warning",
+        RuleSeverity::Low | RuleSeverity::Info => "info",
+    }
+}
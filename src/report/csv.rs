@@ -0,0 +1,65 @@
+use super::Reporter;
+use crate::error::HubstryError;
+use crate::models::{AnalysisResult, OutputFormat};
+
+/// Relatório em CSV (RFC 4180), uma linha por violação, para planilhas.
+pub struct CsvReporter;
+
+impl Reporter for CsvReporter {
+    fn format(&self) -> OutputFormat {
+        OutputFormat::Csv
+    }
+
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError> {
+        let mut csv = csv_row(&[
+            "rule_id",
+            "severity",
+            "file",
+            "line",
+            "function",
+            "message",
+            "suggestion",
+            "citations",
+            "fingerprint",
+        ]);
+        for violation in &result.violations {
+            csv.push_str(&csv_row(&[
+                &violation.rule_id,
+                &violation.severity.to_string(),
+                violation.file.as_deref().unwrap_or(""),
+                &violation.line.map(|l| l.to_string()).unwrap_or_default(),
+                violation.function.as_deref().unwrap_or(""),
+                &violation.message,
+                violation.suggestion.as_deref().unwrap_or(""),
+                &violation
+                    .citations
+                    .iter()
+                    .map(|c| format!("{} ({})", c, c.effective_date))
+                    .collect::<Vec<_>>()
+                    .join("; "),
+                &violation.fingerprint(),
+            ]));
+        }
+        Ok(csv)
+    }
+}
+
+/// Linha CSV terminada em CRLF. Campos com vírgula, aspas ou quebra de linha vão
+/// entre aspas, com as aspas internas duplicadas.
+pub(crate) fn csv_row(fields: &[&str]) -> String {
+    let cells: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    format!("{}\r\n", cells.join(","))
+}
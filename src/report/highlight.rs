@@ -0,0 +1,76 @@
+//! Realce de sintaxe Rust para os trechos de código do relatório HTML.
+//!
+//! Cada linha é realçada isoladamente: comentários e strings que continuam na
+//! linha seguinte não são reconhecidos nela, o que basta para trechos curtos.
+
+use super::escape_xml;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// Linha de código Rust em HTML escapado, com `<span class="...">` para palavras
+/// reservadas (`kw`), chamadas (`fn`), strings (`str`), números (`num`) e
+/// comentários (`com`).
+pub(crate) fn rust_line(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut html = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let class = if c == '/' && chars.get(i + 1) == Some(&'/') {
+            i = chars.len();
+            Some("com")
+        } else if c == '"' {
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            i = (i + 1).min(chars.len());
+            Some("str")
+        } else if let Some(len) = char_literal_len(&chars[i..]) {
+            i += len;
+            Some("str")
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '.')) {
+                i += 1;
+            }
+            Some("num")
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            if KEYWORDS.contains(&word.as_str()) {
+                Some("kw")
+            } else if matches!(chars.get(i), Some('(' | '!')) {
+                Some("fn")
+            } else {
+                None
+            }
+        } else {
+            i += 1;
+            None
+        };
+        let text = escape_xml(&chars[start..i].iter().collect::<String>());
+        match class {
+            Some(class) => html.push_str(&format!("<span class=\"{}\">{}</span>", class, text)),
+            None => html.push_str(&text),
+        }
+    }
+    html
+}
+
+/// Tamanho de um literal de caractere (`'a'`, `'\n'`) no início de `chars`; `None`
+/// para um lifetime (`'a`).
+fn char_literal_len(chars: &[char]) -> Option<usize> {
+    match chars {
+        ['\'', '\\', _, rest @ ..] => rest.iter().position(|&c| c == '\'').map(|end| end + 4),
+        ['\'', _, '\'', ..] => Some(3),
+        _ => None,
+    }
+}
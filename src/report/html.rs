@@ -0,0 +1,328 @@
+use super::{
+    call_chain, citation, escape_xml, group_name, highlight, strict_mode, sub_scores,
+    suppressed_entry, Reporter,
+};
+use crate::error::HubstryError;
+use crate::history::Run;
+use crate::i18n::Locale;
+use crate::models::{AnalysisResult, OutputFormat, Snippet};
+use crate::tr;
+
+/// Relatório executivo em HTML, para C-Levels e gestores.
+#[derive(Default)]
+pub struct HtmlReporter {
+    locale: Locale,
+    trend: Vec<Run>,
+}
+
+impl HtmlReporter {
+    pub fn new(locale: Locale) -> Self {
+        HtmlReporter {
+            locale,
+            trend: Vec::new(),
+        }
+    }
+
+    /// Inclui um gráfico com a evolução do score nas execuções anteriores do mesmo
+    /// alvo, da mais antiga para a mais recente (ver [`crate::history`]).
+    pub fn with_trend(mut self, trend: Vec<Run>) -> Self {
+        self.trend = trend;
+        self
+    }
+}
+
+impl Reporter for HtmlReporter {
+    fn format(&self) -> OutputFormat {
+        OutputFormat::Html
+    }
+
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError> {
+        let locale = self.locale;
+        let mut html = String::new();
+        html.push_str(&format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n",
+            locale.code()
+        ));
+        html.push_str("<meta charset=\"UTF-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n");
+        html.push_str(&format!("<title>{}</title>\n", tr!(locale, "html.title")));
+        html.push_str("<style>\n");
+        html.push_str("body { font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif; margin: 0; padding: 20px; background-color: #f9f9fb; color: #333; }\n");
+        html.push_str(".container { max-width: 800px; margin: auto; background: #fff; padding: 30px; border-radius: 8px; box-shadow: 0 4px 6px rgba(0,0,0,0.1); }\n");
+        html.push_str(
+            "h1 { color: #2c3e50; border-bottom: 2px solid #ecf0f1; padding-bottom: 10px; }\n",
+        );
+        html.push_str(".score-board { text-align: center; margin: 30px 0; padding: 20px; border-radius: 8px; }\n");
+        html.push_str(".score-board.good { background-color: #d4edda; color: #155724; }\n");
+        html.push_str(".score-board.warning { background-color: #fff3cd; color: #856404; }\n");
+        html.push_str(".score-board.danger { background-color: #f8d7da; color: #721c24; }\n");
+        html.push_str(".score-value { font-size: 48px; font-weight: bold; }\n");
+        html.push_str(".violation { border: 1px solid #e2e8f0; border-left: 5px solid #e53e3e; margin-bottom: 20px; padding: 15px; border-radius: 4px; }\n");
+        html.push_str(".violation h3 { margin-top: 0; color: #e53e3e; }\n");
+        html.push_str(".meta { font-size: 0.9em; color: #718096; margin-bottom: 10px; }\n");
+        html.push_str(".breakdown { border-collapse: collapse; width: 100%; margin: 10px 0; }\n");
+        html.push_str(".breakdown th, .breakdown td { border-bottom: 1px solid #e2e8f0; padding: 6px 10px; text-align: left; }\n");
+        html.push_str(".trend line { stroke: #e2e8f0; }\n");
+        html.push_str(".trend polyline { fill: none; stroke: #2b6cb0; stroke-width: 2; }\n");
+        html.push_str(".trend circle { fill: #2b6cb0; }\n");
+        html.push_str(".trend text { font-size: 11px; fill: #718096; }\n");
+        html.push_str(".snippet { background-color: #1a202c; color: #e2e8f0; padding: 10px 0; border-radius: 4px; overflow-x: auto; font-size: 0.85em; }\n");
+        html.push_str(".snippet .line { display: block; padding: 0 10px; }\n");
+        html.push_str(".snippet .hl { background-color: #744210; }\n");
+        html.push_str(".snippet .ln { display: inline-block; width: 3em; color: #718096; user-select: none; }\n");
+        html.push_str(".snippet .kw { color: #f6ad55; } .snippet .fn { color: #63b3ed; } .snippet .str { color: #68d391; } .snippet .num { color: #fc8181; } .snippet .com { color: #a0aec0; font-style: italic; }\n");
+        html.push_str(".citations { font-size: 0.9em; margin-bottom: 10px; } .citations ul { margin: 4px 0; padding-left: 20px; }\n");
+        html.push_str(".suggestion { background-color: #edf2f7; padding: 10px; border-radius: 4px; font-style: italic; }\n");
+        html.push_str("</style>\n</head>\n<body>\n");
+
+        html.push_str("<div class=\"container\">\n");
+        html.push_str(&format!("<h1>{}</h1>\n", tr!(locale, "html.heading")));
+
+        let score_class = if result.compliance_score >= 90.0 {
+            "good"
+        } else if result.compliance_score >= 70.0 {
+            "warning"
+        } else {
+            "danger"
+        };
+
+        html.push_str(&format!("<div class=\"score-board {}\">\n", score_class));
+        html.push_str(&format!("<h2>{}</h2>\n", tr!(locale, "html.score")));
+        html.push_str(&format!(
+            "<div class=\"score-value\">{:.1}%</div>\n",
+            result.compliance_score
+        ));
+        html.push_str("</div>\n");
+
+        if !result.rule_packs.is_empty() {
+            let packs: Vec<String> = result.rule_packs.iter().map(ToString::to_string).collect();
+            html.push_str(&format!(
+                "<p class=\"meta\">{}: {}</p>\n",
+                tr!(locale, "html.rule_packs"),
+                escape_xml(&packs.join(", "))
+            ));
+        }
+        html.push_str(&format!(
+            "<p class=\"meta\">{}: {}</p>\n",
+            tr!(locale, "report.strict_mode"),
+            strict_mode(result, locale)
+        ));
+
+        let breakdown = &result.score_breakdown;
+        html.push_str(&format!("<h2>{}</h2>\n", tr!(locale, "report.breakdown")));
+        html.push_str(&format!(
+            "<p class=\"meta\">{}.</p>\n",
+            tr!(
+                locale,
+                "report.breakdown_summary",
+                obligations = breakdown.obligations,
+                failed = breakdown.failed
+            )
+        ));
+        if breakdown.obligations > 0 {
+            html.push_str(&format!(
+                "<table class=\"breakdown\">\n<tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>\n",
+                tr!(locale, "report.column.group"),
+                tr!(locale, "report.column.name"),
+                tr!(locale, "report.column.score"),
+                tr!(locale, "report.column.failed")
+            ));
+            for (group, name, score) in sub_scores(breakdown) {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{:.1}%</td><td>{}/{}</td></tr>\n",
+                    group_name(group, locale),
+                    escape_xml(name),
+                    score.score,
+                    score.failed,
+                    score.obligations
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str(&format!(
+            "<p class=\"meta\">{}: <code>{}</code></p>\n",
+            tr!(locale, "report.formula"),
+            escape_xml(&breakdown.formula)
+        ));
+
+        if self.trend.len() > 1 {
+            html.push_str(&format!("<h2>{}</h2>\n", tr!(locale, "html.trend")));
+            html.push_str(&trend_chart(&self.trend, locale));
+        }
+
+        if !result.violations.is_empty() {
+            html.push_str(&format!(
+                "<h2>{}</h2>\n",
+                tr!(locale, "html.violations", count = result.violations.len())
+            ));
+            for violation in &result.violations {
+                html.push_str("<div class=\"violation\">\n");
+                html.push_str(&format!(
+                    "<h3>[{}] {}</h3>\n",
+                    escape_xml(&violation.rule_id),
+                    violation.severity
+                ));
+                html.push_str(&format!(
+                    "<p><strong>{}:</strong> {}</p>\n",
+                    tr!(locale, "html.problem"),
+                    escape_xml(&locale.message(violation))
+                ));
+
+                let line = violation.line.unwrap_or(0);
+                let col = violation.column.unwrap_or(0);
+                let file = violation
+                    .file
+                    .as_ref()
+                    .map(|file| format!("{}, ", file))
+                    .unwrap_or_default();
+                html.push_str(&format!(
+                    "<div class=\"meta\">{}: {}{}</div>\n",
+                    tr!(locale, "report.location"),
+                    escape_xml(&file),
+                    tr!(locale, "report.position", line = line, column = col)
+                ));
+                if let Some(chain) = &violation.call_chain {
+                    html.push_str(&format!(
+                        "<div class=\"meta\">{}: <code>{}</code></div>\n",
+                        tr!(locale, "report.call_chain"),
+                        escape_xml(&call_chain(chain, locale))
+                    ));
+                }
+                if !violation.citations.is_empty() {
+                    html.push_str(&format!(
+                        "<div class=\"citations\"><strong>{}:</strong>\n<ul>\n",
+                        tr!(locale, "report.legal_basis")
+                    ));
+                    for c in &violation.citations {
+                        html.push_str(&format!("<li>{}</li>\n", escape_xml(&citation(c, locale))));
+                    }
+                    html.push_str("</ul>\n</div>\n");
+                }
+                if let Some(snippet) = &violation.snippet {
+                    html.push_str(&snippet_html(snippet));
+                }
+
+                if let Some(suggestion) = locale.suggestion(violation) {
+                    html.push_str(&format!(
+                        "<div class=\"suggestion\"><strong>{}:</strong> {}</div>\n",
+                        tr!(locale, "html.mitigation"),
+                        escape_xml(&suggestion)
+                    ));
+                }
+                html.push_str("</div>\n");
+            }
+        } else {
+            html.push_str(&format!("<p>{}</p>\n", tr!(locale, "html.no_violations")));
+        }
+
+        if !result.suppressed.is_empty() {
+            html.push_str(&format!(
+                "<h2>{}</h2>\n<ul>\n",
+                tr!(locale, "html.suppressed", count = result.suppressed.len())
+            ));
+            for violation in &result.suppressed {
+                let (reason, approver) = suppressed_entry(violation, locale);
+                html.push_str(&format!(
+                    "<li>{}</li>\n",
+                    tr!(
+                        locale,
+                        "report.suppressed_entry",
+                        rule = escape_xml(&violation.rule_id),
+                        function = format!(
+                            "<code>{}</code>",
+                            escape_xml(violation.function.as_deref().unwrap_or("-"))
+                        ),
+                        reason = escape_xml(&reason),
+                        approver = escape_xml(&approver)
+                    )
+                ));
+            }
+            html.push_str("</ul>\n");
+        }
+
+        html.push_str("</div>\n</body>\n</html>\n");
+        Ok(html)
+    }
+}
+
+/// Trecho de código com números de linha, realce de sintaxe e as linhas da
+/// violação destacadas. Linhas omitidas aparecem como `⋮`.
+fn snippet_html(snippet: &Snippet) -> String {
+    let mut html = String::from("<pre class=\"snippet\"><code>");
+    let mut previous = None;
+    for line in &snippet.lines {
+        if previous.is_some_and(|n: usize| n + 1 != line.number) {
+            html.push_str("<span class=\"line\"><span class=\"ln\">⋮</span></span>");
+        }
+        html.push_str(&format!(
+            "<span class=\"line{}\"><span class=\"ln\">{}</span>{}</span>",
+            if line.highlight { " hl" } else { "" },
+            line.number,
+            highlight::rust_line(&line.text)
+        ));
+        previous = Some(line.number);
+    }
+    html.push_str("</code></pre>\n");
+    html
+}
+
+/// Gráfico SVG do score geral em cada execução, sem JavaScript.
+fn trend_chart(runs: &[Run], locale: Locale) -> String {
+    const WIDTH: f64 = 740.0;
+    const HEIGHT: f64 = 200.0;
+    const LEFT: f64 = 40.0;
+    const TOP: f64 = 10.0;
+    const BOTTOM: f64 = 30.0;
+
+    let plot_width = WIDTH - LEFT - 10.0;
+    let plot_height = HEIGHT - TOP - BOTTOM;
+    let x = |index: usize| LEFT + plot_width * index as f64 / (runs.len() - 1) as f64;
+    let y = |score: f64| TOP + plot_height * (1.0 - score.clamp(0.0, 100.0) / 100.0);
+
+    let mut svg = format!(
+        "<svg class=\"trend\" viewBox=\"0 0 {WIDTH} {HEIGHT}\" width=\"100%\" role=\"img\" aria-label=\"{}\">\n",
+        tr!(locale, "html.trend")
+    );
+    for score in [0.0, 50.0, 100.0] {
+        svg.push_str(&format!(
+            "<line x1=\"{LEFT}\" y1=\"{0:.1}\" x2=\"{1}\" y2=\"{0:.1}\"/><text x=\"0\" y=\"{2:.1}\">{score}%</text>\n",
+            y(score),
+            WIDTH - 10.0,
+            y(score) + 4.0
+        ));
+    }
+    let points: Vec<String> = runs
+        .iter()
+        .enumerate()
+        .map(|(index, run)| format!("{:.1},{:.1}", x(index), y(run.score)))
+        .collect();
+    svg.push_str(&format!("<polyline points=\"{}\"/>\n", points.join(" ")));
+    for (index, run) in runs.iter().enumerate() {
+        let date = run
+            .recorded_at
+            .format(&tr!(locale, "format.date"))
+            .to_string();
+        svg.push_str(&format!(
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\"><title>{}: {:.1}%</title></circle>\n",
+            x(index),
+            y(run.score),
+            date,
+            run.score
+        ));
+        if index == 0 || index == runs.len() - 1 {
+            let anchor = if index == 0 { "start" } else { "end" };
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"{}\">{}</text>\n",
+                x(index),
+                HEIGHT - 8.0,
+                anchor,
+                escape_xml(&date)
+            ));
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
@@ -0,0 +1,20 @@
+use super::Reporter;
+use crate::error::HubstryError;
+use crate::models::{AnalysisResult, OutputFormat};
+
+/// Relatório para integrações de software.
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn format(&self) -> OutputFormat {
+        OutputFormat::Json
+    }
+
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError> {
+        Ok(serde_json::to_string_pretty(result)?)
+    }
+}
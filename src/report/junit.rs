@@ -0,0 +1,242 @@
+use super::{citations, escape_xml, location, sub_scores, suppressed_entry, Reporter};
+use crate::error::HubstryError;
+use crate::i18n::Locale;
+use crate::models::{AnalysisResult, ComplianceViolation, OutputFormat, RuleSeverity};
+use crate::scoring::ScoreBreakdown;
+use crate::tr;
+
+/// Nome da suíte das violações de escopo `project`, que não pertencem a um arquivo.
+const PROJECT_SUITE: &str = "(project)";
+/// Nome do caso de teste das violações de escopo `file`, que não pertencem a uma função.
+const FILE_CASE: &str = "(file)";
+
+/// Relatório JUnit XML. Cada função anotada é um caso de teste, que falha com as
+/// suas violações ou é marcado como ignorado quando todas foram suprimidas.
+pub struct JUnitReporter {
+    pub locale: Locale,
+}
+
+struct TestCase<'a> {
+    suite: &'a str,
+    name: &'a str,
+    failures: Vec<&'a ComplianceViolation>,
+    suppressed: Vec<&'a ComplianceViolation>,
+}
+
+impl Reporter for JUnitReporter {
+    fn format(&self) -> OutputFormat {
+        OutputFormat::JUnit
+    }
+
+    fn extension(&self) -> &'static str {
+        "junit.xml"
+    }
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError> {
+        let mut cases: Vec<TestCase> = Vec::new();
+        for func in &result.checked_functions {
+            case(
+                &mut cases,
+                func.file.as_deref().unwrap_or(PROJECT_SUITE),
+                &func.name,
+            );
+        }
+        for violation in &result.violations {
+            case_of(&mut cases, violation).failures.push(violation);
+        }
+        for violation in &result.suppressed {
+            case_of(&mut cases, violation).suppressed.push(violation);
+        }
+
+        let mut suites: Vec<&str> = Vec::new();
+        for case in &cases {
+            if !suites.contains(&case.suite) {
+                suites.push(case.suite);
+            }
+        }
+
+        let all: Vec<&TestCase> = cases.iter().collect();
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!("<testsuites name=\"hubstry\" {}>\n", counts(&all)));
+        push_score(&mut xml, &result.score_breakdown);
+        for suite in suites {
+            let suite_cases: Vec<&TestCase> = cases.iter().filter(|c| c.suite == suite).collect();
+            xml.push_str(&format!(
+                "  <testsuite name=\"{}\" {}>\n",
+                escape_xml(suite),
+                counts(&suite_cases)
+            ));
+            for case in suite_cases {
+                push_case(&mut xml, case, self.locale);
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        Ok(xml)
+    }
+}
+
+fn case<'a, 'c>(
+    cases: &'c mut Vec<TestCase<'a>>,
+    suite: &'a str,
+    name: &'a str,
+) -> &'c mut TestCase<'a> {
+    let index = match cases
+        .iter()
+        .position(|c| c.suite == suite && c.name == name)
+    {
+        Some(index) => index,
+        None => {
+            cases.push(TestCase {
+                suite,
+                name,
+                failures: Vec::new(),
+                suppressed: Vec::new(),
+            });
+            cases.len() - 1
+        }
+    };
+    &mut cases[index]
+}
+
+fn case_of<'a, 'c>(
+    cases: &'c mut Vec<TestCase<'a>>,
+    violation: &'a ComplianceViolation,
+) -> &'c mut TestCase<'a> {
+    case(
+        cases,
+        violation.file.as_deref().unwrap_or(PROJECT_SUITE),
+        violation.function.as_deref().unwrap_or(FILE_CASE),
+    )
+}
+
+/// Atributos `tests`, `failures` e `skipped` de uma suíte.
+fn counts(cases: &[&TestCase]) -> String {
+    let failures = cases.iter().filter(|c| !c.failures.is_empty()).count();
+    let skipped = cases
+        .iter()
+        .filter(|c| c.failures.is_empty() && !c.suppressed.is_empty())
+        .count();
+    format!(
+        "tests=\"{}\" failures=\"{}\" skipped=\"{}\"",
+        cases.len(),
+        failures,
+        skipped
+    )
+}
+
+/// O score e os sub-scores, numa suíte vazia: `<testsuites>` não aceita `<properties>`.
+fn push_score(xml: &mut String, breakdown: &ScoreBreakdown) {
+    xml.push_str(
+        "  <testsuite name=\"compliance-score\" tests=\"0\" failures=\"0\" skipped=\"0\">\n",
+    );
+    xml.push_str("    <properties>\n");
+    let mut properties = vec![
+        ("score".to_string(), format!("{:.2}", breakdown.score)),
+        ("formula".to_string(), breakdown.formula.clone()),
+    ];
+    for (group, name, score) in sub_scores(breakdown) {
+        properties.push((format!("{}.{}", group, name), format!("{:.2}", score.score)));
+    }
+    for (name, value) in properties {
+        xml.push_str(&format!(
+            "      <property name=\"{}\" value=\"{}\"/>\n",
+            escape_xml(&name),
+            escape_xml(&value)
+        ));
+    }
+    xml.push_str("    </properties>\n");
+    xml.push_str("  </testsuite>\n");
+}
+
+fn push_case(xml: &mut String, case: &TestCase, locale: Locale) {
+    let open = format!(
+        "    <testcase name=\"{}\" classname=\"{}\"",
+        escape_xml(case.name),
+        escape_xml(case.suite)
+    );
+    if let Some(worst) = case
+        .failures
+        .iter()
+        .map(|v| &v.severity)
+        .min_by_key(|s| rank(s))
+    {
+        let ids: Vec<&str> = case.failures.iter().map(|v| v.rule_id.as_str()).collect();
+        let details: Vec<String> = case
+            .failures
+            .iter()
+            .map(|v| {
+                let mut detail = format!(
+                    "[{}] {}: {} ({})",
+                    v.severity,
+                    v.rule_id,
+                    locale.message(v),
+                    location(v, locale)
+                );
+                if !v.citations.is_empty() {
+                    detail.push_str(&format!(
+                        "\n{}: {}",
+                        tr!(locale, "report.legal_basis"),
+                        citations(&v.citations, locale)
+                    ));
+                }
+                if let Some(suggestion) = locale.suggestion(v) {
+                    detail.push_str(&format!(
+                        "\n{}: {}",
+                        tr!(locale, "report.suggestion"),
+                        suggestion
+                    ));
+                }
+                detail
+            })
+            .collect();
+        xml.push_str(&format!("{}>\n", open));
+        xml.push_str(&format!(
+            "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+            worst,
+            escape_xml(&tr!(
+                locale,
+                "junit.failure",
+                count = case.failures.len(),
+                rules = ids.join(", ")
+            )),
+            escape_xml(&details.join("\n\n"))
+        ));
+        xml.push_str("    </testcase>\n");
+    } else if !case.suppressed.is_empty() {
+        let waivers: Vec<String> = case
+            .suppressed
+            .iter()
+            .map(|v| {
+                let (reason, approver) = suppressed_entry(v, locale);
+                tr!(
+                    locale,
+                    "junit.waiver",
+                    rule = v.rule_id,
+                    reason = reason,
+                    approver = approver
+                )
+            })
+            .collect();
+        xml.push_str(&format!("{}>\n", open));
+        xml.push_str(&format!(
+            "      <skipped message=\"{}\"/>\n",
+            escape_xml(&tr!(locale, "junit.skipped", waivers = waivers.join("; ")))
+        ));
+        xml.push_str("    </testcase>\n");
+    } else {
+        xml.push_str(&format!("{}/>\n", open));
+    }
+}
+
+/// Ordem de gravidade, da mais grave para a menos grave.
+fn rank(severity: &RuleSeverity) -> u8 {
+    match severity {
+        RuleSeverity::Critical => 0,
+        RuleSeverity::High => 1,
+        RuleSeverity::Medium => 2,
+        RuleSeverity::Low => 3,
+        RuleSeverity::Info => 4,
+    }
+}
@@ -0,0 +1,177 @@
+use super::{
+    call_chain, citations, group_name, location, strict_mode, sub_scores, suppressed_entry,
+    Reporter,
+};
+use crate::error::HubstryError;
+use crate::i18n::Locale;
+use crate::models::{AnalysisResult, OutputFormat, Snippet};
+use crate::tr;
+
+/// Relatório em Markdown, para revisão de código e wikis.
+pub struct MarkdownReporter {
+    pub locale: Locale,
+}
+
+impl Reporter for MarkdownReporter {
+    fn format(&self) -> OutputFormat {
+        OutputFormat::Markdown
+    }
+
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError> {
+        let locale = self.locale;
+        let mut report = String::new();
+        report.push_str(&format!("# {}\n\n", tr!(locale, "report.title")));
+        report.push_str(&format!(
+            "**{}:** {:.1}%\n\n",
+            tr!(locale, "report.score"),
+            result.compliance_score
+        ));
+        if !result.rule_packs.is_empty() {
+            let packs: Vec<String> = result.rule_packs.iter().map(ToString::to_string).collect();
+            report.push_str(&format!(
+                "**{}:** {}\n\n",
+                tr!(locale, "report.rule_packs"),
+                packs.join(", ")
+            ));
+        }
+        report.push_str(&format!(
+            "**{}:** {}\n\n",
+            tr!(locale, "report.strict_mode"),
+            strict_mode(result, locale)
+        ));
+
+        let breakdown = &result.score_breakdown;
+        report.push_str(&format!("## {}\n\n", tr!(locale, "report.breakdown")));
+        report.push_str(&format!(
+            "{}.\n\n",
+            tr!(
+                locale,
+                "report.breakdown_summary",
+                obligations = breakdown.obligations,
+                failed = breakdown.failed
+            )
+        ));
+        if breakdown.obligations > 0 {
+            report.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                tr!(locale, "report.column.group"),
+                tr!(locale, "report.column.name"),
+                tr!(locale, "report.column.score"),
+                tr!(locale, "report.column.failed")
+            ));
+            report.push_str("|---|---|---|---|\n");
+            for (group, name, score) in sub_scores(breakdown) {
+                report.push_str(&format!(
+                    "| {} | {} | {:.1}% | {}/{} |\n",
+                    group_name(group, locale),
+                    name,
+                    score.score,
+                    score.failed,
+                    score.obligations
+                ));
+            }
+            report.push('\n');
+        }
+        report.push_str(&format!(
+            "*{}: `{}`*\n\n",
+            tr!(locale, "report.formula"),
+            breakdown.formula
+        ));
+
+        if !result.violations.is_empty() {
+            report.push_str(&format!(
+                "## {}\n\n",
+                tr!(locale, "report.violations", count = result.violations.len())
+            ));
+            for violation in &result.violations {
+                report.push_str(&format!(
+                    "- **{}** [{}]: {}\n",
+                    violation.severity,
+                    violation.rule_id,
+                    locale.message(violation)
+                ));
+                report.push_str(&format!(
+                    "  *{}: {}*\n",
+                    tr!(locale, "report.location"),
+                    location(violation, locale)
+                ));
+                if let Some(chain) = &violation.call_chain {
+                    report.push_str(&format!(
+                        "  *{}:* `{}`\n",
+                        tr!(locale, "report.call_chain"),
+                        call_chain(chain, locale)
+                    ));
+                }
+                if !violation.citations.is_empty() {
+                    report.push_str(&format!(
+                        "  *{}:* {}\n",
+                        tr!(locale, "report.legal_basis"),
+                        citations(&violation.citations, locale)
+                    ));
+                }
+                if let Some(snippet) = &violation.snippet {
+                    report.push_str(&snippet_block(snippet, locale));
+                }
+                if let Some(suggestion) = locale.suggestion(violation) {
+                    report.push_str(&format!(
+                        "  *{}: {}*\n",
+                        tr!(locale, "report.suggestion"),
+                        suggestion
+                    ));
+                }
+                report.push('\n');
+            }
+        }
+
+        if !result.suppressed.is_empty() {
+            report.push_str(&format!(
+                "## {}\n\n",
+                tr!(locale, "report.suppressed", count = result.suppressed.len())
+            ));
+            for violation in &result.suppressed {
+                let (reason, approver) = suppressed_entry(violation, locale);
+                report.push_str(&format!(
+                    "- {}\n",
+                    tr!(
+                        locale,
+                        "report.suppressed_entry",
+                        rule = violation.rule_id,
+                        function = format!("`{}`", violation.function.as_deref().unwrap_or("-")),
+                        reason = reason,
+                        approver = approver
+                    )
+                ));
+            }
+            report.push('\n');
+        }
+        Ok(report)
+    }
+}
+
+/// Trecho de código num bloco ```` ```rust ````, dentro do item da lista. As linhas
+/// destacadas terminam com `// ◀ line n`; linhas omitidas aparecem como `// ⋮`.
+fn snippet_block(snippet: &Snippet, locale: Locale) -> String {
+    let mut block = String::from("\n  ```rust\n");
+    let mut previous = None;
+    for line in &snippet.lines {
+        if previous.is_some_and(|n: usize| n + 1 != line.number) {
+            block.push_str("  // ⋮\n");
+        }
+        block.push_str("  ");
+        block.push_str(&line.text);
+        if line.highlight {
+            block.push_str(&format!(
+                "  // ◀ {}",
+                tr!(locale, "report.snippet_line", line = line.number)
+            ));
+        }
+        block.push('\n');
+        previous = Some(line.number);
+    }
+    block.push_str("  ```\n");
+    block
+}
@@ -0,0 +1,199 @@
+//! Geração de relatórios a partir de um [`AnalysisResult`].
+//!
+//! Cada [`OutputFormat`] tem um [`Reporter`]; a CLI escolhe os formatos com
+//! `--format` (repetível) e grava cada relatório no caminho de `--output`. Os
+//! relatórios para pessoas e integrações de CI são gerados no idioma pedido (ver
+//! [`crate::i18n`]); JSON, YAML e XML serializam o resultado com os textos dos
+//! pacotes de regras.
+
+mod checkstyle;
+mod csv;
+pub(crate) mod highlight;
+mod html;
+mod json;
+mod junit;
+mod markdown;
+mod pdf;
+mod sarif;
+mod text;
+mod xml;
+mod yaml;
+
+pub use checkstyle::CheckstyleReporter;
+pub(crate) use csv::csv_row;
+pub use csv::CsvReporter;
+pub use html::HtmlReporter;
+pub use json::JsonReporter;
+pub use junit::JUnitReporter;
+pub use markdown::MarkdownReporter;
+pub use pdf::{verify_signature, web_scan_pdf, Certificate, PdfReporter, SigningKey};
+pub use sarif::SarifReporter;
+pub use text::TextReporter;
+pub use xml::XmlReporter;
+pub use yaml::YamlReporter;
+
+use crate::error::HubstryError;
+use crate::i18n::Locale;
+use crate::licensing::tier_checker::Feature;
+use crate::models::{AnalysisResult, CallChain, ComplianceViolation, LegalCitation, OutputFormat};
+use crate::scoring::{ScoreBreakdown, SubScore};
+
+/// Nome base dos arquivos gravados quando vários formatos vão para um diretório.
+pub const DEFAULT_REPORT_NAME: &str = "compliance_report";
+
+/// Converte o resultado de uma análise em um relatório.
+pub trait Reporter {
+    /// Formato produzido.
+    fn format(&self) -> OutputFormat;
+
+    /// Extensão do arquivo, sem o ponto.
+    fn extension(&self) -> &'static str;
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError>;
+}
+
+/// Devolve o reporter de um formato, no idioma `locale`.
+pub fn reporter(format: OutputFormat, locale: Locale) -> Box<dyn Reporter> {
+    match format {
+        OutputFormat::Json => Box::new(JsonReporter),
+        OutputFormat::Yaml => Box::new(YamlReporter),
+        OutputFormat::Xml => Box::new(XmlReporter),
+        OutputFormat::PlainText => Box::new(TextReporter { locale }),
+        OutputFormat::Markdown => Box::new(MarkdownReporter { locale }),
+        OutputFormat::Html => Box::new(HtmlReporter::new(locale)),
+        OutputFormat::Sarif => Box::new(SarifReporter { locale }),
+        OutputFormat::JUnit => Box::new(JUnitReporter { locale }),
+        OutputFormat::Checkstyle => Box::new(CheckstyleReporter { locale }),
+        OutputFormat::Pdf => Box::new(PdfReporter { locale }),
+        OutputFormat::Csv => Box::new(CsvReporter),
+    }
+}
+
+/// Se o relatório do formato depende do idioma. JSON, YAML, XML e CSV guardam os
+/// textos dos pacotes de regras.
+pub fn is_localized(format: OutputFormat) -> bool {
+    !matches!(
+        format,
+        OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Xml | OutputFormat::Csv
+    )
+}
+
+/// Funcionalidade do plano exigida por um formato.
+pub fn required_feature(format: OutputFormat) -> Feature {
+    match format {
+        OutputFormat::PlainText | OutputFormat::Markdown => Feature::ReportTerminal,
+        OutputFormat::Html => Feature::ReportHtml,
+        OutputFormat::Pdf => Feature::ReportPdf,
+        OutputFormat::Json
+        | OutputFormat::Yaml
+        | OutputFormat::Xml
+        | OutputFormat::Sarif
+        | OutputFormat::JUnit
+        | OutputFormat::Checkstyle
+        | OutputFormat::Csv => Feature::ReportJson,
+    }
+}
+
+/// Local de uma violação, ex.: `arquivo, Line n, Column m`.
+fn location(violation: &ComplianceViolation, locale: Locale) -> String {
+    let position = crate::tr!(
+        locale,
+        "report.position",
+        line = violation.line.unwrap_or(0),
+        column = violation.column.unwrap_or(0)
+    );
+    match &violation.file {
+        Some(file) => format!("{}, {}", file, position),
+        None => position,
+    }
+}
+
+/// Cadeia de chamadas de uma violação, ex.: `register → save_profile → store
+/// (no call to consent on the path)`.
+fn call_chain(chain: &CallChain, locale: Locale) -> String {
+    if chain.missing.is_empty() {
+        return chain.to_string();
+    }
+    let missing = crate::tr!(
+        locale,
+        "report.chain_missing",
+        calls = chain.missing.join(", ")
+    );
+    format!("{} ({})", chain, missing)
+}
+
+/// Citação legal com a data de vigência no formato do idioma, ex.: `LGPD (Lei nº
+/// 13.709/2018), Art. 14, § 1º (in force since 2020-09-18)`.
+pub(crate) fn citation(citation: &LegalCitation, locale: Locale) -> String {
+    let date = chrono::NaiveDate::parse_from_str(&citation.effective_date, "%Y-%m-%d")
+        .map(|date| date.format(&crate::tr!(locale, "format.date")).to_string())
+        .unwrap_or_else(|_| citation.effective_date.clone());
+    crate::tr!(locale, "report.citation", citation = citation, date = date)
+}
+
+/// Citações legais de uma violação, separadas por `; `.
+pub(crate) fn citations(citations: &[LegalCitation], locale: Locale) -> String {
+    citations
+        .iter()
+        .map(|c| citation(c, locale))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Nome traduzido do grupo de um sub-score (`jurisdiction` ou `category`).
+pub(crate) fn group_name(group: &str, locale: Locale) -> String {
+    locale.text(&format!("report.group.{}", group), &[])
+}
+
+/// `on`/`off` do modo estrito.
+fn strict_mode(result: &AnalysisResult, locale: Locale) -> String {
+    if result.strict_mode {
+        crate::tr!(locale, "report.enabled")
+    } else {
+        crate::tr!(locale, "report.disabled")
+    }
+}
+
+/// Linha de uma violação suprimida: regra, função, justificativa e aprovador.
+fn suppressed_entry(violation: &ComplianceViolation, locale: Locale) -> (String, String) {
+    let suppression = violation.suppression.as_ref();
+    let reason = suppression
+        .and_then(|s| s.reason.clone())
+        .unwrap_or_else(|| crate::tr!(locale, "report.no_reason"));
+    let approver = suppression
+        .and_then(|s| s.approver.clone())
+        .unwrap_or_else(|| crate::tr!(locale, "report.nobody"));
+    (reason, approver)
+}
+
+/// Sub-scores do detalhamento como `(grupo, nome, sub-score)`, com as jurisdições
+/// antes das categorias. O grupo é `jurisdiction` ou `category`.
+pub(crate) fn sub_scores(
+    breakdown: &ScoreBreakdown,
+) -> impl Iterator<Item = (&'static str, &str, &SubScore)> {
+    let jurisdictions = breakdown
+        .by_jurisdiction
+        .iter()
+        .map(|(name, score)| ("jurisdiction", name.as_str(), score));
+    let categories = breakdown
+        .by_category
+        .iter()
+        .map(|(name, score)| ("category", name.as_str(), score));
+    jurisdictions.chain(categories)
+}
+
+/// Escapa texto para uso em conteúdo e atributos XML.
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
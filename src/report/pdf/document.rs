@@ -0,0 +1,467 @@
+//! Gerador mínimo de PDF 1.4, sem dependências externas.
+//!
+//! Usa as fontes padrão Helvetica e Helvetica-Bold (presentes em todo leitor de PDF)
+//! com a codificação WinAnsi, que cobre os acentos do português. O arquivo gerado é
+//! inteiramente ASCII: os streams não são comprimidos e os bytes acima de 127 são
+//! escritos como escapes octais.
+
+pub(super) const PAGE_WIDTH: f32 = 595.0;
+pub(super) const PAGE_HEIGHT: f32 = 842.0;
+pub(super) const MARGIN: f32 = 50.0;
+pub(super) const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+/// Objetos fixos que a assinatura reescreve numa atualização incremental.
+pub(super) const CATALOG_ID: usize = 1;
+pub(super) const INFO_ID: usize = 5;
+pub(super) const FIRST_PAGE_ID: usize = 6;
+const FOOTER_Y: f32 = 28.0;
+const BOTTOM: f32 = MARGIN + 10.0;
+const CELL_PADDING: f32 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Color(pub f32, pub f32, pub f32);
+
+impl Color {
+    pub const BLACK: Color = Color(0.0, 0.0, 0.0);
+    pub const WHITE: Color = Color(1.0, 1.0, 1.0);
+    pub const GRAY: Color = Color(0.44, 0.5, 0.59);
+    pub const LIGHT_GRAY: Color = Color(0.93, 0.95, 0.97);
+    pub const BORDER: Color = Color(0.89, 0.91, 0.94);
+    pub const NAVY: Color = Color(0.17, 0.24, 0.31);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+
+    /// Largura de um caractere em milésimos do tamanho da fonte (métricas AFM).
+    fn char_width(self, c: char) -> u16 {
+        let c = base_letter(c);
+        let widths = match self {
+            Font::Regular => &HELVETICA_WIDTHS,
+            Font::Bold => &HELVETICA_BOLD_WIDTHS,
+        };
+        match c as u32 {
+            32..=126 => widths[c as usize - 32],
+            _ => 556,
+        }
+    }
+}
+
+/// Largura do texto, em pontos.
+pub(super) fn text_width(text: &str, font: Font, size: f32) -> f32 {
+    text.chars().map(|c| font.char_width(c) as f32).sum::<f32>() * size / 1000.0
+}
+
+/// Uma coluna de tabela: título e fração da largura útil da página.
+pub(super) struct Column<'a> {
+    pub title: &'a str,
+    pub width: f32,
+}
+
+/// Documento em construção. O cursor (`y`) desce a partir do topo da página.
+pub(super) struct Document {
+    title: String,
+    pages: Vec<String>,
+    y: f32,
+}
+
+impl Document {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            pages: vec![String::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+
+    pub fn set_y(&mut self, y: f32) {
+        self.y = y;
+    }
+
+    pub fn new_page(&mut self) {
+        self.pages.push(String::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    pub fn space(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    /// Quebra a página se não houver `height` pontos livres acima da margem.
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < BOTTOM {
+            self.new_page();
+        }
+    }
+
+    fn page(&mut self) -> &mut String {
+        self.pages
+            .last_mut()
+            .expect("o documento sempre tem uma página")
+    }
+
+    /// Escreve uma linha de texto com a linha de base em `y`.
+    pub fn text(&mut self, x: f32, y: f32, font: Font, size: f32, color: Color, text: &str) {
+        let op = format!(
+            "BT /{} {:.1} Tf {:.3} {:.3} {:.3} rg {:.2} {:.2} Td ({}) Tj ET\n",
+            font.resource(),
+            size,
+            color.0,
+            color.1,
+            color.2,
+            x,
+            y,
+            encode(text)
+        );
+        self.page().push_str(&op);
+    }
+
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        let op = format!(
+            "{:.3} {:.3} {:.3} rg {:.2} {:.2} {:.2} {:.2} re f\n",
+            color.0, color.1, color.2, x, y, width, height
+        );
+        self.page().push_str(&op);
+    }
+
+    pub fn hline(&mut self, y: f32, color: Color) {
+        let op = format!(
+            "{:.3} {:.3} {:.3} RG 0.5 w {:.2} {:.2} m {:.2} {:.2} l S\n",
+            color.0,
+            color.1,
+            color.2,
+            MARGIN,
+            y,
+            PAGE_WIDTH - MARGIN,
+            y
+        );
+        self.page().push_str(&op);
+    }
+
+    pub fn heading(&mut self, text: &str) {
+        self.ensure(40.0);
+        self.space(22.0);
+        self.text(MARGIN, self.y, Font::Bold, 14.0, Color::NAVY, text);
+        self.space(6.0);
+        self.hline(self.y, Color::BORDER);
+        self.space(12.0);
+    }
+
+    /// Texto corrido com quebra de linha automática.
+    pub fn paragraph(&mut self, text: &str, font: Font, size: f32, color: Color) {
+        let leading = size * 1.35;
+        for line in wrap(text, font, size, CONTENT_WIDTH) {
+            self.ensure(leading);
+            self.space(leading);
+            self.text(MARGIN, self.y, font, size, color, &line);
+        }
+        self.space(size * 0.5);
+    }
+
+    /// Tabela com cabeçalho repetido a cada página. As células quebram linha e `\n`
+    /// separa parágrafos dentro da mesma célula.
+    pub fn table(&mut self, columns: &[Column], rows: &[Vec<String>]) {
+        const SIZE: f32 = 8.5;
+        let leading = SIZE * 1.3;
+        let widths: Vec<f32> = columns.iter().map(|c| c.width * CONTENT_WIDTH).collect();
+
+        let header: Vec<Vec<String>> = columns
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| wrap(c.title, Font::Bold, SIZE, w - 2.0 * CELL_PADDING))
+            .collect();
+        let header_height = row_height(&header, leading);
+        self.ensure(header_height + leading * 2.0 + 2.0 * CELL_PADDING);
+        self.table_row(
+            &widths,
+            &header,
+            Font::Bold,
+            Some(Color::LIGHT_GRAY),
+            leading,
+        );
+
+        for row in rows {
+            let cells: Vec<Vec<String>> = row
+                .iter()
+                .zip(&widths)
+                .map(|(text, w)| wrap(text, Font::Regular, SIZE, w - 2.0 * CELL_PADDING))
+                .collect();
+            if self.y - row_height(&cells, leading) < BOTTOM {
+                self.new_page();
+                self.table_row(
+                    &widths,
+                    &header,
+                    Font::Bold,
+                    Some(Color::LIGHT_GRAY),
+                    leading,
+                );
+            }
+            self.table_row(&widths, &cells, Font::Regular, None, leading);
+        }
+        self.space(8.0);
+    }
+
+    fn table_row(
+        &mut self,
+        widths: &[f32],
+        cells: &[Vec<String>],
+        font: Font,
+        background: Option<Color>,
+        leading: f32,
+    ) {
+        let height = row_height(cells, leading);
+        let top = self.y;
+        if let Some(color) = background {
+            self.rect(MARGIN, top - height, CONTENT_WIDTH, height, color);
+        }
+        let mut x = MARGIN;
+        for (lines, width) in cells.iter().zip(widths) {
+            let mut y = top - CELL_PADDING;
+            for line in lines {
+                y -= leading;
+                self.text(
+                    x + CELL_PADDING,
+                    y + 2.0,
+                    font,
+                    leading / 1.3,
+                    Color::BLACK,
+                    line,
+                );
+            }
+            x += width;
+        }
+        self.y = top - height;
+        self.hline(self.y, Color::BORDER);
+    }
+
+    /// Finaliza o documento, com o rodapé de cada página dado por
+    /// `footer(página, total)`.
+    pub fn finish(
+        mut self,
+        footer: impl Fn(usize, usize) -> String,
+        creation_date: &str,
+    ) -> String {
+        let total = self.pages.len();
+        for (i, page) in self.pages.iter_mut().enumerate() {
+            let text = footer(i + 1, total);
+            page.push_str(&format!(
+                "BT /F1 8.0 Tf {:.3} {:.3} {:.3} rg {:.2} {:.2} Td ({}) Tj ET\n",
+                Color::GRAY.0,
+                Color::GRAY.1,
+                Color::GRAY.2,
+                MARGIN,
+                FOOTER_Y,
+                encode(&text)
+            ));
+        }
+
+        // Objetos fixos: 1 catálogo, 2 árvore de páginas, 3 e 4 fontes, 5 metadados.
+        // Cada página ocupa dois objetos a partir do 6: a página e o seu conteúdo.
+        let page_ids: Vec<usize> = (0..total).map(|i| FIRST_PAGE_ID + 2 * i).collect();
+        let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+        let mut objects = vec![
+            catalog(""),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} /MediaBox [0 0 {} {}] >>",
+                kids.join(" "),
+                total,
+                PAGE_WIDTH,
+                PAGE_HEIGHT
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            format!(
+                "<< /Title ({}) /Producer (hubstry {}) /CreationDate (D:{}) >>",
+                encode(&self.title),
+                env!("CARGO_PKG_VERSION"),
+                creation_date
+            ),
+        ];
+        for (page, id) in self.pages.iter().zip(&page_ids) {
+            objects.push(page_object(*id, ""));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                page.len(),
+                page
+            ));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+        }
+        let xref = pdf.len();
+        pdf.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            pdf.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        pdf.push_str(&format!(
+            "trailer\n<< /Size {} /Root {} 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            CATALOG_ID,
+            INFO_ID,
+            xref
+        ));
+        pdf
+    }
+}
+
+/// Dicionário do catálogo; `extra` acrescenta entradas, como o `/AcroForm` da
+/// assinatura.
+pub(super) fn catalog(extra: &str) -> String {
+    format!("<< /Type /Catalog /Pages 2 0 R{} >>", extra)
+}
+
+/// Dicionário da página `id`, cujo conteúdo é o objeto seguinte; `extra` acrescenta
+/// entradas, como as `/Annots`.
+pub(super) fn page_object(id: usize, extra: &str) -> String {
+    format!(
+        "<< /Type /Page /Parent 2 0 R /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R{} >>",
+        id + 1,
+        extra
+    )
+}
+
+fn row_height(cells: &[Vec<String>], leading: f32) -> f32 {
+    let lines = cells.iter().map(Vec::len).max().unwrap_or(1).max(1);
+    lines as f32 * leading + 2.0 * CELL_PADDING
+}
+
+/// Quebra o texto em linhas que cabem em `width`. Palavras maiores que a linha são
+/// cortadas depois de `.`, `/`, `_` ou `-` (ids de regra, caminhos) e, se ainda não
+/// couberem, em qualquer caractere; `\n` inicia um novo parágrafo.
+pub(super) fn wrap(text: &str, font: Font, size: f32, width: f32) -> Vec<String> {
+    let fits = |line: &str| text_width(line, font, size) <= width;
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+            if fits(&candidate) {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for piece in word.split_inclusive(['.', '/', '_', '-']) {
+                if fits(&format!("{}{}", line, piece)) {
+                    line.push_str(piece);
+                    continue;
+                }
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                for c in piece.chars() {
+                    if !line.is_empty() && !fits(&format!("{}{}", line, c)) {
+                        lines.push(std::mem::take(&mut line));
+                    }
+                    line.push(c);
+                }
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Converte o texto para uma string literal de PDF em WinAnsi. Caracteres sem
+/// representação viram `?`.
+fn encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for c in text.chars() {
+        let byte = match c {
+            '\u{20}'..='\u{7e}' => c as u8,
+            '\u{a0}'..='\u{ff}' => c as u32 as u8,
+            '€' => 0x80,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '\t' => b' ',
+            _ => b'?',
+        };
+        match byte {
+            b'(' | b')' | b'\\' => {
+                encoded.push('\\');
+                encoded.push(byte as char);
+            }
+            0x20..=0x7e => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Letra sem acento, usada apenas para estimar a largura.
+fn base_letter(c: char) -> char {
+    match c {
+        'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+        'ú' | 'ù' | 'û' | 'ü' => 'u',
+        'ç' => 'c',
+        'ñ' => 'n',
+        'Á' | 'À' | 'Â' | 'Ã' | 'Ä' => 'A',
+        'É' | 'È' | 'Ê' | 'Ë' => 'E',
+        'Í' | 'Ì' | 'Î' | 'Ï' => 'I',
+        'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' => 'O',
+        'Ú' | 'Ù' | 'Û' | 'Ü' => 'U',
+        'Ç' => 'C',
+        'Ñ' => 'N',
+        '–' | '—' => '-',
+        _ => c,
+    }
+}
+
+/// Larguras dos caracteres 32 a 126 da Helvetica.
+#[rustfmt::skip]
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Larguras dos caracteres 32 a 126 da Helvetica-Bold.
+#[rustfmt::skip]
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
@@ -0,0 +1,545 @@
+//! Relatório em PDF para o arquivo de auditoria, gerado sem renderizador externo.
+
+mod document;
+mod signature;
+
+use self::document::{
+    Color, Column, Document, Font, CONTENT_WIDTH, MARGIN, PAGE_HEIGHT, PAGE_WIDTH,
+};
+use super::{citations, group_name, strict_mode, sub_scores, suppressed_entry, Reporter};
+use crate::error::HubstryError;
+use crate::i18n::Locale;
+use crate::models::{AnalysisResult, ComplianceViolation, OutputFormat, RulePackRef, RuleSeverity};
+use crate::scanner::WebScanResult;
+use crate::scoring::ScoreBreakdown;
+use crate::tr;
+use chrono::Utc;
+
+pub use self::signature::{verify_signature, Certificate, SigningKey};
+
+/// Relatório executivo em PDF: capa com o score, tabela de violações com local e
+/// correção, pacotes de regras aplicados e aviso legal.
+pub struct PdfReporter {
+    pub locale: Locale,
+}
+
+impl Reporter for PdfReporter {
+    fn format(&self) -> OutputFormat {
+        OutputFormat::Pdf
+    }
+
+    fn extension(&self) -> &'static str {
+        "pdf"
+    }
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError> {
+        let locale = self.locale;
+        let mut doc = Document::new(&format!(
+            "{} - {}",
+            tr!(locale, "pdf.title"),
+            tr!(locale, "pdf.code_subtitle")
+        ));
+        let severities = severity_summary(&result.violations, locale);
+        cover(
+            &mut doc,
+            locale,
+            &tr!(locale, "pdf.code_subtitle"),
+            result.compliance_score,
+            &[
+                (
+                    tr!(locale, "pdf.active_violations"),
+                    format!("{} ({})", result.violations.len(), severities),
+                ),
+                (
+                    tr!(locale, "pdf.suppressed_violations"),
+                    result.suppressed.len().to_string(),
+                ),
+                (
+                    tr!(locale, "pdf.checked_functions"),
+                    result.checked_functions.len().to_string(),
+                ),
+                (
+                    tr!(locale, "report.strict_mode"),
+                    strict_mode(result, locale),
+                ),
+            ],
+        );
+        rule_packs(&mut doc, locale, &result.rule_packs);
+        score_breakdown(&mut doc, locale, &result.score_breakdown);
+
+        doc.new_page();
+        doc.heading(&tr!(
+            locale,
+            "html.violations",
+            count = result.violations.len()
+        ));
+        if result.violations.is_empty() {
+            doc.paragraph(
+                &tr!(locale, "html.no_violations"),
+                Font::Regular,
+                10.0,
+                Color::BLACK,
+            );
+        } else {
+            let rows: Vec<Vec<String>> = result
+                .violations
+                .iter()
+                .map(|v| {
+                    let mut problem = locale.message(v);
+                    if let Some(suggestion) = locale.suggestion(v) {
+                        problem.push_str(&format!("\n{}: {}", tr!(locale, "pdf.fix"), suggestion));
+                    }
+                    if !v.citations.is_empty() {
+                        problem.push_str(&format!(
+                            "\n{}: {}",
+                            tr!(locale, "report.legal_basis"),
+                            citations(&v.citations, locale)
+                        ));
+                    }
+                    vec![
+                        v.severity.to_string(),
+                        v.rule_id.clone(),
+                        location(v, locale),
+                        problem,
+                    ]
+                })
+                .collect();
+            let (severity, rule, place, problem) = (
+                tr!(locale, "pdf.column.severity"),
+                tr!(locale, "pdf.column.rule"),
+                tr!(locale, "pdf.column.location"),
+                tr!(locale, "pdf.column.problem"),
+            );
+            doc.table(
+                &[
+                    Column {
+                        title: &severity,
+                        width: 0.12,
+                    },
+                    Column {
+                        title: &rule,
+                        width: 0.26,
+                    },
+                    Column {
+                        title: &place,
+                        width: 0.2,
+                    },
+                    Column {
+                        title: &problem,
+                        width: 0.42,
+                    },
+                ],
+                &rows,
+            );
+        }
+
+        if !result.suppressed.is_empty() {
+            doc.heading(&tr!(
+                locale,
+                "html.suppressed",
+                count = result.suppressed.len()
+            ));
+            let rows: Vec<Vec<String>> = result
+                .suppressed
+                .iter()
+                .map(|v| {
+                    let (reason, approver) = suppressed_entry(v, locale);
+                    vec![v.rule_id.clone(), location(v, locale), reason, approver]
+                })
+                .collect();
+            let (rule, place, reason, approver) = (
+                tr!(locale, "pdf.column.rule"),
+                tr!(locale, "pdf.column.location"),
+                tr!(locale, "pdf.column.reason"),
+                tr!(locale, "pdf.column.approver"),
+            );
+            doc.table(
+                &[
+                    Column {
+                        title: &rule,
+                        width: 0.25,
+                    },
+                    Column {
+                        title: &place,
+                        width: 0.25,
+                    },
+                    Column {
+                        title: &reason,
+                        width: 0.3,
+                    },
+                    Column {
+                        title: &approver,
+                        width: 0.2,
+                    },
+                ],
+                &rows,
+            );
+        }
+
+        disclaimer(&mut doc, locale);
+        Ok(finish(doc, locale))
+    }
+}
+
+/// Relatório em PDF de um web scan. Os textos das violações já vêm no idioma da
+/// varredura (ver [`crate::scanner::ScanConfig::locale`]).
+pub fn web_scan_pdf(result: &WebScanResult, locale: Locale) -> String {
+    let mut doc = Document::new(&format!(
+        "{} - {}",
+        tr!(locale, "pdf.title"),
+        tr!(locale, "pdf.web_subtitle")
+    ));
+    cover(
+        &mut doc,
+        locale,
+        &tr!(locale, "pdf.web_subtitle"),
+        result.score,
+        &[
+            ("URL".to_string(), result.url.clone()),
+            (tr!(locale, "pdf.scan_date"), result.scan_date.clone()),
+            (
+                tr!(locale, "pdf.pages_scanned"),
+                result.pages_scanned.to_string(),
+            ),
+            (
+                tr!(locale, "pdf.elements_analyzed"),
+                result.elements_analyzed.to_string(),
+            ),
+            (
+                tr!(locale, "pdf.violations"),
+                result.violations.len().to_string(),
+            ),
+        ],
+    );
+    rule_packs(&mut doc, locale, &result.rule_packs);
+    score_breakdown(&mut doc, locale, &result.score_breakdown);
+
+    doc.new_page();
+    doc.heading(&tr!(
+        locale,
+        "html.violations",
+        count = result.violations.len()
+    ));
+    if result.violations.is_empty() {
+        doc.paragraph(
+            &tr!(locale, "pdf.no_web_violations"),
+            Font::Regular,
+            10.0,
+            Color::BLACK,
+        );
+    } else {
+        let rows: Vec<Vec<String>> = result
+            .violations
+            .iter()
+            .map(|v| {
+                let mut place = v.page_url.clone();
+                if let Some(selector) = &v.element_selector {
+                    place.push_str(&format!("\n{}", selector));
+                }
+                let mut problem = format!(
+                    "{}\n{}: {}",
+                    v.business_description,
+                    tr!(locale, "pdf.fix"),
+                    v.remediation_business
+                );
+                if !v.citations.is_empty() {
+                    problem.push_str(&format!(
+                        "\n{}: {}",
+                        tr!(locale, "report.legal_basis"),
+                        citations(&v.citations, locale)
+                    ));
+                }
+                vec![v.severity.clone(), v.rule_id.clone(), place, problem]
+            })
+            .collect();
+        let (severity, rule, place, problem) = (
+            tr!(locale, "pdf.column.severity"),
+            tr!(locale, "pdf.column.rule"),
+            tr!(locale, "pdf.column.page"),
+            tr!(locale, "pdf.column.problem"),
+        );
+        doc.table(
+            &[
+                Column {
+                    title: &severity,
+                    width: 0.12,
+                },
+                Column {
+                    title: &rule,
+                    width: 0.24,
+                },
+                Column {
+                    title: &place,
+                    width: 0.22,
+                },
+                Column {
+                    title: &problem,
+                    width: 0.42,
+                },
+            ],
+            &rows,
+        );
+    }
+
+    disclaimer(&mut doc, locale);
+    finish(doc, locale)
+}
+
+/// Capa: faixa com o título, data de geração, score e fatos principais.
+fn cover(
+    doc: &mut Document,
+    locale: Locale,
+    subtitle: &str,
+    score: f64,
+    facts: &[(String, String)],
+) {
+    doc.rect(0.0, PAGE_HEIGHT - 130.0, PAGE_WIDTH, 130.0, Color::NAVY);
+    doc.text(
+        MARGIN,
+        PAGE_HEIGHT - 70.0,
+        Font::Bold,
+        26.0,
+        Color::WHITE,
+        &tr!(locale, "pdf.title"),
+    );
+    doc.text(
+        MARGIN,
+        PAGE_HEIGHT - 98.0,
+        Font::Regular,
+        13.0,
+        Color::WHITE,
+        &format!("Hubstry CaaS - {}", subtitle),
+    );
+    doc.set_y(PAGE_HEIGHT - 160.0);
+    doc.text(
+        MARGIN,
+        doc.y(),
+        Font::Regular,
+        10.0,
+        Color::GRAY,
+        &tr!(
+            locale,
+            "pdf.generated_at",
+            date = Utc::now().format(&tr!(locale, "format.date_time"))
+        ),
+    );
+
+    let (background, foreground) = if score >= 90.0 {
+        (Color(0.83, 0.93, 0.85), Color(0.08, 0.34, 0.14))
+    } else if score >= 70.0 {
+        (Color(1.0, 0.95, 0.8), Color(0.52, 0.39, 0.02))
+    } else {
+        (Color(0.97, 0.84, 0.85), Color(0.45, 0.11, 0.14))
+    };
+    let top = doc.y() - 25.0;
+    doc.rect(MARGIN, top - 120.0, CONTENT_WIDTH, 120.0, background);
+    doc.text(
+        MARGIN + 20.0,
+        top - 35.0,
+        Font::Bold,
+        14.0,
+        foreground,
+        &tr!(locale, "html.score"),
+    );
+    doc.text(
+        MARGIN + 20.0,
+        top - 95.0,
+        Font::Bold,
+        48.0,
+        foreground,
+        &format!("{:.1}%", score),
+    );
+    doc.set_y(top - 150.0);
+
+    for (label, value) in facts {
+        doc.text(MARGIN, doc.y(), Font::Bold, 11.0, Color::BLACK, label);
+        doc.text(
+            MARGIN + 150.0,
+            doc.y(),
+            Font::Regular,
+            11.0,
+            Color::BLACK,
+            value,
+        );
+        doc.space(20.0);
+    }
+}
+
+fn rule_packs(doc: &mut Document, locale: Locale, packs: &[RulePackRef]) {
+    doc.heading(&tr!(locale, "pdf.rule_packs"));
+    if packs.is_empty() {
+        doc.paragraph(
+            &tr!(locale, "pdf.no_rule_packs"),
+            Font::Regular,
+            10.0,
+            Color::BLACK,
+        );
+        return;
+    }
+    let rows: Vec<Vec<String>> = packs
+        .iter()
+        .map(|p| {
+            vec![
+                p.name.clone(),
+                p.version.clone(),
+                p.effective_date.clone(),
+                p.checksum.clone(),
+            ]
+        })
+        .collect();
+    let (pack, version, effective) = (
+        tr!(locale, "pdf.column.pack"),
+        tr!(locale, "pdf.column.version"),
+        tr!(locale, "pdf.column.effective_date"),
+    );
+    doc.table(
+        &[
+            Column {
+                title: &pack,
+                width: 0.18,
+            },
+            Column {
+                title: &version,
+                width: 0.1,
+            },
+            Column {
+                title: &effective,
+                width: 0.14,
+            },
+            Column {
+                title: "Checksum",
+                width: 0.58,
+            },
+        ],
+        &rows,
+    );
+}
+
+fn score_breakdown(doc: &mut Document, locale: Locale, breakdown: &ScoreBreakdown) {
+    doc.heading(&tr!(locale, "report.breakdown"));
+    doc.paragraph(
+        &format!(
+            "{}. {}",
+            tr!(
+                locale,
+                "report.breakdown_summary",
+                obligations = breakdown.obligations,
+                failed = breakdown.failed
+            ),
+            tr!(locale, "pdf.obligation_definition")
+        ),
+        Font::Regular,
+        10.0,
+        Color::BLACK,
+    );
+    if breakdown.obligations > 0 {
+        let rows: Vec<Vec<String>> = sub_scores(breakdown)
+            .map(|(group, name, score)| {
+                vec![
+                    group_name(group, locale),
+                    name.to_string(),
+                    format!("{:.1}%", score.score),
+                    format!("{}/{}", score.failed, score.obligations),
+                ]
+            })
+            .collect();
+        let (group, name, score, failed) = (
+            tr!(locale, "report.column.group"),
+            tr!(locale, "report.column.name"),
+            tr!(locale, "report.column.score"),
+            tr!(locale, "report.column.failed"),
+        );
+        doc.table(
+            &[
+                Column {
+                    title: &group,
+                    width: 0.25,
+                },
+                Column {
+                    title: &name,
+                    width: 0.35,
+                },
+                Column {
+                    title: &score,
+                    width: 0.2,
+                },
+                Column {
+                    title: &failed,
+                    width: 0.2,
+                },
+            ],
+            &rows,
+        );
+    }
+    doc.paragraph(
+        &format!("{}: {}", tr!(locale, "report.formula"), breakdown.formula),
+        Font::Regular,
+        9.0,
+        Color::GRAY,
+    );
+}
+
+fn disclaimer(doc: &mut Document, locale: Locale) {
+    doc.heading(&tr!(locale, "pdf.disclaimer_heading"));
+    doc.paragraph(
+        &tr!(locale, "pdf.disclaimer"),
+        Font::Regular,
+        9.0,
+        Color::GRAY,
+    );
+}
+
+fn finish(doc: Document, locale: Locale) -> String {
+    doc.finish(
+        |page, total| tr!(locale, "pdf.footer", page = page, total = total),
+        &Utc::now().format("%Y%m%d%H%M%SZ").to_string(),
+    )
+}
+
+/// Contagem de violações por severidade, ex.: `CRITICAL: 1, HIGH: 2`.
+fn severity_summary(violations: &[ComplianceViolation], locale: Locale) -> String {
+    let counts: Vec<String> = [
+        RuleSeverity::Critical,
+        RuleSeverity::High,
+        RuleSeverity::Medium,
+        RuleSeverity::Low,
+        RuleSeverity::Info,
+    ]
+    .iter()
+    .filter_map(|severity| {
+        let count = violations
+            .iter()
+            .filter(|v| &v.severity == severity)
+            .count();
+        (count > 0).then(|| format!("{}: {}", severity, count))
+    })
+    .collect();
+    if counts.is_empty() {
+        tr!(locale, "pdf.none")
+    } else {
+        counts.join(", ")
+    }
+}
+
+fn location(violation: &ComplianceViolation, locale: Locale) -> String {
+    let mut parts = Vec::new();
+    if let Some(file) = &violation.file {
+        parts.push(file.clone());
+    }
+    if let Some(function) = &violation.function {
+        parts.push(format!("fn {}", function));
+    }
+    if let Some(line) = violation.line {
+        parts.push(tr!(
+            locale,
+            "report.position",
+            line = line,
+            column = violation.column.unwrap_or(0)
+        ));
+    }
+    if parts.is_empty() {
+        tr!(locale, "pdf.project")
+    } else {
+        parts.join("\n")
+    }
+}
@@ -0,0 +1,567 @@
+//! Assinatura digital embutida nos relatórios em PDF.
+//!
+//! A assinatura segue o padrão `adbe.pkcs7.detached` do PDF: o relatório ganha uma
+//! atualização incremental com um campo de assinatura (`/Sig`) cujo `/ByteRange`
+//! cobre o arquivo inteiro, menos o próprio `/Contents`. Ali fica um CMS `SignedData`
+//! (RSA com SHA-256) com o certificado X.509 de quem assinou, então leitores de PDF
+//! exibem a assinatura. `hubstry verify-pdf` a confere contra um certificado fixado,
+//! nunca contra o que vem dentro do arquivo.
+//!
+//! O DER do CMS e do certificado é lido e escrito aqui mesmo, sem dependências
+//! externas; só o RSA vem do `ring`.
+
+use super::document::{catalog, page_object, CATALOG_ID, FIRST_PAGE_ID, INFO_ID};
+use crate::error::{HubstryError, ReportError};
+use crate::i18n::Message;
+use crate::msg;
+use chrono::{DateTime, Utc};
+use ring::rand::SystemRandom;
+use ring::signature::{
+    RsaKeyPair, UnparsedPublicKey, RSA_PKCS1_2048_8192_SHA256, RSA_PKCS1_SHA256,
+};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+/// Bytes reservados para o CMS no `/Contents` (o dobro em hexadecimal): cabe uma
+/// chave RSA de 4096 bits com um certificado de tamanho usual.
+const CONTENTS_SIZE: usize = 8192;
+/// Largura de cada número do `/ByteRange`, reservada antes de as posições serem
+/// conhecidas.
+const BYTE_RANGE_WIDTH: usize = 10;
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+const TAG_CONTEXT_0: u8 = 0xA0;
+const TAG_CONTEXT_1: u8 = 0xA1;
+const DER_NULL: &[u8] = &[0x05, 0x00];
+
+// Identificadores de objeto, já codificados em DER (tag, tamanho e valor).
+const OID_SHA256: &[u8] = &[
+    0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01,
+];
+const OID_RSA_ENCRYPTION: &[u8] = &[
+    0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x01,
+];
+const OID_SHA256_WITH_RSA: &[u8] = &[
+    0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0B,
+];
+const OID_DATA: &[u8] = &[
+    0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x01,
+];
+const OID_SIGNED_DATA: &[u8] = &[
+    0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02,
+];
+const OID_CONTENT_TYPE: &[u8] = &[
+    0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x03,
+];
+const OID_MESSAGE_DIGEST: &[u8] = &[
+    0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x04,
+];
+const OID_COMMON_NAME: &[u8] = &[0x06, 0x03, 0x55, 0x04, 0x03];
+
+/// Chave privada RSA e certificado X.509 de quem assina os relatórios.
+pub struct SigningKey {
+    pair: RsaKeyPair,
+    certificate: Certificate,
+}
+
+impl SigningKey {
+    /// Lê a chave (PEM PKCS#8 ou PKCS#1, como as geradas pelo `openssl`) e o
+    /// certificado correspondente (PEM).
+    pub fn from_pem_files(key: &Path, certificate: &Path) -> Result<Self, HubstryError> {
+        let pem = fs::read_to_string(key).map_err(|e| HubstryError::io(key, e))?;
+        let pair = parse_key(&pem).map_err(|message| invalid_key(key, message))?;
+        let certificate = Certificate::from_pem_file(certificate)?;
+        if pair.public().as_ref() != certificate.public_key.as_slice() {
+            return Err(invalid_key(key, msg!("error.signature.key_mismatch")));
+        }
+        Ok(Self { pair, certificate })
+    }
+
+    /// Certificado que acompanha as assinaturas.
+    pub fn certificate(&self) -> &Certificate {
+        &self.certificate
+    }
+
+    /// Devolve o PDF com a assinatura embutida numa atualização incremental.
+    pub fn sign(&self, pdf: &str, signed_at: DateTime<Utc>) -> Result<String, HubstryError> {
+        let unsupported = || signing_error(msg!("error.signature.foreign_pdf"));
+        let size = last_number_after(pdf.as_bytes(), b"/Size ").ok_or_else(unsupported)?;
+        let prev = last_number_after(pdf.as_bytes(), b"startxref\n").ok_or_else(unsupported)?;
+        let (sig_id, widget_id) = (size, size + 1);
+
+        let placeholder = format!(
+            "[0 {} {} {}]",
+            "*".repeat(BYTE_RANGE_WIDTH),
+            "*".repeat(BYTE_RANGE_WIDTH),
+            "*".repeat(BYTE_RANGE_WIDTH)
+        );
+        let objects = [
+            (
+                CATALOG_ID,
+                catalog(&format!(
+                    " /AcroForm << /Fields [{} 0 R] /SigFlags 3 >>",
+                    widget_id
+                )),
+            ),
+            (
+                FIRST_PAGE_ID,
+                page_object(FIRST_PAGE_ID, &format!(" /Annots [{} 0 R]", widget_id)),
+            ),
+            (
+                sig_id,
+                format!(
+                    "<< /Type /Sig /Filter /Adobe.PPKLite /SubFilter /adbe.pkcs7.detached /ByteRange {} /Contents <{}> /M (D:{}) >>",
+                    placeholder,
+                    "0".repeat(2 * CONTENTS_SIZE),
+                    signed_at.format("%Y%m%d%H%M%SZ")
+                ),
+            ),
+            (
+                widget_id,
+                format!(
+                    "<< /Type /Annot /Subtype /Widget /FT /Sig /T (hubstry) /V {} 0 R /Rect [0 0 0 0] /F 132 /P {} 0 R >>",
+                    sig_id, FIRST_PAGE_ID
+                ),
+            ),
+        ];
+
+        let mut signed = pdf.to_string();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (id, object) in &objects {
+            offsets.push((*id, signed.len()));
+            signed.push_str(&format!("{} 0 obj\n{}\nendobj\n", id, object));
+        }
+        offsets.sort_unstable();
+        let xref = signed.len();
+        signed.push_str("xref\n");
+        let mut i = 0;
+        while i < offsets.len() {
+            // Subseções de objetos consecutivos.
+            let run = offsets[i..]
+                .windows(2)
+                .take_while(|pair| pair[1].0 == pair[0].0 + 1)
+                .count()
+                + 1;
+            signed.push_str(&format!("{} {}\n", offsets[i].0, run));
+            for (_, offset) in &offsets[i..i + run] {
+                signed.push_str(&format!("{:010} 00000 n \n", offset));
+            }
+            i += run;
+        }
+        signed.push_str(&format!(
+            "trailer\n<< /Size {} /Root {} 0 R /Info {} 0 R /Prev {} >>\nstartxref\n{}\n%%EOF\n",
+            widget_id + 1,
+            CATALOG_ID,
+            INFO_ID,
+            prev,
+            xref
+        ));
+
+        // Agora que as posições são conhecidas, preenche o `/ByteRange` e o `/Contents`.
+        let range_at = signed.rfind(&placeholder).ok_or_else(unsupported)?;
+        let start = signed[range_at..]
+            .find("/Contents <")
+            .map(|i| range_at + i + "/Contents ".len())
+            .ok_or_else(unsupported)?;
+        let end = start + 2 * CONTENTS_SIZE + 2;
+        let byte_range = format!("[0 {} {} {}]", start, end, signed.len() - end);
+        let byte_range = format!("{:<width$}", byte_range, width = placeholder.len());
+        signed.replace_range(range_at..range_at + placeholder.len(), &byte_range);
+
+        let digest = Sha256::new()
+            .chain_update(&signed.as_bytes()[..start])
+            .chain_update(&signed.as_bytes()[end..])
+            .finalize();
+        let cms = hex::encode(self.signed_data(&digest)?);
+        if cms.len() > 2 * CONTENTS_SIZE {
+            return Err(signing_error(msg!(
+                "error.signature.too_large",
+                size = cms.len() / 2,
+                reserved = CONTENTS_SIZE
+            )));
+        }
+        signed.replace_range(start + 1..start + 1 + cms.len(), &cms);
+        Ok(signed)
+    }
+
+    /// CMS `SignedData` destacado sobre o resumo SHA-256 do PDF.
+    fn signed_data(&self, digest: &[u8]) -> Result<Vec<u8>, HubstryError> {
+        let mut attributes = [
+            sequence(&[OID_CONTENT_TYPE, &der(TAG_SET, OID_DATA)]),
+            sequence(&[
+                OID_MESSAGE_DIGEST,
+                &der(TAG_SET, &der(TAG_OCTET_STRING, digest)),
+            ]),
+        ];
+        // Um SET OF em DER tem os elementos em ordem crescente de codificação.
+        attributes.sort();
+        let attributes = attributes.concat();
+
+        let mut signature = vec![0; self.pair.public().modulus_len()];
+        self.pair
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                &der(TAG_SET, &attributes),
+                &mut signature,
+            )
+            .map_err(|_| signing_error(msg!("error.signature.rsa_failed")))?;
+
+        let certificate = &self.certificate;
+        let signer_info = sequence(&[
+            &der(TAG_INTEGER, &[1]),
+            &sequence(&[&certificate.issuer, &certificate.serial]),
+            &sequence(&[OID_SHA256, DER_NULL]),
+            &der(TAG_CONTEXT_0, &attributes),
+            &sequence(&[OID_RSA_ENCRYPTION, DER_NULL]),
+            &der(TAG_OCTET_STRING, &signature),
+        ]);
+        let signed_data = sequence(&[
+            &der(TAG_INTEGER, &[1]),
+            &der(TAG_SET, &sequence(&[OID_SHA256, DER_NULL])),
+            &sequence(&[OID_DATA]),
+            &der(TAG_CONTEXT_0, &certificate.der),
+            &der(TAG_SET, &signer_info),
+        ]);
+        Ok(sequence(&[
+            OID_SIGNED_DATA,
+            &der(TAG_CONTEXT_0, &signed_data),
+        ]))
+    }
+}
+
+/// Certificado X.509 com chave RSA, de quem assina ou de quem se espera a assinatura.
+#[derive(Debug, Clone)]
+pub struct Certificate {
+    der: Vec<u8>,
+    /// `issuer` e `serialNumber` codificados, que identificam o signatário no CMS.
+    issuer: Vec<u8>,
+    serial: Vec<u8>,
+    /// Chave pública RSA (`RSAPublicKey` em DER).
+    public_key: Vec<u8>,
+    subject: String,
+}
+
+impl Certificate {
+    /// Lê um certificado em PEM.
+    pub fn from_pem_file(path: &Path) -> Result<Self, HubstryError> {
+        let pem = fs::read_to_string(path).map_err(|e| HubstryError::io(path, e))?;
+        pem_block(&pem, &["CERTIFICATE"])
+            .and_then(|der| Self::from_der(&der))
+            .map_err(|message| invalid_key(path, message))
+    }
+
+    fn from_der(der: &[u8]) -> Result<Self, Message> {
+        let invalid = |_| msg!("error.signature.invalid_certificate");
+        let certificate = Reader::new(der).expect(TAG_SEQUENCE).map_err(invalid)?;
+        let mut tbs = Reader::new(certificate.value)
+            .expect(TAG_SEQUENCE)
+            .map(|tbs| Reader::new(tbs.value))
+            .map_err(invalid)?;
+        if tbs.peek() == Some(TAG_CONTEXT_0) {
+            tbs.next().map_err(invalid)?;
+        }
+        let serial = tbs.expect(TAG_INTEGER).map_err(invalid)?;
+        tbs.expect(TAG_SEQUENCE).map_err(invalid)?;
+        let issuer = tbs.expect(TAG_SEQUENCE).map_err(invalid)?;
+        tbs.expect(TAG_SEQUENCE).map_err(invalid)?;
+        let subject = tbs.expect(TAG_SEQUENCE).map_err(invalid)?;
+        let mut key_info = tbs
+            .expect(TAG_SEQUENCE)
+            .map(|info| Reader::new(info.value))
+            .map_err(invalid)?;
+        let mut algorithm = key_info
+            .expect(TAG_SEQUENCE)
+            .map(|alg| Reader::new(alg.value))
+            .map_err(invalid)?;
+        if algorithm.next().map_err(invalid)?.raw != OID_RSA_ENCRYPTION {
+            return Err(msg!("error.signature.not_rsa_certificate"));
+        }
+        let public_key = match key_info.expect(TAG_BIT_STRING).map_err(invalid)?.value {
+            [0, key @ ..] => key.to_vec(),
+            _ => return Err(msg!("error.signature.invalid_certificate")),
+        };
+        Ok(Self {
+            der: certificate.raw.to_vec(),
+            issuer: issuer.raw.to_vec(),
+            serial: serial.raw.to_vec(),
+            public_key,
+            subject: common_name(subject.value).unwrap_or_default(),
+        })
+    }
+
+    /// Nome comum (`CN`) do titular.
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// SHA-256 do certificado, em hexadecimal, para conferência fora da ferramenta.
+    pub fn fingerprint(&self) -> String {
+        hex::encode(Sha256::digest(&self.der))
+    }
+}
+
+/// Confere a assinatura embutida no PDF: o `/ByteRange` precisa cobrir o arquivo
+/// inteiro, o resumo precisa bater com o conteúdo e a assinatura precisa ter sido
+/// feita com a chave do certificado esperado.
+pub fn verify_signature(pdf: &[u8], certificate: &Certificate) -> Result<(), HubstryError> {
+    let unverifiable = |key: &'static str| -> HubstryError {
+        ReportError::Unverifiable { message: msg!(key) }.into()
+    };
+    let range = byte_range(pdf).ok_or_else(|| unverifiable("error.signature.unsigned"))?;
+    let [first, length, second, rest] = range;
+    if first != 0 || length > second || second.checked_add(rest) != Some(pdf.len()) {
+        return Err(unverifiable("error.signature.partial"));
+    }
+    let cms = match &pdf[length..second] {
+        [b'<', hex @ .., b'>'] => hex::decode(hex).ok(),
+        _ => None,
+    }
+    .ok_or_else(|| unverifiable("error.signature.invalid_contents"))?;
+    let signer =
+        SignerInfo::parse(&cms).ok_or_else(|| unverifiable("error.signature.invalid_cms"))?;
+
+    let digest = Sha256::new()
+        .chain_update(&pdf[..length])
+        .chain_update(&pdf[second..])
+        .finalize();
+    if signer.message_digest != digest.as_slice() {
+        return Err(unverifiable("error.signature.tampered"));
+    }
+    UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, &certificate.public_key)
+        .verify(&signer.signed_attributes, signer.signature)
+        .map_err(|_| unverifiable("error.signature.other_certificate"))
+}
+
+/// Campos do `SignerInfo` necessários para conferir a assinatura.
+struct SignerInfo<'a> {
+    /// Atributos assinados, recodificados como SET (a forma que é assinada).
+    signed_attributes: Vec<u8>,
+    message_digest: &'a [u8],
+    signature: &'a [u8],
+}
+
+impl<'a> SignerInfo<'a> {
+    fn parse(cms: &'a [u8]) -> Option<Self> {
+        let mut content_info = Reader::new(Reader::new(cms).expect(TAG_SEQUENCE).ok()?.value);
+        if content_info.next().ok()?.raw != OID_SIGNED_DATA {
+            return None;
+        }
+        let explicit = content_info.expect(TAG_CONTEXT_0).ok()?;
+        let mut signed_data =
+            Reader::new(Reader::new(explicit.value).expect(TAG_SEQUENCE).ok()?.value);
+        signed_data.expect(TAG_INTEGER).ok()?;
+        signed_data.expect(TAG_SET).ok()?;
+        signed_data.expect(TAG_SEQUENCE).ok()?;
+        while matches!(signed_data.peek(), Some(TAG_CONTEXT_0 | TAG_CONTEXT_1)) {
+            signed_data.next().ok()?;
+        }
+        let signer_infos = signed_data.expect(TAG_SET).ok()?;
+        let mut signer = Reader::new(
+            Reader::new(signer_infos.value)
+                .expect(TAG_SEQUENCE)
+                .ok()?
+                .value,
+        );
+        signer.expect(TAG_INTEGER).ok()?;
+        signer.next().ok()?;
+        let mut digest_algorithm = Reader::new(signer.expect(TAG_SEQUENCE).ok()?.value);
+        if digest_algorithm.next().ok()?.raw != OID_SHA256 {
+            return None;
+        }
+        let attributes = signer.expect(TAG_CONTEXT_0).ok()?;
+        let mut signature_algorithm = Reader::new(signer.expect(TAG_SEQUENCE).ok()?.value);
+        if ![OID_RSA_ENCRYPTION, OID_SHA256_WITH_RSA]
+            .contains(&signature_algorithm.next().ok()?.raw)
+        {
+            return None;
+        }
+        let signature = signer.expect(TAG_OCTET_STRING).ok()?.value;
+
+        let mut message_digest = None;
+        let mut reader = Reader::new(attributes.value);
+        while !reader.is_empty() {
+            let mut attribute = Reader::new(reader.expect(TAG_SEQUENCE).ok()?.value);
+            let oid = attribute.next().ok()?.raw;
+            let values = attribute.expect(TAG_SET).ok()?;
+            if oid == OID_MESSAGE_DIGEST {
+                message_digest = Some(
+                    Reader::new(values.value)
+                        .expect(TAG_OCTET_STRING)
+                        .ok()?
+                        .value,
+                );
+            }
+        }
+        let mut signed_attributes = attributes.raw.to_vec();
+        signed_attributes[0] = TAG_SET;
+        Some(Self {
+            signed_attributes,
+            message_digest: message_digest?,
+            signature,
+        })
+    }
+}
+
+/// O último `/ByteRange` do arquivo, o da assinatura mais recente.
+fn byte_range(pdf: &[u8]) -> Option<[usize; 4]> {
+    let key = b"/ByteRange";
+    let at = pdf.windows(key.len()).rposition(|window| window == key)? + key.len();
+    let rest = &pdf[at..];
+    let open = rest.iter().position(|&b| b == b'[')?;
+    let close = rest.iter().position(|&b| b == b']')?;
+    let numbers: Vec<usize> = std::str::from_utf8(rest.get(open + 1..close)?)
+        .ok()?
+        .split_ascii_whitespace()
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    numbers.try_into().ok()
+}
+
+/// O número que segue a última ocorrência de `key`, como em `/Size 12`.
+fn last_number_after(pdf: &[u8], key: &[u8]) -> Option<usize> {
+    let at = pdf.windows(key.len()).rposition(|window| window == key)? + key.len();
+    let digits = pdf[at..].iter().take_while(|b| b.is_ascii_digit()).count();
+    std::str::from_utf8(&pdf[at..at + digits])
+        .ok()?
+        .parse()
+        .ok()
+}
+
+fn parse_key(pem: &str) -> Result<RsaKeyPair, Message> {
+    let der = pem_block(pem, &["PRIVATE KEY", "RSA PRIVATE KEY"])?;
+    RsaKeyPair::from_pkcs8(&der)
+        .or_else(|_| RsaKeyPair::from_der(&der))
+        .map_err(|e| msg!("error.signature.not_rsa_key", detail = e))
+}
+
+/// Conteúdo do primeiro bloco PEM com um dos rótulos `labels`.
+fn pem_block(pem: &str, labels: &[&str]) -> Result<Vec<u8>, Message> {
+    let mut lines = pem.lines().map(str::trim);
+    let label = lines
+        .by_ref()
+        .find_map(|line| {
+            labels
+                .iter()
+                .find(|label| line == format!("-----BEGIN {}-----", label))
+        })
+        .ok_or_else(|| msg!("error.signature.missing_pem", label = labels[0]))?;
+    let end = format!("-----END {}-----", label);
+    let body: String = lines.take_while(|line| *line != end).collect();
+    base64::Engine::decode(&base64::engine::general_purpose::STANDARD, body)
+        .map_err(|e| msg!("error.signature.invalid_pem", detail = e))
+}
+
+/// O `CN` de um `Name` X.509 (o conteúdo da SEQUENCE).
+fn common_name(name: &[u8]) -> Option<String> {
+    let mut rdns = Reader::new(name);
+    while !rdns.is_empty() {
+        let set = rdns.expect(TAG_SET).ok()?;
+        let mut attribute = Reader::new(Reader::new(set.value).expect(TAG_SEQUENCE).ok()?.value);
+        if attribute.next().ok()?.raw == OID_COMMON_NAME {
+            return Some(String::from_utf8_lossy(attribute.next().ok()?.value).into_owned());
+        }
+    }
+    None
+}
+
+fn invalid_key(path: &Path, message: Message) -> HubstryError {
+    ReportError::SigningKey {
+        path: path.to_path_buf(),
+        message,
+    }
+    .into()
+}
+
+fn signing_error(message: Message) -> HubstryError {
+    ReportError::Signing { message }.into()
+}
+
+/// Codifica um elemento DER.
+fn der(tag: u8, value: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    match value.len() {
+        len @ 0..=0x7F => out.push(len as u8),
+        len => {
+            let bytes = len.to_be_bytes();
+            let skip = bytes.iter().take_while(|&&b| b == 0).count();
+            out.push(0x80 | (bytes.len() - skip) as u8);
+            out.extend_from_slice(&bytes[skip..]);
+        }
+    }
+    out.extend_from_slice(value);
+    out
+}
+
+fn sequence(items: &[&[u8]]) -> Vec<u8> {
+    der(TAG_SEQUENCE, &items.concat())
+}
+
+/// Um elemento DER lido: a tag, o valor e a codificação completa.
+struct Element<'a> {
+    tag: u8,
+    value: &'a [u8],
+    raw: &'a [u8],
+}
+
+/// Leitor sequencial dos elementos DER de um trecho.
+struct Reader<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self { input }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.first().copied()
+    }
+
+    fn next(&mut self) -> Result<Element<'a>, ()> {
+        let (&tag, rest) = self.input.split_first().ok_or(())?;
+        let (&first, rest) = rest.split_first().ok_or(())?;
+        let (len, rest) = if first < 0x80 {
+            (usize::from(first), rest)
+        } else {
+            let count = usize::from(first & 0x7F);
+            if count == 0 || count > std::mem::size_of::<usize>() || rest.len() < count {
+                return Err(());
+            }
+            let len = rest[..count]
+                .iter()
+                .fold(0usize, |len, &b| (len << 8) | usize::from(b));
+            (len, &rest[count..])
+        };
+        if rest.len() < len {
+            return Err(());
+        }
+        let header = self.input.len() - rest.len();
+        let raw = &self.input[..header + len];
+        self.input = &rest[len..];
+        Ok(Element {
+            tag,
+            value: &rest[..len],
+            raw,
+        })
+    }
+
+    fn expect(&mut self, tag: u8) -> Result<Element<'a>, ()> {
+        let element = self.next()?;
+        if element.tag == tag {
+            Ok(element)
+        } else {
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use super::{citations, Reporter};
+use crate::error::HubstryError;
+use crate::i18n::Locale;
+use crate::models::{AnalysisResult, ComplianceViolation, Fix, OutputFormat, RuleSeverity};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+/// Chave de `partialFingerprints`; o sufixo muda se o cálculo mudar.
+const FINGERPRINT_KEY: &str = "hubstryFingerprint/v2";
+
+/// Relatório SARIF 2.1.0, para as ferramentas de code scanning que exibem os
+/// achados na própria revisão de código.
+pub struct SarifReporter {
+    pub locale: Locale,
+}
+
+impl Reporter for SarifReporter {
+    fn format(&self) -> OutputFormat {
+        OutputFormat::Sarif
+    }
+
+    fn extension(&self) -> &'static str {
+        "sarif"
+    }
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError> {
+        // Um descritor por id de regra, na ordem em que aparecem.
+        let mut rules: Vec<&ComplianceViolation> = Vec::new();
+        let mut rule_index: HashMap<&str, usize> = HashMap::new();
+        for violation in result.violations.iter().chain(&result.suppressed) {
+            if !rule_index.contains_key(violation.rule_id.as_str()) {
+                rule_index.insert(&violation.rule_id, rules.len());
+                rules.push(violation);
+            }
+        }
+
+        let mut occurrences: HashMap<String, usize> = HashMap::new();
+        let results: Vec<Value> = result
+            .violations
+            .iter()
+            .chain(&result.suppressed)
+            .map(|violation| {
+                let fingerprint = violation.fingerprint();
+                let occurrence = occurrences.entry(fingerprint.clone()).or_insert(0);
+                *occurrence += 1;
+                sarif_result(
+                    violation,
+                    self.locale,
+                    rule_index[violation.rule_id.as_str()],
+                    format!("{}:{}", fingerprint, occurrence),
+                )
+            })
+            .collect();
+
+        let log = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": SARIF_VERSION,
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "hubstry",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                        "rules": rules.iter().map(|v| rule_descriptor(v, self.locale)).collect::<Vec<_>>(),
+                    }
+                },
+                "results": results,
+                "properties": {
+                    "complianceScore": result.compliance_score,
+                    "scoreBreakdown": result.score_breakdown,
+                    "strictMode": result.strict_mode,
+                    "rulePacks": result.rule_packs,
+                },
+            }],
+        });
+        Ok(serde_json::to_string_pretty(&log)?)
+    }
+}
+
+fn level(severity: &RuleSeverity) -> &'static str {
+    match severity {
+        RuleSeverity::Critical | RuleSeverity::High => "error",
+        RuleSeverity::Medium => "warning",
+        RuleSeverity::Low | RuleSeverity::Info => "note",
+    }
+}
+
+/// Nota de 0 a 10 usada pelas ferramentas para ordenar os achados de segurança.
+fn security_severity(severity: &RuleSeverity) -> &'static str {
+    match severity {
+        RuleSeverity::Critical => "9.0",
+        RuleSeverity::High => "7.0",
+        RuleSeverity::Medium => "5.0",
+        RuleSeverity::Low => "3.0",
+        RuleSeverity::Info => "1.0",
+    }
+}
+
+fn rule_descriptor(violation: &ComplianceViolation, locale: Locale) -> Value {
+    let message = locale.message(violation);
+    let mut help = locale
+        .suggestion(violation)
+        .unwrap_or_else(|| message.clone());
+    if !violation.citations.is_empty() {
+        help.push_str(&format!(
+            "\n{}: {}",
+            crate::tr!(locale, "report.legal_basis"),
+            citations(&violation.citations, locale)
+        ));
+    }
+    json!({
+        "id": violation.rule_id,
+        "shortDescription": { "text": message },
+        "fullDescription": { "text": message },
+        "help": { "text": help },
+        "defaultConfiguration": { "level": level(&violation.severity) },
+        "properties": {
+            "tags": ["compliance"],
+            "security-severity": security_severity(&violation.severity),
+            "legalCitations": violation.citations,
+        },
+    })
+}
+
+fn sarif_result(
+    violation: &ComplianceViolation,
+    locale: Locale,
+    rule_index: usize,
+    fingerprint: String,
+) -> Value {
+    let mut result = json!({
+        "ruleId": violation.rule_id,
+        "ruleIndex": rule_index,
+        "level": level(&violation.severity),
+        "message": { "text": locale.message(violation) },
+        "partialFingerprints": { FINGERPRINT_KEY: fingerprint },
+    });
+    if let Some(file) = &violation.file {
+        let mut location = json!({
+            "physicalLocation": {
+                "artifactLocation": { "uri": file.replace('\\', "/") },
+            }
+        });
+        if let Some(region) = region(violation) {
+            location["physicalLocation"]["region"] = region;
+        }
+        if let Some(function) = &violation.function {
+            location["logicalLocations"] = json!([{ "name": function, "kind": "function" }]);
+        }
+        result["locations"] = json!([location]);
+        if let Some(fix) = &violation.fix {
+            result["fixes"] = json!([sarif_fix(fix, file, locale, &violation.rule_id)]);
+        }
+    }
+    if let Some(suppression) = &violation.suppression {
+        let mut entry = json!({ "kind": "inSource", "status": "accepted" });
+        if let Some(reason) = &suppression.reason {
+            entry["justification"] = json!(reason);
+        }
+        if let Some(approver) = &suppression.approver {
+            entry["properties"] = json!({ "approver": approver });
+        }
+        result["suppressions"] = json!([entry]);
+    }
+    result
+}
+
+/// Correção automática como um `fix` do SARIF: cada edição substitui a região
+/// `deletedRegion` (vazia numa inserção) por `insertedContent`.
+fn sarif_fix(fix: &Fix, file: &str, locale: Locale, rule_id: &str) -> Value {
+    let replacements: Vec<Value> = fix
+        .edits
+        .iter()
+        .map(|edit| {
+            json!({
+                "deletedRegion": {
+                    "startLine": edit.line,
+                    "startColumn": edit.column + 1,
+                    "endLine": edit.end_line,
+                    "endColumn": edit.end_column + 1,
+                },
+                "insertedContent": { "text": edit.new_text },
+            })
+        })
+        .collect();
+    json!({
+        "description": {
+            "text": locale.rule_text(rule_id, "fix").unwrap_or(&fix.description),
+        },
+        "artifactChanges": [{
+            "artifactLocation": { "uri": file.replace('\\', "/") },
+            "replacements": replacements,
+        }],
+    })
+}
+
+/// Região da violação. O SARIF conta linhas e colunas a partir de 1, e a coluna
+/// final aponta para o caractere seguinte ao trecho.
+fn region(violation: &ComplianceViolation) -> Option<Value> {
+    let start_line = violation.line.filter(|&line| line > 0)?;
+    let mut region = json!({ "startLine": start_line });
+    if let Some(column) = violation.column {
+        region["startColumn"] = json!(column + 1);
+    }
+    region["endLine"] = json!(violation.end_line.unwrap_or(start_line).max(start_line));
+    if let Some(column) = violation.end_column {
+        region["endColumn"] = json!(column + 1);
+    }
+    Some(region)
+}
@@ -0,0 +1,137 @@
+use super::{citations, group_name, location, strict_mode, sub_scores, suppressed_entry, Reporter};
+use crate::error::HubstryError;
+use crate::i18n::Locale;
+use crate::models::{AnalysisResult, OutputFormat};
+use crate::tr;
+
+/// Relatório em texto simples, para o terminal e logs de CI.
+pub struct TextReporter {
+    pub locale: Locale,
+}
+
+impl Reporter for TextReporter {
+    fn format(&self) -> OutputFormat {
+        OutputFormat::PlainText
+    }
+
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError> {
+        let locale = self.locale;
+        let mut report = String::new();
+        let title = tr!(locale, "report.title");
+        report.push_str(&format!(
+            "{}\n{}\n\n",
+            title,
+            "=".repeat(title.chars().count())
+        ));
+        report.push_str(&format!(
+            "{}: {:.1}%\n",
+            tr!(locale, "report.score"),
+            result.compliance_score
+        ));
+        if !result.rule_packs.is_empty() {
+            let packs: Vec<String> = result.rule_packs.iter().map(ToString::to_string).collect();
+            report.push_str(&format!(
+                "{}: {}\n",
+                tr!(locale, "report.rule_packs"),
+                packs.join(", ")
+            ));
+        }
+        report.push_str(&format!(
+            "{}: {}\n",
+            tr!(locale, "report.strict_mode"),
+            strict_mode(result, locale)
+        ));
+
+        let breakdown = &result.score_breakdown;
+        report.push_str(&format!(
+            "\n{} ({}):\n",
+            tr!(locale, "report.breakdown"),
+            tr!(
+                locale,
+                "report.breakdown_summary",
+                obligations = breakdown.obligations,
+                failed = breakdown.failed
+            )
+        ));
+        for (group, name, score) in sub_scores(breakdown) {
+            report.push_str(&format!(
+                "  {:<12} {:<16} {:>6.1}%  ({})\n",
+                group_name(group, locale),
+                name,
+                score.score,
+                tr!(
+                    locale,
+                    "report.failed_ratio",
+                    failed = score.failed,
+                    obligations = score.obligations
+                )
+            ));
+        }
+        report.push_str(&format!(
+            "{}: {}\n",
+            tr!(locale, "report.formula"),
+            breakdown.formula
+        ));
+
+        if result.violations.is_empty() {
+            report.push_str(&format!("\n{}\n", tr!(locale, "report.no_violations")));
+        } else {
+            report.push_str(&format!(
+                "\n{}:\n",
+                tr!(locale, "report.violations", count = result.violations.len())
+            ));
+            for violation in &result.violations {
+                report.push_str(&format!(
+                    "\n  [{}] {}: {}\n",
+                    violation.severity,
+                    violation.rule_id,
+                    locale.message(violation)
+                ));
+                report.push_str(&format!(
+                    "    {}\n",
+                    tr!(locale, "report.at", location = location(violation, locale))
+                ));
+                if !violation.citations.is_empty() {
+                    report.push_str(&format!(
+                        "    {}: {}\n",
+                        tr!(locale, "report.legal_basis"),
+                        citations(&violation.citations, locale)
+                    ));
+                }
+                if let Some(suggestion) = locale.suggestion(violation) {
+                    report.push_str(&format!(
+                        "    {}: {}\n",
+                        tr!(locale, "report.suggestion"),
+                        suggestion
+                    ));
+                }
+            }
+        }
+
+        if !result.suppressed.is_empty() {
+            report.push_str(&format!(
+                "\n{}:\n",
+                tr!(locale, "report.suppressed", count = result.suppressed.len())
+            ));
+            for violation in &result.suppressed {
+                let (reason, approver) = suppressed_entry(violation, locale);
+                report.push_str(&format!(
+                    "  {}\n",
+                    tr!(
+                        locale,
+                        "report.suppressed_entry",
+                        rule = violation.rule_id,
+                        function = violation.function.as_deref().unwrap_or("-"),
+                        reason = reason,
+                        approver = approver
+                    )
+                ));
+            }
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,154 @@
+use super::{escape_xml, sub_scores, Reporter};
+use crate::error::HubstryError;
+use crate::models::{AnalysisResult, ComplianceViolation, OutputFormat};
+
+/// Relatório em XML, para ferramentas que não consomem JSON.
+pub struct XmlReporter;
+
+impl Reporter for XmlReporter {
+    fn format(&self) -> OutputFormat {
+        OutputFormat::Xml
+    }
+
+    fn extension(&self) -> &'static str {
+        "xml"
+    }
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError> {
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<complianceReport score=\"{:.1}\" strictMode=\"{}\">\n",
+            result.compliance_score, result.strict_mode
+        ));
+
+        xml.push_str("  <rulePacks>\n");
+        for pack in &result.rule_packs {
+            xml.push_str(&format!(
+                "    <rulePack name=\"{}\" version=\"{}\" effectiveDate=\"{}\" checksum=\"{}\"/>\n",
+                escape_xml(&pack.name),
+                escape_xml(&pack.version),
+                escape_xml(&pack.effective_date),
+                escape_xml(&pack.checksum)
+            ));
+        }
+        xml.push_str("  </rulePacks>\n");
+
+        let breakdown = &result.score_breakdown;
+        xml.push_str(&format!(
+            "  <scoreBreakdown obligations=\"{}\" failed=\"{}\">\n",
+            breakdown.obligations, breakdown.failed
+        ));
+        for (group, name, score) in sub_scores(breakdown) {
+            xml.push_str(&format!(
+                "    <{} name=\"{}\" score=\"{:.2}\" obligations=\"{}\" failed=\"{}\"/>\n",
+                group,
+                escape_xml(name),
+                score.score,
+                score.obligations,
+                score.failed
+            ));
+        }
+        xml.push_str(&format!(
+            "    <formula>{}</formula>\n",
+            escape_xml(&breakdown.formula)
+        ));
+        xml.push_str("  </scoreBreakdown>\n");
+
+        xml.push_str(&format!(
+            "  <violations count=\"{}\">\n",
+            result.violations.len()
+        ));
+        for violation in &result.violations {
+            push_violation(&mut xml, violation);
+        }
+        xml.push_str("  </violations>\n");
+
+        xml.push_str(&format!(
+            "  <suppressed count=\"{}\">\n",
+            result.suppressed.len()
+        ));
+        for violation in &result.suppressed {
+            push_violation(&mut xml, violation);
+        }
+        xml.push_str("  </suppressed>\n");
+
+        push_list(&mut xml, "suggestions", "suggestion", &result.suggestions);
+        push_list(&mut xml, "warnings", "warning", &result.warnings);
+
+        xml.push_str("  <metadata>\n");
+        for (key, value) in &result.metadata {
+            xml.push_str(&format!(
+                "    <entry key=\"{}\">{}</entry>\n",
+                escape_xml(key),
+                escape_xml(value)
+            ));
+        }
+        xml.push_str("  </metadata>\n");
+
+        xml.push_str("</complianceReport>\n");
+        Ok(xml)
+    }
+}
+
+fn push_violation(xml: &mut String, violation: &ComplianceViolation) {
+    let mut attrs = format!(
+        "ruleId=\"{}\" severity=\"{}\"",
+        escape_xml(&violation.rule_id),
+        violation.severity
+    );
+    let optional = [
+        ("file", violation.file.clone()),
+        ("function", violation.function.clone()),
+        ("line", violation.line.map(|l| l.to_string())),
+        ("column", violation.column.map(|c| c.to_string())),
+    ];
+    for (name, value) in optional {
+        if let Some(value) = value {
+            attrs.push_str(&format!(" {}=\"{}\"", name, escape_xml(&value)));
+        }
+    }
+    xml.push_str(&format!("    <violation {}>\n", attrs));
+    xml.push_str(&format!(
+        "      <message>{}</message>\n",
+        escape_xml(&violation.message)
+    ));
+    if let Some(suggestion) = &violation.suggestion {
+        xml.push_str(&format!(
+            "      <suggestion>{}</suggestion>\n",
+            escape_xml(suggestion)
+        ));
+    }
+    if !violation.citations.is_empty() {
+        xml.push_str("      <citations>\n");
+        for citation in &violation.citations {
+            xml.push_str(&format!(
+                "        <citation law=\"{}\" number=\"{}\" article=\"{}\" effectiveDate=\"{}\"/>\n",
+                escape_xml(&citation.law),
+                escape_xml(&citation.number),
+                escape_xml(&citation.article),
+                escape_xml(&citation.effective_date)
+            ));
+        }
+        xml.push_str("      </citations>\n");
+    }
+    if let Some(suppression) = &violation.suppression {
+        let mut attrs = String::new();
+        if let Some(reason) = &suppression.reason {
+            attrs.push_str(&format!(" reason=\"{}\"", escape_xml(reason)));
+        }
+        if let Some(approver) = &suppression.approver {
+            attrs.push_str(&format!(" approver=\"{}\"", escape_xml(approver)));
+        }
+        xml.push_str(&format!("      <suppression{}/>\n", attrs));
+    }
+    xml.push_str("    </violation>\n");
+}
+
+fn push_list(xml: &mut String, list: &str, item: &str, values: &[String]) {
+    xml.push_str(&format!("  <{}>\n", list));
+    for value in values {
+        xml.push_str(&format!("    <{0}>{1}</{0}>\n", item, escape_xml(value)));
+    }
+    xml.push_str(&format!("  </{}>\n", list));
+}
@@ -0,0 +1,26 @@
+use super::Reporter;
+use crate::error::{HubstryError, ReportError};
+use crate::models::{AnalysisResult, OutputFormat};
+
+/// Mesmo conteúdo do relatório JSON, em YAML.
+pub struct YamlReporter;
+
+impl Reporter for YamlReporter {
+    fn format(&self) -> OutputFormat {
+        OutputFormat::Yaml
+    }
+
+    fn extension(&self) -> &'static str {
+        "yaml"
+    }
+
+    fn render(&self, result: &AnalysisResult) -> Result<String, HubstryError> {
+        serde_yaml::to_string(result).map_err(|e| {
+            ReportError::Serialize {
+                format: "YAML",
+                message: e.to_string(),
+            }
+            .into()
+        })
+    }
+}
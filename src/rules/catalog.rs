@@ -0,0 +1,464 @@
+//! Catálogo das regras: `hubstry explain`, `hubstry rules list` e o site estático
+//! de `hubstry rules site`.
+//!
+//! Há uma entrada por regra de código, regra do Web Scanner e regra do próprio
+//! motor (como a do modo estrito). A descrição, a base legal e a correção vêm do
+//! catálogo do idioma (`locales/*.yml`) e, na falta dele, do bloco `docs:` do
+//! pacote; os exemplos vêm só do pacote.
+
+use super::{CodeRule, RulePack, WebRule};
+use crate::i18n::Locale;
+use crate::models::{Jurisdiction, LegalCitation, RuleSeverity};
+use crate::report::{citation, escape_xml, highlight};
+use crate::semantic_engine::{UNKNOWN_PREFIX_CATEGORY, UNKNOWN_PREFIX_RULE};
+use crate::tr;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Onde a regra é avaliada.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    /// Regra de um pacote avaliada sobre o código.
+    Code,
+    /// Regra de um pacote avaliada pelo Web Scanner.
+    Web,
+    /// Regra embutida no `SemanticEngine`.
+    Engine,
+}
+
+impl RuleKind {
+    fn label(self, locale: Locale) -> String {
+        match self {
+            RuleKind::Code => tr!(locale, "catalog.kind.code"),
+            RuleKind::Web => tr!(locale, "catalog.kind.web"),
+            RuleKind::Engine => tr!(locale, "catalog.kind.engine"),
+        }
+    }
+}
+
+/// A documentação de uma regra.
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub kind: RuleKind,
+    pub jurisdiction: Jurisdiction,
+    pub severity: RuleSeverity,
+    pub category: String,
+    /// Prefixo (regras de código) ou detector (regras do Web Scanner) da regra.
+    pub applies_to: Option<String>,
+    /// `nome@versão` do pacote que define a regra.
+    pub pack: Option<String>,
+    pub summary: String,
+    pub description: Option<String>,
+    pub legal_basis: Option<String>,
+    /// Dispositivos legais que a regra aplica.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<LegalCitation>,
+    pub remediation: Option<String>,
+    pub compliant: Option<String>,
+    pub non_compliant: Option<String>,
+}
+
+/// Como o conteúdo de uma seção é exibido.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Body {
+    Prose,
+    /// Um item por linha.
+    List,
+    /// Exemplo de código.
+    Code,
+}
+
+/// Exemplos da regra do modo estrito, que não pertence a nenhum pacote.
+const UNKNOWN_PREFIX_NON_COMPLIANT: &str =
+    "/// ECA.AGE.VERIFI: Acesso a conteúdo restrito.\nfn open_restricted(user: &User) -> Result<Content, Error> {\n    serpro_datavalid::verify_age(&user.cpf, 18)?;\n    Ok(load_restricted_content(user))\n}\n";
+const UNKNOWN_PREFIX_COMPLIANT: &str =
+    "/// ECA.AGE.VERIFY: Acesso a conteúdo restrito.\nfn open_restricted(user: &User) -> Result<Content, Error> {\n    serpro_datavalid::verify_age(&user.cpf, 18)?;\n    Ok(load_restricted_content(user))\n}\n";
+
+impl CatalogEntry {
+    fn from_code_rule(rule: &CodeRule, pack: &RulePack, locale: Locale) -> Self {
+        CatalogEntry {
+            id: rule.id.clone(),
+            kind: RuleKind::Code,
+            jurisdiction: rule.jurisdiction.clone(),
+            severity: rule.severity.clone(),
+            category: rule.category.clone(),
+            applies_to: Some(rule.prefix.clone()),
+            pack: Some(pack_name(pack)),
+            summary: text(locale, &rule.id, "message").unwrap_or_else(|| rule.message.clone()),
+            description: documented(locale, &rule.id, "description", &rule.docs.description),
+            legal_basis: documented(locale, &rule.id, "legal_basis", &rule.docs.legal_basis),
+            citations: rule.citations.clone(),
+            remediation: documented(locale, &rule.id, "remediation", &rule.docs.remediation)
+                .or_else(|| text(locale, &rule.id, "suggestion"))
+                .or_else(|| rule.suggestion.clone()),
+            compliant: rule.docs.compliant.clone(),
+            non_compliant: rule.docs.non_compliant.clone(),
+        }
+    }
+
+    fn from_web_rule(rule: &WebRule, pack: &RulePack, locale: Locale) -> Self {
+        let remediation = [
+            text(locale, &rule.id, "remediation_business")
+                .unwrap_or_else(|| rule.remediation_business.clone()),
+            text(locale, &rule.id, "remediation_technical")
+                .unwrap_or_else(|| rule.remediation_technical.clone()),
+        ];
+        CatalogEntry {
+            id: rule.id.clone(),
+            kind: RuleKind::Web,
+            jurisdiction: rule.jurisdiction.clone(),
+            severity: rule.severity.clone(),
+            category: rule.category.clone(),
+            applies_to: Some(rule.detector.name().to_string()),
+            pack: Some(pack_name(pack)),
+            summary: text(locale, &rule.id, "business_description")
+                .unwrap_or_else(|| rule.business_description.clone()),
+            description: documented(locale, &rule.id, "description", &rule.docs.description),
+            legal_basis: documented(locale, &rule.id, "legal_basis", &rule.docs.legal_basis),
+            citations: rule.citations.clone(),
+            remediation: documented(locale, &rule.id, "remediation", &rule.docs.remediation)
+                .or_else(|| Some(remediation.join(" "))),
+            compliant: rule.docs.compliant.clone(),
+            non_compliant: rule.docs.non_compliant.clone(),
+        }
+    }
+
+    fn unknown_prefix(locale: Locale) -> Self {
+        let id = UNKNOWN_PREFIX_RULE;
+        CatalogEntry {
+            id: id.to_string(),
+            kind: RuleKind::Engine,
+            jurisdiction: Jurisdiction::Generic,
+            severity: RuleSeverity::High,
+            category: UNKNOWN_PREFIX_CATEGORY.to_string(),
+            applies_to: None,
+            pack: None,
+            summary: text(locale, id, "summary").unwrap_or_default(),
+            description: text(locale, id, "description"),
+            legal_basis: text(locale, id, "legal_basis"),
+            citations: Vec::new(),
+            remediation: text(locale, id, "remediation"),
+            compliant: Some(UNKNOWN_PREFIX_COMPLIANT.to_string()),
+            non_compliant: Some(UNKNOWN_PREFIX_NON_COMPLIANT.to_string()),
+        }
+    }
+
+    /// Linguagem dos exemplos.
+    pub fn example_language(&self) -> &'static str {
+        match self.kind {
+            RuleKind::Web => "html",
+            RuleKind::Code | RuleKind::Engine => "rust",
+        }
+    }
+
+    /// Tipo, prefixo ou detector e pacote, separados por ` · `.
+    fn origin(&self, locale: Locale) -> String {
+        let mut parts = vec![self.kind.label(locale)];
+        if let Some(applies_to) = &self.applies_to {
+            parts.push(match self.kind {
+                RuleKind::Web => tr!(locale, "catalog.detector", detector = applies_to),
+                _ => tr!(locale, "catalog.prefix", prefix = applies_to),
+            });
+        }
+        if let Some(pack) = &self.pack {
+            parts.push(tr!(locale, "catalog.pack", pack = pack));
+        }
+        parts.join(" · ")
+    }
+
+    /// Seções com texto: (título, conteúdo, forma de exibição).
+    fn sections(&self, locale: Locale) -> Vec<(String, String, Body)> {
+        let citations = (!self.citations.is_empty()).then(|| {
+            self.citations
+                .iter()
+                .map(|c| citation(c, locale))
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        [
+            ("catalog.description", self.description.clone(), Body::Prose),
+            ("catalog.legal_basis", self.legal_basis.clone(), Body::Prose),
+            ("catalog.citations", citations, Body::List),
+            (
+                "catalog.non_compliant",
+                self.non_compliant.clone(),
+                Body::Code,
+            ),
+            ("catalog.compliant", self.compliant.clone(), Body::Code),
+            ("catalog.remediation", self.remediation.clone(), Body::Prose),
+        ]
+        .into_iter()
+        .filter_map(|(key, value, body)| value.map(|value| (locale.text(key, &[]), value, body)))
+        .collect()
+    }
+
+    /// Texto para o terminal (`hubstry explain`).
+    pub fn to_text(&self, locale: Locale) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{} — {} · {} · {}",
+            self.id, self.severity, self.jurisdiction, self.category
+        );
+        let _ = writeln!(out, "{}\n\n{}", self.origin(locale), self.summary);
+        for (title, value, body) in self.sections(locale) {
+            let _ = writeln!(out, "\n{}:", title);
+            for line in value.trim_end().lines() {
+                let indent = match body {
+                    Body::Prose => "  ",
+                    Body::List => "  - ",
+                    Body::Code => "    ",
+                };
+                let _ = writeln!(out, "{}{}", indent, line);
+            }
+        }
+        out
+    }
+
+    pub fn to_markdown(&self, locale: Locale) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}\n", self.id);
+        let _ = writeln!(
+            out,
+            "**{}** · {} · {} · {}\n",
+            self.severity,
+            self.jurisdiction,
+            self.category,
+            self.origin(locale)
+        );
+        let _ = writeln!(out, "{}", self.summary);
+        for (title, value, body) in self.sections(locale) {
+            let _ = writeln!(out, "\n## {}\n", title);
+            match body {
+                Body::Prose => {
+                    let _ = writeln!(out, "{}", value.trim_end());
+                }
+                Body::List => {
+                    for line in value.lines() {
+                        let _ = writeln!(out, "- {}", line);
+                    }
+                }
+                Body::Code => {
+                    let _ = writeln!(
+                        out,
+                        "```{}\n{}\n```",
+                        self.example_language(),
+                        value.trim_end()
+                    );
+                }
+            }
+        }
+        out
+    }
+
+    fn to_html(&self, locale: Locale) -> String {
+        let mut body = String::new();
+        let _ = writeln!(
+            body,
+            "<p><a href=\"index.html\">{}</a></p>",
+            escape_xml(&tr!(locale, "catalog.back"))
+        );
+        let _ = writeln!(body, "<h1>{}</h1>", escape_xml(&self.id));
+        let _ = writeln!(
+            body,
+            "<p class=\"meta\"><span class=\"severity {}\">{}</span> · {} · {} · {}</p>",
+            severity_class(&self.severity),
+            self.severity,
+            self.jurisdiction,
+            escape_xml(&self.category),
+            escape_xml(&self.origin(locale))
+        );
+        let _ = writeln!(
+            body,
+            "<p class=\"summary\">{}</p>",
+            escape_xml(&self.summary)
+        );
+        for (title, value, kind) in self.sections(locale) {
+            let _ = writeln!(body, "<h2>{}</h2>", escape_xml(&title));
+            match kind {
+                Body::Prose => {
+                    let _ = writeln!(body, "<p>{}</p>", escape_xml(value.trim_end()));
+                }
+                Body::List => {
+                    let items: Vec<String> = value
+                        .lines()
+                        .map(|line| format!("<li>{}</li>", escape_xml(line)))
+                        .collect();
+                    let _ = writeln!(body, "<ul>\n{}\n</ul>", items.join("\n"));
+                }
+                Body::Code => {
+                    let lines: Vec<String> = value
+                        .trim_end()
+                        .lines()
+                        .map(|line| match self.kind {
+                            RuleKind::Web => escape_xml(line),
+                            _ => highlight::rust_line(line),
+                        })
+                        .collect();
+                    let _ = writeln!(body, "<pre class=\"example\">{}</pre>", lines.join("\n"));
+                }
+            }
+        }
+        page(&self.id, &body, locale)
+    }
+}
+
+/// O catálogo de todas as regras.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RuleCatalog {
+    pub entries: Vec<CatalogEntry>,
+}
+
+impl RuleCatalog {
+    /// Entradas das regras dos pacotes, na ordem dos pacotes, seguidas das regras do
+    /// motor. Uma regra repetida em mais de um pacote aparece só com o primeiro.
+    pub fn new(packs: &[RulePack], locale: Locale) -> Self {
+        let mut entries: Vec<CatalogEntry> = Vec::new();
+        for pack in packs {
+            let rules = pack
+                .rules
+                .iter()
+                .map(|rule| CatalogEntry::from_code_rule(rule, pack, locale))
+                .chain(
+                    pack.web_rules
+                        .iter()
+                        .map(|rule| CatalogEntry::from_web_rule(rule, pack, locale)),
+                );
+            for entry in rules {
+                if !entries.iter().any(|known| known.id == entry.id) {
+                    entries.push(entry);
+                }
+            }
+        }
+        entries.push(CatalogEntry::unknown_prefix(locale));
+        RuleCatalog { entries }
+    }
+
+    /// Entrada da regra, sem diferenciar maiúsculas.
+    pub fn get(&self, rule_id: &str) -> Option<&CatalogEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id.eq_ignore_ascii_case(rule_id))
+    }
+
+    /// Ids que contêm `rule_id` ou que compartilham o prefixo dele (`ECA.AGE.VERIFY.2`
+    /// sugere `ECA.AGE.VERIFY.1` e `ECA.AGE.VERIFY`).
+    pub fn similar(&self, rule_id: &str) -> Vec<&str> {
+        let wanted = rule_id.to_ascii_uppercase();
+        let stem = wanted
+            .rsplit_once('.')
+            .map_or(wanted.as_str(), |(stem, _)| stem);
+        self.entries
+            .iter()
+            .map(|entry| entry.id.as_str())
+            .filter(|id| id.contains(&wanted) || id.starts_with(stem))
+            .collect()
+    }
+
+    /// Entradas de um pacote (`nome@versão`), ou as do motor com `None`.
+    pub fn of_pack(&self, pack: Option<&str>) -> Vec<&CatalogEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.pack.as_deref() == pack)
+            .collect()
+    }
+
+    /// Páginas do site estático, como `(arquivo, HTML)`: `index.html` com a lista das
+    /// regras e uma página `<id>.html` por regra.
+    pub fn site(&self, locale: Locale) -> Vec<(String, String)> {
+        let mut body = String::new();
+        let _ = writeln!(
+            body,
+            "<h1>{}</h1>\n<p>{}</p>",
+            escape_xml(&tr!(locale, "catalog.title")),
+            escape_xml(&tr!(locale, "catalog.summary", count = self.entries.len()))
+        );
+        let _ = writeln!(
+            body,
+            "<table>\n<tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>",
+            escape_xml(&tr!(locale, "catalog.column.rule")),
+            escape_xml(&tr!(locale, "catalog.column.severity")),
+            escape_xml(&tr!(locale, "catalog.column.jurisdiction")),
+            escape_xml(&tr!(locale, "catalog.column.kind")),
+            escape_xml(&tr!(locale, "catalog.column.summary"))
+        );
+        for entry in &self.entries {
+            let _ = writeln!(
+                body,
+                "<tr><td><a href=\"{}\">{}</a></td><td><span class=\"severity {}\">{}</span></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                page_name(&entry.id),
+                escape_xml(&entry.id),
+                severity_class(&entry.severity),
+                entry.severity,
+                entry.jurisdiction,
+                escape_xml(&entry.kind.label(locale)),
+                escape_xml(&entry.summary)
+            );
+        }
+        body.push_str("</table>\n");
+
+        let mut pages = vec![(
+            "index.html".to_string(),
+            page(&tr!(locale, "catalog.title"), &body, locale),
+        )];
+        pages.extend(
+            self.entries
+                .iter()
+                .map(|entry| (page_name(&entry.id), entry.to_html(locale))),
+        );
+        pages
+    }
+}
+
+fn pack_name(pack: &RulePack) -> String {
+    format!("{}@{}", pack.name(), pack.version())
+}
+
+fn text(locale: Locale, rule_id: &str, field: &str) -> Option<String> {
+    locale.rule_text(rule_id, field).map(str::to_string)
+}
+
+/// Texto do catálogo do idioma ou, na falta dele, do pacote.
+fn documented(
+    locale: Locale,
+    rule_id: &str,
+    field: &str,
+    from_pack: &Option<String>,
+) -> Option<String> {
+    text(locale, rule_id, field).or_else(|| from_pack.clone())
+}
+
+/// Arquivo da página de uma regra no site.
+pub fn page_name(rule_id: &str) -> String {
+    format!("{}.html", rule_id)
+}
+
+fn severity_class(severity: &RuleSeverity) -> String {
+    severity.to_string().to_ascii_lowercase()
+}
+
+fn page(title: &str, body: &str, locale: Locale) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"UTF-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n<div class=\"container\">\n{}</div>\n</body>\n</html>\n",
+        locale.code(),
+        escape_xml(title),
+        STYLE,
+        body
+    )
+}
+
+const STYLE: &str = "body { font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif; margin: 0; padding: 20px; background-color: #f9f9fb; color: #333; }
+.container { max-width: 900px; margin: auto; background: #fff; padding: 30px; border-radius: 8px; box-shadow: 0 4px 6px rgba(0,0,0,0.1); }
+h1 { color: #2c3e50; border-bottom: 2px solid #ecf0f1; padding-bottom: 10px; }
+a { color: #2b6cb0; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border-bottom: 1px solid #e2e8f0; padding: 6px 10px; text-align: left; vertical-align: top; }
+.meta { font-size: 0.9em; color: #718096; }
+.summary { font-size: 1.1em; }
+.severity { font-weight: bold; }
+.severity.critical { color: #9b2c2c; } .severity.high { color: #e53e3e; } .severity.medium { color: #dd6b20; } .severity.low { color: #3182ce; }
+.example { background-color: #1a202c; color: #e2e8f0; padding: 10px; border-radius: 4px; overflow-x: auto; font-size: 0.85em; }
+.example .kw { color: #f6ad55; } .example .fn { color: #63b3ed; } .example .str { color: #68d391; } .example .num { color: #fc8181; } .example .com { color: #a0aec0; font-style: italic; }
+";
@@ -0,0 +1,289 @@
+//! Condições declarativas avaliadas sobre o grafo de chamadas de uma função.
+//!
+//! Uma condição descreve o comportamento **conforme**. Todas as cláusulas
+//! informadas precisam ser satisfeitas; cláusulas omitidas são ignoradas.
+//! Palavras-chave iniciadas por `$` são substituídas pelas listas do prefixo
+//! (`$expected_calls` e `$data_collection_keywords`).
+
+use crate::ast::CallSite;
+use crate::jurisdictions::eca::{CallGraph, KeywordMatch};
+use serde::Deserialize;
+
+/// Referência à lista `expected_calls` do prefixo.
+pub const EXPECTED_CALLS_VAR: &str = "$expected_calls";
+/// Referência à lista `data_collection_keywords` do prefixo.
+pub const DATA_COLLECTION_VAR: &str = "$data_collection_keywords";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Condition {
+    /// A função precisa chamar (direta ou indiretamente) alguma destas funções.
+    #[serde(default)]
+    pub requires_any_call: Vec<String>,
+    /// A função não pode chamar nenhuma destas funções.
+    #[serde(default)]
+    pub forbids_call: Vec<String>,
+    /// Se a função chamar alguma destas funções...
+    #[serde(default)]
+    pub if_calls_any: Vec<String>,
+    /// ...então precisa chamar também alguma destas.
+    #[serde(default)]
+    pub then_requires_any: Vec<String>,
+    /// Uma chamada de `before` precisa ocorrer antes da primeira chamada de `after`.
+    pub must_precede: Option<Precedence>,
+    /// Comparações sobre argumentos literais de chamadas.
+    #[serde(default)]
+    pub params: Vec<ParamCheck>,
+    /// Todas as subcondições precisam ser satisfeitas.
+    #[serde(default)]
+    pub all_of: Vec<Condition>,
+    /// Ao menos uma das subcondições precisa ser satisfeita.
+    #[serde(default)]
+    pub any_of: Vec<Condition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Precedence {
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// Compara o argumento `arg` (0-based) de toda chamada a `call` com `value`.
+/// Argumentos que não são literais não podem ser verificados e são ignorados.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParamCheck {
+    pub call: String,
+    pub arg: usize,
+    pub op: CompareOp,
+    pub value: ParamValue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum CompareOp {
+    #[serde(rename = "==")]
+    Eq,
+    #[serde(rename = "!=")]
+    Ne,
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = "<=")]
+    Le,
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = ">=")]
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ParamValue {
+    Number(f64),
+    Text(String),
+}
+
+/// O que a avaliação de uma condição precisa saber sobre a função analisada.
+pub struct EvalContext<'a> {
+    pub function: &'a str,
+    pub call_graph: &'a CallGraph,
+    pub expected_calls: &'a [String],
+    pub data_collection_keywords: &'a [String],
+    /// Como os nomes das chamadas são comparados às palavras-chave.
+    pub matching: KeywordMatch,
+}
+
+impl<'a> EvalContext<'a> {
+    fn expand<'k>(&'k self, keywords: &'k [String]) -> Vec<&'k str> {
+        keywords
+            .iter()
+            .flat_map(|kw| match kw.as_str() {
+                EXPECTED_CALLS_VAR => self.expected_calls.iter().map(String::as_str).collect(),
+                DATA_COLLECTION_VAR => self
+                    .data_collection_keywords
+                    .iter()
+                    .map(String::as_str)
+                    .collect(),
+                other => vec![other],
+            })
+            .collect()
+    }
+
+    fn calls_any(&self, keywords: &[String]) -> bool {
+        self.expand(keywords).iter().any(|kw| {
+            self.call_graph
+                .calls_matching(self.function, kw, self.matching)
+        })
+    }
+
+    /// Caminho até a primeira chamada a alguma das palavras-chave.
+    fn path_to_any(&self, keywords: &[String]) -> Option<Vec<&'a CallSite>> {
+        let keywords = self.expand(keywords);
+        self.call_graph.path_to(self.function, |site| {
+            keywords
+                .iter()
+                .any(|kw| self.matching.matches(&site.name, kw))
+        })
+    }
+}
+
+/// Evidência de uma condição não satisfeita, exibida nos relatórios como
+/// `register → save_profile → store (nenhuma chamada a consent no caminho)`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Evidence<'g> {
+    /// Chamadas da função analisada até a chamada que viola a condição. Vazio
+    /// quando a violação é só a falta de uma chamada.
+    pub path: Vec<&'g CallSite>,
+    /// Palavras-chave esperadas que não foram chamadas no caminho.
+    pub missing: Vec<String>,
+}
+
+impl<'g> Evidence<'g> {
+    fn missing(path: Vec<&'g CallSite>, ctx: &EvalContext, keywords: &[String]) -> Self {
+        let mut missing: Vec<String> = Vec::new();
+        for keyword in ctx.expand(keywords) {
+            if !missing.iter().any(|m| m == keyword) {
+                missing.push(keyword.to_string());
+            }
+        }
+        Evidence { path, missing }
+    }
+}
+
+impl Condition {
+    /// Avalia a condição; retorna `true` quando a função está em conformidade.
+    pub fn holds(&self, ctx: &EvalContext) -> bool {
+        self.explain(ctx).is_none()
+    }
+
+    /// Por que a função não está em conformidade: a evidência da primeira cláusula
+    /// não satisfeita, ou `None` quando a condição é satisfeita.
+    pub fn explain<'g>(&self, ctx: &EvalContext<'g>) -> Option<Evidence<'g>> {
+        if !self.requires_any_call.is_empty() && !ctx.calls_any(&self.requires_any_call) {
+            return Some(Evidence::missing(Vec::new(), ctx, &self.requires_any_call));
+        }
+        if !self.forbids_call.is_empty() && ctx.calls_any(&self.forbids_call) {
+            let path = ctx.path_to_any(&self.forbids_call).unwrap_or_default();
+            return Some(Evidence::missing(path, ctx, &[]));
+        }
+        if !self.then_requires_any.is_empty() && !ctx.calls_any(&self.then_requires_any) {
+            if self.if_calls_any.is_empty() {
+                return Some(Evidence::missing(Vec::new(), ctx, &self.then_requires_any));
+            }
+            if ctx.calls_any(&self.if_calls_any) {
+                let path = ctx.path_to_any(&self.if_calls_any).unwrap_or_default();
+                return Some(Evidence::missing(path, ctx, &self.then_requires_any));
+            }
+        }
+        if let Some(evidence) = self.must_precede.as_ref().and_then(|p| p.explain(ctx)) {
+            return Some(evidence);
+        }
+        if let Some(evidence) = self.params.iter().find_map(|check| check.explain(ctx)) {
+            return Some(evidence);
+        }
+        if let Some(evidence) = self.all_of.iter().find_map(|c| c.explain(ctx)) {
+            return Some(evidence);
+        }
+        if !self.any_of.is_empty() {
+            let mut failures = self.any_of.iter().map(|c| c.explain(ctx));
+            let first = failures.next().flatten()?;
+            if failures.all(|failure| failure.is_some()) {
+                return Some(first);
+            }
+        }
+        None
+    }
+
+    /// Visita todas as listas de palavras-chave da condição e das subcondições.
+    pub fn keyword_lists(&self) -> Vec<&[String]> {
+        let mut lists: Vec<&[String]> = vec![
+            &self.requires_any_call,
+            &self.forbids_call,
+            &self.if_calls_any,
+            &self.then_requires_any,
+        ];
+        if let Some(precedence) = &self.must_precede {
+            lists.push(&precedence.before);
+            lists.push(&precedence.after);
+        }
+        for condition in self.all_of.iter().chain(&self.any_of) {
+            lists.extend(condition.keyword_lists());
+        }
+        lists
+    }
+
+    /// Nomes de chamadas usados em comparações de parâmetros.
+    pub fn param_calls(&self) -> Vec<&str> {
+        let mut calls: Vec<&str> = self.params.iter().map(|p| p.call.as_str()).collect();
+        for condition in self.all_of.iter().chain(&self.any_of) {
+            calls.extend(condition.param_calls());
+        }
+        calls
+    }
+}
+
+impl Precedence {
+    /// Caminho até a primeira chamada de `after`, quando ela ocorre antes de
+    /// qualquer chamada de `before`.
+    fn explain<'g>(&self, ctx: &EvalContext<'g>) -> Option<Evidence<'g>> {
+        let before = ctx.expand(&self.before);
+        let after = ctx.expand(&self.after);
+        let matches = |site: &CallSite, keywords: &[&str]| {
+            keywords
+                .iter()
+                .any(|kw| ctx.matching.matches(&site.name, kw))
+        };
+
+        let path = ctx.call_graph.path_to(ctx.function, |site| {
+            matches(site, &before) || matches(site, &after)
+        })?;
+        let first = path.last()?;
+        if matches(first, &before) {
+            return None;
+        }
+        Some(Evidence::missing(path, ctx, &self.before))
+    }
+}
+
+impl ParamCheck {
+    /// Caminho até a primeira chamada cujo argumento literal não passa na comparação.
+    fn explain<'g>(&self, ctx: &EvalContext<'g>) -> Option<Evidence<'g>> {
+        let path = ctx.call_graph.path_to(ctx.function, |site| {
+            ctx.matching.matches(&site.name, &self.call)
+                && site
+                    .args
+                    .get(self.arg)
+                    .cloned()
+                    .flatten()
+                    .is_some_and(|literal| !self.compare(&literal))
+        })?;
+        Some(Evidence::missing(path, ctx, &[]))
+    }
+
+    fn compare(&self, literal: &str) -> bool {
+        match &self.value {
+            ParamValue::Number(expected) => match literal.parse::<f64>() {
+                Ok(actual) => self.op.apply(actual.partial_cmp(expected)),
+                Err(_) => true,
+            },
+            ParamValue::Text(expected) => self.op.apply(Some(literal.cmp(expected.as_str()))),
+        }
+    }
+}
+
+impl CompareOp {
+    fn apply(self, ordering: Option<std::cmp::Ordering>) -> bool {
+        use std::cmp::Ordering::*;
+        let Some(ordering) = ordering else {
+            return false;
+        };
+        match self {
+            CompareOp::Eq => ordering == Equal,
+            CompareOp::Ne => ordering != Equal,
+            CompareOp::Lt => ordering == Less,
+            CompareOp::Le => ordering != Greater,
+            CompareOp::Gt => ordering == Greater,
+            CompareOp::Ge => ordering != Less,
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Regras personalizadas (`EngineConfig.custom_rules`).
+//!
+//! Cada [`ComplianceRule`] aplica a expressão regular `validation_pattern` ao
+//! nome das funções, aos doc comments, aos nomes das chamadas, às strings
+//! literais ou ao texto-fonte, no escopo de uma função, de um arquivo ou do
+//! projeto inteiro. No modo `forbid`, cada ocorrência é uma violação; no modo
+//! `require`, a violação é não haver nenhuma ocorrência no escopo.
+
+use super::{check_citations, invalid};
+use crate::ast::{FileAst, FunctionAst};
+use crate::error::HubstryError;
+use crate::models::{ComplianceRule, ComplianceViolation, MatchMode, PatternTarget, RuleScope};
+use crate::msg;
+use crate::scoring;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Uma regra personalizada com a expressão regular já compilada.
+#[derive(Debug, Clone)]
+pub struct CustomRule {
+    pub rule: ComplianceRule,
+    pattern: Regex,
+}
+
+/// Um trecho de texto candidato, com a sua posição no arquivo.
+struct Candidate<'a> {
+    text: &'a str,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+/// Compila as regras, falhando na primeira sem padrão ou com uma expressão inválida.
+pub fn compile(rules: &[ComplianceRule]) -> Result<Vec<CustomRule>, HubstryError> {
+    rules
+        .iter()
+        .map(|rule| {
+            let Some(pattern) = &rule.validation_pattern else {
+                return Err(invalid(&rule.id, msg!("error.rule.missing_pattern")));
+            };
+            if rule.scope != RuleScope::Function && rule.prefix.is_some() {
+                return Err(invalid(&rule.id, msg!("error.rule.prefix_scope")));
+            }
+            check_citations(&rule.id, &rule.citations)?;
+            let pattern = Regex::new(pattern)
+                .map_err(|e| invalid(&rule.id, msg!("error.rule.invalid_pattern", detail = e)))?;
+            Ok(CustomRule {
+                rule: rule.clone(),
+                pattern,
+            })
+        })
+        .collect()
+}
+
+impl CustomRule {
+    /// Avalia as regras de escopo `function` e `file` sobre um arquivo.
+    pub fn evaluate_file(&self, file: &FileAst) -> Vec<ComplianceViolation> {
+        match self.rule.scope {
+            RuleScope::Function => file
+                .functions
+                .iter()
+                .filter(|func| self.applies_to(func))
+                .flat_map(|func| {
+                    self.check(function_candidates(self.rule.target, file, func))
+                        .into_iter()
+                        .map(|mut violation| {
+                            violation.function = Some(func.name.clone());
+                            if violation.line.is_none() {
+                                violation.line = Some(func.line);
+                                violation.column = Some(func.column);
+                                violation.end_line = Some(func.end_line);
+                            }
+                            violation.file = file_path(file);
+                            violation
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+            RuleScope::File => self
+                .check(file_candidates(self.rule.target, file))
+                .into_iter()
+                .map(|mut violation| {
+                    violation.file = file_path(file);
+                    violation
+                })
+                .collect(),
+            RuleScope::Project => Vec::new(),
+        }
+    }
+
+    /// Alvos em que a regra é verificada neste arquivo (ver [`scoring::subject`]).
+    /// As regras de escopo `project` têm um único alvo, o projeto.
+    pub fn subjects(&self, file: &FileAst) -> Vec<String> {
+        match self.rule.scope {
+            RuleScope::Function => file
+                .functions
+                .iter()
+                .filter(|func| self.applies_to(func))
+                .map(|func| scoring::subject(Some(&file.path), Some(&func.name)))
+                .collect(),
+            RuleScope::File => vec![scoring::subject(Some(&file.path), None)],
+            RuleScope::Project => Vec::new(),
+        }
+    }
+
+    fn applies_to(&self, func: &FunctionAst) -> bool {
+        match &self.rule.prefix {
+            Some(prefix) => func.annotations().any(|p| p == prefix),
+            None => true,
+        }
+    }
+
+    /// Avalia as regras de escopo `project` sobre todos os arquivos analisados.
+    pub fn evaluate_project(&self, files: &[FileAst]) -> Vec<ComplianceViolation> {
+        if self.rule.scope != RuleScope::Project {
+            return Vec::new();
+        }
+        match self.rule.mode {
+            MatchMode::Forbid => files
+                .iter()
+                .flat_map(|file| {
+                    self.check(file_candidates(self.rule.target, file))
+                        .into_iter()
+                        .map(|mut violation| {
+                            violation.file = file_path(file);
+                            violation
+                        })
+                })
+                .collect(),
+            MatchMode::Require => {
+                let found = files.iter().any(|file| {
+                    file_candidates(self.rule.target, file)
+                        .iter()
+                        .any(|c| self.pattern.is_match(c.text))
+                });
+                if found {
+                    Vec::new()
+                } else {
+                    vec![self.violation(None, None, None)]
+                }
+            }
+        }
+    }
+
+    fn check(&self, candidates: Vec<Candidate>) -> Vec<ComplianceViolation> {
+        match self.rule.mode {
+            MatchMode::Forbid => candidates
+                .iter()
+                .filter_map(|c| {
+                    let found = self.pattern.find(c.text)?;
+                    let column = c.column.map(|column| column + found.start());
+                    let mut violation = self.violation(Some(found.as_str()), c.line, column);
+                    violation.end_line = c.line;
+                    violation.end_column = c.column.map(|column| column + found.end());
+                    Some(violation)
+                })
+                .collect(),
+            MatchMode::Require => {
+                if candidates.iter().any(|c| self.pattern.is_match(c.text)) {
+                    Vec::new()
+                } else {
+                    vec![self.violation(None, None, None)]
+                }
+            }
+        }
+    }
+
+    fn violation(
+        &self,
+        matched: Option<&str>,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> ComplianceViolation {
+        // O trecho encontrado fica em `args`; `Locale::message` o acrescenta à descrição.
+        ComplianceViolation {
+            rule_id: self.rule.id.clone(),
+            severity: self.rule.severity.clone(),
+            message: self.rule.description.clone(),
+            file: None,
+            function: None,
+            line,
+            column,
+            end_line: None,
+            end_column: None,
+            suggestion: self.rule.remediation_hint.clone(),
+            suppression: None,
+            args: matched
+                .map(|matched| BTreeMap::from([("matched".to_string(), matched.to_string())]))
+                .unwrap_or_default(),
+            call_chain: None,
+            snippet: None,
+            citations: self.rule.citations.clone(),
+            fix: None,
+        }
+    }
+}
+
+fn file_path(file: &FileAst) -> Option<String> {
+    (!file.path.is_empty()).then(|| file.path.clone())
+}
+
+fn function_candidates<'a>(
+    target: PatternTarget,
+    file: &'a FileAst,
+    func: &'a FunctionAst,
+) -> Vec<Candidate<'a>> {
+    let at = |text, line, column| Candidate {
+        text,
+        line: Some(line),
+        column: Some(column),
+    };
+    match target {
+        PatternTarget::FunctionName => vec![at(&func.name, func.line, func.column)],
+        PatternTarget::DocText => func
+            .doc_comments
+            .iter()
+            .map(|doc| at(doc, func.line, func.column))
+            .collect(),
+        PatternTarget::CallNames => func
+            .calls
+            .iter()
+            .map(|call| at(&call.name, call.line, call.column))
+            .collect(),
+        PatternTarget::StringLiterals => func
+            .string_literals
+            .iter()
+            .map(|lit| at(&lit.value, lit.line, lit.column))
+            .collect(),
+        PatternTarget::SourceText => source_lines(file)
+            .filter(|c| c.line.is_some_and(|l| l >= func.line && l <= func.end_line))
+            .collect(),
+    }
+}
+
+fn file_candidates(target: PatternTarget, file: &FileAst) -> Vec<Candidate<'_>> {
+    match target {
+        PatternTarget::SourceText => source_lines(file).collect(),
+        _ => file
+            .functions
+            .iter()
+            .flat_map(|func| function_candidates(target, file, func))
+            .collect(),
+    }
+}
+
+fn source_lines(file: &FileAst) -> impl Iterator<Item = Candidate<'_>> {
+    file.source.lines().enumerate().map(|(i, text)| Candidate {
+        text,
+        line: Some(i + 1),
+        column: Some(0),
+    })
+}
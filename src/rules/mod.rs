@@ -0,0 +1,398 @@
+//! Motor de regras declarativas.
+//!
+//! As regras de código são escritas em YAML (ver `rules/eca_digital.yml` e
+//! `rules/lgpd.yml`): cada regra tem um id, o prefixo a que se aplica, a
+//! severidade, a mensagem, a sugestão e uma [`Condition`] que descreve o
+//! comportamento esperado. Adicionar uma regra não exige alterar código Rust.
+//!
+//! As regras são distribuídas em pacotes versionados (ver [`pack`]).
+
+pub mod catalog;
+pub mod condition;
+pub mod custom;
+pub mod pack;
+
+use crate::ast::FunctionAst;
+use crate::error::{HubstryError, RuleError};
+use crate::i18n::Message;
+use crate::jurisdictions::eca::{CallGraph, KeywordMatch};
+use crate::models::{
+    CallChain, ComplianceViolation, Jurisdiction, LegalCitation, RulePackRef, RuleSeverity,
+};
+use crate::msg;
+use crate::prefix_manager::PrefixInfo;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+pub use condition::{Condition, EvalContext, DATA_COLLECTION_VAR, EXPECTED_CALLS_VAR};
+pub use pack::{PackVerification, RulePack, WebDetector, WebFix, WebRule};
+
+/// Listas de palavras-chave de `prefixes.yml` consultadas pelas regras de um prefixo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeywordUsage {
+    pub expected_calls: bool,
+    pub data_collection_keywords: bool,
+}
+
+/// Listas usadas quando o prefixo não define as suas em `prefixes.yml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Fallback {
+    #[serde(default)]
+    pub expected_calls: Vec<String>,
+    #[serde(default)]
+    pub data_collection_keywords: Vec<String>,
+}
+
+/// Uma regra de código, como escrita no arquivo YAML.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CodeRule {
+    pub id: String,
+    pub prefix: String,
+    pub severity: RuleSeverity,
+    pub message: String,
+    pub suggestion: Option<String>,
+    /// Categoria usada nos sub-scores (ex.: `age`, `consent`, `ads`, `lootbox`).
+    #[serde(default = "default_category")]
+    pub category: String,
+    #[serde(default)]
+    pub condition: Condition,
+    /// Dispositivos legais que a regra aplica, copiados para as violações.
+    #[serde(default)]
+    pub citations: Vec<LegalCitation>,
+    /// Correção automática aplicada pelo `hubstry fix`.
+    pub fix: Option<CodeFix>,
+    #[serde(default)]
+    pub docs: RuleDocs,
+    /// Preenchido a partir do arquivo que contém a regra.
+    #[serde(skip, default = "default_jurisdiction")]
+    pub jurisdiction: Jurisdiction,
+}
+
+/// Correção automática de uma regra de código (ver [`crate::fix`]).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CodeFix {
+    /// O que a correção faz. O texto de `fix` do catálogo de idioma tem precedência.
+    pub description: String,
+    /// Instrução Rust inserida no início do corpo das funções que violam a regra,
+    /// ex.: `verify_age(todo!("conta do jogador"), 18)?;`.
+    pub guard: Option<String>,
+    /// Funções sem a anotação do prefixo da regra que chamam alguma destas recebem
+    /// a anotação.
+    #[serde(default)]
+    pub annotate_callers_of: Vec<String>,
+}
+
+/// Documentação de uma regra para o catálogo (ver [`catalog`]). Os textos dos
+/// catálogos de idioma (`locales/*.yml`) têm precedência sobre os do pacote; os
+/// exemplos vêm só do pacote.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleDocs {
+    pub description: Option<String>,
+    pub legal_basis: Option<String>,
+    pub remediation: Option<String>,
+    /// Código em conformidade com a regra.
+    pub compliant: Option<String>,
+    /// Código que viola a regra.
+    pub non_compliant: Option<String>,
+}
+
+fn default_jurisdiction() -> Jurisdiction {
+    Jurisdiction::Generic
+}
+
+fn default_category() -> String {
+    crate::scoring::DEFAULT_CATEGORY.to_string()
+}
+
+/// Conjunto de regras usado por um `SemanticEngine` e pelo Web Scanner, formado
+/// por um ou mais pacotes.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<CodeRule>,
+    fallbacks: HashMap<String, Fallback>,
+    web_rules: Vec<WebRule>,
+    packs: Vec<RulePack>,
+}
+
+impl RuleSet {
+    /// Cria um conjunto vazio.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Carrega os pacotes embutidos do ECA Digital e da LGPD.
+    pub fn builtin() -> Self {
+        Self::from_packs(pack::builtin_packs())
+    }
+
+    /// Cria um conjunto a partir de pacotes, aplicados na ordem recebida.
+    pub fn from_packs(packs: impl IntoIterator<Item = RulePack>) -> Self {
+        let mut set = Self::new();
+        for pack in packs {
+            set.add_pack(pack);
+        }
+        set
+    }
+
+    /// Carrega um pacote a partir de um arquivo YAML, verificando o checksum.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, HubstryError> {
+        let pack = RulePack::from_path(path)?;
+        pack.verify()?;
+        Ok(Self::from_packs([pack]))
+    }
+
+    /// Carrega um pacote a partir de uma string YAML. `origin` é usado apenas nas
+    /// mensagens de erro.
+    pub fn from_yaml_str(yaml: &str, origin: &Path) -> Result<Self, HubstryError> {
+        let pack = RulePack::parse(yaml, origin)?;
+        pack.verify()?;
+        Ok(Self::from_packs([pack]))
+    }
+
+    /// Acrescenta as regras de um pacote. Uma regra com o mesmo id de outra já
+    /// carregada a substitui, e um pacote com o mesmo nome de outro o substitui na
+    /// lista de pacotes aplicados.
+    pub fn add_pack(&mut self, pack: RulePack) {
+        for rule in &pack.rules {
+            self.rules.retain(|existing| existing.id != rule.id);
+            self.rules.push(rule.clone());
+        }
+        for rule in &pack.web_rules {
+            self.web_rules.retain(|existing| existing.id != rule.id);
+            self.web_rules.push(rule.clone());
+        }
+        self.fallbacks
+            .extend(pack.fallbacks.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self.packs.retain(|existing| existing.name() != pack.name());
+        self.packs.push(pack);
+    }
+
+    /// Acrescenta todos os pacotes de outro conjunto, com a mesma semântica de [`RuleSet::add_pack`].
+    pub fn merge(&mut self, other: RuleSet) {
+        for pack in other.packs {
+            self.add_pack(pack);
+        }
+    }
+
+    /// Pacotes aplicados, na ordem de carregamento.
+    pub fn packs(&self) -> &[RulePack] {
+        &self.packs
+    }
+
+    /// Referências dos pacotes aplicados, como registradas nos relatórios.
+    pub fn pack_refs(&self) -> Vec<RulePackRef> {
+        self.packs.iter().map(RulePack::reference).collect()
+    }
+
+    /// Regras do Web Scanner.
+    pub fn web_rules(&self) -> &[WebRule] {
+        &self.web_rules
+    }
+
+    /// Todas as regras carregadas.
+    pub fn rules(&self) -> &[CodeRule] {
+        &self.rules
+    }
+
+    /// Regra de código com o id dado.
+    pub fn rule(&self, id: &str) -> Option<&CodeRule> {
+        self.rules.iter().find(|rule| rule.id == id)
+    }
+
+    /// Regras aplicáveis a um prefixo.
+    pub fn rules_for<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a CodeRule> {
+        self.rules.iter().filter(move |rule| rule.prefix == prefix)
+    }
+
+    /// Listas de palavras-chave consultadas pelas regras do prefixo, ou `None` se
+    /// nenhuma regra se aplica a ele.
+    pub fn keyword_usage(&self, prefix: &str) -> Option<KeywordUsage> {
+        let mut rules = self.rules_for(prefix).peekable();
+        rules.peek()?;
+        let mut usage = KeywordUsage::default();
+        for rule in rules {
+            for keyword in rule.condition.keyword_lists().into_iter().flatten() {
+                usage.expected_calls |= keyword == EXPECTED_CALLS_VAR;
+                usage.data_collection_keywords |= keyword == DATA_COLLECTION_VAR;
+            }
+        }
+        Some(usage)
+    }
+
+    /// Palavras-chave do prefixo que a função alcança no grafo de chamadas: os dados
+    /// pessoais tratados e as salvaguardas encontradas.
+    pub fn reached_keywords(
+        &self,
+        func: &FunctionAst,
+        prefix_info: &PrefixInfo,
+        call_graph: &CallGraph,
+        strict: bool,
+    ) -> ReachedKeywords {
+        let (expected_calls, data_collection_keywords) = self.keyword_lists(prefix_info, strict);
+        let reached = |keywords: Vec<String>| -> Vec<String> {
+            keywords
+                .into_iter()
+                .filter(|kw| call_graph.calls_matching(&func.name, kw, matching(strict)))
+                .collect()
+        };
+        ReachedKeywords {
+            data_collection: reached(data_collection_keywords),
+            safeguards: reached(expected_calls),
+        }
+    }
+
+    /// `expected_calls` e `data_collection_keywords` do prefixo; quando o prefixo
+    /// não as define, as listas de fallback (exceto no modo estrito).
+    pub fn keyword_lists(
+        &self,
+        prefix_info: &PrefixInfo,
+        strict: bool,
+    ) -> (Vec<String>, Vec<String>) {
+        let fallback = if strict {
+            None
+        } else {
+            self.fallbacks.get(&prefix_info.prefix)
+        };
+        let pick = |configured: &'_ Vec<String>, fallback: Option<&'_ Vec<String>>| {
+            if configured.is_empty() {
+                fallback.cloned().unwrap_or_default()
+            } else {
+                configured.clone()
+            }
+        };
+        (
+            pick(
+                &prefix_info.expected_calls,
+                fallback.map(|f| &f.expected_calls),
+            ),
+            pick(
+                &prefix_info.data_collection_keywords,
+                fallback.map(|f| &f.data_collection_keywords),
+            ),
+        )
+    }
+
+    /// Avalia todas as regras do prefixo para uma função anotada. No modo estrito,
+    /// as listas de fallback não são usadas e as chamadas precisam ter exatamente o
+    /// nome da palavra-chave.
+    pub fn evaluate(
+        &self,
+        func: &FunctionAst,
+        prefix_info: &PrefixInfo,
+        call_graph: &CallGraph,
+        strict: bool,
+    ) -> Vec<ComplianceViolation> {
+        let (expected_calls, data_collection_keywords) = self.keyword_lists(prefix_info, strict);
+        let ctx = EvalContext {
+            function: &func.name,
+            call_graph,
+            expected_calls: &expected_calls,
+            data_collection_keywords: &data_collection_keywords,
+            matching: matching(strict),
+        };
+
+        self.rules_for(&prefix_info.prefix)
+            .filter_map(|rule| Some((rule, rule.condition.explain(&ctx)?)))
+            .map(|(rule, evidence)| ComplianceViolation {
+                rule_id: rule.id.clone(),
+                severity: rule.severity.clone(),
+                message: rule.message.clone(),
+                file: None,
+                function: Some(func.name.clone()),
+                line: Some(func.line),
+                column: Some(func.column),
+                end_line: Some(func.end_line),
+                end_column: None,
+                suggestion: rule.suggestion.clone(),
+                suppression: None,
+                args: Default::default(),
+                call_chain: Some(CallChain {
+                    path: std::iter::once(func.name.clone())
+                        .chain(evidence.path.iter().map(|site| site.name.clone()))
+                        .collect(),
+                    line: evidence.path.first().map(|site| site.line),
+                    missing: evidence.missing,
+                }),
+                snippet: None,
+                citations: rule.citations.clone(),
+                fix: None,
+            })
+            .collect()
+    }
+}
+
+/// Palavras-chave de um prefixo alcançadas por uma função (ver
+/// [`RuleSet::reached_keywords`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReachedKeywords {
+    /// `data_collection_keywords` alcançadas.
+    pub data_collection: Vec<String>,
+    /// `expected_calls` alcançadas.
+    pub safeguards: Vec<String>,
+}
+
+/// Comparação das chamadas: exata no modo estrito, por substring fora dele.
+pub fn matching(strict: bool) -> KeywordMatch {
+    if strict {
+        KeywordMatch::Exact
+    } else {
+        KeywordMatch::Substring
+    }
+}
+
+fn is_known_var(keyword: &str) -> bool {
+    keyword == EXPECTED_CALLS_VAR || keyword == DATA_COLLECTION_VAR
+}
+
+/// Falha se a correção da regra não tem efeito ou se `guard` não é uma instrução Rust.
+fn check_fix(rule_id: &str, fix: &CodeFix) -> Result<(), HubstryError> {
+    if fix.guard.is_none() && fix.annotate_callers_of.is_empty() {
+        return Err(invalid(rule_id, msg!("error.rule.fix_without_effect")));
+    }
+    if let Some(guard) = &fix.guard {
+        if syn::parse_str::<syn::Stmt>(guard).is_err() {
+            return Err(invalid(
+                rule_id,
+                msg!("error.rule.invalid_guard", guard = guard),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Falha se alguma citação legal da regra tem campos vazios ou data de vigência
+/// fora do formato AAAA-MM-DD.
+fn check_citations(rule_id: &str, citations: &[LegalCitation]) -> Result<(), HubstryError> {
+    for citation in citations {
+        if [&citation.law, &citation.number, &citation.article]
+            .iter()
+            .any(|field| field.trim().is_empty())
+        {
+            return Err(invalid(rule_id, msg!("error.rule.empty_citation")));
+        }
+        if chrono::NaiveDate::parse_from_str(&citation.effective_date, "%Y-%m-%d").is_err() {
+            return Err(invalid(
+                rule_id,
+                msg!(
+                    "error.rule.citation_date",
+                    date = citation.effective_date,
+                    citation = citation
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn invalid(rule_id: &str, message: Message) -> HubstryError {
+    RuleError::Invalid {
+        rule_id: rule_id.to_string(),
+        message,
+    }
+    .into()
+}
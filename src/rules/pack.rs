@@ -0,0 +1,521 @@
+//! Pacotes de regras versionados.
+//!
+//! Um pacote é um arquivo YAML com um bloco `pack:` de metadados (nome, versão
+//! semântica, data de vigência, autoria e checksum) seguido do conteúdo: a
+//! jurisdição, prefixos de código opcionais (no mesmo formato de `prefixes.yml`),
+//! as listas de fallback, as regras de código e as regras do Web Scanner.
+//!
+//! O checksum é o SHA-256 do arquivo sem a chave `checksum:` do bloco `pack:`, de
+//! modo que o próprio arquivo possa declarar o valor esperado. Os relatórios registram a
+//! versão e o checksum de cada pacote aplicado.
+
+use super::{check_citations, check_fix, invalid, is_known_var, CodeRule, Fallback, RuleDocs};
+use crate::error::{ConfigError, HubstryError, RuleError};
+use crate::i18n::Message;
+use crate::models::{Jurisdiction, LegalCitation, RulePackRef, RuleSeverity};
+use crate::msg;
+use regex::Regex;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Pacotes embutidos no binário, na ordem em que são carregados.
+pub const BUILTIN_PACKS: &[(&str, &str)] = &[
+    (
+        "rules/eca_digital.yml",
+        include_str!("../../rules/eca_digital.yml"),
+    ),
+    ("rules/lgpd.yml", include_str!("../../rules/lgpd.yml")),
+];
+
+/// Diretório, relativo ao projeto, onde `hubstry rules install` copia os pacotes.
+pub const INSTALL_DIR: &str = ".hubstry/rules";
+
+/// Metadados do bloco `pack:`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackMetadata {
+    pub name: String,
+    /// Versão semântica (`MAJOR.MINOR.PATCH`).
+    pub version: String,
+    /// Data (AAAA-MM-DD) a partir da qual as regras do pacote valem.
+    pub effective_date: String,
+    /// Entidade responsável pela redação das regras.
+    pub authority: String,
+    /// `sha256:<hex>` do arquivo sem esta linha.
+    pub checksum: Option<String>,
+}
+
+/// Verificação que uma regra do Web Scanner executa sobre o HTML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebDetector {
+    /// Verificação de idade baseada apenas em autodeclaração.
+    SelfDeclaredAge,
+    /// Nenhuma verificação de idade encontrada.
+    MissingAgeGate,
+    /// Autoplay, scroll infinito e padrões semelhantes.
+    DarkPatterns,
+    /// Scripts de rastreamento de anúncios.
+    AdTrackers,
+    /// Nenhum link para a política de privacidade.
+    MissingPrivacyPolicy,
+    /// Mecânicas de loot box.
+    Lootbox,
+}
+
+impl WebDetector {
+    /// Nome usado no campo `detector:` do pacote.
+    pub fn name(self) -> &'static str {
+        match self {
+            WebDetector::SelfDeclaredAge => "self_declared_age",
+            WebDetector::MissingAgeGate => "missing_age_gate",
+            WebDetector::DarkPatterns => "dark_patterns",
+            WebDetector::AdTrackers => "ad_trackers",
+            WebDetector::MissingPrivacyPolicy => "missing_privacy_policy",
+            WebDetector::Lootbox => "lootbox",
+        }
+    }
+}
+
+/// Correção automática de uma regra do Web Scanner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebFix {
+    /// Envolve cada script de rastreamento num `<template data-consent="marketing">`,
+    /// que só é ativado pelo gerenciador de consentimento. Exige o detector
+    /// `ad_trackers`.
+    ConsentGate,
+}
+
+/// Uma regra do Web Scanner.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebRule {
+    pub id: String,
+    pub detector: WebDetector,
+    pub severity: RuleSeverity,
+    /// Categoria usada nos sub-scores (ex.: `age`, `design`, `lootbox`).
+    #[serde(default = "super::default_category")]
+    pub category: String,
+    pub business_description: String,
+    /// `{findings}` é substituído pelos elementos encontrados pelo detector.
+    pub technical_description: String,
+    pub remediation_business: String,
+    pub remediation_technical: String,
+    /// Dispositivos legais que a regra aplica, copiados para as violações.
+    #[serde(default)]
+    pub citations: Vec<LegalCitation>,
+    /// Correção automática aplicada pelo `hubstry fix`.
+    pub fix: Option<WebFix>,
+    #[serde(default)]
+    pub docs: RuleDocs,
+    /// Preenchido a partir do arquivo que contém a regra.
+    #[serde(skip, default = "super::default_jurisdiction")]
+    pub jurisdiction: Jurisdiction,
+}
+
+/// Um arquivo de pacote, como escrito em YAML.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackFile {
+    pack: PackMetadata,
+    jurisdiction: Jurisdiction,
+    #[serde(default)]
+    prefixes: Option<serde_yaml::Value>,
+    #[serde(default)]
+    fallbacks: HashMap<String, Fallback>,
+    #[serde(default)]
+    rules: Vec<CodeRule>,
+    #[serde(default)]
+    web_rules: Vec<WebRule>,
+}
+
+/// Resultado da verificação do checksum de um pacote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackVerification {
+    Valid,
+    /// O pacote não declara `checksum:`.
+    Unsigned,
+    Mismatch {
+        declared: String,
+        actual: String,
+    },
+}
+
+/// Um pacote de regras carregado.
+#[derive(Debug, Clone)]
+pub struct RulePack {
+    pub metadata: PackMetadata,
+    pub jurisdiction: Jurisdiction,
+    pub fallbacks: HashMap<String, Fallback>,
+    pub rules: Vec<CodeRule>,
+    pub web_rules: Vec<WebRule>,
+    /// Arquivo de origem (para pacotes embutidos, o caminho no repositório).
+    pub origin: PathBuf,
+    content: String,
+    has_prefixes: bool,
+    checksum: String,
+}
+
+impl RulePack {
+    /// Carrega um pacote de um arquivo. O checksum não é verificado; ver [`RulePack::verify`].
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, HubstryError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&content, path)
+    }
+
+    /// Interpreta o conteúdo de um pacote. `origin` é usado nas mensagens de erro.
+    pub fn parse(yaml: &str, origin: &Path) -> Result<Self, HubstryError> {
+        let file: PackFile = serde_yaml::from_str(yaml).map_err(|source| ConfigError::Yaml {
+            path: origin.to_path_buf(),
+            source,
+        })?;
+        let config_error = |message: Message| -> HubstryError {
+            ConfigError::Invalid {
+                path: origin.to_path_buf(),
+                message,
+            }
+            .into()
+        };
+
+        let name = Regex::new(r"^[A-Za-z0-9_.-]+$").expect("padrão válido");
+        if !name.is_match(&file.pack.name) || file.pack.name.contains("..") {
+            return Err(config_error(msg!(
+                "error.pack.invalid_name",
+                name = file.pack.name
+            )));
+        }
+        let semver = Regex::new(r"^\d+\.\d+\.\d+(-[0-9A-Za-z.-]+)?$").expect("padrão válido");
+        if !semver.is_match(&file.pack.version) {
+            return Err(config_error(msg!(
+                "error.pack.invalid_version",
+                version = file.pack.version,
+                name = file.pack.name
+            )));
+        }
+        if chrono::NaiveDate::parse_from_str(&file.pack.effective_date, "%Y-%m-%d").is_err() {
+            return Err(config_error(msg!(
+                "error.pack.invalid_date",
+                date = file.pack.effective_date,
+                name = file.pack.name
+            )));
+        }
+        if let Some(prefixes) = &file.prefixes {
+            if !prefixes.is_mapping() {
+                return Err(config_error(msg!("error.pack.prefixes_not_mapping")));
+            }
+        }
+
+        let mut rules = file.rules;
+        let mut ids = HashSet::new();
+        for rule in &mut rules {
+            rule.jurisdiction = file.jurisdiction.clone();
+            if !ids.insert(rule.id.clone()) {
+                return Err(invalid(&rule.id, msg!("error.rule.duplicate_id")));
+            }
+            check_citations(&rule.id, &rule.citations)?;
+            if let Some(fix) = &rule.fix {
+                check_fix(&rule.id, fix)?;
+            }
+            for list in rule.condition.keyword_lists() {
+                if let Some(unknown) = list
+                    .iter()
+                    .find(|kw| kw.starts_with('$') && !is_known_var(kw))
+                {
+                    return Err(invalid(
+                        &rule.id,
+                        msg!("error.rule.unknown_variable", variable = unknown),
+                    ));
+                }
+            }
+        }
+        let mut web_rules = file.web_rules;
+        for rule in &mut web_rules {
+            rule.jurisdiction = file.jurisdiction.clone();
+            if !ids.insert(rule.id.clone()) {
+                return Err(invalid(&rule.id, msg!("error.rule.duplicate_id")));
+            }
+            check_citations(&rule.id, &rule.citations)?;
+            if rule.fix == Some(WebFix::ConsentGate) && rule.detector != WebDetector::AdTrackers {
+                return Err(invalid(&rule.id, msg!("error.rule.consent_gate_detector")));
+            }
+        }
+
+        Ok(RulePack {
+            metadata: file.pack,
+            jurisdiction: file.jurisdiction,
+            fallbacks: file.fallbacks,
+            rules,
+            web_rules,
+            origin: origin.to_path_buf(),
+            has_prefixes: file.prefixes.is_some(),
+            checksum: compute_checksum(yaml),
+            content: yaml.to_string(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.metadata.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.metadata.version
+    }
+
+    /// Checksum calculado a partir do conteúdo carregado.
+    pub fn checksum(&self) -> &str {
+        &self.checksum
+    }
+
+    /// Conteúdo YAML do pacote, como carregado.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Compara o checksum declarado com o calculado.
+    pub fn verification(&self) -> PackVerification {
+        match &self.metadata.checksum {
+            None => PackVerification::Unsigned,
+            Some(declared) if *declared == self.checksum => PackVerification::Valid,
+            Some(declared) => PackVerification::Mismatch {
+                declared: declared.clone(),
+                actual: self.checksum.clone(),
+            },
+        }
+    }
+
+    /// Falha se o checksum declarado não confere. Pacotes sem checksum são aceitos.
+    pub fn verify(&self) -> Result<(), HubstryError> {
+        match self.verification() {
+            PackVerification::Mismatch { declared, actual } => Err(RuleError::Checksum {
+                pack: self.metadata.name.clone(),
+                declared,
+                actual,
+            }
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Referência registrada nos relatórios.
+    pub fn reference(&self) -> RulePackRef {
+        RulePackRef {
+            name: self.metadata.name.clone(),
+            version: self.metadata.version.clone(),
+            effective_date: self.metadata.effective_date.clone(),
+            checksum: self.checksum.clone(),
+        }
+    }
+
+    /// Os prefixos do pacote como uma camada de `prefixes.yml`, ou `None` se o pacote
+    /// não define `prefixes:`. As demais linhas ficam em branco para que os números
+    /// de linha coincidam com os do pacote.
+    pub fn prefix_layer(&self) -> Option<String> {
+        if !self.has_prefixes {
+            return None;
+        }
+        let mut inside = false;
+        let lines: Vec<&str> = self
+            .content
+            .lines()
+            .map(|line| {
+                let top_level = !line.is_empty() && !line.starts_with([' ', '\t', '#', '-']);
+                if top_level {
+                    inside = line.starts_with("prefixes:");
+                    if inside {
+                        return "jurisdictions:";
+                    }
+                }
+                if inside {
+                    line
+                } else {
+                    ""
+                }
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+}
+
+/// Calcula o checksum (`sha256:<hex>`) de um pacote, ignorando a chave `checksum:`
+/// do bloco `pack:` e diferenças de fim de linha.
+pub fn compute_checksum(content: &str) -> String {
+    let skipped = checksum_line(content);
+    let mut hasher = Sha256::new();
+    for (index, line) in content.lines().enumerate() {
+        if Some(index) == skipped {
+            continue;
+        }
+        hasher.update(line.trim_end_matches('\r').as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("sha256:{}", hex::encode(hasher.finalize()))
+}
+
+/// Devolve o conteúdo com a chave `checksum:` do bloco `pack:` atualizada (ou
+/// inserida ao final do bloco).
+pub fn with_checksum(content: &str) -> String {
+    let checksum = compute_checksum(content);
+    let replaced = checksum_line(content);
+    let mut out = Vec::new();
+    let mut written = false;
+    for (index, line) in content.lines().enumerate() {
+        if Some(index) == replaced {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            out.push(format!("{}checksum: \"{}\"", indent, checksum));
+            written = true;
+        } else {
+            out.push(line.to_string());
+        }
+    }
+    if !written {
+        if let Some(start) = out.iter().position(|line| line.trim_end() == "pack:") {
+            let end = out[start + 1..]
+                .iter()
+                .position(|line| !line.starts_with([' ', '\t']))
+                .map_or(out.len(), |offset| start + 1 + offset);
+            out.insert(end, format!("  checksum: \"{}\"", checksum));
+        }
+    }
+    let mut result = out.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Índice da linha da chave `checksum:` do bloco `pack:`, no nível das demais
+/// chaves do bloco. Outras linhas começadas por `checksum:` entram no cálculo.
+fn checksum_line(content: &str) -> Option<usize> {
+    let lines: Vec<&str> = content.lines().map(|l| l.trim_end_matches('\r')).collect();
+    let start = lines.iter().position(|line| line.trim_end() == "pack:")?;
+    let mut key_indent = None;
+    for (index, line) in lines.iter().enumerate().skip(start + 1) {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        if indent == 0 {
+            return None;
+        }
+        let key_indent = *key_indent.get_or_insert(indent);
+        if indent == key_indent && trimmed.starts_with("checksum:") {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// Os pacotes embutidos no binário.
+pub fn builtin_packs() -> Vec<RulePack> {
+    BUILTIN_PACKS
+        .iter()
+        .map(|(name, content)| {
+            RulePack::parse(content, Path::new(name))
+                .unwrap_or_else(|e| panic!("os pacotes embutidos devem ser válidos: {}", e))
+        })
+        .collect()
+}
+
+/// Pacotes instalados em `<project_dir>/.hubstry/rules`, em ordem alfabética de arquivo.
+pub fn installed_packs(project_dir: &Path) -> Result<Vec<RulePack>, HubstryError> {
+    let dir = project_dir.join(INSTALL_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| HubstryError::io(&dir, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("yml" | "yaml")
+            )
+        })
+        .collect();
+    paths.sort();
+    paths.iter().map(RulePack::from_path).collect()
+}
+
+/// Pacotes disponíveis para o projeto: os embutidos, substituídos pelos instalados
+/// com o mesmo nome.
+pub fn available_packs(project_dir: &Path) -> Result<Vec<RulePack>, HubstryError> {
+    let mut packs = builtin_packs();
+    for pack in installed_packs(project_dir)? {
+        match packs.iter_mut().find(|p| p.name() == pack.name()) {
+            Some(existing) => *existing = pack,
+            None => packs.push(pack),
+        }
+    }
+    Ok(packs)
+}
+
+/// Resolve um pacote pelo caminho do arquivo ou pelo nome, opcionalmente fixando a
+/// versão (`eca_digital@1.0.0`). O checksum é verificado.
+pub fn resolve(spec: &str, project_dir: &Path) -> Result<RulePack, HubstryError> {
+    let pack = find(spec, project_dir)?;
+    pack.verify()?;
+    Ok(pack)
+}
+
+/// Como [`resolve`], mas sem verificar o checksum.
+pub fn find(spec: &str, project_dir: &Path) -> Result<RulePack, HubstryError> {
+    let path = Path::new(spec);
+    let is_path = path.is_file()
+        || matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yml" | "yaml")
+        );
+    let pack = if is_path {
+        RulePack::from_path(path)?
+    } else {
+        let (name, version) = match spec.split_once('@') {
+            Some((name, version)) => (name, Some(version)),
+            None => (spec, None),
+        };
+        available_packs(project_dir)?
+            .into_iter()
+            .find(|pack| pack.name() == name && version.is_none_or(|v| pack.version() == v))
+            .ok_or_else(|| RuleError::UnknownPack {
+                name: spec.to_string(),
+            })?
+    };
+    Ok(pack)
+}
+
+/// Instala um pacote em `<project_dir>/.hubstry/rules/<nome>.yml`. Apenas pacotes
+/// com checksum válido podem ser instalados.
+pub fn install(path: &Path, project_dir: &Path) -> Result<PathBuf, HubstryError> {
+    let pack = RulePack::from_path(path)?;
+    if pack.verification() == PackVerification::Unsigned {
+        return Err(ConfigError::Invalid {
+            path: path.to_path_buf(),
+            message: msg!("error.pack.unsigned", name = pack.name()),
+        }
+        .into());
+    }
+    pack.verify()?;
+
+    let dir = project_dir.join(INSTALL_DIR);
+    fs::create_dir_all(&dir).map_err(|e| HubstryError::io(&dir, e))?;
+    let target = dir.join(format!("{}.yml", pack.name()));
+    if !target.starts_with(&dir) || target.parent() != Some(dir.as_path()) {
+        return Err(ConfigError::Invalid {
+            path: path.to_path_buf(),
+            message: msg!(
+                "error.pack.outside_install_dir",
+                name = pack.name(),
+                dir = INSTALL_DIR
+            ),
+        }
+        .into());
+    }
+    fs::write(&target, pack.content()).map_err(|e| HubstryError::io(&target, e))?;
+    Ok(target)
+}
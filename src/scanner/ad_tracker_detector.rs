@@ -1,4 +1,4 @@
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 
 #[derive(Debug)]
 pub struct AdTrackerResult {
@@ -13,15 +13,12 @@ pub fn detect_ad_trackers(html: &str) -> AdTrackerResult {
 
     let script_selector = Selector::parse("script").unwrap();
     for el in document.select(&script_selector) {
+        // Scripts num `<template data-consent>` só rodam após o consentimento.
+        let gated = el.ancestors().filter_map(ElementRef::wrap).any(|ancestor| {
+            ancestor.value().name() == "template" && ancestor.value().attr("data-consent").is_some()
+        });
         if let Some(src) = el.value().attr("src") {
-            let src_lower = src.to_lowercase();
-            if src_lower.contains("google-analytics")
-                || src_lower.contains("googletagmanager")
-                || src_lower.contains("gtag")
-                || src_lower.contains("facebook") && src_lower.contains("pixel")
-                || src_lower.contains("mixpanel")
-                || src_lower.contains("hotjar")
-            {
+            if is_tracker(src) && !gated {
                 has_trackers = true;
                 scripts_found.push(format!("Ad Tracker script: {:?}", src));
             }
@@ -33,3 +30,14 @@ pub fn detect_ad_trackers(html: &str) -> AdTrackerResult {
         scripts_found,
     }
 }
+
+/// Se o endereço de um script é de um serviço de rastreamento de anúncios.
+pub fn is_tracker(src: &str) -> bool {
+    let src = src.to_lowercase();
+    src.contains("google-analytics")
+        || src.contains("googletagmanager")
+        || src.contains("gtag")
+        || src.contains("facebook") && src.contains("pixel")
+        || src.contains("mixpanel")
+        || src.contains("hotjar")
+}
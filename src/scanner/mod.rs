@@ -1,5 +1,10 @@
-use anyhow::Result;
+use crate::error::{FetchError, Result};
+use crate::i18n::Locale;
+use crate::models::{Fix, LegalCitation, RulePackRef};
+use crate::rules::{RuleSet, WebDetector, WebFix};
+use crate::scoring::{self, Obligation, ScoreBreakdown, ScoringConfig};
 use async_trait::async_trait;
+use sha2::{Digest, Sha256};
 
 pub mod ad_tracker_detector;
 pub mod age_gate_detector;
@@ -13,8 +18,12 @@ pub struct WebScanResult {
     pub scan_date: String,
     pub violations: Vec<WebViolation>,
     pub score: f64,
+    /// Sub-scores por jurisdição e categoria, e a fórmula usada.
+    pub score_breakdown: ScoreBreakdown,
     pub pages_scanned: usize,
     pub elements_analyzed: usize,
+    /// Pacotes de regras aplicados.
+    pub rule_packs: Vec<RulePackRef>,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -28,13 +37,42 @@ pub struct WebViolation {
     pub page_url: String,
     pub remediation_business: String,
     pub remediation_technical: String,
+    /// Dispositivos legais que a regra aplica.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<LegalCitation>,
+    /// Correção automática do HTML da página, quando a regra tem uma.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<Fix>,
+}
+
+impl WebViolation {
+    /// Identifica a violação entre varreduras: SHA-256 da regra, da página e do
+    /// seletor do elemento.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            self.rule_id.as_str(),
+            self.page_url.as_str(),
+            self.element_selector.as_deref().unwrap_or(""),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hex::encode(hasher.finalize())
+    }
 }
 
 pub struct ScanConfig {
     pub max_pages: usize,
     pub follow_links: bool,
     pub check_subpages: Vec<String>,
-    pub rules_path: String,
+    /// Regras avaliadas; apenas as regras web dos pacotes são usadas.
+    pub rules: RuleSet,
+    /// Pesos das severidades no score, os mesmos da análise de código.
+    pub scoring: ScoringConfig,
+    /// Idioma dos textos das violações. Regras sem tradução no catálogo mantêm o
+    /// texto do pacote.
+    pub locale: Locale,
 }
 
 #[async_trait]
@@ -52,15 +90,30 @@ pub struct QuickScanResult {
     pub recommendation: String,
 }
 
-pub async fn quick_scan(url: &str) -> Result<QuickScanResult> {
+/// Busca o HTML de uma URL, associando eventuais falhas de rede à URL solicitada.
+pub async fn fetch_html(url: &str) -> Result<String> {
+    let to_error = |source| FetchError::Http {
+        url: url.to_string(),
+        source,
+    };
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(30))
-        .build()?;
+        .build()
+        .map_err(to_error)?;
 
-    let response = client.get(url).send().await?;
-    let html = response.text().await?;
+    let response = client.get(url).send().await.map_err(to_error)?;
+    Ok(response.text().await.map_err(to_error)?)
+}
+
+pub async fn quick_scan(url: &str, locale: Locale) -> Result<QuickScanResult> {
+    let html = fetch_html(url).await?;
+    Ok(quick_scan_html(url, &html, locale))
+}
 
-    let age_gate_result = age_gate_detector::detect_age_gate(&html);
+/// Avaliação rápida de um HTML já obtido, com o resumo e a recomendação no idioma
+/// `locale`.
+pub fn quick_scan_html(url: &str, html: &str, locale: Locale) -> QuickScanResult {
+    let age_gate_result = age_gate_detector::detect_age_gate(html);
 
     let has_age_verification =
         age_gate_result.method != age_gate_detector::AgeVerificationMethod::None;
@@ -73,29 +126,20 @@ pub async fn quick_scan(url: &str) -> Result<QuickScanResult> {
         _ => "OK",
     };
 
-    let (summary, recommendation) = match age_gate_result.method {
-        age_gate_detector::AgeVerificationMethod::SelfDeclarationOnly => (
-            "Detectada autodeclaração de idade na página.".to_string(),
-            "A autodeclaração é proibida pelo ECA Digital. Substitua por verificação oficial via API (Serpro/Gov.br).".to_string()
-        ),
-        age_gate_detector::AgeVerificationMethod::None => (
-            "Nenhum bloqueio ou verificação de idade foi encontrado.".to_string(),
-            "Implemente um Age-Gate na entrada da aplicação ou em fluxos de acesso sensíveis.".to_string()
-        ),
-        _ => (
-            "Métodos de verificação oficiais detectados (API/ZKP).".to_string(),
-            "A verificação está em conformidade. Continue garantindo a proteção aos menores.".to_string()
-        ),
+    let outcome = match age_gate_result.method {
+        age_gate_detector::AgeVerificationMethod::SelfDeclarationOnly => "self_declaration",
+        age_gate_detector::AgeVerificationMethod::None => "no_age_gate",
+        _ => "verified",
     };
 
-    Ok(QuickScanResult {
+    QuickScanResult {
         url: url.to_string(),
         has_age_verification,
         verification_method,
         risk_level: risk_level.to_string(),
-        summary,
-        recommendation,
-    })
+        summary: locale.text(&format!("quick_scan.{}.summary", outcome), &[]),
+        recommendation: locale.text(&format!("quick_scan.{}.recommendation", outcome), &[]),
+    }
 }
 
 /// Implementação do DOM Scanner baseada em estática (fetch e extração de regras MVP)
@@ -113,124 +157,104 @@ impl Default for StaticDomScanner {
     }
 }
 
-#[async_trait]
-impl WebScanner for StaticDomScanner {
-    async fn scan(&self, url: &str, _config: &ScanConfig) -> Result<WebScanResult> {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
-
-        let response = client.get(url).send().await?;
-        let html = response.text().await?;
-
+impl StaticDomScanner {
+    /// Avalia as regras web de `config.rules` sobre um HTML já obtido.
+    pub fn scan_html(&self, url: &str, html: &str, config: &ScanConfig) -> WebScanResult {
         let mut violations = Vec::new();
-        let mut score: f64 = 100.0;
+        let mut obligations = Vec::new();
 
-        // 1. Age Gate Detect
-        let age_gate_result = age_gate_detector::detect_age_gate(&html);
-        if age_gate_result.method == age_gate_detector::AgeVerificationMethod::SelfDeclarationOnly {
-            violations.push(WebViolation {
-                  rule_id: "ECA.AGE.SELF_DECLARATION_BAN".to_string(),
-                  severity: "CRITICAL".to_string(),
-                  business_description: "Autodeclaração de idade localizada na página.".to_string(),
-                  technical_description: format!("Elementos restritos encontrados: {:?}", age_gate_result.elements_found),
-                  element_selector: None,
-                  element_html: None,
-                  page_url: url.to_string(),
-                  remediation_business: "Remova a autodeclaração de idade.".to_string(),
-                  remediation_technical: "Substitua checkboxes/inputs simples por integrações em backend usando as APIs oficiais como o Serpro DataValid.".to_string(),
-             });
-            score -= 20.0;
-        } else if age_gate_result.method == age_gate_detector::AgeVerificationMethod::None {
+        for rule in config.rules.web_rules() {
+            let findings = detect(rule.detector, html);
+            obligations.push(Obligation {
+                rule_id: rule.id.clone(),
+                severity: rule.severity.clone(),
+                jurisdiction: rule.jurisdiction.clone(),
+                category: rule.category.clone(),
+                subject: url.to_string(),
+                failed: findings.is_some(),
+            });
+            let Some(findings) = findings else {
+                continue;
+            };
+            let text = |field: &str, pack_text: &str| {
+                config
+                    .locale
+                    .rule_text(&rule.id, field)
+                    .unwrap_or(pack_text)
+                    .replace("{findings}", &format!("{:?}", findings))
+            };
             violations.push(WebViolation {
-                  rule_id: "ECA.AGE.VERIFY".to_string(),
-                  severity: "CRITICAL".to_string(),
-                  business_description: "Nenhum sistema de verificação de idade foi encontrado para a plataforma.".to_string(),
-                  technical_description: "A página e a aplicação atual falharam em apresentar bloqueios baseados em idade no frontend.".to_string(),
-                  element_selector: None,
-                  element_html: None,
-                  page_url: url.to_string(),
-                  remediation_business: "Adicione verificação de idade segura ao fluxo inicial da aplicação.".to_string(),
-                  remediation_technical: "Crie um interceptador de requisições de página para realizar o Age-Gate com chamadas de integridade backend.".to_string(),
-             });
-            score -= 20.0;
+                rule_id: rule.id.clone(),
+                severity: rule.severity.to_string(),
+                business_description: text("business_description", &rule.business_description),
+                technical_description: text("technical_description", &rule.technical_description),
+                element_selector: None,
+                element_html: None,
+                page_url: url.to_string(),
+                remediation_business: text("remediation_business", &rule.remediation_business),
+                remediation_technical: text("remediation_technical", &rule.remediation_technical),
+                citations: rule.citations.clone(),
+                fix: rule.fix.and_then(|fix| match fix {
+                    WebFix::ConsentGate => {
+                        let edits = crate::fix::consent_gate(html);
+                        (!edits.is_empty()).then(|| Fix {
+                            description: crate::tr!(config.locale, "fix.consent_gate"),
+                            edits,
+                        })
+                    }
+                }),
+            });
         }
 
-        // 2. Dark Patterns
-        let dark_patterns_result = dark_pattern_detector::detect_dark_patterns(&html);
-        if dark_patterns_result.has_dark_patterns {
-            violations.push(WebViolation {
-                  rule_id: "ECA.DESIGN.DARK_PATTERNS".to_string(),
-                  severity: "HIGH".to_string(),
-                  business_description: "Padrão de design para engajamento e hiperuso contínuo detectado.".to_string(),
-                  technical_description: format!("Elementos problemáticos: {:?}", dark_patterns_result.elements_found),
-                  element_selector: None,
-                  element_html: None,
-                  page_url: url.to_string(),
-                  remediation_business: "Desative autoplay de mídias e scrolls infinitos.".to_string(),
-                  remediation_technical: "Remova a tag 'autoplay' do video e substitua 'infinite-scroll' por paginação controlada.".to_string(),
-             });
-            score -= 10.0;
+        let score_breakdown = scoring::score(&obligations, &config.scoring.weights);
+        WebScanResult {
+            url: url.to_string(),
+            scan_date: chrono::Utc::now().to_rfc3339(),
+            violations,
+            score: score_breakdown.score,
+            score_breakdown,
+            pages_scanned: 1,
+            elements_analyzed: html.len(), // Just an approximation for the MVP
+            rule_packs: config.rules.pack_refs(),
         }
+    }
+}
 
-        // 3. Ad Trackers
-        let trackers_result = ad_tracker_detector::detect_ad_trackers(&html);
-        if trackers_result.has_trackers {
-            violations.push(WebViolation {
-                  rule_id: "ECA.DATA.RETENTION_BAN".to_string(),
-                  severity: "HIGH".to_string(),
-                  business_description: "Scripts de rastreamento de anúncios (Trackers/Analytics) estão injetados na página desprotegida.".to_string(),
-                  technical_description: format!("Trackers encontrados: {:?}", trackers_result.scripts_found),
-                  element_selector: None,
-                  element_html: None,
-                  page_url: url.to_string(),
-                  remediation_business: "Solicite gerenciamento de consentimento ou remova o tracking por padrão para perfis de risco (menores de 18).".to_string(),
-                  remediation_technical: "Envolva os scripts identificados com regras de validação para injetá-los apenas após aprovação explícita e verificação de idade do visitante.".to_string(),
-             });
-            score -= 15.0;
+/// Executa um detector; `Some` com os elementos encontrados quando a regra é violada.
+fn detect(detector: WebDetector, html: &str) -> Option<Vec<String>> {
+    match detector {
+        WebDetector::SelfDeclaredAge => {
+            let result = age_gate_detector::detect_age_gate(html);
+            (result.method == age_gate_detector::AgeVerificationMethod::SelfDeclarationOnly)
+                .then_some(result.elements_found)
         }
-
-        // 4. Privacy Policy
-        let privacy_result = privacy_policy_checker::check_privacy_policy(&html);
-        if !privacy_result.has_policy_link {
-            violations.push(WebViolation {
-                  rule_id: "ECA.PRIVACY.MAX_DEFAULT".to_string(),
-                  severity: "HIGH".to_string(),
-                  business_description: "O portal parece não conter um link de acesso ou referência explícita a uma política de privacidade.".to_string(),
-                  technical_description: "Nenhuma tag com href referenciando 'Privacy Policy' ou 'Política de Privacidade' encontrada.".to_string(),
-                  element_selector: None,
-                  element_html: None,
-                  page_url: url.to_string(),
-                  remediation_business: "Inclua a política detalhando LGPD e adequações para menores de idade de modo vísivel no rodapé/header.".to_string(),
-                  remediation_technical: "Adicione <a href='/politica-de-privacidade'>Política de Privacidade</a> globalmente no DOM e atualize os menús.".to_string(),
-             });
-            score -= 10.0;
+        WebDetector::MissingAgeGate => {
+            let result = age_gate_detector::detect_age_gate(html);
+            (result.method == age_gate_detector::AgeVerificationMethod::None).then(Vec::new)
         }
-
-        // 5. Lootboxes
-        let lootbox_result = lootbox_detector::detect_lootbox(&html);
-        if lootbox_result.has_lootbox {
-            violations.push(WebViolation {
-                  rule_id: "ECA.DESIGN.LOOTBOX_BAN".to_string(),
-                  severity: "CRITICAL".to_string(),
-                  business_description: "Termos ligados à mecânica gacha/lootboxes identificados nas rotinas sem restrições explícitas acionadas.".to_string(),
-                  technical_description: format!("Identificadores listados no script: {:?}", lootbox_result.terms_found),
-                  element_selector: None,
-                  element_html: None,
-                  page_url: url.to_string(),
-                  remediation_business: "A compra ou acesso sem controle a lootboxes é proibida para menores. Requer Gate Bloqueante imediato.".to_string(),
-                  remediation_technical: "Restringir execução dessa mecânica. Implementar `verify_age_wall` e certificar-se da validade do token de acesso do usuário de forma rigorosa.".to_string(),
-             });
-            score -= 25.0;
+        WebDetector::DarkPatterns => {
+            let result = dark_pattern_detector::detect_dark_patterns(html);
+            result.has_dark_patterns.then_some(result.elements_found)
+        }
+        WebDetector::AdTrackers => {
+            let result = ad_tracker_detector::detect_ad_trackers(html);
+            result.has_trackers.then_some(result.scripts_found)
+        }
+        WebDetector::MissingPrivacyPolicy => {
+            let result = privacy_policy_checker::check_privacy_policy(html);
+            (!result.has_policy_link).then(Vec::new)
         }
+        WebDetector::Lootbox => {
+            let result = lootbox_detector::detect_lootbox(html);
+            result.has_lootbox.then_some(result.terms_found)
+        }
+    }
+}
 
-        Ok(WebScanResult {
-            url: url.to_string(),
-            scan_date: chrono::Utc::now().to_rfc3339(),
-            violations,
-            score: score.max(0.0),
-            pages_scanned: 1,
-            elements_analyzed: html.len(), // Just an approximation for the MVP
-        })
+#[async_trait]
+impl WebScanner for StaticDomScanner {
+    async fn scan(&self, url: &str, config: &ScanConfig) -> Result<WebScanResult> {
+        let html = fetch_html(url).await?;
+        Ok(self.scan_html(url, &html, config))
     }
 }
@@ -0,0 +1,198 @@
+//! Modelo de pontuação de conformidade, comum à análise de código e ao Web Scanner.
+//!
+//! Cada regra aplicada a uma função, arquivo, projeto ou página é uma *obrigação*
+//! verificada, com o peso da severidade da regra. O score é a fração do peso total
+//! que foi cumprida:
+//!
+//! ```text
+//! score = 100 * (1 - peso das obrigações violadas / peso das obrigações verificadas)
+//! ```
+//!
+//! Assim, cinco violações HIGH pesam muito mais num projeto com 5 funções anotadas
+//! do que num com 500. Uma obrigação com várias violações conta uma única vez, e
+//! violações suprimidas contam como cumpridas. Sem obrigações verificadas, o score
+//! é 100. Os mesmos cálculos produzem os sub-scores por jurisdição e por categoria.
+
+use crate::i18n::Message;
+use crate::models::{Jurisdiction, RuleSeverity};
+use crate::msg;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Categoria das obrigações que não declaram uma.
+pub const DEFAULT_CATEGORY: &str = "other";
+
+/// Peso de cada severidade no cálculo do score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct SeverityWeights {
+    pub critical: f64,
+    pub high: f64,
+    pub medium: f64,
+    pub low: f64,
+    pub info: f64,
+}
+
+impl Default for SeverityWeights {
+    fn default() -> Self {
+        Self {
+            critical: 10.0,
+            high: 5.0,
+            medium: 2.0,
+            low: 1.0,
+            info: 0.5,
+        }
+    }
+}
+
+impl SeverityWeights {
+    pub fn weight(&self, severity: &RuleSeverity) -> f64 {
+        match severity {
+            RuleSeverity::Critical => self.critical,
+            RuleSeverity::High => self.high,
+            RuleSeverity::Medium => self.medium,
+            RuleSeverity::Low => self.low,
+            RuleSeverity::Info => self.info,
+        }
+    }
+
+    /// Verifica se todos os pesos são números finitos e não negativos.
+    pub fn validate(&self) -> Result<(), Message> {
+        let weights = [
+            ("critical", self.critical),
+            ("high", self.high),
+            ("medium", self.medium),
+            ("low", self.low),
+            ("info", self.info),
+        ];
+        for (name, weight) in weights {
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(msg!("error.scoring.negative_weight", name = name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Seção `scoring:` do `hubstry.yml`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScoringConfig {
+    #[serde(default)]
+    pub weights: SeverityWeights,
+}
+
+/// Uma regra aplicada a um alvo (função, arquivo, projeto ou página).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Obligation {
+    pub rule_id: String,
+    pub severity: RuleSeverity,
+    pub jurisdiction: Jurisdiction,
+    pub category: String,
+    /// Identifica o alvo, ex.: `src/ads.rs::show_ads`, `src/ads.rs` ou a URL.
+    pub subject: String,
+    pub failed: bool,
+}
+
+/// Score de um subconjunto das obrigações.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubScore {
+    pub score: f64,
+    pub obligations: usize,
+    pub failed: usize,
+}
+
+/// Score geral, sub-scores e a fórmula usada, incluídos em todo relatório.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    pub score: f64,
+    pub obligations: usize,
+    pub failed: usize,
+    pub weights: SeverityWeights,
+    /// Chaveado pelo nome da jurisdição (ex.: `ECA Digital`, `LGPD`).
+    pub by_jurisdiction: BTreeMap<String, SubScore>,
+    /// Chaveado pela categoria das regras (ex.: `age`, `consent`, `ads`, `lootbox`).
+    pub by_category: BTreeMap<String, SubScore>,
+    pub formula: String,
+}
+
+impl Default for ScoreBreakdown {
+    fn default() -> Self {
+        score(&[], &SeverityWeights::default())
+    }
+}
+
+/// Alvo de uma obrigação, ex.: `src/ads.rs::show_ads`, usado para associar cada
+/// violação à obrigação que ela descumpre.
+pub fn subject(file: Option<&str>, function: Option<&str>) -> String {
+    let file = file.unwrap_or_default();
+    match function {
+        Some(function) => format!("{}::{}", file, function),
+        None => file.to_string(),
+    }
+}
+
+/// Fórmula com os pesos usados, em notação independente do idioma do relatório.
+pub fn formula(weights: &SeverityWeights) -> String {
+    format!(
+        "score = 100 * (1 - sum(weight of failed obligations) / sum(weight of checked obligations)); \
+         weights: CRITICAL={}, HIGH={}, MEDIUM={}, LOW={}, INFO={}",
+        weights.critical, weights.high, weights.medium, weights.low, weights.info
+    )
+}
+
+/// Calcula o score geral e os sub-scores.
+pub fn score(obligations: &[Obligation], weights: &SeverityWeights) -> ScoreBreakdown {
+    let mut by_jurisdiction: BTreeMap<String, Vec<&Obligation>> = BTreeMap::new();
+    let mut by_category: BTreeMap<String, Vec<&Obligation>> = BTreeMap::new();
+    for obligation in obligations {
+        by_jurisdiction
+            .entry(obligation.jurisdiction.to_string())
+            .or_default()
+            .push(obligation);
+        by_category
+            .entry(obligation.category.clone())
+            .or_default()
+            .push(obligation);
+    }
+    let all: Vec<&Obligation> = obligations.iter().collect();
+    let overall = sub_score(&all, weights);
+    let sub_scores = |groups: BTreeMap<String, Vec<&Obligation>>| {
+        groups
+            .into_iter()
+            .map(|(key, group)| (key, sub_score(&group, weights)))
+            .collect()
+    };
+
+    ScoreBreakdown {
+        score: overall.score,
+        obligations: overall.obligations,
+        failed: overall.failed,
+        weights: weights.clone(),
+        by_jurisdiction: sub_scores(by_jurisdiction),
+        by_category: sub_scores(by_category),
+        formula: formula(weights),
+    }
+}
+
+fn sub_score(obligations: &[&Obligation], weights: &SeverityWeights) -> SubScore {
+    let total: f64 = obligations
+        .iter()
+        .map(|o| weights.weight(&o.severity))
+        .sum();
+    let failed: f64 = obligations
+        .iter()
+        .filter(|o| o.failed)
+        .map(|o| weights.weight(&o.severity))
+        .sum();
+    let score = if total > 0.0 {
+        100.0 * (1.0 - failed / total)
+    } else {
+        100.0
+    };
+    SubScore {
+        score: (score * 100.0).round() / 100.0,
+        obligations: obligations.len(),
+        failed: obligations.iter().filter(|o| o.failed).count(),
+    }
+}
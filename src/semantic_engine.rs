@@ -1,24 +1,126 @@
 //! Semantic engine module for the Hubstry-ISO_Code framework.
-//! This module dispatches analysis to the appropriate jurisdiction-specific modules.
+//! This module finds annotated functions and evaluates the declarative rules of their prefixes.
 
 use crate::ast::{FileAst, FunctionAst};
+use crate::error::HubstryError;
+use crate::fix::PlannedFix;
+use crate::graph::ComplianceGraph;
+use crate::i18n::Locale;
+use crate::inventory;
 use crate::models::{
-    AnalysisResult, ComplianceViolation, EngineConfig, Jurisdiction, RuleSeverity,
+    AnalysisResult, CheckedFunction, ComplianceViolation, EngineConfig, Jurisdiction, RuleScope,
+    RuleSeverity,
 };
-use crate::prefix_manager::{self, PrefixError};
-use std::collections::HashMap;
+use crate::parser;
+use crate::prefix_lint::{self, LintDiagnostic};
+use crate::prefix_manager::PrefixRegistry;
+use crate::provenance;
+use crate::rules::custom::{self, CustomRule};
+use crate::rules::{ReachedKeywords, RuleSet};
+use crate::scoring::{self, subject, Obligation, DEFAULT_CATEGORY};
+use crate::snippet;
+use crate::suppression;
+use crate::tr;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, RwLock, RwLockReadGuard};
 use syn::{spanned::Spanned, Expr, File, Item, Lit, Meta};
 
+/// Rule id of the violation raised in strict mode for an annotation whose prefix is
+/// not in the registry.
+pub const UNKNOWN_PREFIX_RULE: &str = "HUBSTRY.STRICT.UNKNOWN_PREFIX";
+
+/// Score category of the unknown-prefix violations raised in strict mode.
+pub const UNKNOWN_PREFIX_CATEGORY: &str = "prefixes";
+
+/// Whether an annotation looks like a compliance prefix (e.g. `ECA.AGE.VERIFY`)
+/// rather than ordinary documentation such as `Note: ...`.
+fn is_prefix_like(text: &str) -> bool {
+    let mut segments = text.split('.');
+    let first = segments.next().unwrap_or_default();
+    let valid = |segment: &str| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+    };
+    valid(first) && text.contains('.') && segments.all(valid)
+}
+
 /// The main semantic engine for compliance analysis.
+///
+/// Each engine owns its own [`PrefixRegistry`]. The registry sits behind a lock so a
+/// long-running server can reload the rules through a shared reference.
 #[derive(Debug)]
 pub struct SemanticEngine {
     config: EngineConfig,
+    registry: RwLock<PrefixRegistry>,
+    rules: RuleSet,
+    /// Lint of the registry against the rules, computed on first use and dropped
+    /// whenever either of them changes.
+    lint: Mutex<Option<Vec<LintDiagnostic>>>,
 }
 
 impl SemanticEngine {
-    /// Creates a new semantic engine with the given configuration.
+    /// Creates a new semantic engine with the given configuration and the embedded
+    /// default prefixes.
     pub fn new(config: EngineConfig) -> Self {
-        SemanticEngine { config }
+        Self::with_registry(config, PrefixRegistry::embedded())
+    }
+
+    /// Creates a new semantic engine that uses the given prefix registry.
+    pub fn with_registry(config: EngineConfig, registry: PrefixRegistry) -> Self {
+        SemanticEngine {
+            config,
+            registry: RwLock::new(registry),
+            rules: RuleSet::builtin(),
+            lint: Mutex::new(None),
+        }
+    }
+
+    /// Replaces the built-in code rules with the given rule set.
+    pub fn with_rules(mut self, rules: RuleSet) -> Self {
+        self.rules = rules;
+        *self.lint.get_mut().unwrap_or_else(|e| e.into_inner()) = None;
+        self
+    }
+
+    /// The code rules evaluated by this engine.
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// Returns a read guard over the prefix registry in use.
+    pub fn registry(&self) -> RwLockReadGuard<'_, PrefixRegistry> {
+        self.registry.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replaces the prefix registry. Analyses already running keep the old one.
+    pub fn set_registry(&self, registry: PrefixRegistry) {
+        let mut current = self.registry.write().unwrap_or_else(|e| e.into_inner());
+        *current = registry;
+        *self.lint.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Lint diagnostics of the current registry, computed once per registry load.
+    pub fn lint(&self) -> Vec<LintDiagnostic> {
+        let registry = self.registry();
+        self.lint
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(|| prefix_lint::lint(&registry, &self.rules))
+            .clone()
+    }
+
+    /// Reloads the prefix registry from its source file if it changed on disk.
+    /// Returns `true` when a reload happened. On failure the current registry is kept.
+    pub fn reload_prefixes_if_changed(&self) -> Result<bool, HubstryError> {
+        if !self.registry().is_stale() {
+            return Ok(false);
+        }
+        let mut reloaded = self.registry().clone();
+        reloaded.reload()?;
+        self.set_registry(reloaded);
+        Ok(true)
     }
 }
 
@@ -38,6 +140,8 @@ impl SemanticEngine {
                 let name = func.sig.ident.to_string();
                 let line = func.span().start().line;
                 let column = func.span().start().column;
+                let end_line = func.span().end().line;
+                let body = func.block.brace_token.span.open().start();
 
                 let mut doc_comments = Vec::new();
                 for attr in &func.attrs {
@@ -59,215 +163,374 @@ impl SemanticEngine {
                     name,
                     doc_comments,
                     called_functions: call_finder.calls,
+                    calls: call_finder.sites,
+                    string_literals: call_finder.literals,
                     line,
                     column,
+                    end_line,
+                    body_line: body.line,
+                    body_column: body.column,
                 });
             }
         }
 
-        FileAst { functions }
+        FileAst {
+            functions,
+            ..FileAst::default()
+        }
+    }
+
+    /// Parses a source file into a `FileAst` that keeps its path and source text,
+    /// as needed by custom rules that match the source.
+    pub fn load_file(path: &str, source: &str) -> Result<FileAst, HubstryError> {
+        let file_ast = parser::parse_source(path, source)?;
+        Ok(FileAst {
+            path: path.to_string(),
+            source: source.to_string(),
+            ..Self::syn_to_generic_ast(&file_ast)
+        })
+    }
+
+    /// Parses and analyzes several source files given as `(path, source)` pairs.
+    ///
+    /// Outside strict mode a file that fails to parse is skipped with a warning; in
+    /// strict mode the parse failure is returned as an error.
+    pub fn analyze_sources(
+        &self,
+        sources: &[(String, String)],
+    ) -> Result<AnalysisResult, HubstryError> {
+        let (files, skipped) = self.load_sources(sources)?;
+        let mut result = self.analyze_project(&files)?;
+        result.warnings.extend(skipped);
+        Ok(result)
+    }
+
+    /// Parses several `(path, source)` pairs, as [`SemanticEngine::analyze_sources`]
+    /// does, for callers that also need the ASTs. Returns the files that parsed and
+    /// a warning for each file skipped outside strict mode.
+    pub fn load_sources(
+        &self,
+        sources: &[(String, String)],
+    ) -> Result<(Vec<FileAst>, Vec<String>), HubstryError> {
+        let mut files = Vec::new();
+        let mut skipped = Vec::new();
+        for (path, source) in sources {
+            match Self::load_file(path, source) {
+                Ok(file) => files.push(file),
+                Err(err) if !self.config.strict_mode => {
+                    let locale = self.config.locale;
+                    skipped.push(tr!(
+                        locale,
+                        "engine.file_skipped",
+                        code = err.code(),
+                        error = err.message(locale)
+                    ))
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok((files, skipped))
     }
 
     /// Analyzes a `syn::File` AST for compliance violations.
     /// This function can fail if the prefix configuration cannot be loaded.
-    pub fn analyze(&self, file_ast: &File) -> Result<AnalysisResult, PrefixError> {
+    pub fn analyze(&self, file_ast: &File) -> Result<AnalysisResult, HubstryError> {
         let generic_ast = Self::syn_to_generic_ast(file_ast);
         self.analyze_generic(&generic_ast)
     }
 
     /// Analyzes a generic `FileAst` for compliance violations.
-    pub fn analyze_generic(&self, file_ast: &FileAst) -> Result<AnalysisResult, PrefixError> {
+    pub fn analyze_generic(&self, file_ast: &FileAst) -> Result<AnalysisResult, HubstryError> {
+        self.analyze_project(std::slice::from_ref(file_ast))
+    }
+
+    /// Call graph of the given files, with each node classified by the registry's
+    /// keywords and the call chains of `violations` highlighted.
+    pub fn compliance_graph(
+        &self,
+        files: &[FileAst],
+        violations: &[ComplianceViolation],
+    ) -> ComplianceGraph {
+        let mut graph = ComplianceGraph::build(
+            files,
+            &self.registry(),
+            &self.rules,
+            self.config.strict_mode,
+        );
+        graph.highlight(violations);
+        graph
+    }
+
+    /// Missing annotations that `hubstry fix` can add: unannotated functions that
+    /// call a rule's `annotate_callers_of` (see [`crate::fix::annotations`]).
+    pub fn annotation_fixes(&self, files: &[FileAst], locale: Locale) -> Vec<PlannedFix> {
+        crate::fix::annotations(
+            files,
+            self.rules.rules(),
+            &self.registry(),
+            &self.config.enabled_jurisdictions,
+            locale,
+        )
+    }
+
+    /// Analyzes several files together. Project-scoped custom rules see all of them.
+    pub fn analyze_project(&self, files: &[FileAst]) -> Result<AnalysisResult, HubstryError> {
+        let custom_rules: Vec<CustomRule> = custom::compile(&self.config.custom_rules)?
+            .into_iter()
+            .filter(|custom| {
+                custom.rule.jurisdiction == Jurisdiction::Generic
+                    || self
+                        .config
+                        .enabled_jurisdictions
+                        .contains(&custom.rule.jurisdiction)
+            })
+            .collect();
+
+        let strict = self.config.strict_mode;
+        let locale = self.config.locale;
+        let mut warnings: Vec<String> = self.lint().iter().map(|d| d.text(locale)).collect();
+        let prefix_map = self.registry();
         let mut violations = Vec::new();
-        // Load the prefix map once at the beginning.
-        let prefix_map = prefix_manager::try_get_prefix_map()?;
+        let mut suppressed = Vec::new();
+        let mut checked_functions = Vec::new();
+        let mut obligations = Vec::new();
 
-        // Build the basic call graph for inter-procedural analysis
-        let call_graph = crate::jurisdictions::eca::CallGraph::build_from_generic(file_ast);
+        for file_ast in files {
+            // Build the basic call graph for inter-procedural analysis
+            let call_graph = crate::jurisdictions::eca::CallGraph::build_from_generic(file_ast);
+            let path = (!file_ast.path.is_empty()).then(|| file_ast.path.clone());
+            let mut file_violations = Vec::new();
 
-        for func in &file_ast.functions {
-            // Find all compliance prefixes in the function's doc comments
-            for comment in &func.doc_comments {
-                if let Some(prefix) = self.get_potential_prefix_from_string(comment) {
+            for func in &file_ast.functions {
+                let mut checked_prefixes = Vec::new();
+                let mut reached = ReachedKeywords::default();
+                // Find all compliance prefixes in the function's doc comments
+                for prefix in func.annotations() {
                     // Look up the prefix to find its jurisdiction
-                    if let Some(prefix_info) = prefix_map.get(&prefix) {
-                        let jurisdiction = match prefix_info.standard.as_str() {
-                            "Eca" => Jurisdiction::Eca,
-                            "Lgpd" => Jurisdiction::Lgpd,
-                            _ => Jurisdiction::Generic,
-                        };
-
-                        // Dispatch to the correct jurisdiction if it's enabled
-                        if self.config.enabled_jurisdictions.contains(&jurisdiction) {
-                            match jurisdiction {
-                                Jurisdiction::Eca => {
-                                    violations.extend(crate::jurisdictions::eca::validate(
-                                        func,
-                                        prefix_info,
-                                        &call_graph,
-                                    ));
-                                }
-                                Jurisdiction::Lgpd => {
-                                    violations.extend(crate::jurisdictions::lgpd::validate(
-                                        func,
-                                        prefix_info,
-                                        &call_graph,
-                                    ));
+                    let Some(prefix_info) = prefix_map.get(prefix) else {
+                        if strict && is_prefix_like(prefix) {
+                            obligations.push(Obligation {
+                                rule_id: UNKNOWN_PREFIX_RULE.to_string(),
+                                severity: RuleSeverity::High,
+                                jurisdiction: Jurisdiction::Generic,
+                                category: UNKNOWN_PREFIX_CATEGORY.to_string(),
+                                subject: subject(path.as_deref(), Some(&func.name)),
+                                failed: false,
+                            });
+                            file_violations.push(ComplianceViolation {
+                                rule_id: UNKNOWN_PREFIX_RULE.to_string(),
+                                severity: RuleSeverity::High,
+                                message: format!(
+                                    "O prefixo '{}' não está definido na configuração de prefixos.",
+                                    prefix
+                                ),
+                                file: path.clone(),
+                                function: Some(func.name.clone()),
+                                line: Some(func.line),
+                                column: Some(func.column),
+                                end_line: Some(func.end_line),
+                                end_column: None,
+                                suggestion: Some(
+                                    "Corrija o nome do prefixo ou declare-o em prefixes.yml."
+                                        .to_string(),
+                                ),
+                                suppression: None,
+                                args: BTreeMap::from([("prefix".to_string(), prefix.to_string())]),
+                                call_chain: None,
+                                snippet: snippet::function(&file_ast.source, func, prefix, None),
+                                citations: Vec::new(),
+                                fix: None,
+                            });
+                        }
+                        continue;
+                    };
+                    let jurisdiction = Jurisdiction::from_standard(&prefix_info.standard)
+                        .unwrap_or(Jurisdiction::Generic);
+
+                    // Evaluate the prefix's rules if its jurisdiction is enabled
+                    if self.config.enabled_jurisdictions.contains(&jurisdiction) {
+                        checked_prefixes.push(prefix.to_string());
+                        let keywords =
+                            self.rules
+                                .reached_keywords(func, prefix_info, &call_graph, strict);
+                        for (list, found) in [
+                            (&mut reached.data_collection, keywords.data_collection),
+                            (&mut reached.safeguards, keywords.safeguards),
+                        ] {
+                            for keyword in found {
+                                if !list.contains(&keyword) {
+                                    list.push(keyword);
                                 }
-                                _ => {} // Other jurisdictions are not handled
                             }
                         }
+                        obligations.extend(self.rules.rules_for(prefix).map(|rule| Obligation {
+                            rule_id: rule.id.clone(),
+                            severity: rule.severity.clone(),
+                            jurisdiction: rule.jurisdiction.clone(),
+                            category: rule.category.clone(),
+                            subject: subject(path.as_deref(), Some(&func.name)),
+                            failed: false,
+                        }));
+                        file_violations.extend(
+                            self.rules
+                                .evaluate(func, prefix_info, &call_graph, strict)
+                                .into_iter()
+                                .map(|violation| ComplianceViolation {
+                                    file: path.clone(),
+                                    fix: self.rules.rule(&violation.rule_id).and_then(|rule| {
+                                        crate::fix::code_fix(rule, &file_ast.source, func)
+                                    }),
+                                    snippet: snippet::function(
+                                        &file_ast.source,
+                                        func,
+                                        prefix,
+                                        violation.call_chain.as_ref().and_then(|c| c.line),
+                                    ),
+                                    ..violation
+                                }),
+                        );
                     }
                 }
+                if !checked_prefixes.is_empty() {
+                    let processing = inventory::of_function(func);
+                    checked_functions.push(CheckedFunction {
+                        file: path.clone(),
+                        name: func.name.clone(),
+                        line: func.line,
+                        description: func.annotation(&checked_prefixes[0]).map(str::to_string),
+                        prefixes: checked_prefixes,
+                        data_collection: reached.data_collection,
+                        safeguards: reached.safeguards,
+                        legal_basis: processing.legal_basis,
+                        purpose: processing.purpose,
+                    });
+                }
             }
-        }
-
-        let compliance_score = self.calculate_compliance_score(&violations);
 
-        Ok(AnalysisResult {
-            compliance_score,
-            violations,
-            suggestions: Vec::new(),  // Placeholder for future implementation
-            warnings: Vec::new(),     // Placeholder for future implementation
-            metadata: HashMap::new(), // Placeholder for future implementation
-        })
-    }
+            for custom in &custom_rules {
+                obligations.extend(
+                    custom
+                        .subjects(file_ast)
+                        .into_iter()
+                        .map(|subject| custom_obligation(custom, subject)),
+                );
+                file_violations.extend(custom.evaluate_file(file_ast));
+            }
+            for violation in &mut file_violations {
+                if let (None, Some(line)) = (&violation.snippet, violation.line) {
+                    violation.snippet = snippet::around(&file_ast.source, line, violation.end_line);
+                }
+            }
 
-    /// Extracts a potential prefix string from a doc comment string.
-    fn get_potential_prefix_from_string(&self, comment_text: &str) -> Option<String> {
-        let parts: Vec<&str> = comment_text.trim().splitn(2, ':').collect();
-        if parts.len() > 1 {
-            return Some(parts[0].trim().to_string());
+            let (active, waived, refused) =
+                suppression::apply(file_ast, file_violations, strict, locale);
+            violations.extend(active);
+            suppressed.extend(waived);
+            warnings.extend(refused);
         }
-        None
-    }
 
-    /// Calculates a compliance score based on violations.
-    fn calculate_compliance_score(&self, violations: &[ComplianceViolation]) -> f64 {
-        if violations.is_empty() {
-            100.0
-        } else {
-            let total_weight: f64 = violations
-                .iter()
-                .map(|v| match v.severity {
-                    RuleSeverity::Critical => 10.0,
-                    RuleSeverity::High => 5.0,
-                    RuleSeverity::Medium => 2.0,
-                    RuleSeverity::Low => 1.0,
-                    RuleSeverity::Info => 0.5,
-                })
-                .sum();
-            (100.0 - (total_weight * 2.0)).max(0.0)
+        let mut project_violations = Vec::new();
+        for custom in &custom_rules {
+            if custom.rule.scope == RuleScope::Project {
+                obligations.push(custom_obligation(custom, String::new()));
+            }
+            project_violations.extend(custom.evaluate_project(files));
         }
-    }
-
-    /// Generates a compliance report in Markdown text format.
-    pub fn generate_report(&self, result: &AnalysisResult) -> String {
-        let mut report = String::new();
-        report.push_str("# Hubstry-ISO_Code Compliance Report\n\n");
-        report.push_str(&format!(
-            "**Compliance Score:** {:.1}%\n\n",
-            result.compliance_score
-        ));
-
-        if !result.violations.is_empty() {
-            report.push_str(&format!("## Violations ({})\n\n", result.violations.len()));
-            for violation in &result.violations {
-                report.push_str(&format!(
-                    "- **{}** [{}]: {}\n",
-                    violation.severity, violation.rule_id, violation.message
-                ));
-                let line = violation.line.unwrap_or(0);
-                let col = violation.column.unwrap_or(0);
-                report.push_str(&format!("  *Location: Line {}, Column {}*\n", line, col));
-                if let Some(suggestion) = &violation.suggestion {
-                    report.push_str(&format!("  *Suggestion: {}*\n", suggestion));
+        // Project-scoped violations found in a file go through that file's suppressions,
+        // like the file-scoped ones. Those with no file (a missing `Require` match) have
+        // nowhere to be suppressed.
+        for file_ast in files.iter().filter(|file| !file.path.is_empty()) {
+            let (mut file_violations, rest): (Vec<_>, Vec<_>) = project_violations
+                .into_iter()
+                .partition(|violation| violation.file.as_deref() == Some(&file_ast.path));
+            project_violations = rest;
+            if file_violations.is_empty() {
+                continue;
+            }
+            for violation in &mut file_violations {
+                if let (None, Some(line)) = (&violation.snippet, violation.line) {
+                    violation.snippet = snippet::around(&file_ast.source, line, violation.end_line);
                 }
-                report.push('\n');
+            }
+            let (active, waived, refused) =
+                suppression::apply(file_ast, file_violations, strict, locale);
+            violations.extend(active);
+            suppressed.extend(waived);
+            warnings.extend(refused);
+        }
+        violations.extend(project_violations);
+
+        mark_failed(&mut obligations, &violations);
+        let score_breakdown = scoring::score(&obligations, &self.config.scoring.weights);
+
+        let mut suggestions: Vec<String> = Vec::new();
+        for suggestion in violations.iter().filter_map(|v| v.suggestion.as_ref()) {
+            if !suggestions.contains(suggestion) {
+                suggestions.push(suggestion.clone());
             }
         }
-        report
+        let rule_packs = self.rules.pack_refs();
+        let metadata = provenance::engine_metadata(
+            &self.config,
+            &prefix_map,
+            &rule_packs,
+            files
+                .iter()
+                .filter(|file| !file.path.is_empty())
+                .map(|file| (file.path.as_str(), file.source.as_str())),
+        );
+
+        Ok(AnalysisResult {
+            compliance_score: score_breakdown.score,
+            score_breakdown,
+            violations,
+            suggestions,
+            warnings,
+            metadata,
+            rule_packs,
+            suppressed,
+            strict_mode: strict,
+            checked_functions,
+        })
     }
+}
 
-    /// Generates a compliance report in JSON format.
-    pub fn generate_json_report(&self, result: &AnalysisResult) -> String {
-        serde_json::to_string_pretty(result).unwrap_or_else(|_| "{}".to_string())
+fn custom_obligation(custom: &CustomRule, subject: String) -> Obligation {
+    Obligation {
+        rule_id: custom.rule.id.clone(),
+        severity: custom.rule.severity.clone(),
+        jurisdiction: custom.rule.jurisdiction.clone(),
+        category: custom
+            .rule
+            .category
+            .clone()
+            .unwrap_or_else(|| DEFAULT_CATEGORY.to_string()),
+        subject,
+        failed: false,
     }
+}
 
-    /// Generates a compliance report in HTML format suitable for C-Levels.
-    pub fn generate_html_report(&self, result: &AnalysisResult) -> String {
-        let mut html = String::new();
-        html.push_str("<!DOCTYPE html>\n<html lang=\"pt-BR\">\n<head>\n");
-        html.push_str("<meta charset=\"UTF-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n");
-        html.push_str("<title>Relatório de Compliance - Hubstry CaaS</title>\n");
-        html.push_str("<style>\n");
-        html.push_str("body { font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif; margin: 0; padding: 20px; background-color: #f9f9fb; color: #333; }\n");
-        html.push_str(".container { max-width: 800px; margin: auto; background: #fff; padding: 30px; border-radius: 8px; box-shadow: 0 4px 6px rgba(0,0,0,0.1); }\n");
-        html.push_str(
-            "h1 { color: #2c3e50; border-bottom: 2px solid #ecf0f1; padding-bottom: 10px; }\n",
-        );
-        html.push_str(".score-board { text-align: center; margin: 30px 0; padding: 20px; border-radius: 8px; }\n");
-        html.push_str(".score-board.good { background-color: #d4edda; color: #155724; }\n");
-        html.push_str(".score-board.warning { background-color: #fff3cd; color: #856404; }\n");
-        html.push_str(".score-board.danger { background-color: #f8d7da; color: #721c24; }\n");
-        html.push_str(".score-value { font-size: 48px; font-weight: bold; }\n");
-        html.push_str(".violation { border: 1px solid #e2e8f0; border-left: 5px solid #e53e3e; margin-bottom: 20px; padding: 15px; border-radius: 4px; }\n");
-        html.push_str(".violation h3 { margin-top: 0; color: #e53e3e; }\n");
-        html.push_str(".meta { font-size: 0.9em; color: #718096; margin-bottom: 10px; }\n");
-        html.push_str(".suggestion { background-color: #edf2f7; padding: 10px; border-radius: 4px; font-style: italic; }\n");
-        html.push_str("</style>\n</head>\n<body>\n");
-
-        html.push_str("<div class=\"container\">\n");
-        html.push_str("<h1>Relatório Executivo de Compliance</h1>\n");
-
-        let score_class = if result.compliance_score >= 90.0 {
-            "good"
-        } else if result.compliance_score >= 70.0 {
-            "warning"
-        } else {
-            "danger"
+/// Marks the obligations broken by the active violations. A project-scoped rule
+/// reports its matches per file, so a violation without an obligation for its own
+/// target falls back to the rule's project-wide obligation.
+fn mark_failed(obligations: &mut Vec<Obligation>, violations: &[ComplianceViolation]) {
+    for violation in violations {
+        let target = subject(violation.file.as_deref(), violation.function.as_deref());
+        let position = |target: &str| {
+            obligations
+                .iter()
+                .position(|o| o.rule_id == violation.rule_id && o.subject == target)
         };
-
-        html.push_str(&format!("<div class=\"score-board {}\">\n", score_class));
-        html.push_str("<h2>Score Geral de Conformidade</h2>\n");
-        html.push_str(&format!(
-            "<div class=\"score-value\">{:.1}%</div>\n",
-            result.compliance_score
-        ));
-        html.push_str("</div>\n");
-
-        if !result.violations.is_empty() {
-            html.push_str(&format!(
-                "<h2>Violações Detectadas ({})</h2>\n",
-                result.violations.len()
-            ));
-            for violation in &result.violations {
-                html.push_str("<div class=\"violation\">\n");
-                html.push_str(&format!(
-                    "<h3>[{}] {}</h3>\n",
-                    violation.rule_id, violation.severity
-                ));
-                html.push_str(&format!(
-                    "<p><strong>Problema:</strong> {}</p>\n",
-                    violation.message
-                ));
-
-                let line = violation.line.unwrap_or(0);
-                let col = violation.column.unwrap_or(0);
-                html.push_str(&format!(
-                    "<div class=\"meta\">Localização: Linha {}, Coluna {}</div>\n",
-                    line, col
-                ));
-
-                if let Some(suggestion) = &violation.suggestion {
-                    html.push_str(&format!("<div class=\"suggestion\"><strong>Sugestão de Mitigação:</strong> {}</div>\n", suggestion));
-                }
-                html.push_str("</div>\n");
-            }
-        } else {
-            html.push_str("<p>Nenhuma violação detectada. O código está em conformidade com as regras verificadas.</p>\n");
+        match position(&target).or_else(|| position("")) {
+            Some(index) => obligations[index].failed = true,
+            None => obligations.push(Obligation {
+                rule_id: violation.rule_id.clone(),
+                severity: violation.severity.clone(),
+                jurisdiction: Jurisdiction::Generic,
+                category: DEFAULT_CATEGORY.to_string(),
+                subject: target,
+                failed: true,
+            }),
         }
-
-        html.push_str("</div>\n</body>\n</html>\n");
-        html
     }
 }
@@ -0,0 +1,79 @@
+//! Trechos do código-fonte exibidos junto das violações nos relatórios.
+//!
+//! O trecho de uma violação de prefixo é a função anotada inteira, com a linha da
+//! anotação e a linha da chamada problemática destacadas. Funções longas são
+//! encurtadas para as linhas ao redor das destacadas.
+
+use crate::ast::FunctionAst;
+use crate::models::{Snippet, SnippetLine};
+
+/// Maior trecho exibido inteiro.
+pub const MAX_LINES: usize = 40;
+/// Linhas mantidas antes e depois de cada linha destacada num trecho encurtado.
+pub const CONTEXT: usize = 2;
+
+/// Trecho das linhas `start..=end` (a partir de 1) de `source`, destacando
+/// `highlights`. `None` quando o intervalo está fora do arquivo.
+pub fn excerpt(source: &str, start: usize, end: usize, highlights: &[usize]) -> Option<Snippet> {
+    let start = start.max(1);
+    let source_lines: Vec<&str> = source.lines().collect();
+    let end = end.min(source_lines.len());
+    if start > end {
+        return None;
+    }
+    let shortened = end - start + 1 > MAX_LINES;
+    let near = |number: usize| {
+        number < start + CONTEXT
+            || number == end
+            || highlights
+                .iter()
+                .any(|&line| number.abs_diff(line) <= CONTEXT)
+    };
+    let lines = (start..=end)
+        .filter(|&number| !shortened || near(number))
+        .map(|number| SnippetLine {
+            number,
+            text: source_lines[number - 1].trim_end().to_string(),
+            highlight: highlights.contains(&number),
+        })
+        .collect();
+    Some(Snippet { lines })
+}
+
+/// Trecho da função anotada, destacando a anotação `prefix` e, se houver, a linha
+/// `call_line` da chamada problemática.
+pub fn function(
+    source: &str,
+    func: &FunctionAst,
+    prefix: &str,
+    call_line: Option<usize>,
+) -> Option<Snippet> {
+    let highlights: Vec<usize> = annotation_line(source, func, prefix)
+        .into_iter()
+        .chain(call_line)
+        .collect();
+    excerpt(source, func.line, func.end_line, &highlights)
+}
+
+/// Trecho ao redor da linha `line` (até `end_line`), para violações que não vêm
+/// de uma anotação, como as das regras personalizadas.
+pub fn around(source: &str, line: usize, end_line: Option<usize>) -> Option<Snippet> {
+    let end = end_line.unwrap_or(line).max(line);
+    excerpt(source, line.saturating_sub(CONTEXT), end + CONTEXT, &[line])
+}
+
+/// Linha do comentário `/// PREFIX: ...` da função.
+fn annotation_line(source: &str, func: &FunctionAst, prefix: &str) -> Option<usize> {
+    source
+        .lines()
+        .enumerate()
+        .skip(func.line.saturating_sub(1))
+        .take(func.end_line.saturating_sub(func.line) + 1)
+        .find(|(_, text)| {
+            text.trim_start()
+                .strip_prefix("///")
+                .and_then(|doc| doc.trim_start().strip_prefix(prefix))
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+        })
+        .map(|(index, _)| index + 1)
+}
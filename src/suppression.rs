@@ -0,0 +1,109 @@
+//! Supressões de violações.
+//!
+//! Uma função pode dispensar regras específicas com um doc comment:
+//!
+//! ```text
+//! /// hubstry:allow(ECA.AGE.VERIFY.1, LGPD.CONSENT.REQUIRED.1) reason = "fluxo legado" approver = "dpo@empresa.com"
+//! ```
+//!
+//! No modo estrito, supressões sem `approver` não são aceitas e a violação
+//! continua ativa.
+
+use crate::ast::{FileAst, FunctionAst};
+use crate::i18n::Locale;
+use crate::models::{ComplianceViolation, Suppression};
+use crate::tr;
+use regex::Regex;
+
+/// Início de um comentário de supressão.
+pub const MARKER: &str = "hubstry:allow";
+
+/// Interpreta um doc comment de supressão; `None` se não for um.
+pub fn parse(comment: &str) -> Option<Suppression> {
+    let rest = comment.trim().strip_prefix(MARKER)?.trim_start();
+    let rest = rest.strip_prefix('(')?;
+    let (ids, options) = rest.split_once(')')?;
+    let rule_ids: Vec<String> = ids
+        .split(',')
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect();
+    if rule_ids.is_empty() {
+        return None;
+    }
+
+    let option = Regex::new(r#"(\w+)\s*=\s*"([^"]*)""#).expect("padrão válido");
+    let mut suppression = Suppression {
+        rule_ids,
+        reason: None,
+        approver: None,
+    };
+    for caps in option.captures_iter(options) {
+        let value = Some(caps[2].trim().to_string()).filter(|v| !v.is_empty());
+        match &caps[1] {
+            "reason" => suppression.reason = value,
+            "approver" => suppression.approver = value,
+            _ => {}
+        }
+    }
+    Some(suppression)
+}
+
+/// Supressões declaradas em uma função.
+pub fn of_function(func: &FunctionAst) -> Vec<Suppression> {
+    func.doc_comments.iter().filter_map(|c| parse(c)).collect()
+}
+
+/// Separa as violações de um arquivo em ativas e suprimidas. Devolve também um aviso
+/// para cada supressão recusada no modo estrito, no idioma `locale`.
+pub fn apply(
+    file: &FileAst,
+    violations: Vec<ComplianceViolation>,
+    strict: bool,
+    locale: Locale,
+) -> (
+    Vec<ComplianceViolation>,
+    Vec<ComplianceViolation>,
+    Vec<String>,
+) {
+    let mut active = Vec::new();
+    let mut suppressed = Vec::new();
+    let mut warnings = Vec::new();
+
+    for mut violation in violations {
+        let func = file
+            .functions
+            .iter()
+            .find(|func| match &violation.function {
+                Some(name) => *name == func.name,
+                None => violation
+                    .line
+                    .is_some_and(|line| line >= func.line && line <= func.end_line),
+            });
+        let suppression = func.and_then(|func| {
+            of_function(func)
+                .into_iter()
+                .find(|s| s.rule_ids.contains(&violation.rule_id))
+                .map(|s| (func, s))
+        });
+
+        match suppression {
+            Some((func, suppression)) if strict && suppression.approver.is_none() => {
+                warnings.push(tr!(
+                    locale,
+                    "suppression.refused",
+                    rule_id = violation.rule_id,
+                    function = func.name
+                ));
+                active.push(violation);
+            }
+            Some((_, suppression)) => {
+                violation.suppression = Some(suppression);
+                suppressed.push(violation);
+            }
+            None => active.push(violation),
+        }
+    }
+
+    (active, suppressed, warnings)
+}
@@ -0,0 +1,43 @@
+// tests/common/mod.rs
+//
+// Helpers shared by the integration tests. Each test crate uses only part of them.
+#![allow(dead_code)]
+
+use hubstry_iso_code::models::{AnalysisResult, EngineConfig};
+use hubstry_iso_code::semantic_engine::SemanticEngine;
+use std::path::PathBuf;
+
+/// Creates an empty, unique scratch directory for a test.
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("hubstry-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("Failed to create scratch dir");
+    dir
+}
+
+/// Analyzes `code` as the single file `path`.
+pub fn analyze(path: &str, code: &str) -> AnalysisResult {
+    analyze_with(path, code, EngineConfig::default())
+}
+
+/// Analyzes `code` as the single file `path` with the given configuration.
+pub fn analyze_with(path: &str, code: &str, config: EngineConfig) -> AnalysisResult {
+    analyze_files(&SemanticEngine::new(config), &[(path, code)])
+}
+
+/// Analyzes the `(path, code)` files together with `engine`.
+pub fn analyze_files(engine: &SemanticEngine, files: &[(&str, &str)]) -> AnalysisResult {
+    let sources: Vec<(String, String)> = files
+        .iter()
+        .map(|(path, code)| (path.to_string(), code.to_string()))
+        .collect();
+    engine.analyze_sources(&sources).unwrap()
+}
+
+/// The default configuration, in strict mode or not.
+pub fn strict_config(strict: bool) -> EngineConfig {
+    EngineConfig {
+        strict_mode: strict,
+        ..EngineConfig::default()
+    }
+}
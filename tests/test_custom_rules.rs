@@ -0,0 +1,242 @@
+// tests/test_custom_rules.rs
+
+mod common;
+
+use common::scratch_dir;
+use hubstry_iso_code::ast::FileAst;
+use hubstry_iso_code::models::{
+    ComplianceRule, ComplianceViolation, EngineConfig, MatchMode, PatternTarget, RuleScope,
+    RuleSeverity,
+};
+use hubstry_iso_code::project_config::ProjectConfig;
+use hubstry_iso_code::semantic_engine::SemanticEngine;
+
+const BILLING: &str = r#"
+/// LGPD.DATA.COLLECTION: stores the customer's document.
+fn store_customer(cpf: &str) {
+    log_cpf(cpf);
+    db.insert("customers.cpf", cpf);
+}
+
+fn export_report() {
+    let query = "SELECT * FROM customers";
+    run(query);
+}
+"#;
+
+const AUDIT: &str = r#"
+fn audit() {
+    write_audit_trail();
+}
+"#;
+
+fn rule(
+    id: &str,
+    pattern: &str,
+    target: PatternTarget,
+    scope: RuleScope,
+    mode: MatchMode,
+) -> ComplianceRule {
+    ComplianceRule {
+        id: id.to_string(),
+        jurisdiction: hubstry_iso_code::models::Jurisdiction::Generic,
+        severity: RuleSeverity::High,
+        description: format!("{} violated.", id),
+        validation_pattern: Some(pattern.to_string()),
+        remediation_hint: Some(format!("Fix {}.", id)),
+        target,
+        scope,
+        mode,
+        prefix: None,
+        category: None,
+        citations: Vec::new(),
+    }
+}
+
+fn run(rules: Vec<ComplianceRule>, files: &[(&str, &str)]) -> Vec<ComplianceViolation> {
+    let config = EngineConfig {
+        custom_rules: rules,
+        ..EngineConfig::default()
+    };
+    let engine = SemanticEngine::new(config);
+    let files: Vec<FileAst> = files
+        .iter()
+        .map(|(path, code)| SemanticEngine::load_file(path, code).unwrap())
+        .collect();
+    engine.analyze_project(&files).unwrap().violations
+}
+
+#[test]
+fn test_forbidden_call_carries_severity_hint_and_location() {
+    let violations = run(
+        vec![rule(
+            "ACME.LOG",
+            "_cpf$",
+            PatternTarget::CallNames,
+            RuleScope::Function,
+            MatchMode::Forbid,
+        )],
+        &[("src/billing.rs", BILLING)],
+    );
+    assert_eq!(violations.len(), 1);
+    let v = &violations[0];
+    assert_eq!(v.rule_id, "ACME.LOG");
+    assert_eq!(v.severity, RuleSeverity::High);
+    assert_eq!(v.suggestion.as_deref(), Some("Fix ACME.LOG."));
+    assert_eq!(v.file.as_deref(), Some("src/billing.rs"));
+    assert_eq!(v.function.as_deref(), Some("store_customer"));
+    assert_eq!(v.line, Some(4));
+}
+
+#[test]
+fn test_string_literals_and_source_text() {
+    let literals = run(
+        vec![rule(
+            "ACME.SQL",
+            "(?i)select \\*",
+            PatternTarget::StringLiterals,
+            RuleScope::File,
+            MatchMode::Forbid,
+        )],
+        &[("src/billing.rs", BILLING)],
+    );
+    assert_eq!(literals.len(), 1);
+    assert_eq!(literals[0].line, Some(9));
+    assert_eq!(literals[0].function, None);
+
+    // Doc comments are not string literals, but they are part of the source text.
+    let source = run(
+        vec![rule(
+            "ACME.DOC",
+            "customer's",
+            PatternTarget::SourceText,
+            RuleScope::File,
+            MatchMode::Forbid,
+        )],
+        &[("src/billing.rs", BILLING)],
+    );
+    assert_eq!(source.len(), 1);
+    assert_eq!(source[0].line, Some(2));
+    assert_eq!(source[0].column, Some(37));
+}
+
+#[test]
+fn test_require_by_prefix_and_by_project() {
+    let mut needs_consent = rule(
+        "ACME.CONSENT",
+        "consent",
+        PatternTarget::CallNames,
+        RuleScope::Function,
+        MatchMode::Require,
+    );
+    needs_consent.prefix = Some("LGPD.DATA.COLLECTION".to_string());
+    let audit_somewhere = rule(
+        "ACME.AUDIT",
+        "audit_trail",
+        PatternTarget::FunctionName,
+        RuleScope::Project,
+        MatchMode::Require,
+    );
+    let audit_calls = rule(
+        "ACME.AUDIT.CALL",
+        "audit_trail",
+        PatternTarget::CallNames,
+        RuleScope::Project,
+        MatchMode::Require,
+    );
+
+    let violations = run(
+        vec![needs_consent, audit_somewhere, audit_calls],
+        &[("src/billing.rs", BILLING), ("src/audit.rs", AUDIT)],
+    );
+    let ids: Vec<&str> = violations.iter().map(|v| v.rule_id.as_str()).collect();
+    // Only the annotated function is checked for consent, and the audit trail is
+    // called (but no function is named after it) somewhere in the project.
+    assert_eq!(ids, vec!["ACME.CONSENT", "ACME.AUDIT"]);
+    assert_eq!(violations[0].function.as_deref(), Some("store_customer"));
+    assert_eq!(violations[1].file, None);
+}
+
+#[test]
+fn test_project_rule_violations_can_be_suppressed() {
+    let forbid_sql = || {
+        rule(
+            "ACME.SQL",
+            "(?i)select \\*",
+            PatternTarget::StringLiterals,
+            RuleScope::Project,
+            MatchMode::Forbid,
+        )
+    };
+    let waived = BILLING.replace(
+        "fn export_report() {",
+        "/// hubstry:allow(ACME.SQL) reason = \"relatório interno\"\nfn export_report() {",
+    );
+    let config = EngineConfig {
+        custom_rules: vec![forbid_sql()],
+        ..EngineConfig::default()
+    };
+    let files = vec![
+        SemanticEngine::load_file("src/billing.rs", &waived).unwrap(),
+        SemanticEngine::load_file("src/audit.rs", AUDIT).unwrap(),
+    ];
+    let result = SemanticEngine::new(config).analyze_project(&files).unwrap();
+    assert!(result.violations.is_empty(), "{:?}", result.violations);
+    assert_eq!(result.suppressed.len(), 1);
+    assert_eq!(result.suppressed[0].file.as_deref(), Some("src/billing.rs"));
+    assert!(result.suppressed[0].snippet.is_some());
+
+    // Without the comment the violation stays active.
+    let violations = run(vec![forbid_sql()], &[("src/billing.rs", BILLING)]);
+    assert_eq!(violations.len(), 1);
+}
+
+#[test]
+fn test_invalid_pattern_is_a_rule_error() {
+    let engine = SemanticEngine::new(EngineConfig {
+        custom_rules: vec![rule(
+            "ACME.BAD",
+            "(",
+            PatternTarget::CallNames,
+            RuleScope::Function,
+            MatchMode::Forbid,
+        )],
+        ..EngineConfig::default()
+    });
+    let err = engine.analyze_generic(&FileAst::default()).unwrap_err();
+    assert_eq!(err.code(), "HUB-RULE-001");
+}
+
+#[test]
+fn test_project_config_is_discovered() {
+    let dir = scratch_dir("project-config");
+    std::fs::write(
+        dir.join("hubstry.yml"),
+        r#"
+custom_rules:
+  - id: "ACME.NO_PRINT"
+    severity: Low
+    description: "Do not print."
+    validation_pattern: "^println$"
+    remediation_hint: "Use the logger."
+"#,
+    )
+    .unwrap();
+    let nested = dir.join("src");
+    std::fs::create_dir_all(&nested).unwrap();
+
+    let config = ProjectConfig::discover(&nested).unwrap();
+    assert_eq!(config.path, Some(dir.join("hubstry.yml")));
+    let rule = &config.custom_rules[0];
+    assert_eq!(rule.target, PatternTarget::CallNames);
+    assert_eq!(rule.scope, RuleScope::Function);
+    assert_eq!(rule.mode, MatchMode::Forbid);
+
+    std::fs::write(
+        dir.join("hubstry.yml"),
+        "custom_rules:\n  - { id: X, severity: Low, description: d }\n",
+    )
+    .unwrap();
+    let err = ProjectConfig::discover(&nested).unwrap_err();
+    assert_eq!(err.code(), "HUB-RULE-001");
+}
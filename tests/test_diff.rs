@@ -0,0 +1,133 @@
+// tests/test_diff.rs
+
+mod common;
+
+use common::{analyze, scratch_dir};
+use hubstry_iso_code::diff;
+use hubstry_iso_code::i18n::Locale;
+
+const BEFORE: &str = r#"
+/// ECA.AD.NO_TARGETING: banner.
+fn banner() { track_user(); }
+
+/// ECA.AD.NO_TARGETING: sidebar.
+fn sidebar() { track_user(); }
+"#;
+
+/// Desloca o código, corrige `sidebar` e adiciona uma loot box sem verificação.
+fn after() -> String {
+    let moved = format!("\n\n\n{}", BEFORE).replace(
+        "fn sidebar() { track_user(); }",
+        "fn sidebar() { serve_generic_ads(); }",
+    );
+    moved + "\n/// ECA.LOOTBOX.BLOCK: shop.\nfn shop() { open_lootbox(); }\n"
+}
+
+#[test]
+fn test_moved_violation_is_unchanged() {
+    let old = analyze("src/ads.rs", BEFORE);
+    let new = analyze("src/ads.rs", &format!("\n\n{}", BEFORE));
+    let diff = diff::diff(&old, &new);
+
+    assert!(diff.new.is_empty());
+    assert!(diff.fixed.is_empty());
+    assert_eq!(diff.unchanged.len(), 2);
+    assert_ne!(diff.unchanged[0].line, old.violations[0].line);
+    assert_eq!(diff.score.delta, Some(0.0));
+    assert!(!diff.has_regressions());
+
+    // Reescrever a mensagem da regra (ex.: numa nova versão do pacote) não muda a
+    // impressão digital.
+    let mut reworded = old.clone();
+    for violation in &mut reworded.violations {
+        violation.message = format!("{} (nova redação)", violation.message);
+    }
+    let diff = diff::diff(&old, &reworded);
+    assert!(diff.new.is_empty() && diff.fixed.is_empty());
+}
+
+#[test]
+fn test_new_and_fixed_violations_with_score_deltas() {
+    let diff = diff::diff(
+        &analyze("src/ads.rs", BEFORE),
+        &analyze("src/ads.rs", &after()),
+    );
+
+    assert_eq!(diff.new.len(), 1);
+    assert_eq!(diff.new[0].rule_id, "ECA.LOOTBOX.BLOCK.1");
+    assert_eq!(diff.fixed.len(), 1);
+    assert_eq!(diff.fixed[0].function.as_deref(), Some("sidebar"));
+    assert_eq!(diff.unchanged.len(), 1);
+    assert!(diff.has_regressions());
+
+    assert_eq!(diff.score.before, Some(0.0));
+    assert_eq!(diff.score.delta, Some(33.33));
+    assert_eq!(diff.by_category["ads"].delta, Some(50.0));
+    assert_eq!(diff.by_category["lootbox"].before, None);
+    assert_eq!(diff.by_category["lootbox"].delta, None);
+    assert_eq!(diff.by_jurisdiction["ECA Digital"].after, Some(33.33));
+
+    let text = diff.to_text(Locale::En);
+    assert!(
+        text.contains("Compliance Score: 0.0% -> 33.3% (+33.3)"),
+        "{}",
+        text
+    );
+    assert!(text.contains("New: 1  Fixed: 1  Suppressed: 0  Unchanged: 1"));
+    assert!(text.contains("[HIGH] ECA.LOOTBOX.BLOCK.1 at src/ads.rs:"));
+
+    let markdown = diff.to_markdown(Locale::En);
+    assert!(markdown.starts_with("## ❌ Compliance diff"));
+    assert!(markdown.contains("| category | ads | 0.0% | 50.0% | +50.0 |"));
+    assert!(markdown.contains("### Fixed violations (1)"));
+}
+
+#[test]
+fn test_suppressed_violation_is_not_fixed() {
+    let waived = BEFORE.replace(
+        "/// ECA.AD.NO_TARGETING: sidebar.",
+        "/// ECA.AD.NO_TARGETING: sidebar.\n/// hubstry:allow(ECA.AD.NO_TARGETING.1) reason = \"campanha encerrada\"",
+    );
+    let diff = diff::diff(
+        &analyze("src/ads.rs", BEFORE),
+        &analyze("src/ads.rs", &waived),
+    );
+
+    assert!(diff.fixed.is_empty());
+    assert_eq!(diff.suppressed.len(), 1);
+    assert_eq!(diff.suppressed[0].function.as_deref(), Some("sidebar"));
+    assert!(diff
+        .to_markdown(Locale::En)
+        .starts_with("## ✅ Compliance diff"));
+}
+
+#[test]
+fn test_load_report_round_trip() {
+    let dir = scratch_dir("diff");
+    let old = analyze("src/ads.rs", BEFORE);
+    let new = analyze("src/ads.rs", &after());
+
+    let json = dir.join("old.json");
+    std::fs::write(&json, serde_json::to_string_pretty(&old).unwrap()).unwrap();
+    let yaml = dir.join("new.yaml");
+    std::fs::write(&yaml, serde_yaml::to_string(&new).unwrap()).unwrap();
+
+    let loaded = diff::diff(
+        &diff::load_report(&json).unwrap(),
+        &diff::load_report(&yaml).unwrap(),
+    );
+    let direct = diff::diff(&old, &new);
+    assert_eq!(
+        serde_json::to_value(&loaded).unwrap(),
+        serde_json::to_value(&direct).unwrap()
+    );
+
+    let invalid = dir.join("report.json");
+    std::fs::write(&invalid, "Hubstry-ISO_Code Compliance Report").unwrap();
+    assert_eq!(
+        diff::load_report(&invalid).unwrap_err().code(),
+        "HUB-REPORT-004"
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
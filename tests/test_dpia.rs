@@ -0,0 +1,107 @@
+// tests/test_dpia.rs
+
+mod common;
+
+use common::analyze;
+use hubstry_iso_code::dpia::{Dpia, ProjectInfo};
+use hubstry_iso_code::i18n::Locale;
+
+const CODE: &str = r#"
+/// LGPD.DATA.COLLECTION: Sign-up form.
+fn register(name: &str) {
+    save_user_info(name);
+}
+
+/// LGPD.DATA.COLLECTION: Newsletter subscription.
+fn subscribe(email: &str) {
+    collect_personal_data(email);
+    anonymize_data(email);
+}
+
+/// LGPD.CONSENT.REQUIRED: Marketing e-mails.
+/// hubstry:allow(LGPD.CONSENT.REQUIRED.1) reason = "Legitimate interest" approver = "dpo@acme"
+fn send_campaign() {}
+"#;
+
+fn dpia() -> Dpia {
+    Dpia::from_analysis(
+        &analyze("src/users.rs", CODE),
+        ProjectInfo {
+            name: "Acme <Portal>".to_string(),
+            version: Some("abc123".to_string()),
+            date: "2026-10-18".to_string(),
+        },
+    )
+}
+
+#[test]
+fn test_checked_functions_record_data_and_safeguards() {
+    let result = analyze("src/users.rs", CODE);
+    let subscribe = result
+        .checked_functions
+        .iter()
+        .find(|f| f.name == "subscribe")
+        .unwrap();
+    assert_eq!(
+        subscribe.description.as_deref(),
+        Some("Newsletter subscription.")
+    );
+    assert_eq!(subscribe.data_collection, vec!["collect_personal_data"]);
+    assert_eq!(subscribe.safeguards, vec!["anonymize_data"]);
+}
+
+#[test]
+fn test_dpia_is_built_from_the_analysis() {
+    let dpia = dpia();
+    assert_eq!(dpia.operations.len(), 3);
+    assert_eq!(
+        dpia.data_categories.keys().collect::<Vec<_>>(),
+        vec!["collect_personal_data", "save_user_info"]
+    );
+    assert_eq!(dpia.safeguards["anonymize_data"], vec!["subscribe"]);
+    assert_eq!(dpia.risks.len(), 1);
+    assert_eq!(dpia.risks[0].rule_id, "LGPD.DATA.COLLECTION.1");
+    assert_eq!(dpia.accepted_risks.len(), 1);
+    let unprotected: Vec<&str> = dpia
+        .unprotected_operations()
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(unprotected, vec!["register"]);
+}
+
+#[test]
+fn test_markdown_follows_the_template_sections() {
+    let markdown = dpia().to_markdown(Locale::PtBr);
+    for section in [
+        "# Relatório de Impacto à Proteção de Dados Pessoais (RIPD)",
+        "## 1. Descrição da Operação de Tratamento",
+        "## 2. Necessidade e Proporcionalidade",
+        "## 3. Consulta às Partes Interessadas",
+        "## 4. Avaliação de Riscos aos Direitos e Liberdades",
+        "## 5. Medidas para Mitigação dos Riscos",
+        "## 6. Aprovação e Conclusão",
+    ] {
+        assert!(markdown.contains(section), "{}", section);
+    }
+    assert!(markdown.contains("- **Versão:** abc123"));
+    assert!(markdown.contains(
+        "| register | src/users.rs:2 | LGPD.DATA.COLLECTION | Sign-up form. | save_user_info | - |"
+    ));
+    assert!(markdown.contains("| anonymize_data | subscribe |"));
+    assert!(markdown.contains("| [A PREENCHER] | Médio | MEDIUM |"));
+    assert!(markdown.contains("| [LGPD.CONSENT.REQUIRED.1] send_campaign, src/users.rs:13 | Legitimate interest | dpo@acme |"));
+    assert!(markdown.contains("- **Finalidade:** [A PREENCHER]"));
+}
+
+#[test]
+fn test_html_is_escaped_and_localized() {
+    let html = dpia().to_html(Locale::En);
+    assert!(html.contains("<html lang=\"en\">"));
+    assert!(html.contains("<h1>Data Protection Impact Assessment (DPIA)</h1>"));
+    assert!(html.contains("Acme &lt;Portal&gt;"));
+    assert!(html.contains("<td>save_user_info</td><td>register</td>"));
+    assert!(html.contains(
+        "[TO BE COMPLETED] Functions that handle personal data with no safeguard found: register."
+    ));
+}
@@ -0,0 +1,238 @@
+// tests/test_fixes.rs
+
+mod common;
+
+use common::analyze;
+use hubstry_iso_code::fix::{self, PlannedFix};
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::models::{EngineConfig, OutputFormat, TextEdit};
+use hubstry_iso_code::report;
+use hubstry_iso_code::rules::{RulePack, RuleSet};
+use hubstry_iso_code::scanner::{ScanConfig, StaticDomScanner};
+use hubstry_iso_code::scoring::ScoringConfig;
+use hubstry_iso_code::semantic_engine::SemanticEngine;
+use std::path::Path;
+
+const GAME: &str = r#"
+/// ECA.LOOTBOX.BLOCK: Abertura de baús.
+fn open_reward_chest(player: &Player) -> Item {
+    open_box(player)
+}
+
+/// ECA.AD.NO_TARGETING: Anúncios.
+fn show_ads() { serve_ad(); }
+
+/// ECA.AGE.VERIFY: Conteúdo restrito.
+fn open_restricted() {
+}
+
+fn subscribe(email: &str) {
+    add_to_mailing_list(email);
+}
+"#;
+
+fn apply_all(source: &str, fixes: &[PlannedFix]) -> String {
+    let edits: Vec<TextEdit> = fixes.iter().flat_map(|fix| fix.edits.clone()).collect();
+    let (fixed, applied) = fix::apply(source, &edits);
+    assert_eq!(applied, edits.len());
+    fixed
+}
+
+fn scan(html: &str) -> Vec<PlannedFix> {
+    let config = ScanConfig {
+        max_pages: 1,
+        follow_links: false,
+        check_subpages: vec![],
+        rules: RuleSet::builtin(),
+        scoring: ScoringConfig::default(),
+        locale: Locale::En,
+    };
+    StaticDomScanner::new()
+        .scan_html("index.html", html, &config)
+        .violations
+        .iter()
+        .filter_map(PlannedFix::from_web_violation)
+        .collect()
+}
+
+#[test]
+fn test_guards_resolve_the_violations() {
+    let result = analyze("src/game.rs", GAME);
+    let fixes: Vec<PlannedFix> = result
+        .violations
+        .iter()
+        .filter_map(|violation| PlannedFix::from_violation(violation, Locale::En))
+        .collect();
+    assert_eq!(fixes.len(), 3);
+    assert_eq!(
+        fixes[0].description,
+        "Insert an age verification guard at the top of the function."
+    );
+
+    let fixed = apply_all(GAME, &fixes);
+    assert!(fixed.contains(
+        "fn open_reward_chest(player: &Player) -> Item {\n    verify_age(todo!(\"conta do jogador\"), 18)?;\n    open_box(player)\n}"
+    ));
+    assert!(fixed.contains("fn show_ads() { disable_targeted_ads(); serve_ad(); }"));
+    assert!(fixed.contains(
+        "fn open_restricted() {\n    verify_age(todo!(\"documento do usuário\"), 18)?;\n}"
+    ));
+    syn::parse_file(&fixed).unwrap();
+    assert!(analyze("src/game.rs", &fixed).violations.is_empty());
+
+    // O JSON leva as edições; o SARIF, um `fix` por resultado.
+    let json: serde_json::Value = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["violations"][0]["fix"]["edits"][0]["line"], 3);
+    let sarif: serde_json::Value = serde_json::from_str(
+        &report::reporter(OutputFormat::Sarif, Locale::En)
+            .render(&result)
+            .unwrap(),
+    )
+    .unwrap();
+    let change = &sarif["runs"][0]["results"][0]["fixes"][0]["artifactChanges"][0];
+    assert_eq!(change["artifactLocation"]["uri"], "src/game.rs");
+    assert_eq!(change["replacements"][0]["deletedRegion"]["startLine"], 3);
+    assert_eq!(
+        change["replacements"][0]["insertedContent"]["text"],
+        "\n    verify_age(todo!(\"conta do jogador\"), 18)?;"
+    );
+}
+
+#[test]
+fn test_inferred_functions_are_annotated() {
+    let source = GAME.replace('\n', "\r\n");
+    let engine = SemanticEngine::new(EngineConfig::default());
+    let files = vec![SemanticEngine::load_file("src/game.rs", &source).unwrap()];
+    let fixes = engine.annotation_fixes(&files, Locale::PtBr);
+    assert_eq!(fixes.len(), 1);
+    assert_eq!(fixes[0].rule_id, "LGPD.CONSENT.REQUIRED.1");
+    assert_eq!(fixes[0].function.as_deref(), Some("subscribe"));
+    assert_eq!(
+        fixes[0].description,
+        "Anotar a função com LGPD.CONSENT.REQUIRED"
+    );
+
+    let annotated = apply_all(&source, &fixes);
+    assert!(annotated.contains(
+        "\r\n/// LGPD.CONSENT.REQUIRED: Verificação de consentimento explícito do titular.\r\nfn subscribe"
+    ));
+    assert!(!annotated.replace("\r\n", "").contains('\n'));
+
+    // Anotada, a função passa a ser verificada e ganha a correção da regra.
+    let result = analyze("src/game.rs", &annotated);
+    let consent = result
+        .violations
+        .iter()
+        .find(|v| v.rule_id == "LGPD.CONSENT.REQUIRED.1")
+        .unwrap();
+    let fixed = apply_all(
+        &annotated,
+        &[PlannedFix::from_violation(consent, Locale::En).unwrap()],
+    );
+    assert!(fixed.contains(
+        "fn subscribe(email: &str) {\r\n    check_user_consent(todo!(\"titular dos dados\"))?;\r\n"
+    ));
+    let files = vec![SemanticEngine::load_file("src/game.rs", &fixed).unwrap()];
+    assert!(engine.annotation_fixes(&files, Locale::En).is_empty());
+}
+
+#[test]
+fn test_tracker_scripts_get_a_consent_gate() {
+    let html = "<head>\n  <script async src=\"https://www.googletagmanager.com/gtag/js?id=G-1\"></script>\n  <script src=\"/app.js\"></script>\n</head>\n";
+    let fixes = scan(html);
+    assert_eq!(fixes.len(), 1);
+    assert_eq!(fixes[0].rule_id, "ECA.DATA.RETENTION_BAN");
+    assert_eq!(fixes[0].file, "index.html");
+
+    let gated = apply_all(html, &fixes);
+    assert_eq!(
+        gated,
+        "<head>\n  <template data-consent=\"marketing\"><script async src=\"https://www.googletagmanager.com/gtag/js?id=G-1\"></script></template>\n  <script src=\"/app.js\"></script>\n</head>\n"
+    );
+    // O script bloqueado não é mais um rastreador ativo nem recebe outra correção.
+    assert!(scan(&gated).is_empty());
+    assert!(fix::consent_gate(&gated).is_empty());
+}
+
+#[test]
+fn test_diff_overlaps_and_invalid_fixes() {
+    let before = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\n";
+    let edits = [
+        TextEdit::insert(2, 1, "!"),
+        TextEdit::insert(2, 1, "!"),
+        TextEdit {
+            line: 11,
+            column: 0,
+            end_line: 11,
+            end_column: 1,
+            new_text: "K".to_string(),
+        },
+        TextEdit::insert(11, 1, "?"),
+        TextEdit {
+            line: 10,
+            column: 0,
+            end_line: 11,
+            end_column: 1,
+            new_text: "x".to_string(),
+        },
+    ];
+    let (after, applied) = fix::apply(before, &edits);
+    // A repetida e a que se sobrepõe à troca da linha 11 são ignoradas.
+    assert_eq!(applied, 3);
+    assert_eq!(after, "a\nb!\nc\nd\ne\nf\ng\nh\ni\nj\nK?\nl\n");
+    assert_eq!(
+        fix::unified_diff("src/lib.rs", before, &after),
+        "--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+         @@ -1,5 +1,5 @@\n a\n-b\n+b!\n c\n d\n e\n\
+         @@ -8,5 +8,5 @@\n h\n i\n j\n-k\n+K?\n l\n"
+    );
+    assert_eq!(fix::unified_diff("src/lib.rs", before, before), "");
+
+    let pack = |fix: &str| {
+        let yaml = format!(
+            r#"
+pack:
+  name: acme
+  version: 1.0.0
+  effective_date: 2026-01-01
+  authority: ACME
+jurisdiction: Lgpd
+rules:
+  - id: "ACME.CONSENT.1"
+    prefix: "LGPD.CONSENT.REQUIRED"
+    severity: High
+    message: "Sem consentimento."
+{}
+"#,
+            fix
+        );
+        RulePack::parse(&yaml, Path::new("acme.yml"))
+    };
+    let valid = pack("    fix: { description: Consent., guard: 'consent()?;' }").unwrap();
+    assert_eq!(
+        valid.rules[0].fix.as_ref().unwrap().guard.as_deref(),
+        Some("consent()?;")
+    );
+    let err = pack("    fix: { description: Consent., guard: 'consent(' }").unwrap_err();
+    assert_eq!(err.code(), "HUB-RULE-001");
+    assert!(pack("    fix: { description: Consent. }").is_err());
+
+    let web = RulePack::parse(
+        r#"
+pack: { name: acme, version: 1.0.0, effective_date: 2026-01-01, authority: ACME }
+jurisdiction: Eca
+web_rules:
+  - id: "ACME.LOOTBOX"
+    detector: lootbox
+    severity: High
+    business_description: "b"
+    technical_description: "t"
+    remediation_business: "rb"
+    remediation_technical: "rt"
+    fix: consent_gate
+"#,
+        Path::new("acme.yml"),
+    )
+    .unwrap_err();
+    assert!(web.to_string().contains("ad_trackers"), "{}", web);
+}
@@ -0,0 +1,144 @@
+// tests/test_graph.rs
+
+use hubstry_iso_code::graph::{ComplianceGraph, GraphFormat, Role};
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::models::EngineConfig;
+use hubstry_iso_code::semantic_engine::SemanticEngine;
+
+const USERS: &str = r#"
+/// ECA.PARENT.CONSENT: Child profile.
+fn register_child(name: &str) {
+    if get_parental_consent() {
+        save_profile(name);
+    }
+}
+
+/// ECA.PARENT.CONSENT: Missing consent.
+fn quick_register(name: &str) {
+    save_profile(name);
+}
+
+fn save_profile(name: &str) {
+    store(name);
+}
+"#;
+
+const ADS: &str = r#"
+/// ECA.AGE.VERIFY: Age gate.
+fn gate() {
+    verify_age(18);
+    show_banner();
+}
+
+fn show_banner() {
+    serve_generic_ads();
+}
+"#;
+
+fn graph(strict: bool) -> ComplianceGraph {
+    let engine = SemanticEngine::new(EngineConfig {
+        strict_mode: strict,
+        ..EngineConfig::default()
+    });
+    let sources = [
+        ("src/users.rs".to_string(), USERS.to_string()),
+        ("src/ads.rs".to_string(), ADS.to_string()),
+    ];
+    let (files, skipped) = engine.load_sources(&sources).unwrap();
+    assert!(skipped.is_empty());
+    let result = engine.analyze_project(&files).unwrap();
+    engine.compliance_graph(&files, &result.violations)
+}
+
+#[test]
+fn test_roles_and_violating_path() {
+    let graph = graph(false);
+    let role = |name: &str| graph.nodes[name].role;
+    assert_eq!(role("register_child"), Role::Annotated);
+    assert_eq!(role("get_parental_consent"), Role::Consent);
+    assert_eq!(role("verify_age"), Role::AgeCheck);
+    assert_eq!(role("serve_generic_ads"), Role::Ad);
+    assert_eq!(role("save_profile"), Role::DataCollection);
+    assert_eq!(role("show_banner"), Role::Other);
+    assert_eq!(
+        graph.nodes["register_child"].prefixes,
+        vec!["ECA.PARENT.CONSENT"]
+    );
+    assert!(graph.nodes["save_profile"].defined);
+    assert!(!graph.nodes["store"].defined);
+
+    // Só `quick_register` viola a regra de consentimento parental.
+    assert!(graph.nodes["quick_register"].violating);
+    assert!(!graph.nodes["register_child"].violating);
+    assert!(graph
+        .violating_edges
+        .contains(&("quick_register".to_string(), "save_profile".to_string())));
+    assert!(!graph
+        .violating_edges
+        .contains(&("register_child".to_string(), "save_profile".to_string())));
+}
+
+#[test]
+fn test_strict_mode_matches_exact_names() {
+    let graph = graph(true);
+    assert_eq!(graph.nodes["save_profile"].role, Role::Other);
+    assert_eq!(graph.nodes["store"].role, Role::DataCollection);
+}
+
+#[test]
+fn test_subgraph_filters() {
+    let graph = graph(false);
+    let from = graph.reachable_from(&["show_banner".to_string()]);
+    assert_eq!(
+        from.nodes.keys().collect::<Vec<_>>(),
+        vec!["serve_generic_ads", "show_banner"]
+    );
+    assert_eq!(from.edges.len(), 1);
+
+    let mut roots = graph.annotated_with("ECA.AGE");
+    assert_eq!(roots, vec!["gate"]);
+    roots = graph.annotated_with("ECA");
+    assert_eq!(roots, vec!["gate", "quick_register", "register_child"]);
+    assert!(graph.annotated_with("ECA.AG").is_empty());
+
+    let consent = graph.reachable_from(&graph.annotated_with("ECA.PARENT.CONSENT"));
+    assert!(consent.nodes.contains_key("store"));
+    assert!(!consent.nodes.contains_key("gate"));
+    assert_eq!(consent.violating_edges.len(), 1);
+
+    assert!(graph
+        .reachable_from(&["missing".to_string()])
+        .nodes
+        .is_empty());
+}
+
+#[test]
+fn test_dot_and_mermaid_output() {
+    let graph = graph(false).reachable_from(&["quick_register".to_string()]);
+
+    let dot = graph.render(GraphFormat::Dot, Locale::En);
+    assert!(dot.starts_with("digraph compliance {\n"));
+    assert!(dot.contains(
+        "\"quick_register\" [label=\"quick_register\\nECA.PARENT.CONSENT\", fillcolor=\"#bee3f8\", color=\"#e53e3e\", penwidth=2.5];"
+    ));
+    assert!(
+        dot.contains("\"quick_register\" -> \"save_profile\" [color=\"#e53e3e\", penwidth=2.5];")
+    );
+    assert!(dot.contains("\"save_profile\" -> \"store\";"));
+    assert!(dot.contains("style=\"rounded,filled,dashed\""));
+    assert!(dot.contains("label=\"Data collection\""));
+    assert!(dot.contains("label=\"Violation path\""));
+
+    let mermaid = graph.render(GraphFormat::Mermaid, Locale::Es);
+    assert!(mermaid.starts_with("flowchart LR\n"));
+    assert!(mermaid.contains("  n0[\"quick_register<br/>ECA.PARENT.CONSENT\"]\n"));
+    assert!(mermaid.contains("  n0 --> n1\n"));
+    assert!(mermaid.contains("  n1 -.-> n2\n"));
+    assert!(mermaid.contains("  subgraph legend[\"Leyenda\"]\n"));
+    assert!(mermaid.contains("  class n0 annotated\n"));
+    assert!(mermaid.contains("  linkStyle 0 stroke:#e53e3e,stroke-width:3px\n"));
+
+    assert_eq!("mermaid".parse(), Ok(GraphFormat::Mermaid));
+    assert_eq!("DOT".parse(), Ok(GraphFormat::Dot));
+    assert!("svg".parse::<GraphFormat>().is_err());
+}
@@ -0,0 +1,181 @@
+// tests/test_history.rs
+
+mod common;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use common::{analyze, scratch_dir};
+use hubstry_iso_code::history::{HistoryStore, RunKind};
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::models::OutputFormat;
+use hubstry_iso_code::report::{self, HtmlReporter, Reporter};
+use hubstry_iso_code::rules::RuleSet;
+use hubstry_iso_code::scanner::{ScanConfig, StaticDomScanner};
+use hubstry_iso_code::scoring::ScoringConfig;
+
+const ADS: &str = r#"
+/// ECA.AD.NO_TARGETING: banner.
+fn banner() { track_user(); }
+
+/// ECA.AD.NO_TARGETING: sidebar.
+fn sidebar() { track_user(); }
+"#;
+
+fn day(n: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 1, 9, 0, 0).unwrap() + Duration::days(n)
+}
+
+#[test]
+fn test_trend_time_to_fix_and_oldest_open() {
+    let dir = scratch_dir("trend");
+    let mut store = HistoryStore::open(&dir).unwrap();
+    assert!(store.path().ends_with("history.db"));
+
+    let fixed = ADS.replace(
+        "fn sidebar() { track_user(); }",
+        "fn sidebar() { serve_generic_ads(); }",
+    );
+    store
+        .record_analysis("src", &analyze("src/ads.rs", ADS), day(0))
+        .unwrap();
+    store
+        .record_analysis("src", &analyze("src/ads.rs", ADS), day(1))
+        .unwrap();
+    // `sidebar` corrigida três dias depois da primeira aparição.
+    store
+        .record_analysis("src", &analyze("src/ads.rs", &fixed), day(3))
+        .unwrap();
+    // Outro alvo forma outra série.
+    store
+        .record_analysis("lib", &analyze("src/ads.rs", ADS), day(2))
+        .unwrap();
+
+    let trend = store.trend(RunKind::Analysis, "src").unwrap();
+    let scores: Vec<f64> = trend.iter().map(|run| run.score).collect();
+    assert_eq!(scores, vec![0.0, 0.0, 50.0]);
+    assert_eq!(trend[2].by_jurisdiction["ECA Digital"], 50.0);
+    assert_eq!(trend[2].violations, 1);
+
+    // Reabrir o banco preserva as execuções.
+    drop(store);
+    let report = HistoryStore::open(&dir).unwrap().report(10).unwrap();
+    assert_eq!(report.runs.len(), 4);
+
+    assert_eq!(report.time_to_fix.len(), 1);
+    assert_eq!(report.time_to_fix[0].rule_id, "ECA.AD.NO_TARGETING.1");
+    assert_eq!(report.time_to_fix[0].fixed, 1);
+    assert_eq!(report.time_to_fix[0].mean_hours, 72.0);
+
+    // `banner` em `src` (aberta desde o dia 0) e as duas de `lib` (desde o dia 2).
+    assert_eq!(report.oldest_open.len(), 3);
+    let oldest = &report.oldest_open[0];
+    assert_eq!(oldest.target, "src");
+    assert_eq!(oldest.location, "src/ads.rs::banner");
+    assert_eq!(oldest.first_seen, day(0));
+    assert_eq!(oldest.open_days, 3);
+    assert_eq!(
+        HistoryStore::open(&dir)
+            .unwrap()
+            .report(1)
+            .unwrap()
+            .oldest_open
+            .len(),
+        1
+    );
+
+    let text = report.to_text(Locale::En);
+    assert!(
+        text.contains("Score trend: analysis src (3 runs)"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("ECA.AD.NO_TARGETING.1        3.0d (1 fixed)"),
+        "{}",
+        text
+    );
+    assert!(text.contains("[HIGH] ECA.AD.NO_TARGETING.1 at src/ads.rs::banner"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_reappearing_violation_starts_a_new_streak() {
+    let dir = scratch_dir("streak");
+    let mut store = HistoryStore::open(&dir).unwrap();
+    let clean = ADS.replace("track_user", "serve_generic_ads");
+
+    store
+        .record_analysis("src", &analyze("src/ads.rs", ADS), day(0))
+        .unwrap();
+    store
+        .record_analysis("src", &analyze("src/ads.rs", &clean), day(1))
+        .unwrap();
+    store
+        .record_analysis("src", &analyze("src/ads.rs", ADS), day(5))
+        .unwrap();
+
+    let report = store.report(10).unwrap();
+    assert_eq!(report.time_to_fix[0].fixed, 2);
+    assert_eq!(report.time_to_fix[0].mean_hours, 24.0);
+    assert!(report.oldest_open.iter().all(|v| v.first_seen == day(5)));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_web_scans_are_recorded() {
+    let dir = scratch_dir("web");
+    let mut store = HistoryStore::open(&dir).unwrap();
+    let html = std::fs::read_to_string("examples/web/non_compliant_site.html").unwrap();
+    let config = ScanConfig {
+        max_pages: 1,
+        follow_links: false,
+        check_subpages: vec![],
+        rules: RuleSet::builtin(),
+        scoring: ScoringConfig::default(),
+        locale: Locale::default(),
+    };
+    let result = StaticDomScanner::new().scan_html("https://example.com", &html, &config);
+    store.record_web_scan(&result, day(0)).unwrap();
+
+    let trend = store
+        .trend(RunKind::WebScan, "https://example.com")
+        .unwrap();
+    assert_eq!(trend.len(), 1);
+    assert_eq!(trend[0].score, result.score);
+    assert_eq!(trend[0].violations, result.violations.len());
+    assert!(store
+        .trend(RunKind::Analysis, "https://example.com")
+        .unwrap()
+        .is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_html_report_has_trend_chart() {
+    let dir = scratch_dir("html");
+    let mut store = HistoryStore::open(&dir).unwrap();
+    let result = analyze("src/ads.rs", ADS);
+    store.record_analysis("src", &result, day(0)).unwrap();
+
+    let single =
+        HtmlReporter::new(Locale::PtBr).with_trend(store.trend(RunKind::Analysis, "src").unwrap());
+    assert!(!single.render(&result).unwrap().contains("<svg"));
+
+    store.record_analysis("src", &result, day(7)).unwrap();
+    let html = HtmlReporter::new(Locale::PtBr)
+        .with_trend(store.trend(RunKind::Analysis, "src").unwrap())
+        .render(&result)
+        .unwrap();
+    assert!(html.contains("Evolução do Score"));
+    assert!(html.contains("<polyline points="));
+    assert!(html.contains("08/01/2026: 0.0%"));
+
+    let plain = report::reporter(OutputFormat::Html, Locale::PtBr)
+        .render(&result)
+        .unwrap();
+    assert!(!plain.contains("<svg"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
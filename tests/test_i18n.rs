@@ -0,0 +1,180 @@
+// tests/test_i18n.rs
+
+mod common;
+
+use common::{analyze_with, scratch_dir, strict_config};
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::models::OutputFormat;
+use hubstry_iso_code::report;
+use hubstry_iso_code::rules::RuleSet;
+use hubstry_iso_code::scanner::{ScanConfig, StaticDomScanner};
+use hubstry_iso_code::scoring::ScoringConfig;
+use std::process::Command;
+
+const CODE: &str = r#"
+/// ECA.AD.NO_TARGETING: banner.
+fn banner() { track_user(); }
+
+/// ECA.AGE.VERIFI: typo in the prefix.
+fn check() {}
+"#;
+
+#[test]
+fn test_every_catalog_has_every_key() {
+    for locale in Locale::ALL {
+        assert_eq!(locale.missing_keys(), Vec::<String>::new(), "{}", locale);
+    }
+}
+
+#[test]
+fn test_locale_names() {
+    assert_eq!("pt-BR".parse(), Ok(Locale::PtBr));
+    assert_eq!("pt_br".parse(), Ok(Locale::PtBr));
+    assert_eq!("EN-us".parse(), Ok(Locale::En));
+    assert_eq!("es".parse(), Ok(Locale::Es));
+    assert!("fr"
+        .parse::<Locale>()
+        .unwrap_err()
+        .to_string()
+        .contains("'fr'"));
+    assert_eq!(Locale::default(), Locale::PtBr);
+    for locale in Locale::ALL {
+        assert_eq!(locale.to_string().parse(), Ok(locale));
+    }
+}
+
+#[test]
+fn test_same_result_in_each_locale() {
+    let result = analyze_with("src/ads.rs", CODE, strict_config(true));
+    let render = |locale| {
+        report::reporter(OutputFormat::PlainText, locale)
+            .render(&result)
+            .unwrap()
+    };
+
+    let pt = render(Locale::PtBr);
+    assert!(pt.contains("Score de Conformidade: "), "{}", pt);
+    assert!(pt.contains("A função parece exibir anúncios"));
+    assert!(pt.contains("O prefixo 'ECA.AGE.VERIFI' não está definido"));
+
+    let en = render(Locale::En);
+    assert!(en.contains("Compliance Score: "));
+    assert!(en.contains("Function appears to serve ads"));
+    assert!(en.contains("Prefix 'ECA.AGE.VERIFI' is not defined"));
+
+    let es = render(Locale::Es);
+    assert!(es.contains("Puntuación de Cumplimiento: "));
+    assert!(es.contains("La función parece mostrar anuncios"));
+    assert!(es.contains("Línea 2, Columna 0"));
+
+    // The violations keep the rule pack text, so JSON reports and fingerprints do
+    // not depend on the locale.
+    assert!(result.violations[0]
+        .message
+        .starts_with("Function appears to serve ads"));
+    let json = report::reporter(OutputFormat::Json, Locale::Es)
+        .render(&result)
+        .unwrap();
+    assert!(!json.contains("La función"));
+}
+
+#[test]
+fn test_rules_without_translation_keep_the_pack_text() {
+    assert_eq!(Locale::Es.rule_text("ACME.NO_PRINT", "message"), None);
+    assert_eq!(
+        Locale::En.rule_text("ECA.AD.NO_TARGETING.1", "message"),
+        Some("Function appears to serve ads without explicitly serving generic, non-targeted ads.")
+    );
+    assert_eq!(Locale::Es.text("no.such.key", &[]), "no.such.key");
+}
+
+#[test]
+fn test_web_scan_in_each_locale() {
+    let html = std::fs::read_to_string("examples/web/non_compliant_site.html").unwrap();
+    let scan = |locale| {
+        let config = ScanConfig {
+            max_pages: 1,
+            follow_links: false,
+            check_subpages: vec![],
+            rules: RuleSet::builtin(),
+            scoring: ScoringConfig::default(),
+            locale,
+        };
+        StaticDomScanner::new().scan_html("https://example.com", &html, &config)
+    };
+    let pt = scan(Locale::PtBr);
+    let en = scan(Locale::En);
+
+    let find = |result: &hubstry_iso_code::scanner::WebScanResult| {
+        result
+            .violations
+            .iter()
+            .find(|v| v.rule_id == "ECA.AGE.SELF_DECLARATION_BAN")
+            .cloned()
+            .unwrap()
+    };
+    let (pt_violation, en_violation) = (find(&pt), find(&en));
+    assert_eq!(
+        pt_violation.business_description,
+        "Autodeclaração de idade localizada na página."
+    );
+    assert_eq!(
+        en_violation.business_description,
+        "Self-declared age check found on the page."
+    );
+    assert!(en_violation
+        .technical_description
+        .starts_with("Restricted elements found: ["));
+    assert_eq!(pt_violation.fingerprint(), en_violation.fingerprint());
+    assert_eq!(pt.score, en.score);
+
+    let pdf = report::web_scan_pdf(&en, Locale::En);
+    assert!(pdf.contains("(Legal Notice)"));
+}
+
+#[test]
+fn test_cli_warnings_follow_the_locale() {
+    let dir = scratch_dir("i18n-cli");
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(dir.join("src/broken.rs"), "fn broken( {\n").unwrap();
+    std::fs::write(dir.join("src/ok.rs"), "fn ok() {}\n").unwrap();
+    std::fs::write(
+        dir.join("prefixes.yml"),
+        r#"jurisdictions:
+  GENERIC:
+    - prefix: "ACME.NOTHING"
+      description: "No rules."
+      standard: "Generic"
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_hubstry_iso_code"))
+        .current_dir(&dir)
+        .args(["--locale", "en", "--prefixes", "prefixes.yml"])
+        .args(["analyze", "--dir", "src", "--no-history"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("prefixes.yml:3: warning [no-validator]"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("file skipped [HUB-PARSE-001]: failed to parse the source file"));
+    for portuguese in ["aviso", "arquivo", "ignorado", "falha", "não", "ção"] {
+        assert!(!stderr.contains(portuguese), "{}: {}", portuguese, stderr);
+    }
+
+    // In strict mode the broken file ends the run, with the error in the requested locale.
+    let output = Command::new(env!("CARGO_BIN_EXE_hubstry_iso_code"))
+        .current_dir(&dir)
+        .args(["--locale", "en", "--prefixes", "prefixes.yml"])
+        .args(["analyze", "--dir", "src", "--no-history", "--strict"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Error [HUB-PARSE-001]: failed to parse the source file"));
+    assert!(!stderr.contains("falha"), "{}", stderr);
+}
@@ -0,0 +1,117 @@
+// tests/test_inventory.rs
+
+mod common;
+
+use common::{analyze_files, strict_config};
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::inventory::{self, Inventory, ProcessingDeclaration};
+use hubstry_iso_code::models::OutputFormat;
+use hubstry_iso_code::report;
+use hubstry_iso_code::semantic_engine::SemanticEngine;
+
+const USERS: &str = r#"
+/// LGPD.DATA.COLLECTION: Student sign-up, "class A".
+/// hubstry:processing legal_basis = "consent (art. 7, I)" purpose = "enrollment"
+fn register(name: &str) {
+    save_user_info(name);
+    anonymize_data(name);
+}
+
+fn helper() {}
+"#;
+
+const GAMES: &str = r#"
+/// ECA.AGE.VERIFY: Age gate.
+fn check_age() {
+    verify_age(user, 18);
+}
+"#;
+
+const FILES: &[(&str, &str)] = &[("src/users.rs", USERS), ("src/games.rs", GAMES)];
+
+#[test]
+fn test_parse_processing_declaration() {
+    assert_eq!(
+        inventory::parse(r#"hubstry:processing purpose = "billing" legal_basis = "contract""#),
+        Some(ProcessingDeclaration {
+            legal_basis: Some("contract".to_string()),
+            purpose: Some("billing".to_string()),
+        })
+    );
+    assert_eq!(
+        inventory::parse("hubstry:processing"),
+        Some(ProcessingDeclaration::default())
+    );
+    assert_eq!(inventory::parse("LGPD.DATA.COLLECTION: Sign-up."), None);
+}
+
+#[test]
+fn test_inventory_lists_annotated_functions() {
+    // The declaration is not mistaken for an unknown prefix in strict mode.
+    let result = analyze_files(&SemanticEngine::new(strict_config(true)), FILES);
+    assert!(result.violations.is_empty(), "{:?}", result.violations);
+
+    let inventory = Inventory::from_analysis(&result);
+    let functions: Vec<&str> = inventory
+        .entries
+        .iter()
+        .map(|e| e.function.as_str())
+        .collect();
+    assert_eq!(functions, vec!["check_age", "register"]);
+
+    let register = &inventory.entries[1];
+    assert_eq!(register.location(), "src/users.rs:2");
+    assert_eq!(
+        register.description.as_deref(),
+        Some("Student sign-up, \"class A\".")
+    );
+    assert_eq!(register.data_categories, vec!["save_user_info"]);
+    assert_eq!(register.legal_basis.as_deref(), Some("consent (art. 7, I)"));
+    assert_eq!(register.purpose.as_deref(), Some("enrollment"));
+    assert_eq!(inventory.missing_legal_basis()[0].function, "check_age");
+}
+
+#[test]
+fn test_inventory_exports() {
+    let inventory = Inventory::from_analysis(&analyze_files(
+        &SemanticEngine::new(strict_config(false)),
+        FILES,
+    ));
+
+    let csv = inventory.to_csv();
+    let lines: Vec<&str> = csv.split("\r\n").collect();
+    assert_eq!(
+        lines[0],
+        "file,line,function,prefixes,description,data_categories,legal_basis,purpose"
+    );
+    assert_eq!(
+        lines[2],
+        r#"src/users.rs,2,register,LGPD.DATA.COLLECTION,"Student sign-up, ""class A"".",save_user_info,"consent (art. 7, I)",enrollment"#
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&inventory.to_json().unwrap()).unwrap();
+    assert_eq!(json["entries"][1]["purpose"], "enrollment");
+    assert_eq!(json["entries"][0]["legal_basis"], serde_json::Value::Null);
+
+    let markdown = inventory.to_markdown(Locale::En);
+    assert!(markdown.contains("2 processing operation(s) annotated in the code"));
+    assert!(markdown.contains(
+        "| `check_age` | Age gate. | - | not declared | not declared | ECA.AGE.VERIFY | src/games.rs:2 |"
+    ));
+    assert!(markdown.contains("No legal basis declared: `check_age`."));
+}
+
+#[test]
+fn test_csv_report_lists_violations() {
+    let result = common::analyze(
+        "src/ads.rs",
+        "/// ECA.AD.NO_TARGETING: banner.\nfn banner() { track_user(); }\n",
+    );
+    let csv = report::reporter(OutputFormat::Csv, Locale::En)
+        .render(&result)
+        .unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 2);
+    assert!(rows[1].starts_with("ECA.AD.NO_TARGETING.1,HIGH,src/ads.rs,1,banner,"));
+    assert!(rows[1].ends_with(&result.violations[0].fingerprint()));
+}
@@ -0,0 +1,198 @@
+// tests/test_legal_citations.rs
+
+mod common;
+
+use common::analyze;
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::models::{LegalCitation, OutputFormat};
+use hubstry_iso_code::report;
+use hubstry_iso_code::rules::catalog::RuleCatalog;
+use hubstry_iso_code::rules::{pack, RulePack, RuleSet};
+use hubstry_iso_code::scanner::{ScanConfig, StaticDomScanner};
+use hubstry_iso_code::scoring::ScoringConfig;
+use std::path::Path;
+
+const CODE: &str = r#"
+/// LGPD.CONSENT.REQUIRED: Newsletter.
+fn subscribe(email: &str) {
+    add_to_mailing_list(email);
+}
+"#;
+
+fn lgpd(article: &str) -> LegalCitation {
+    LegalCitation {
+        law: "LGPD".to_string(),
+        number: "Lei nº 13.709/2018".to_string(),
+        article: article.to_string(),
+        effective_date: "2020-09-18".to_string(),
+    }
+}
+
+#[test]
+fn test_every_rule_cites_the_law() {
+    let rules = RuleSet::builtin();
+    for rule in rules.rules() {
+        assert!(!rule.citations.is_empty(), "{}", rule.id);
+    }
+    for rule in rules.web_rules() {
+        assert!(!rule.citations.is_empty(), "{}", rule.id);
+    }
+
+    let result = analyze("src/newsletter.rs", CODE);
+    assert_eq!(result.violations.len(), 1);
+    assert_eq!(
+        result.violations[0].citations,
+        vec![lgpd("Art. 7º, I"), lgpd("Art. 8º, § 2º")]
+    );
+    assert_eq!(
+        result.violations[0].citations[1].to_string(),
+        "LGPD (Lei nº 13.709/2018), Art. 8º, § 2º"
+    );
+
+    let config = ScanConfig {
+        max_pages: 1,
+        follow_links: false,
+        check_subpages: vec![],
+        rules,
+        scoring: ScoringConfig::default(),
+        locale: Locale::default(),
+    };
+    let scan = StaticDomScanner::new().scan_html(
+        "index.html",
+        "<script>var box = lootbox.open();</script>",
+        &config,
+    );
+    let lootbox = scan
+        .violations
+        .iter()
+        .find(|v| v.rule_id == "ECA.DESIGN.LOOTBOX_BAN")
+        .unwrap();
+    assert_eq!(lootbox.citations[0].law, "ECA Digital");
+    assert_eq!(lootbox.citations[0].number, "Lei nº 15.211/2025");
+    assert_eq!(lootbox.citations[0].effective_date, "2026-03-17");
+    let json = serde_json::to_value(&scan).unwrap();
+    assert!(json["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|v| v["citations"][0]["article"] == "Art. 20"));
+}
+
+#[test]
+fn test_citations_in_every_report_format() {
+    let result = analyze("src/newsletter.rs", CODE);
+    for format in OutputFormat::ALL {
+        if format == OutputFormat::Pdf {
+            continue;
+        }
+        let rendered = report::reporter(format, Locale::En)
+            .render(&result)
+            .unwrap();
+        assert!(rendered.contains("Art. 8º, § 2º"), "{}", format);
+    }
+    let render = |format, locale| report::reporter(format, locale).render(&result).unwrap();
+
+    assert!(render(OutputFormat::PlainText, Locale::En).contains(
+        "    Legal basis: LGPD (Lei nº 13.709/2018), Art. 7º, I (in force since 2020-09-18); "
+    ));
+    assert!(render(OutputFormat::Markdown, Locale::PtBr).contains(
+        "  *Base legal:* LGPD (Lei nº 13.709/2018), Art. 7º, I (em vigor desde 18/09/2020); "
+    ));
+    assert!(render(OutputFormat::Html, Locale::Es)
+        .contains("<li>LGPD (Lei nº 13.709/2018), Art. 8º, § 2º (en vigor desde 18/09/2020)</li>"));
+    assert!(render(OutputFormat::Xml, Locale::En).contains(
+        "<citation law=\"LGPD\" number=\"Lei nº 13.709/2018\" article=\"Art. 7º, I\" effectiveDate=\"2020-09-18\"/>"
+    ));
+    assert!(render(OutputFormat::Csv, Locale::En)
+        .lines()
+        .next()
+        .unwrap()
+        .contains(",suggestion,citations,fingerprint"));
+
+    let sarif: serde_json::Value =
+        serde_json::from_str(&render(OutputFormat::Sarif, Locale::En)).unwrap();
+    let rule = &sarif["runs"][0]["tool"]["driver"]["rules"][0];
+    assert_eq!(
+        rule["properties"]["legalCitations"][1]["article"],
+        "Art. 8º, § 2º"
+    );
+    assert!(rule["help"]["text"]
+        .as_str()
+        .unwrap()
+        .contains("\nLegal basis: LGPD"));
+}
+
+#[test]
+fn test_catalog_lists_citations() {
+    let catalog = RuleCatalog::new(&pack::builtin_packs(), Locale::En);
+    let entry = catalog.get("ECA.PARENT.CONSENT.1").unwrap();
+    assert_eq!(entry.citations, vec![lgpd("Art. 14, § 1º")]);
+
+    let text = entry.to_text(Locale::En);
+    assert!(text.contains(
+        "\nLegal provisions:\n  - LGPD (Lei nº 13.709/2018), Art. 14, § 1º (in force since 2020-09-18)\n"
+    ));
+    let markdown = entry.to_markdown(Locale::PtBr);
+    assert!(markdown.contains(
+        "## Dispositivos legais\n\n- LGPD (Lei nº 13.709/2018), Art. 14, § 1º (em vigor desde 18/09/2020)\n"
+    ));
+    let (_, page) = catalog
+        .site(Locale::En)
+        .into_iter()
+        .find(|(name, _)| name == "ECA.PARENT.CONSENT.1.html")
+        .unwrap();
+    assert!(page.contains(
+        "<ul>\n<li>LGPD (Lei nº 13.709/2018), Art. 14, § 1º (in force since 2020-09-18)</li>\n</ul>"
+    ));
+
+    // A regra do motor não cita nenhuma lei.
+    let engine_rule = catalog.get("HUBSTRY.STRICT.UNKNOWN_PREFIX").unwrap();
+    assert!(engine_rule.citations.is_empty());
+    assert!(!engine_rule.to_text(Locale::En).contains("Legal provisions"));
+}
+
+#[test]
+fn test_invalid_citations_are_rejected() {
+    let pack = |citation: &str| {
+        let yaml = format!(
+            r#"
+pack:
+  name: acme
+  version: 1.0.0
+  effective_date: 2026-01-01
+  authority: ACME
+jurisdiction: Lgpd
+rules:
+  - id: "ACME.CONSENT.1"
+    prefix: "LGPD.CONSENT.REQUIRED"
+    severity: High
+    message: "Sem consentimento."
+    citations:
+{}
+"#,
+            citation
+        );
+        RulePack::parse(&yaml, Path::new("acme.yml"))
+    };
+
+    let valid = pack(
+        "      - { law: LGPD, number: \"Lei nº 13.709/2018\", article: \"Art. 8º\", effective_date: 2020-09-18 }",
+    )
+    .unwrap();
+    assert_eq!(valid.rules[0].citations, vec![lgpd("Art. 8º")]);
+
+    let err = pack(
+        "      - { law: LGPD, number: \"Lei nº 13.709/2018\", article: \"Art. 8º\", effective_date: 18/09/2020 }",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("18/09/2020"), "{}", err);
+    assert_eq!(err.code(), "HUB-RULE-001");
+
+    let err = pack(
+        "      - { law: LGPD, number: \"Lei nº 13.709/2018\", article: \"\", effective_date: 2020-09-18 }",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("ACME.CONSENT.1"), "{}", err);
+
+    assert!(pack("      - { law: LGPD, article: \"Art. 8º\" }").is_err());
+}
@@ -0,0 +1,363 @@
+// tests/test_lsp.rs
+
+mod common;
+
+use common::scratch_dir;
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::lsp::LanguageServer;
+use hubstry_iso_code::models::EngineConfig;
+use hubstry_iso_code::prefix_manager::PrefixRegistry;
+use hubstry_iso_code::semantic_engine::SemanticEngine;
+use lsp_server::{Connection, Message, Notification, Request, RequestId};
+use serde_json::{json, Value};
+use std::thread::{self, JoinHandle};
+
+const URI: &str = "file:///projeto/src/jogo.rs";
+
+const GAME: &str = "\
+/// ECA.LOOTBOX.BLOCK: Abertura de baús.
+fn open_reward_chest(player: &Player) -> Item {
+    open_box(player)
+}
+
+fn subscribe(email: &str) {
+    add_to_mailing_list(email);
+}
+";
+
+struct Client {
+    connection: Connection,
+    server: JoinHandle<()>,
+    next_id: i32,
+}
+
+impl Client {
+    fn start(locale: Locale) -> Self {
+        Self::with_engine(SemanticEngine::new(EngineConfig::default()), locale)
+    }
+
+    fn with_engine(engine: SemanticEngine, locale: Locale) -> Self {
+        let (server, connection) = Connection::memory();
+        let server = thread::spawn(move || {
+            LanguageServer::new(engine, locale).serve(&server).unwrap();
+        });
+        let mut client = Self {
+            connection,
+            server,
+            next_id: 0,
+        };
+        let capabilities = client.request("initialize", json!({ "capabilities": {} }));
+        assert_eq!(capabilities["capabilities"]["hoverProvider"], true);
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        self.connection
+            .sender
+            .send(Request::new(id.clone(), method.to_string(), params).into())
+            .unwrap();
+        loop {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Response(response) if response.id == id => {
+                    assert!(response.error.is_none(), "{:?}", response.error);
+                    return response.result.unwrap_or(Value::Null);
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        self.connection
+            .sender
+            .send(Notification::new(method.to_string(), params).into())
+            .unwrap();
+    }
+
+    fn open(&self, text: &str) -> Value {
+        self.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": { "uri": URI, "languageId": "rust", "version": 1, "text": text } }),
+        );
+        self.diagnostics()
+    }
+
+    fn diagnostics(&self) -> Value {
+        let params = self.notification("textDocument/publishDiagnostics");
+        assert_eq!(params["uri"], URI);
+        params["diagnostics"].clone()
+    }
+
+    fn notification(&self, method: &str) -> Value {
+        loop {
+            if let Message::Notification(notification) = self.connection.receiver.recv().unwrap() {
+                if notification.method == method {
+                    return notification.params;
+                }
+            }
+        }
+    }
+
+    fn position(&mut self, method: &str, line: u32, character: u32) -> Value {
+        self.request(
+            method,
+            json!({
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character }
+            }),
+        )
+    }
+
+    fn shutdown(mut self) {
+        assert_eq!(self.request("shutdown", Value::Null), Value::Null);
+        self.notify("exit", Value::Null);
+        self.server.join().unwrap();
+    }
+}
+
+#[test]
+fn test_open_documents_get_diagnostics() {
+    let mut client = Client::start(Locale::En);
+    let diagnostics = client.open(GAME);
+    let diagnostics = diagnostics.as_array().unwrap();
+    let lootbox = diagnostics
+        .iter()
+        .find(|d| d["code"] == "ECA.LOOTBOX.BLOCK.1")
+        .unwrap();
+    assert_eq!(lootbox["source"], "hubstry");
+    assert_eq!(lootbox["severity"], 1);
+    assert_eq!(lootbox["range"]["start"]["line"], 0);
+
+    // Uma edição que não compila limpa os diagnósticos e não oferece ações de
+    // código, cujas posições seriam as do texto anterior.
+    let change = |client: &Client, version: i32, text: &str| {
+        client.notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": URI, "version": version },
+                "contentChanges": [{ "text": text }]
+            }),
+        );
+        client.diagnostics()
+    };
+    let broken = format!("fn broken( {{\n{}", GAME);
+    assert_eq!(change(&client, 2, &broken), json!([]));
+    let actions = client.request(
+        "textDocument/codeAction",
+        json!({
+            "textDocument": { "uri": URI },
+            "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 9, "character": 0 } },
+            "context": { "diagnostics": [] }
+        }),
+    );
+    assert_eq!(actions, json!([]));
+    let fixed = GAME.replace(
+        "-> Item {\n",
+        "-> Item {\n    verify_age(todo!(\"conta do jogador\"), 18)?;\n",
+    );
+    let fixed = change(&client, 3, &fixed);
+    assert!(fixed
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|d| d["code"] != "ECA.LOOTBOX.BLOCK.1"));
+
+    client.notify(
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    assert_eq!(client.diagnostics(), json!([]));
+    // Pedidos desconhecidos recebem erro, mas o servidor continua atendendo.
+    client
+        .connection
+        .sender
+        .send(
+            Request::new(
+                RequestId::from(99),
+                "hubstry/unknown".to_string(),
+                json!({}),
+            )
+            .into(),
+        )
+        .unwrap();
+    match client.connection.receiver.recv().unwrap() {
+        Message::Response(response) => assert!(response.error.is_some()),
+        other => panic!("{:?}", other),
+    }
+    client.shutdown();
+}
+
+#[test]
+fn test_hover_explains_prefixes_and_rules() {
+    let mut client = Client::start(Locale::En);
+    client.open(&format!(
+        "{}/// hubstry:allow(LGPD.CONSENT.REQUIRED.1) reason = \"\"\nfn other() {{}}\n",
+        GAME
+    ));
+
+    let hover = client.position("textDocument/hover", 0, 10);
+    let markdown = hover["contents"]["value"].as_str().unwrap();
+    assert!(
+        markdown.starts_with("**ECA.LOOTBOX.BLOCK** · "),
+        "{}",
+        markdown
+    );
+    assert!(markdown.contains("# ECA.LOOTBOX.BLOCK.1"), "{}", markdown);
+    assert_eq!(hover["range"]["start"]["character"], 4);
+    assert_eq!(hover["range"]["end"]["character"], 21);
+
+    let hover = client.position("textDocument/hover", 8, 25);
+    let markdown = hover["contents"]["value"].as_str().unwrap();
+    assert!(
+        markdown.starts_with("# LGPD.CONSENT.REQUIRED.1"),
+        "{}",
+        markdown
+    );
+
+    // Fora de comentários, nada.
+    assert_eq!(client.position("textDocument/hover", 2, 6), Value::Null);
+    client.shutdown();
+}
+
+#[test]
+fn test_completion_of_prefixes_and_rule_ids() {
+    let mut client = Client::start(Locale::PtBr);
+    client.open("/// ECA.\nfn a() {}\n/// hubstry:allow(ECA.LOOTBOX.BLOCK.1, \nfn b() {}\n");
+
+    let items = client.position("textDocument/completion", 0, 8);
+    let lootbox = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["label"] == "ECA.LOOTBOX.BLOCK")
+        .unwrap();
+    assert_eq!(lootbox["insertText"], "ECA.LOOTBOX.BLOCK: ");
+
+    let items = client.position("textDocument/completion", 2, 40);
+    let labels: Vec<&str> = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["label"].as_str().unwrap())
+        .collect();
+    assert!(labels.contains(&"LGPD.CONSENT.REQUIRED.1"));
+    assert!(!labels.contains(&"ECA.DATA.RETENTION_BAN"));
+
+    assert_eq!(
+        client.position("textDocument/completion", 1, 5),
+        Value::Null
+    );
+    client.shutdown();
+}
+
+#[test]
+fn test_code_actions_fix_suppress_and_annotate() {
+    let mut client = Client::start(Locale::En);
+    client.open(GAME);
+    let actions = client.request(
+        "textDocument/codeAction",
+        json!({
+            "textDocument": { "uri": URI },
+            "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 7, "character": 0 } },
+            "context": { "diagnostics": [] }
+        }),
+    );
+    let actions = actions.as_array().unwrap();
+    let titled = |title: &str| {
+        actions
+            .iter()
+            .find(|action| action["title"] == title)
+            .unwrap_or_else(|| panic!("{}: {:#?}", title, actions))
+    };
+
+    let fix = titled("Hubstry: Insert an age verification guard at the top of the function.");
+    assert_eq!(fix["kind"], "quickfix");
+    assert_eq!(fix["isPreferred"], true);
+    assert_eq!(fix["diagnostics"][0]["code"], "ECA.LOOTBOX.BLOCK.1");
+    let edit = &fix["edit"]["changes"][URI][0];
+    assert_eq!(
+        edit["range"]["start"],
+        json!({ "line": 1, "character": 47 })
+    );
+    assert_eq!(
+        edit["newText"],
+        "\n    verify_age(todo!(\"conta do jogador\"), 18)?;"
+    );
+
+    let suppress = titled("Hubstry: Suppress ECA.LOOTBOX.BLOCK.1 in this function");
+    let edit = &suppress["edit"]["changes"][URI][0];
+    assert_eq!(edit["range"]["start"], json!({ "line": 0, "character": 0 }));
+    assert!(edit["newText"]
+        .as_str()
+        .unwrap()
+        .starts_with("/// hubstry:allow(ECA.LOOTBOX.BLOCK.1)"));
+
+    let annotate = titled("Hubstry: Annotate the function with LGPD.CONSENT.REQUIRED");
+    assert_eq!(
+        annotate["edit"]["changes"][URI][0]["range"]["start"]["line"],
+        5
+    );
+
+    // Fora do intervalo das violações, nenhuma ação.
+    let none = client.request(
+        "textDocument/codeAction",
+        json!({
+            "textDocument": { "uri": URI },
+            "range": { "start": { "line": 4, "character": 0 }, "end": { "line": 4, "character": 0 } },
+            "context": { "diagnostics": [] }
+        }),
+    );
+    assert_eq!(none, json!([]));
+    client.shutdown();
+}
+
+#[test]
+fn test_changed_prefixes_are_reloaded() {
+    let dir = scratch_dir("lsp-reload");
+    let path = dir.join("prefixes.yml");
+    let prefixes = r#"
+jurisdictions:
+  ECA:
+    - prefix: "ECA.AGE.VERIFY"
+      description: "Verificação de idade via serviço interno."
+      standard: "Eca"
+      expected_calls: ["age_service_verify"]
+"#;
+    std::fs::write(&path, prefixes).unwrap();
+    let engine = SemanticEngine::with_registry(
+        EngineConfig::default(),
+        PrefixRegistry::from_path(&path).unwrap(),
+    );
+    let mut client = Client::with_engine(engine, Locale::En);
+    let diagnostics = client.open("/// ECA.AGE.VERIFY: Idade.\nfn check() { verify_age(id); }\n");
+    assert_eq!(diagnostics[0]["code"], "ECA.AGE.VERIFY.1");
+
+    // Qualquer mensagem seguinte à alteração do arquivo republica os diagnósticos.
+    let touch = |content: &str, offset: u64| {
+        std::fs::write(&path, content).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(offset))
+            .unwrap();
+        client.notify("workspace/didChangeWatchedFiles", json!({ "changes": [] }));
+    };
+    touch(&prefixes.replace("age_service_verify", "verify_age"), 1);
+    assert_eq!(client.diagnostics(), json!([]));
+
+    // Um arquivo inválido mantém o registro anterior e avisa o editor.
+    touch("jurisdictions: [", 2);
+    let message = client.notification("window/showMessage");
+    assert_eq!(message["type"], 2);
+    assert!(message["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("[HUB-CFG-002]"));
+    let hover = client.position("textDocument/hover", 0, 8);
+    assert!(hover["contents"]["value"]
+        .as_str()
+        .unwrap()
+        .starts_with("**ECA.AGE.VERIFY**"));
+    client.shutdown();
+}
@@ -0,0 +1,126 @@
+// tests/test_prefix_lint.rs
+
+use hubstry_iso_code::models::EngineConfig;
+use hubstry_iso_code::prefix_lint::{self, LintDiagnostic, LintLevel};
+use hubstry_iso_code::prefix_manager::PrefixRegistry;
+use hubstry_iso_code::rules::RuleSet;
+use hubstry_iso_code::semantic_engine::SemanticEngine;
+
+const PREFIXES: &str = r#"jurisdictions:
+  ECA:
+    - prefix: "ECA.AGE.VERIFY"
+      description: "Verificação de idade."
+      standard: "Eca"
+      expected_calls:
+        - "verify_age"
+        - "save_consent"
+    - prefix: "ECA.DATA.MINIMIZE"
+      description: "Minimização."
+      standard: "Eca"
+    - prefix: "ECA.AGE.VERIFY"
+      description: "Duplicado."
+      standard: "Eca"
+  LGPD:
+    - prefix: "LGPD.DATA.COLLECTION"
+      description: "Coleta."
+      standard: "Lgpd"
+      expected_calls:
+        - "anonymize_data"
+      data_collection_keywords:
+        - "save"
+    - prefix: "LGPD.CONSENT.REQUIRED"
+      description: "Consentimento."
+      standard: "LGPD"
+    - prefix: "CUSTOM.AUDIT"
+      description: "Genérico."
+      standard: "Generic"
+"#;
+
+fn diagnostics() -> Vec<LintDiagnostic> {
+    let registry = PrefixRegistry::from_yaml_str(PREFIXES).expect("Valid YAML");
+    prefix_lint::lint(&registry, &RuleSet::builtin())
+}
+
+fn find<'a>(diagnostics: &'a [LintDiagnostic], code: &str, needle: &str) -> &'a LintDiagnostic {
+    diagnostics
+        .iter()
+        .find(|d| d.code == code && d.message.to_string().contains(needle))
+        .unwrap_or_else(|| panic!("missing {} diagnostic for {}", code, needle))
+}
+
+#[test]
+fn test_duplicate_prefix_is_an_error_with_line() {
+    let diagnostics = diagnostics();
+    let duplicate = find(&diagnostics, "duplicate-prefix", "ECA.AGE.VERIFY");
+    assert_eq!(duplicate.level, LintLevel::Error);
+    assert_eq!(duplicate.line, Some(12));
+    assert!(prefix_lint::has_errors(&diagnostics));
+}
+
+#[test]
+fn test_lines_follow_the_file_across_jurisdictions() {
+    // The jurisdictions are not in alphabetical order in the file.
+    let yaml = r#"jurisdictions:
+  LGPD:
+    - prefix: "SHARED.AUDIT"
+      description: "Auditoria."
+      standard: "Lgpd"
+  ECA:
+    - prefix: "SHARED.AUDIT"
+      description: "Auditoria."
+      standard: "Ecaa"
+"#;
+    let registry = PrefixRegistry::from_yaml_str(yaml).expect("Valid YAML");
+    let diagnostics = prefix_lint::lint(&registry, &RuleSet::builtin());
+    assert_eq!(
+        find(&diagnostics, "duplicate-prefix", "SHARED.AUDIT").line,
+        Some(7)
+    );
+    assert_eq!(
+        find(&diagnostics, "unknown-standard", "SHARED.AUDIT").line,
+        Some(7)
+    );
+}
+
+#[test]
+fn test_unknown_and_generic_standards() {
+    let diagnostics = diagnostics();
+    let unknown = find(&diagnostics, "unknown-standard", "LGPD.CONSENT.REQUIRED");
+    assert_eq!(unknown.level, LintLevel::Error);
+    assert_eq!(unknown.line, Some(23));
+
+    let generic = find(&diagnostics, "no-validator", "CUSTOM.AUDIT");
+    assert_eq!(generic.level, LintLevel::Warning);
+    assert_eq!(generic.line, Some(26));
+}
+
+#[test]
+fn test_prefix_without_validator() {
+    let diagnostics = diagnostics();
+    let minimize = find(&diagnostics, "no-validator", "ECA.DATA.MINIMIZE");
+    assert_eq!(minimize.line, Some(9));
+}
+
+#[test]
+fn test_keyword_overlap_points_at_keyword_line() {
+    let diagnostics = diagnostics();
+    let overlap = find(&diagnostics, "keyword-overlap", "save_consent");
+    assert!(overlap.message.to_string().contains("LGPD.DATA.COLLECTION"));
+    assert_eq!(overlap.line, Some(8));
+}
+
+#[test]
+fn test_builtin_prefixes_are_clean() {
+    let diagnostics = prefix_lint::lint(&PrefixRegistry::embedded(), &RuleSet::builtin());
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+}
+
+#[test]
+fn test_engine_relints_after_registry_reload() {
+    let registry = PrefixRegistry::from_yaml_str(PREFIXES).expect("Valid YAML");
+    let engine = SemanticEngine::with_registry(EngineConfig::default(), registry);
+    assert!(prefix_lint::has_errors(&engine.lint()));
+
+    engine.set_registry(PrefixRegistry::embedded());
+    assert!(engine.lint().is_empty(), "{:?}", engine.lint());
+}
@@ -0,0 +1,173 @@
+// tests/test_prefix_registry.rs
+
+mod common;
+
+use common::scratch_dir;
+use hubstry_iso_code::models::EngineConfig;
+use hubstry_iso_code::prefix_manager::{PrefixRegistry, PrefixSource};
+use hubstry_iso_code::semantic_engine::SemanticEngine;
+
+const CUSTOM_PREFIXES: &str = r#"
+jurisdictions:
+  ECA:
+    - prefix: "ECA.AGE.VERIFY"
+      description: "Verificação de idade via serviço interno."
+      standard: "Eca"
+      expected_calls:
+        - "age_service_verify"
+"#;
+
+const CODE: &str = r#"
+    /// ECA.AGE.VERIFY: This function must check the user's age.
+    fn check_user_age() {
+        verify_age(id);
+    }
+"#;
+
+#[test]
+fn test_engines_with_different_registries_coexist() {
+    let ast = syn::parse_file(CODE).expect("Failed to parse code");
+
+    let default_engine = SemanticEngine::default();
+    let custom_engine = SemanticEngine::with_registry(
+        EngineConfig::default(),
+        PrefixRegistry::from_yaml_str(CUSTOM_PREFIXES).expect("Valid YAML"),
+    );
+
+    let default_result = default_engine.analyze(&ast).unwrap();
+    let custom_result = custom_engine.analyze(&ast).unwrap();
+
+    assert!(default_result.violations.is_empty());
+    assert_eq!(custom_result.violations.len(), 1);
+    assert_eq!(custom_result.violations[0].rule_id, "ECA.AGE.VERIFY.1");
+}
+
+#[test]
+fn test_discover_walks_up_from_subdirectory() {
+    let root = scratch_dir("discover");
+    std::fs::write(root.join("prefixes.yml"), CUSTOM_PREFIXES).unwrap();
+    let nested = root.join("src").join("handlers");
+    std::fs::create_dir_all(&nested).unwrap();
+
+    let registry = PrefixRegistry::discover(&nested).unwrap();
+    assert_eq!(
+        registry.source(),
+        &PrefixSource::File(root.join("prefixes.yml"))
+    );
+    assert_eq!(registry.len(), 1);
+}
+
+#[test]
+fn test_invalid_yaml_is_a_config_error() {
+    let err = PrefixRegistry::from_yaml_str("jurisdictions: [").unwrap_err();
+    assert_eq!(err.code(), "HUB-CFG-002");
+}
+
+#[test]
+fn test_engine_hot_reloads_changed_file() {
+    let root = scratch_dir("reload");
+    let path = root.join("prefixes.yml");
+    std::fs::write(&path, CUSTOM_PREFIXES).unwrap();
+
+    let engine = SemanticEngine::with_registry(
+        EngineConfig::default(),
+        PrefixRegistry::from_path(&path).unwrap(),
+    );
+    assert!(!engine.reload_prefixes_if_changed().unwrap());
+
+    std::fs::write(
+        &path,
+        CUSTOM_PREFIXES.replace("age_service_verify", "verify_age"),
+    )
+    .unwrap();
+    // Move the modification time forward explicitly; some filesystems have a coarse clock.
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(1))
+        .unwrap();
+
+    assert!(engine.reload_prefixes_if_changed().unwrap());
+    let ast = syn::parse_file(CODE).unwrap();
+    assert!(engine.analyze(&ast).unwrap().violations.is_empty());
+}
+
+#[test]
+fn test_layers_append_replace_and_remove() {
+    let root = scratch_dir("layers");
+    std::fs::write(
+        root.join("org.yml"),
+        r#"
+extends: builtin
+jurisdictions:
+  ECA:
+    - prefix: "ECA.AD.NO_TARGETING"
+      merge: remove
+    - prefix: "ECA.LOOTBOX.BLOCK"
+      merge: replace
+      expected_calls: ["age_gate"]
+      data_collection_keywords: ["lootbox"]
+"#,
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("project")).unwrap();
+    std::fs::write(
+        root.join("project").join("prefixes.yml"),
+        r#"
+extends: "../org.yml"
+jurisdictions:
+  ECA:
+    - prefix: "ECA.AGE.VERIFY"
+      expected_calls: ["age_service::verify"]
+      remove_expected_calls: ["check_age"]
+"#,
+    )
+    .unwrap();
+
+    let registry = PrefixRegistry::from_path(root.join("project").join("prefixes.yml")).unwrap();
+    assert_eq!(registry.layers().len(), 3);
+    assert_eq!(registry.layers()[0], PrefixSource::Embedded);
+
+    let age = registry.get("ECA.AGE.VERIFY").unwrap();
+    assert!(age
+        .expected_calls
+        .contains(&"age_service::verify".to_string()));
+    assert!(age.expected_calls.contains(&"verify_age".to_string()));
+    assert!(!age.expected_calls.contains(&"check_age".to_string()));
+
+    let lootbox = registry.get("ECA.LOOTBOX.BLOCK").unwrap();
+    assert_eq!(lootbox.expected_calls, vec!["age_gate".to_string()]);
+    assert_eq!(lootbox.standard, "Eca");
+
+    assert!(registry.get("ECA.AD.NO_TARGETING").is_none());
+    assert!(registry.to_yaml().contains("age_service::verify"));
+}
+
+#[test]
+fn test_layers_passed_in_order() {
+    let root = scratch_dir("ordered");
+    std::fs::write(root.join("base.yml"), CUSTOM_PREFIXES).unwrap();
+    std::fs::write(
+        root.join("override.yml"),
+        r#"
+jurisdictions:
+  ECA:
+    - prefix: "ECA.AGE.VERIFY"
+      expected_calls: ["verify_age"]
+"#,
+    )
+    .unwrap();
+
+    let registry =
+        PrefixRegistry::from_layers([root.join("base.yml"), root.join("override.yml")]).unwrap();
+    let age = registry.get("ECA.AGE.VERIFY").unwrap();
+    assert_eq!(age.expected_calls, vec!["age_service_verify", "verify_age"]);
+}
+
+#[test]
+fn test_extends_cycle_is_rejected() {
+    let root = scratch_dir("cycle");
+    std::fs::write(root.join("a.yml"), "extends: b.yml\n").unwrap();
+    std::fs::write(root.join("b.yml"), "extends: a.yml\n").unwrap();
+
+    let err = PrefixRegistry::from_path(root.join("a.yml")).unwrap_err();
+    assert_eq!(err.code(), "HUB-CFG-003");
+}
@@ -0,0 +1,134 @@
+// tests/test_provenance.rs
+
+mod common;
+
+use common::{analyze_with, scratch_dir, strict_config};
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::models::AnalysisResult;
+use hubstry_iso_code::provenance::{self, GitInfo, RecordedReport, Worktree};
+use std::path::Path;
+use std::process::Command;
+
+const CODE: &str = r#"
+/// ECA.AD.NO_TARGETING: banner.
+fn banner() { track_user(); }
+
+/// ECA.AD.NO_TARGETING: sidebar.
+fn sidebar() { track_user(); }
+"#;
+
+fn recorded(result: &AnalysisResult) -> RecordedReport {
+    RecordedReport::from_value(serde_json::to_value(result).unwrap()).unwrap()
+}
+
+#[test]
+fn test_metadata_records_reproducibility_data() {
+    let result = analyze_with("src/ads.rs", CODE, strict_config(false));
+    let metadata = &result.metadata;
+
+    assert_eq!(metadata["engine.version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        metadata["file.src/ads.rs"],
+        provenance::sha256(CODE.as_bytes())
+    );
+    assert_eq!(metadata["files.count"], "1");
+    assert!(metadata["rule_pack.eca_digital"].starts_with("1.0.0 sha256:"));
+    assert!(metadata["prefixes.sha256"].starts_with("sha256:"));
+    assert_eq!(metadata["config.strict_mode"], "false");
+    assert_eq!(metadata["config.jurisdictions"], "ECA Digital, LGPD");
+    assert!(metadata.contains_key("analysis.timestamp"));
+
+    // Duas violações com a mesma sugestão geram uma única sugestão.
+    assert_eq!(result.violations.len(), 2);
+    assert_eq!(result.suggestions.len(), 1);
+}
+
+#[test]
+fn test_compare_ignores_volatile_keys() {
+    let first = analyze_with("src/ads.rs", CODE, strict_config(false));
+    let mut second = analyze_with("src/ads.rs", CODE, strict_config(false));
+    second
+        .metadata
+        .insert("analysis.timestamp".to_string(), "later".to_string());
+    second
+        .metadata
+        .insert("git.commit".to_string(), "abc".to_string());
+    assert!(
+        provenance::compare(&recorded(&first), &second, Locale::default())
+            .unwrap()
+            .is_empty()
+    );
+
+    let report = recorded(&first);
+    assert_eq!(report.files().collect::<Vec<_>>(), vec!["src/ads.rs"]);
+    assert_eq!(
+        report.rule_packs(),
+        vec!["eca_digital@1.0.0".to_string(), "lgpd@1.0.0".to_string()]
+    );
+}
+
+#[test]
+fn test_compare_reports_differences() {
+    let before = analyze_with("src/ads.rs", CODE, strict_config(false));
+    let fixed = CODE.replace(
+        "fn sidebar() { track_user(); }",
+        "fn sidebar() { serve_generic_ads(); }",
+    );
+    let after = analyze_with("src/ads.rs", &fixed, strict_config(true));
+
+    let differences = provenance::compare(&recorded(&before), &after, Locale::default()).unwrap();
+    assert!(differences
+        .iter()
+        .any(|d| d.starts_with("file.src/ads.rs: sha256:")));
+    assert!(differences.contains(&"config.strict_mode: false -> true".to_string()));
+    assert!(differences.contains(&"compliance_score: 0 -> 50".to_string()));
+    assert!(differences
+        .iter()
+        .any(|d| d.contains("deixou de ocorrer: ECA.AD.NO_TARGETING.1 src/ads.rs:sidebar:")));
+}
+
+#[test]
+fn test_report_without_metadata_is_rejected() {
+    let err =
+        RecordedReport::from_value(serde_json::json!({ "compliance_score": 100.0 })).unwrap_err();
+    assert_eq!(err.code(), "HUB-REPORT-003");
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=Hubstry", "-c", "user.email=ci@hubstry.dev"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?}", args);
+}
+
+#[test]
+fn test_git_info_and_worktree() {
+    let repo = scratch_dir("provenance");
+    std::fs::create_dir_all(repo.join("src")).unwrap();
+    git(&repo, &["init", "--quiet"]);
+    std::fs::write(repo.join("src/ads.rs"), CODE).unwrap();
+    git(&repo, &["add", "."]);
+    git(&repo, &["commit", "--quiet", "-m", "ads"]);
+
+    let info = GitInfo::discover(&repo.join("src")).unwrap();
+    assert_eq!(info.commit.len(), 40);
+    assert!(!info.dirty);
+    assert_eq!(info.prefix, "src/");
+
+    std::fs::write(repo.join("src/ads.rs"), "fn changed() {}").unwrap();
+    assert!(GitInfo::discover(&repo).unwrap().dirty);
+
+    let path = {
+        let worktree = Worktree::checkout(&repo, &info.commit).unwrap();
+        let content = std::fs::read_to_string(worktree.path().join("src/ads.rs")).unwrap();
+        assert_eq!(content, CODE);
+        worktree.path().to_path_buf()
+    };
+    assert!(!path.exists(), "a cópia temporária deve ser removida");
+
+    std::fs::remove_dir_all(&repo).unwrap();
+}
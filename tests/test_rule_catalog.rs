@@ -0,0 +1,182 @@
+// tests/test_rule_catalog.rs
+
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::models::EngineConfig;
+use hubstry_iso_code::rules::catalog::{RuleCatalog, RuleKind};
+use hubstry_iso_code::rules::{pack, RuleSet};
+use hubstry_iso_code::scanner::{ScanConfig, StaticDomScanner};
+use hubstry_iso_code::scoring::ScoringConfig;
+use hubstry_iso_code::semantic_engine::SemanticEngine;
+
+fn catalog(locale: Locale) -> RuleCatalog {
+    RuleCatalog::new(&pack::builtin_packs(), locale)
+}
+
+#[test]
+fn test_every_rule_is_documented() {
+    let rules = RuleSet::builtin();
+    let ids: Vec<&str> = rules
+        .rules()
+        .iter()
+        .map(|rule| rule.id.as_str())
+        .chain(rules.web_rules().iter().map(|rule| rule.id.as_str()))
+        .chain(["HUBSTRY.STRICT.UNKNOWN_PREFIX"])
+        .collect();
+    for locale in Locale::ALL {
+        let catalog = catalog(locale);
+        assert_eq!(catalog.entries.len(), ids.len());
+        for id in &ids {
+            let entry = catalog.get(id).unwrap_or_else(|| panic!("{} ausente", id));
+            for (field, value) in [
+                ("summary", Some(&entry.summary)),
+                ("description", entry.description.as_ref()),
+                ("legal_basis", entry.legal_basis.as_ref()),
+                ("remediation", entry.remediation.as_ref()),
+                ("compliant", entry.compliant.as_ref()),
+                ("non_compliant", entry.non_compliant.as_ref()),
+            ] {
+                assert!(
+                    value.is_some_and(|value| !value.is_empty()),
+                    "{} sem {} em {}",
+                    id,
+                    field,
+                    locale
+                );
+            }
+        }
+    }
+
+    let catalog = catalog(Locale::En);
+    let dark_patterns = catalog.get("eca.design.dark_patterns").unwrap();
+    assert_eq!(dark_patterns.kind, RuleKind::Web);
+    assert_eq!(dark_patterns.applies_to.as_deref(), Some("dark_patterns"));
+    assert_eq!(dark_patterns.pack.as_deref(), Some("eca_digital@1.0.0"));
+    assert_eq!(
+        catalog.get("HUBSTRY.STRICT.UNKNOWN_PREFIX").unwrap().kind,
+        RuleKind::Engine
+    );
+}
+
+#[test]
+fn test_code_examples_match_their_rules() {
+    let catalog = catalog(Locale::default());
+    let engine = SemanticEngine::new(EngineConfig::default());
+    for entry in catalog
+        .entries
+        .iter()
+        .filter(|entry| entry.kind == RuleKind::Code)
+    {
+        let violations = |source: &str| {
+            let result = engine
+                .analyze_sources(&[("example.rs".to_string(), source.to_string())])
+                .unwrap();
+            result
+                .violations
+                .iter()
+                .filter(|violation| violation.rule_id == entry.id)
+                .count()
+        };
+        assert_eq!(
+            violations(entry.non_compliant.as_ref().unwrap()),
+            1,
+            "{}",
+            entry.id
+        );
+        assert_eq!(
+            violations(entry.compliant.as_ref().unwrap()),
+            0,
+            "{}",
+            entry.id
+        );
+    }
+
+    let strict = SemanticEngine::new(EngineConfig {
+        strict_mode: true,
+        ..EngineConfig::default()
+    });
+    let entry = catalog.get("HUBSTRY.STRICT.UNKNOWN_PREFIX").unwrap();
+    let rule_ids = |source: &str| -> Vec<String> {
+        strict
+            .analyze_sources(&[("example.rs".to_string(), source.to_string())])
+            .unwrap()
+            .violations
+            .into_iter()
+            .map(|violation| violation.rule_id)
+            .collect()
+    };
+    assert_eq!(
+        rule_ids(entry.non_compliant.as_ref().unwrap()),
+        vec![entry.id.clone()]
+    );
+    assert!(rule_ids(entry.compliant.as_ref().unwrap()).is_empty());
+}
+
+#[test]
+fn test_web_examples_match_their_rules() {
+    let catalog = catalog(Locale::default());
+    let config = ScanConfig {
+        max_pages: 1,
+        follow_links: false,
+        check_subpages: vec![],
+        rules: RuleSet::builtin(),
+        scoring: ScoringConfig::default(),
+        locale: Locale::default(),
+    };
+    let scanner = StaticDomScanner::new();
+    for entry in catalog
+        .entries
+        .iter()
+        .filter(|entry| entry.kind == RuleKind::Web)
+    {
+        let flags = |html: &str| {
+            scanner
+                .scan_html("example.html", html, &config)
+                .violations
+                .iter()
+                .any(|violation| violation.rule_id == entry.id)
+        };
+        assert!(flags(entry.non_compliant.as_ref().unwrap()), "{}", entry.id);
+        assert!(!flags(entry.compliant.as_ref().unwrap()), "{}", entry.id);
+    }
+}
+
+#[test]
+fn test_explain_and_site() {
+    let catalog = catalog(Locale::En);
+    assert_eq!(
+        catalog.similar("ECA.AGE.VERIFY.2"),
+        vec!["ECA.AGE.VERIFY.1", "ECA.AGE.VERIFY"]
+    );
+    assert_eq!(
+        catalog.similar("LOOTBOX"),
+        vec!["ECA.LOOTBOX.BLOCK.1", "ECA.DESIGN.LOOTBOX_BAN"]
+    );
+    assert!(catalog.get("ECA.AGE.VERIFY.2").is_none());
+
+    let entry = catalog.get("ECA.LOOTBOX.BLOCK.1").unwrap();
+    let text = entry.to_text(Locale::En);
+    assert!(text.starts_with("ECA.LOOTBOX.BLOCK.1 — HIGH · ECA Digital · lootbox\n"));
+    assert!(text.contains("Code rule · prefix ECA.LOOTBOX.BLOCK · pack eca_digital@1.0.0"));
+    assert!(text.contains("\nLegal basis:\n  Law No. 15,211/2025"));
+    assert!(text.contains("\n        verify_age(&player.account, 18)?;\n"));
+    let markdown = entry.to_markdown(Locale::En);
+    assert!(markdown.contains("## Non-compliant example\n\n```rust\n/// ECA.LOOTBOX.BLOCK:"));
+
+    let pages = catalog.site(Locale::Es);
+    assert_eq!(pages.len(), catalog.entries.len() + 1);
+    let (name, index) = &pages[0];
+    assert_eq!(name, "index.html");
+    assert!(index.contains("<html lang=\"es\">"));
+    assert!(index.contains("<a href=\"ECA.LOOTBOX.BLOCK.1.html\">ECA.LOOTBOX.BLOCK.1</a>"));
+    let (_, page) = pages
+        .iter()
+        .find(|(name, _)| name == "ECA.AGE.SELF_DECLARATION_BAN.html")
+        .unwrap();
+    assert!(page.contains("<h2>Ejemplo que infringe la regla</h2>"));
+    assert!(page.contains("&lt;input type=&quot;checkbox&quot;"));
+    let (_, page) = pages
+        .iter()
+        .find(|(name, _)| name == "ECA.LOOTBOX.BLOCK.1.html")
+        .unwrap();
+    assert!(page.contains("<span class=\"fn\">verify_age</span>"));
+}
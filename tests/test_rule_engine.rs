@@ -0,0 +1,212 @@
+// tests/test_rule_engine.rs
+
+use hubstry_iso_code::models::{ComplianceViolation, EngineConfig, RuleSeverity};
+use hubstry_iso_code::prefix_manager::PrefixRegistry;
+use hubstry_iso_code::rules::RuleSet;
+use hubstry_iso_code::semantic_engine::SemanticEngine;
+use std::path::Path;
+
+const PREFIXES: &str = r#"
+jurisdictions:
+  ECA:
+    - prefix: "ECA.LOOTBOX.BLOCK"
+      description: "Loot boxes."
+      standard: "Eca"
+      expected_calls: ["verify_age"]
+      data_collection_keywords: ["open_box"]
+"#;
+
+const RULES: &str = r#"
+pack:
+  name: test
+  version: 0.1.0
+  effective_date: 2026-01-01
+  authority: Tests
+jurisdiction: Eca
+rules:
+  - id: "TEST.ORDER.1"
+    prefix: "ECA.LOOTBOX.BLOCK"
+    severity: Critical
+    message: "Age must be verified before the box is opened."
+    condition:
+      must_precede:
+        before: ["$expected_calls"]
+        after: ["$data_collection_keywords"]
+  - id: "TEST.FORBID.1"
+    prefix: "ECA.LOOTBOX.BLOCK"
+    severity: Medium
+    message: "Real-money purchases are forbidden."
+    condition:
+      forbids_call: ["charge_card"]
+  - id: "TEST.PARAM.1"
+    prefix: "ECA.LOOTBOX.BLOCK"
+    severity: High
+    message: "Minimum age must be at least 18."
+    condition:
+      params:
+        - call: "verify_age"
+          arg: 1
+          op: ">="
+          value: 18
+  - id: "TEST.ANY.1"
+    prefix: "ECA.LOOTBOX.BLOCK"
+    severity: Low
+    message: "Must log either to audit or to telemetry."
+    condition:
+      any_of:
+        - requires_any_call: ["audit_log"]
+        - requires_any_call: ["telemetry"]
+"#;
+
+fn run(code: &str) -> Vec<ComplianceViolation> {
+    let engine = SemanticEngine::with_registry(
+        EngineConfig::default(),
+        PrefixRegistry::from_yaml_str(PREFIXES).unwrap(),
+    )
+    .with_rules(RuleSet::from_yaml_str(RULES, Path::new("test.yml")).unwrap());
+    let ast = syn::parse_file(code).expect("Failed to parse code");
+    engine.analyze(&ast).unwrap().violations
+}
+
+fn ids(violations: &[ComplianceViolation]) -> Vec<&str> {
+    violations.iter().map(|v| v.rule_id.as_str()).collect()
+}
+
+#[test]
+fn test_compliant_function_passes_all_conditions() {
+    let violations = run(r#"
+        /// ECA.LOOTBOX.BLOCK: Opens a loot box.
+        fn open() {
+            verify_age(user, 18);
+            open_box();
+            audit_log("opened");
+        }
+    "#);
+    assert!(violations.is_empty(), "{:?}", ids(&violations));
+}
+
+#[test]
+fn test_must_precede_follows_calls_into_helpers() {
+    let violations = run(r#"
+        /// ECA.LOOTBOX.BLOCK: Opens a loot box.
+        fn open() {
+            reward();
+            verify_age(user, 18);
+            telemetry();
+        }
+
+        fn reward() {
+            open_box();
+        }
+    "#);
+    assert_eq!(ids(&violations), vec!["TEST.ORDER.1"]);
+    assert_eq!(violations[0].severity, RuleSeverity::Critical);
+}
+
+#[test]
+fn test_forbids_call_and_param_comparison() {
+    let violations = run(r#"
+        /// ECA.LOOTBOX.BLOCK: Opens a loot box.
+        fn open() {
+            verify_age(user, 16);
+            open_box();
+            payments.charge_card(10);
+            audit_log("opened");
+        }
+    "#);
+    assert_eq!(ids(&violations), vec!["TEST.FORBID.1", "TEST.PARAM.1"]);
+}
+
+#[test]
+fn test_any_of_requires_one_branch() {
+    let violations = run(r#"
+        /// ECA.LOOTBOX.BLOCK: Opens a loot box.
+        fn open() {
+            verify_age(user, 18);
+            open_box();
+        }
+    "#);
+    assert_eq!(ids(&violations), vec!["TEST.ANY.1"]);
+}
+
+#[test]
+fn test_call_chain_explains_each_violation() {
+    let violations = run(r#"
+        /// ECA.LOOTBOX.BLOCK: Opens a loot box.
+        fn open() {
+            reward();
+            verify_age(user, 16);
+            pay();
+        }
+
+        fn reward() {
+            open_box();
+        }
+
+        fn pay() {
+            payments.charge_card(10);
+        }
+    "#);
+    let chain = |id: &str| {
+        violations
+            .iter()
+            .find(|v| v.rule_id == id)
+            .and_then(|v| v.call_chain.clone())
+            .unwrap()
+    };
+
+    let order = chain("TEST.ORDER.1");
+    assert_eq!(order.path, vec!["open", "reward", "open_box"]);
+    assert_eq!(order.missing, vec!["verify_age"]);
+    assert_eq!(order.line, Some(4));
+    assert_eq!(order.to_string(), "open → reward → open_box");
+
+    let forbid = chain("TEST.FORBID.1");
+    assert_eq!(forbid.path, vec!["open", "pay", "charge_card"]);
+    assert!(forbid.missing.is_empty());
+
+    assert_eq!(chain("TEST.PARAM.1").path, vec!["open", "verify_age"]);
+
+    // A missing call has no path beyond the function itself.
+    let any = chain("TEST.ANY.1");
+    assert_eq!(any.path, vec!["open"]);
+    assert_eq!(any.missing, vec!["audit_log"]);
+    assert_eq!(any.line, None);
+}
+
+#[test]
+fn test_unknown_variable_is_rejected() {
+    let yaml = r#"
+pack:
+  name: bad
+  version: 0.1.0
+  effective_date: 2026-01-01
+  authority: Tests
+jurisdiction: Eca
+rules:
+  - id: "TEST.BAD.1"
+    prefix: "ECA.AGE.VERIFY"
+    severity: High
+    message: "Bad rule."
+    condition:
+      requires_any_call: ["$expected_cals"]
+"#;
+    let err = RuleSet::from_yaml_str(yaml, Path::new("bad.yml")).unwrap_err();
+    assert_eq!(err.code(), "HUB-RULE-001");
+}
+
+#[test]
+fn test_builtin_rules_cover_existing_checks() {
+    let rules = RuleSet::builtin();
+    for id in [
+        "ECA.AGE.VERIFY.1",
+        "ECA.PARENT.CONSENT.1",
+        "ECA.LOOTBOX.BLOCK.1",
+        "ECA.AD.NO_RETENTION.1",
+        "ECA.AD.NO_TARGETING.1",
+        "LGPD.DATA.COLLECTION.1",
+        "LGPD.CONSENT.REQUIRED.1",
+    ] {
+        assert!(rules.rules().iter().any(|r| r.id == id), "missing {}", id);
+    }
+}
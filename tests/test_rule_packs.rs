@@ -0,0 +1,199 @@
+// tests/test_rule_packs.rs
+
+mod common;
+
+use common::scratch_dir;
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::models::EngineConfig;
+use hubstry_iso_code::prefix_manager::{PrefixRegistry, PrefixSource};
+use hubstry_iso_code::rules::pack::{self, compute_checksum, with_checksum};
+use hubstry_iso_code::rules::{PackVerification, RulePack, RuleSet};
+use hubstry_iso_code::scanner::{ScanConfig, StaticDomScanner};
+use hubstry_iso_code::scoring::ScoringConfig;
+use hubstry_iso_code::semantic_engine::SemanticEngine;
+use std::path::Path;
+
+const PACK: &str = r#"
+pack:
+  name: acme_policy
+  version: 2.1.0
+  effective_date: 2026-01-01
+  authority: ACME Legal
+
+jurisdiction: Lgpd
+
+prefixes:
+  LGPD:
+    - prefix: "ACME.EXPORT"
+      description: "Exportação de dados pessoais."
+      standard: "Lgpd"
+      expected_calls: ["audit_export"]
+
+rules:
+  - id: "ACME.EXPORT.1"
+    prefix: "ACME.EXPORT"
+    severity: High
+    message: "Exports must be audited."
+    condition:
+      requires_any_call: ["$expected_calls"]
+"#;
+
+#[test]
+fn test_builtin_packs_have_valid_checksums() {
+    let packs = pack::builtin_packs();
+    let names: Vec<&str> = packs.iter().map(|p| p.name()).collect();
+    assert_eq!(names, vec!["eca_digital", "lgpd"]);
+    for pack in &packs {
+        assert_eq!(
+            pack.verification(),
+            PackVerification::Valid,
+            "{}",
+            pack.name()
+        );
+    }
+}
+
+#[test]
+fn test_tampered_pack_is_rejected() {
+    let signed = with_checksum(PACK);
+    assert!(RuleSet::from_yaml_str(&signed, Path::new("acme.yml")).is_ok());
+
+    let tampered = signed.replace("severity: High", "severity: Low");
+    let err = RuleSet::from_yaml_str(&tampered, Path::new("acme.yml")).unwrap_err();
+    assert_eq!(err.code(), "HUB-RULE-002");
+
+    // Fins de linha diferentes não alteram o checksum.
+    assert_eq!(
+        compute_checksum(&signed.replace('\n', "\r\n")),
+        compute_checksum(&signed)
+    );
+
+    // Só a chave `checksum:` do bloco `pack:` fica fora do cálculo.
+    let smuggled = signed.replace("    condition:", "    checksum: x\n    condition:");
+    assert_ne!(compute_checksum(&smuggled), compute_checksum(&signed));
+    let resigned = with_checksum(&smuggled);
+    assert!(resigned.contains("    checksum: x\n"));
+    assert_eq!(resigned.matches("checksum: \"sha256:").count(), 1);
+}
+
+#[test]
+fn test_pack_name_cannot_leave_the_install_dir() {
+    let dir = scratch_dir("pack-traversal");
+    for name in ["../../src/main", "/etc/x", "..", "acme policy"] {
+        let yaml =
+            with_checksum(&PACK.replace("name: acme_policy", &format!("name: \"{}\"", name)));
+        let err = RulePack::parse(&yaml, Path::new("acme.yml")).unwrap_err();
+        assert_eq!(err.code(), "HUB-CFG-003", "{}", name);
+
+        let source = dir.join("evil.yml");
+        std::fs::write(&source, &yaml).unwrap();
+        assert!(pack::install(&source, &dir).is_err());
+    }
+    assert!(!dir.join(".hubstry").exists());
+}
+
+#[test]
+fn test_invalid_version_is_a_config_error() {
+    let yaml = PACK.replace("version: 2.1.0", "version: latest");
+    let err = RulePack::parse(&yaml, Path::new("acme.yml")).unwrap_err();
+    assert_eq!(err.code(), "HUB-CFG-003");
+}
+
+#[test]
+fn test_resolve_by_name_and_pinned_version() {
+    let dir = scratch_dir("pack-resolve");
+    assert_eq!(pack::resolve("lgpd", &dir).unwrap().version(), "1.0.0");
+    assert!(pack::resolve("lgpd@1.0.0", &dir).is_ok());
+    let err = pack::resolve("lgpd@9.9.9", &dir).unwrap_err();
+    assert_eq!(err.code(), "HUB-RULE-003");
+}
+
+#[test]
+fn test_installed_pack_overrides_builtin() {
+    let dir = scratch_dir("pack-install");
+    let source = dir.join("lgpd-next.yml");
+    let next = pack::BUILTIN_PACKS[1]
+        .1
+        .replace("version: 1.0.0", "version: 1.1.0");
+    std::fs::write(&source, with_checksum(&next)).unwrap();
+
+    let target = pack::install(&source, &dir).unwrap();
+    assert_eq!(target, dir.join(".hubstry/rules/lgpd.yml"));
+
+    let packs = pack::available_packs(&dir).unwrap();
+    let lgpd = packs.iter().find(|p| p.name() == "lgpd").unwrap();
+    assert_eq!(lgpd.version(), "1.1.0");
+    assert_eq!(packs.len(), 2);
+}
+
+#[test]
+fn test_pack_prefixes_and_versions_reach_the_report() {
+    let pack = RulePack::parse(&with_checksum(PACK), Path::new("acme.yml")).unwrap();
+    let mut registry = PrefixRegistry::embedded();
+    registry.apply_pack(&pack).unwrap();
+    assert_eq!(
+        registry.source(),
+        &PrefixSource::Pack("acme_policy".to_string())
+    );
+    assert!(registry.get("ACME.EXPORT").is_some());
+
+    let engine = SemanticEngine::with_registry(EngineConfig::default(), registry)
+        .with_rules(RuleSet::from_packs([pack]));
+    let ast = syn::parse_file(
+        r#"
+        /// ACME.EXPORT: exports the user's data.
+        fn export_user() {
+            write_file();
+        }
+    "#,
+    )
+    .unwrap();
+    let result = engine.analyze(&ast).unwrap();
+
+    assert_eq!(result.violations.len(), 1);
+    assert_eq!(result.violations[0].rule_id, "ACME.EXPORT.1");
+    assert_eq!(result.rule_packs.len(), 1);
+    assert_eq!(result.rule_packs[0].to_string(), "acme_policy@2.1.0");
+    assert!(result.rule_packs[0].checksum.starts_with("sha256:"));
+}
+
+#[test]
+fn test_web_rules_come_from_packs() {
+    let html = std::fs::read_to_string("examples/web/non_compliant_site.html")
+        .expect("Failed to read example");
+    let scanner = StaticDomScanner::new();
+    let config = |rules| ScanConfig {
+        max_pages: 1,
+        follow_links: false,
+        check_subpages: vec![],
+        rules,
+        scoring: ScoringConfig::default(),
+        locale: Locale::default(),
+    };
+
+    let result = scanner.scan_html("local", &html, &config(RuleSet::builtin()));
+    let ids: Vec<&str> = result
+        .violations
+        .iter()
+        .map(|v| v.rule_id.as_str())
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            "ECA.AGE.SELF_DECLARATION_BAN",
+            "ECA.DESIGN.DARK_PATTERNS",
+            "ECA.DATA.RETENTION_BAN",
+            "ECA.PRIVACY.MAX_DEFAULT",
+            "ECA.DESIGN.LOOTBOX_BAN",
+        ]
+    );
+    // 35 dos 45 pontos de peso das regras web foram descumpridos.
+    assert_eq!(result.score, 22.22);
+    assert_eq!(result.rule_packs.len(), 2);
+
+    // O pacote da LGPD não tem regras web.
+    let lgpd = RuleSet::from_packs([pack::resolve("lgpd", Path::new(".")).unwrap()]);
+    let result = scanner.scan_html("local", &html, &config(lgpd));
+    assert!(result.violations.is_empty());
+    assert_eq!(result.score, 100.0);
+}
@@ -0,0 +1,163 @@
+// tests/test_scoring.rs
+
+mod common;
+
+use common::{analyze, analyze_with, scratch_dir};
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::models::{EngineConfig, Jurisdiction, RuleSeverity};
+use hubstry_iso_code::project_config::ProjectConfig;
+use hubstry_iso_code::rules::RuleSet;
+use hubstry_iso_code::scanner::{ScanConfig, StaticDomScanner};
+use hubstry_iso_code::scoring::{self, Obligation, ScoringConfig, SeverityWeights};
+
+/// `compliant` functions that serve generic ads, plus `failing` that track the user.
+fn ad_slots(compliant: usize, failing: usize) -> String {
+    let mut code = String::new();
+    for i in 0..compliant {
+        code.push_str(&format!(
+            "/// ECA.AD.NO_TARGETING: slot {i}.\nfn generic_{i}() {{ serve_generic_ads(); }}\n"
+        ));
+    }
+    for i in 0..failing {
+        code.push_str(&format!(
+            "/// ECA.AD.NO_TARGETING: slot {i}.\nfn targeted_{i}() {{ track_user(); }}\n"
+        ));
+    }
+    code
+}
+
+#[test]
+fn test_score_is_normalized_by_checked_obligations() {
+    let large = analyze("src/ads.rs", &ad_slots(495, 5));
+    let small = analyze("src/ads.rs", &ad_slots(0, 5));
+
+    assert_eq!(large.violations.len(), 5);
+    assert_eq!(small.violations.len(), 5);
+    assert_eq!(large.score_breakdown.obligations, 500);
+    assert_eq!(large.compliance_score, 99.0);
+    assert_eq!(small.compliance_score, 0.0);
+
+    let empty = analyze("src/ads.rs", "fn main() {}");
+    assert_eq!(empty.score_breakdown.obligations, 0);
+    assert_eq!(empty.compliance_score, 100.0);
+}
+
+#[test]
+fn test_sub_scores_per_jurisdiction_and_category() {
+    let code = r#"
+        /// ECA.AGE.VERIFY: entry point.
+        fn enter() { verify_age(); }
+
+        /// ECA.AD.NO_TARGETING: banner.
+        fn banner() { track_user(); }
+
+        /// LGPD.CONSENT.REQUIRED: signup.
+        fn signup() { check_user_consent(); }
+    "#;
+    let result = analyze("src/ads.rs", code);
+    let breakdown = &result.score_breakdown;
+
+    assert_eq!(breakdown.obligations, 3);
+    assert_eq!(breakdown.failed, 1);
+    assert_eq!(breakdown.by_jurisdiction["ECA Digital"].score, 50.0);
+    assert_eq!(breakdown.by_jurisdiction["LGPD"].score, 100.0);
+    assert_eq!(breakdown.by_category["ads"].score, 0.0);
+    assert_eq!(breakdown.by_category["age"].score, 100.0);
+    assert_eq!(breakdown.by_category["consent"].obligations, 1);
+    assert!(breakdown.formula.contains("HIGH=5"));
+
+    // Uma obrigação suprimida conta como cumprida.
+    let waived = code.replace(
+        "/// ECA.AD.NO_TARGETING: banner.",
+        "/// ECA.AD.NO_TARGETING: banner.\n/// hubstry:allow(ECA.AD.NO_TARGETING.1) reason = \"desativado\"",
+    );
+    assert_eq!(analyze("src/ads.rs", &waived).compliance_score, 100.0);
+}
+
+#[test]
+fn test_weights_come_from_project_config() {
+    let dir = scratch_dir("scoring");
+    let path = dir.join("hubstry.yml");
+
+    std::fs::write(&path, "scoring:\n  weights:\n    high: 1\n    medium: 3\n").unwrap();
+    let project = ProjectConfig::from_path(&path).unwrap();
+    assert_eq!(project.scoring.weights.high, 1.0);
+    assert_eq!(project.scoring.weights.critical, 10.0);
+
+    // HIGH (ad slot, violada) com peso 1 e MEDIUM (coleta de dados, cumprida) com peso 3.
+    let code = r#"
+        /// ECA.AD.NO_TARGETING: banner.
+        fn banner() { track_user(); }
+
+        /// LGPD.DATA.COLLECTION: profile.
+        fn profile() { collect_personal_data(); anonymize_data(); }
+    "#;
+    let mut config = EngineConfig::default();
+    project.apply_to(&mut config);
+    assert_eq!(
+        analyze_with("src/ads.rs", code, config).compliance_score,
+        75.0
+    );
+    assert_eq!(analyze("src/ads.rs", code).compliance_score, 28.57);
+
+    std::fs::write(&path, "scoring:\n  weights:\n    low: -1\n").unwrap();
+    let err = ProjectConfig::from_path(&path).unwrap_err();
+    assert!(err.to_string().contains("low"), "{}", err);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_web_scan_uses_the_same_model() {
+    let html = std::fs::read_to_string("examples/web/non_compliant_site.html").unwrap();
+    let config = |weights| ScanConfig {
+        max_pages: 1,
+        follow_links: false,
+        check_subpages: vec![],
+        rules: RuleSet::builtin(),
+        scoring: ScoringConfig { weights },
+        locale: Locale::default(),
+    };
+
+    let result =
+        StaticDomScanner::new().scan_html("local", &html, &config(SeverityWeights::default()));
+    let breakdown = &result.score_breakdown;
+    assert_eq!(breakdown.obligations, 6);
+    assert_eq!(breakdown.failed, 5);
+    assert_eq!(result.score, breakdown.score);
+    assert_eq!(breakdown.by_category["age"].failed, 1);
+    assert_eq!(breakdown.by_category["lootbox"].score, 0.0);
+    assert_eq!(breakdown.by_jurisdiction["ECA Digital"].obligations, 6);
+
+    // Todas as regras web são CRITICAL ou HIGH; sem peso, nenhuma conta.
+    let weights = SeverityWeights {
+        critical: 0.0,
+        high: 0.0,
+        ..SeverityWeights::default()
+    };
+    let result = StaticDomScanner::new().scan_html("local", &html, &config(weights));
+    assert_eq!(result.score, 100.0);
+}
+
+#[test]
+fn test_score_of_obligations() {
+    let obligation = |severity, failed| Obligation {
+        rule_id: "X.1".to_string(),
+        severity,
+        jurisdiction: Jurisdiction::Generic,
+        category: "custom".to_string(),
+        subject: String::new(),
+        failed,
+    };
+    let breakdown = scoring::score(
+        &[
+            obligation(RuleSeverity::Critical, true),
+            obligation(RuleSeverity::Low, false),
+            obligation(RuleSeverity::Info, false),
+        ],
+        &SeverityWeights::default(),
+    );
+    // 100 * (1 - 10 / 11.5)
+    assert_eq!(breakdown.score, 13.04);
+    assert_eq!(breakdown.by_jurisdiction["Generic"].failed, 1);
+}
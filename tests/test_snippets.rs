@@ -0,0 +1,95 @@
+// tests/test_snippets.rs
+
+mod common;
+
+use common::analyze;
+use hubstry_iso_code::i18n::Locale;
+use hubstry_iso_code::models::{OutputFormat, Snippet};
+use hubstry_iso_code::report;
+use hubstry_iso_code::snippet;
+
+const CODE: &str = r#"use std::fmt;
+
+/// LGPD.DATA.COLLECTION: Sign-up form.
+fn register(name: &str) {
+    let greeting = "<hello>";
+    validate(name, 3);
+    save_profile(name);
+}
+
+fn save_profile(name: &str) {
+    save_user_info(name);
+}
+"#;
+
+fn highlighted(snippet: &Snippet) -> Vec<usize> {
+    snippet
+        .lines
+        .iter()
+        .filter(|line| line.highlight)
+        .map(|line| line.number)
+        .collect()
+}
+
+#[test]
+fn test_snippet_highlights_annotation_and_call_site() {
+    let result = analyze("src/signup.rs", CODE);
+    let violation = &result.violations[0];
+    assert_eq!(violation.rule_id, "LGPD.DATA.COLLECTION.1");
+
+    let chain = violation.call_chain.as_ref().unwrap();
+    assert_eq!(
+        chain.path,
+        vec!["register", "save_profile", "save_user_info"]
+    );
+    assert_eq!(chain.missing, vec!["anonymize_data"]);
+
+    let snippet = violation.snippet.as_ref().unwrap();
+    let numbers: Vec<usize> = snippet.lines.iter().map(|line| line.number).collect();
+    assert_eq!(numbers, (3..=8).collect::<Vec<_>>());
+    assert_eq!(highlighted(snippet), vec![3, 7]);
+    assert_eq!(snippet.lines[4].text, "    save_profile(name);");
+}
+
+#[test]
+fn test_long_functions_are_shortened_around_highlights() {
+    let source: String = (1..=100).map(|n| format!("line {}\n", n)).collect();
+    let snippet = snippet::excerpt(&source, 1, 100, &[50]).unwrap();
+    let numbers: Vec<usize> = snippet.lines.iter().map(|line| line.number).collect();
+    assert_eq!(numbers, vec![1, 2, 48, 49, 50, 51, 52, 100]);
+    assert_eq!(highlighted(&snippet), vec![50]);
+
+    let short = snippet::excerpt(&source, 10, 20, &[]).unwrap();
+    assert_eq!(short.lines.len(), 11);
+    assert!(snippet::excerpt(&source, 200, 210, &[]).is_none());
+}
+
+#[test]
+fn test_html_report_embeds_escaped_highlighted_code() {
+    let html = report::reporter(OutputFormat::Html, Locale::En)
+        .render(&analyze("src/signup.rs", CODE))
+        .unwrap();
+    assert!(html.contains(
+        "Call chain: <code>register → save_profile → save_user_info (no call to anonymize_data on the path)</code>"
+    ));
+    assert!(html.contains("<pre class=\"snippet\">"));
+    assert!(html.contains("<span class=\"str\">&quot;&lt;hello&gt;&quot;</span>"));
+    assert!(html.contains(
+        "<span class=\"line hl\"><span class=\"ln\">7</span>    <span class=\"fn\">save_profile</span>(name);</span>"
+    ));
+    assert!(!html.contains("<hello>"));
+}
+
+#[test]
+fn test_markdown_report_embeds_code_block() {
+    let markdown = report::reporter(OutputFormat::Markdown, Locale::PtBr)
+        .render(&analyze("src/signup.rs", CODE))
+        .unwrap();
+    assert!(markdown.contains(
+        "*Cadeia de chamadas:* `register → save_profile → save_user_info (nenhuma chamada a anonymize_data no caminho)`"
+    ));
+    assert!(
+        markdown.contains("  ```rust\n  /// LGPD.DATA.COLLECTION: Sign-up form.  // ◀ linha 3\n")
+    );
+    assert!(markdown.contains("      save_profile(name);  // ◀ linha 7\n"));
+}
@@ -0,0 +1,126 @@
+// tests/test_strict_mode.rs
+
+mod common;
+
+use common::{analyze_files, strict_config};
+use hubstry_iso_code::models::AnalysisResult;
+use hubstry_iso_code::prefix_manager::PrefixRegistry;
+use hubstry_iso_code::semantic_engine::{SemanticEngine, UNKNOWN_PREFIX_RULE};
+
+const PREFIXES: &str = r#"
+jurisdictions:
+  ECA:
+    - prefix: "ECA.AGE.VERIFY"
+      description: "Verificação de idade."
+      standard: "Eca"
+    - prefix: "ECA.AD.NO_TARGETING"
+      description: "Anúncios sem segmentação."
+      standard: "Eca"
+      expected_calls: ["serve_generic_ads"]
+"#;
+
+fn analyze(code: &str, strict: bool) -> AnalysisResult {
+    let engine = SemanticEngine::with_registry(
+        strict_config(strict),
+        PrefixRegistry::from_yaml_str(PREFIXES).unwrap(),
+    );
+    analyze_files(&engine, &[("src/lib.rs", code)])
+}
+
+fn ids(result: &AnalysisResult) -> Vec<&str> {
+    result
+        .violations
+        .iter()
+        .map(|v| v.rule_id.as_str())
+        .collect()
+}
+
+#[test]
+fn test_unknown_prefixes_are_violations_only_in_strict_mode() {
+    let code = r#"
+        /// ECA.AGE.VERIFI: typo in the prefix.
+        /// Note: ordinary documentation is not a prefix.
+        fn check() {}
+    "#;
+    assert!(analyze(code, false).violations.is_empty());
+
+    let strict = analyze(code, true);
+    assert_eq!(ids(&strict), vec![UNKNOWN_PREFIX_RULE]);
+    assert!(strict.violations[0].message.contains("ECA.AGE.VERIFI"));
+    assert!(strict.strict_mode);
+}
+
+#[test]
+fn test_strict_mode_disables_fallback_lists() {
+    // `ECA.AGE.VERIFY` has no `expected_calls`; only the pack fallback accepts `verify_age`.
+    let code = r#"
+        /// ECA.AGE.VERIFY: checks the user's age.
+        fn check(user: &User) {
+            verify_age(user);
+        }
+    "#;
+    assert!(analyze(code, false).violations.is_empty());
+    assert_eq!(ids(&analyze(code, true)), vec!["ECA.AGE.VERIFY.1"]);
+}
+
+#[test]
+fn test_strict_mode_requires_exact_call_names() {
+    let code = r#"
+        /// ECA.AD.NO_TARGETING: serves ads.
+        fn show_ads() {
+            serve_generic_ads_v2();
+        }
+    "#;
+    assert!(analyze(code, false).violations.is_empty());
+    assert_eq!(ids(&analyze(code, true)), vec!["ECA.AD.NO_TARGETING.1"]);
+}
+
+#[test]
+fn test_parse_failures_fail_only_strict_runs() {
+    let sources = vec![
+        ("src/ok.rs".to_string(), "fn ok() {}".to_string()),
+        ("src/broken.rs".to_string(), "fn broken( {".to_string()),
+    ];
+    let engine = |strict| SemanticEngine::new(strict_config(strict));
+
+    let lenient = engine(false).analyze_sources(&sources).unwrap();
+    assert!(lenient
+        .warnings
+        .iter()
+        .any(|w| w.contains("HUB-PARSE-001") && w.contains("src/broken.rs")));
+
+    let err = engine(true).analyze_sources(&sources).unwrap_err();
+    assert_eq!(err.code(), "HUB-PARSE-001");
+}
+
+#[test]
+fn test_suppressions_need_approvers_in_strict_mode() {
+    let code = r#"
+        /// ECA.AD.NO_TARGETING: legacy ad slot.
+        /// hubstry:allow(ECA.AD.NO_TARGETING.1) reason = "slot desativado em 2026"
+        fn legacy_ads() {}
+
+        /// ECA.AD.NO_TARGETING: partner ad slot.
+        /// hubstry:allow(ECA.AD.NO_TARGETING.1) reason = "contrato vigente" approver = "dpo@acme.com"
+        fn partner_ads() {}
+    "#;
+
+    let lenient = analyze(code, false);
+    assert!(lenient.violations.is_empty());
+    assert_eq!(lenient.suppressed.len(), 2);
+
+    let strict = analyze(code, true);
+    assert_eq!(ids(&strict), vec!["ECA.AD.NO_TARGETING.1"]);
+    assert_eq!(strict.violations[0].function.as_deref(), Some("legacy_ads"));
+    assert!(strict
+        .warnings
+        .iter()
+        .any(|w| w.contains("legacy_ads") && w.contains("approver")));
+
+    assert_eq!(strict.suppressed.len(), 1);
+    let suppression = strict.suppressed[0].suppression.as_ref().unwrap();
+    assert_eq!(suppression.approver.as_deref(), Some("dpo@acme.com"));
+    assert_eq!(suppression.reason.as_deref(), Some("contrato vigente"));
+    // One of the two ad slots is non-compliant.
+    assert_eq!(strict.compliance_score, 50.0);
+}